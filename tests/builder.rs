@@ -0,0 +1,150 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for configuring the bindings with a `Builder`.
+
+mod common;
+
+use common::fixture;
+
+fn build(builder: winapi_tlb_bindgen::Builder) -> String {
+	let mut out = vec![];
+	builder.build(&mut out).unwrap();
+	String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn same_as_build() {
+	let mut expected = vec![];
+	winapi_tlb_bindgen::build(&fixture("simple.tlb"), true, &mut expected).unwrap();
+	let expected = String::from_utf8(expected).unwrap();
+
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+	let out = build(winapi_tlb_bindgen::Builder::from_type_lib(type_lib).emit_dispinterfaces(true));
+	assert_eq!(out, expected);
+}
+
+#[test]
+fn default_type_mappings() {
+	// The stdole2.tlb types are mapped for the backend
+	let out = build(winapi_tlb_bindgen::Builder::from_path(fixture("simple.tlb")).backend(winapi_tlb_bindgen::Backend::Com));
+	assert!(out.contains("pub unsafe interface IShape: com::interfaces::IUnknown {"), "{}", out);
+
+	let out =
+		build(
+			winapi_tlb_bindgen::Builder::from_path(fixture("simple.tlb"))
+			.backend(winapi_tlb_bindgen::Backend::Com)
+			.type_mappings(winapi_tlb_bindgen::TypeMappings::empty()));
+	assert!(out.contains("pub unsafe interface IShape: IUnknown {"), "{}", out);
+}
+
+#[test]
+fn filter() {
+	let out =
+		build(
+			winapi_tlb_bindgen::Builder::from_path(fixture("simple.tlb"))
+			.backend(winapi_tlb_bindgen::Backend::ReprC)
+			.filter(|type_info| type_info.name.starts_with('I'))
+			.filter(|type_info| type_info.name != "IShape"));
+	assert!(out.contains("pub struct IShapeDual {"), "{}", out);
+	assert!(!out.contains("pub struct IShape {"), "{}", out);
+	assert!(!out.contains("pub struct Point {"), "{}", out);
+
+	// Types that are filtered out are still resolved
+	assert!(out.contains("color: *mut Color,"), "{}", out);
+}
+
+#[test]
+fn rename() {
+	let out =
+		build(
+			winapi_tlb_bindgen::Builder::from_path(fixture("simple.tlb"))
+			.backend(winapi_tlb_bindgen::Backend::ReprC)
+			.rename(|type_info| type_info.name.strip_prefix('I').map(|name| format!("I{name}Ext")))
+			.rename(|type_info| (type_info.name == "Point").then(|| "POINT".to_string())));
+	assert!(out.contains("pub struct IShapeExt {"), "{}", out);
+	assert!(out.contains("pub const IID_IShapeExt: GUID = "), "{}", out);
+	assert!(out.contains("pub struct POINT {"), "{}", out);
+	assert!(!out.contains("Point"), "{}", out);
+
+	// References to the renamed types use the new names
+	assert!(out.contains("// Implements IShapeExt\n"), "{}", out);
+	assert!(out.contains("by: *const POINT,"), "{}", out);
+}
+
+/// Gets the names of the types that the given `#[repr(C)]` bindings define, other than modules
+fn defined_types(out: &str) -> Vec<&str> {
+	out.lines()
+	.filter_map(|line| {
+		let name =
+			line.strip_prefix("pub struct ").or_else(|| line.strip_prefix("pub union ")).and_then(|rest| rest.strip_suffix(" {"))
+			.or_else(|| line.strip_prefix("pub type ").and_then(|rest| rest.split(' ').next()))
+			.or_else(|| line.strip_prefix("pub const CLSID_").and_then(|rest| rest.split(':').next()))?;
+		if name.ends_with("Vtbl") { None } else { Some(name) }
+	})
+	.collect()
+}
+
+fn selected(configure: impl FnOnce(winapi_tlb_bindgen::Builder) -> winapi_tlb_bindgen::Builder) -> Vec<String> {
+	let builder =
+		winapi_tlb_bindgen::Builder::from_path(fixture("simple.tlb"))
+		.backend(winapi_tlb_bindgen::Backend::ReprC)
+		.emit_dispinterfaces(true);
+	let out = build(configure(builder));
+	defined_types(&out).into_iter().map(ToOwned::to_owned).collect()
+}
+
+#[test]
+fn allowlist() {
+	assert_eq!(
+		selected(|builder| builder),
+		["Color", "Point", "Shape", "Value", "PPOINT", "IShape", "IShapeDual", "DShapeEvents", "ShapeObject"]);
+
+	// Parameter types, but not the parents from stdole2.tlb
+	assert_eq!(selected(|builder| builder.allowlist_type("IShapeDual")), ["Color", "IShapeDual"]);
+
+	// Alias targets
+	assert_eq!(selected(|builder| builder.allowlist_type("PPOINT")), ["Point", "PPOINT"]);
+
+	// Field types, including a reference to the record itself
+	assert_eq!(selected(|builder| builder.allowlist_type("Shape")), ["Color", "Point", "Shape"]);
+
+	// The interfaces that a coclass implements, and their dependencies
+	assert_eq!(
+		selected(|builder| builder.allowlist_type("ShapeObject")),
+		["Color", "Point", "IShape", "IShapeDual", "DShapeEvents", "ShapeObject"]);
+
+	// Patterns and typekinds
+	assert_eq!(selected(|builder| builder.allowlist_type("I*Dual")), ["Color", "IShapeDual"]);
+	assert_eq!(selected(|builder| builder.allowlist_type("*Shape*").allowlist_type("Value").allowlist_type("*POINT")), selected(|builder| builder));
+	assert_eq!(selected(|builder| builder.allowlist_type_kind(winapi_tlb_bindgen::types::TypeKind::Union)), ["Point", "Value"]);
+	assert_eq!(selected(|builder| builder.allowlist_type("Nothing*")), Vec::<String>::new());
+}
+
+#[test]
+fn blocklist() {
+	// Without an allowlist, every type that isn't blocklisted is emitted, even the ones that only blocklisted types depend on
+	assert_eq!(
+		selected(|builder| builder.blocklist_type("I*").blocklist_type_kind(winapi_tlb_bindgen::types::TypeKind::Alias)),
+		["Color", "Point", "Shape", "Value", "DShapeEvents", "ShapeObject"]);
+
+	// Blocklisted types aren't emitted even if an allowlisted type depends on them, and nor are the types that only they depend on
+	assert_eq!(selected(|builder| builder.allowlist_type("ShapeObject").blocklist_type("IShape")), ["Color", "IShapeDual", "DShapeEvents", "ShapeObject"]);
+
+	let out =
+		build(
+			winapi_tlb_bindgen::Builder::from_path(fixture("simple.tlb"))
+			.backend(winapi_tlb_bindgen::Backend::ReprC)
+			.allowlist_type("Shape")
+			.blocklist_type("Point"));
+	assert_eq!(defined_types(&out), ["Color", "Shape"]);
+	assert!(out.contains("pub points: [Point; 4],"), "{}", out);
+}
+
+#[test]
+fn allowlist_and_filter() {
+	// Filters are applied after the dependencies of allowlisted types are found
+	assert_eq!(
+		selected(|builder| builder.allowlist_type("Shape").filter(|type_info| type_info.name != "Color")),
+		["Point", "Shape"]);
+}
@@ -0,0 +1,57 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for loading typelibs from bytes and readers rather than paths.
+
+mod common;
+
+use common::fixture;
+
+fn build(builder: winapi_tlb_bindgen::Builder) -> Result<String, winapi_tlb_bindgen::Error> {
+	let mut out = vec![];
+	builder.emit_dispinterfaces(true).build(&mut out)?;
+	Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn formats() {
+	let expected = std::fs::read_to_string(fixture("simple.rs")).unwrap();
+
+	// The format is detected from the bytes, including typelibs embedded in PE32 and PE32+ images
+	for name in &["simple.tlb", "simple-sltg.tlb", "simple.json", "simple.dll", "simple32.dll"] {
+		let data = std::fs::read(fixture(name)).unwrap();
+
+		let type_lib = winapi_tlb_bindgen::load_type_lib_from_bytes(&data).unwrap();
+		assert_eq!(type_lib.name, "Simple", "{name}");
+
+		let type_lib = winapi_tlb_bindgen::load_type_lib_from_reader(&*data).unwrap();
+		assert_eq!(type_lib.name, "Simple", "{name}");
+
+		assert_eq!(build(winapi_tlb_bindgen::Builder::from_bytes(&*data)).unwrap(), expected, "{name}");
+		assert_eq!(build(winapi_tlb_bindgen::Builder::from_reader(std::io::Cursor::new(data))).unwrap(), expected, "{name}");
+	}
+}
+
+#[test]
+fn unsupported() {
+	match winapi_tlb_bindgen::load_type_lib_from_bytes(b"not a typelib") {
+		Err(winapi_tlb_bindgen::Error::UnsupportedFormat) => (),
+		result => panic!("expected Error::UnsupportedFormat, got {:?}", result),
+	}
+}
+
+#[test]
+fn reader_error() {
+	struct FailingReader;
+
+	impl std::io::Read for FailingReader {
+		fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+			Err(std::io::Error::other("artifact store is unavailable"))
+		}
+	}
+
+	match build(winapi_tlb_bindgen::Builder::from_reader(FailingReader)) {
+		Err(winapi_tlb_bindgen::Error::Io(err)) => assert_eq!(err.to_string(), "artifact store is unavailable"),
+		result => panic!("expected Error::Io, got {:?}", result),
+	}
+}
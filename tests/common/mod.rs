@@ -0,0 +1,66 @@
+//! Helpers that are shared by the tests. Each test uses only some of them.
+
+#![allow(dead_code)]
+
+use winapi_tlb_bindgen::types;
+
+/// Gets the path of the given file in `tests/fixtures`
+pub fn fixture(name: &str) -> std::path::PathBuf {
+	std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+}
+
+/// Checks that the given output matches the given file in `tests/fixtures`.
+///
+/// If the `UPDATE_GOLDEN` environment variable is set, the file is regenerated from the output instead.
+pub fn check_golden(actual: &[u8], expected_filename: &str) {
+	let expected_path = fixture(expected_filename);
+
+	if std::env::var_os("UPDATE_GOLDEN").is_some() {
+		std::fs::write(&expected_path, actual).unwrap();
+		return;
+	}
+
+	let expected = std::fs::read_to_string(&expected_path).unwrap();
+	let actual = std::str::from_utf8(actual).unwrap();
+	assert!(actual == expected, "output does not match {}:\n{}", expected_path.display(), actual);
+}
+
+/// Loads `simple.tlb`
+pub fn load_simple() -> types::TypeLib {
+	winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap()
+}
+
+/// Finds the type with the given name in the given typelib
+pub fn find<'a>(type_lib: &'a types::TypeLib, name: &str) -> &'a types::TypeInfo {
+	type_lib.type_infos.iter().find(|type_info| type_info.name == name).unwrap()
+}
+
+/// Finds the type with the given name in the given typelib, to change it
+pub fn find_mut<'a>(type_lib: &'a mut types::TypeLib, name: &str) -> &'a mut types::TypeInfo {
+	type_lib.type_infos.iter_mut().find(|type_info| type_info.name == name).unwrap()
+}
+
+/// A temporary directory that is deleted when dropped
+pub struct TempDir(pub std::path::PathBuf);
+
+impl TempDir {
+	/// Creates a new temporary directory for the given test. `test_file` is the name of the file of the test, so that tests in different files don't collide.
+	pub fn new(test_file: &str, test_name: &str) -> Self {
+		let path = std::env::temp_dir().join(format!("winapi-tlb-bindgen-test-{}-{}-{}", test_file, test_name, std::process::id()));
+		std::fs::create_dir_all(&path).unwrap();
+		TempDir(path)
+	}
+
+	/// Writes a file with the given name and contents in the directory, and gets its path
+	pub fn write(&self, file_name: &str, contents: impl AsRef<[u8]>) -> std::path::PathBuf {
+		let path = self.0.join(file_name);
+		std::fs::write(&path, contents).unwrap();
+		path
+	}
+}
+
+impl Drop for TempDir {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_dir_all(&self.0);
+	}
+}
@@ -0,0 +1,204 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for resolving references to types in imported typelibs.
+//!
+//! `Main` imports `Dep`. Both are compiled from IDL, and `Dep` is written as a `.tlb` so that `Main` can `importlib` it.
+
+mod common;
+
+use common::TempDir;
+use winapi_tlb_bindgen::types;
+
+const DEP_LIBID: &str = "6B2F1D4B-2C3D-4E4F-9051-000000000001";
+
+const MAIN_IDL: &str = r#"
+[uuid(6B2F1D4B-2C3D-4E4F-9051-000000000010), version(1.0)]
+library Main {
+	importlib("stdole2.tlb");
+	importlib("dep.tlb");
+
+	[uuid(6B2F1D4B-2C3D-4E4F-9051-000000000011), object]
+	interface IMain : IDep {
+		HRESULT Bar([in] DepPoint point, [in] IDep *dep);
+	};
+};
+"#;
+
+fn dep_idl(version: &str) -> String {
+	format!(r#"
+[uuid({DEP_LIBID}), version({version})]
+library Dep {{
+	importlib("stdole2.tlb");
+
+	typedef struct DepPoint {{
+		long x;
+		long y;
+	}} DepPoint;
+
+	[uuid(6B2F1D4B-2C3D-4E4F-9051-000000000002), object]
+	interface IDep : IUnknown {{
+		HRESULT Foo();
+	}};
+}};
+"#)
+}
+
+impl TempDir {
+	/// Compiles the given version of `Dep`, and writes it to a file with the given name in a new subdirectory with the given name
+	fn write_dep(&self, dir_name: &str, file_name: &str, version: &str) -> std::path::PathBuf {
+		let dir = self.0.join(dir_name);
+		std::fs::create_dir_all(&dir).unwrap();
+
+		let idl_path = dir.join("dep.idl");
+		std::fs::write(&idl_path, dep_idl(version)).unwrap();
+		let type_lib = winapi_tlb_bindgen::load_idl(&idl_path, types::SysKind::Win64).unwrap();
+		std::fs::remove_file(&idl_path).unwrap();
+
+		let file = std::fs::File::create(dir.join(file_name)).unwrap();
+		winapi_tlb_bindgen::write_type_lib(&type_lib, file).unwrap();
+
+		dir
+	}
+
+	/// Compiles `MAIN_IDL` against version 1.2 of `Dep`
+	fn main(&self) -> types::TypeLib {
+		let dir = self.write_dep("main", "dep.tlb", "1.2");
+		let idl_path = dir.join("main.idl");
+		std::fs::write(&idl_path, MAIN_IDL).unwrap();
+		winapi_tlb_bindgen::load_idl(&idl_path, types::SysKind::Win64).unwrap()
+	}
+}
+
+fn build(type_lib: &types::TypeLib, dependencies: &[types::TypeLib]) -> (String, winapi_tlb_bindgen::BuildResult) {
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build_from_type_lib(type_lib, dependencies, &winapi_tlb_bindgen::TypeMappings::default(), winapi_tlb_bindgen::Backend::Winapi, false, false, &mut out).unwrap();
+	(String::from_utf8(out).unwrap(), build_result)
+}
+
+#[test]
+fn missing() {
+	let temp_dir = TempDir::new("dependencies", "missing");
+	let main = temp_dir.main();
+
+	let (out, build_result) = build(&main, &[]);
+	assert!(out.contains("interface IMain(IMainVtbl): __missing_type__(__missing_type__Vtbl) {"), "{}", out);
+	assert!(out.contains("point: __missing_type__,"), "{}", out);
+
+	let dep_libid: types::Guid = DEP_LIBID.parse().unwrap();
+	let missing_types: Vec<_> = build_result.missing_types.iter().map(|missing_type| match &missing_type.type_ref {
+		types::TypeRef::External { lib_guid, .. } => (&*missing_type.referrer, *lib_guid),
+		types::TypeRef::Local(_) => panic!("unexpected local type"),
+	}).collect();
+	assert_eq!(missing_types, vec![
+		("IMain", Some(dep_libid)),
+		("IMain::Bar", Some(dep_libid)),
+		("IMain::Bar", Some(dep_libid)),
+	]);
+
+	assert_eq!(
+		build_result.missing_types[1].to_string(),
+		format!("IMain::Bar references type 0 in typelib {{{DEP_LIBID}}}, which was not found"));
+}
+
+#[test]
+fn missing_type_policy() {
+	let temp_dir = TempDir::new("dependencies", "missing-type-policy");
+	let main = temp_dir.main();
+
+	let mut out = vec![];
+	let result =
+		winapi_tlb_bindgen::Builder::from_type_lib(main)
+		.missing_types(winapi_tlb_bindgen::MissingTypePolicy::Error)
+		.build(&mut out);
+	match result {
+		Err(winapi_tlb_bindgen::Error::MissingTypes(missing_types)) => assert_eq!(missing_types.len(), 3),
+		result => panic!("expected Error::MissingTypes, got {:?}", result),
+	}
+
+	// Nothing is written if any types are missing
+	assert!(out.is_empty());
+}
+
+#[test]
+fn search_path() {
+	let temp_dir = TempDir::new("dependencies", "search-path");
+	let main = temp_dir.main();
+
+	// The first directory doesn't have the typelib. The second one has a typelib with a different LIBID under the same name.
+	// The file name is matched case-insensitively.
+	let empty_dir = temp_dir.0.join("empty");
+	std::fs::create_dir_all(&empty_dir).unwrap();
+	let wrong_libid_dir = temp_dir.0.join("wrong-libid");
+	std::fs::create_dir_all(&wrong_libid_dir).unwrap();
+	winapi_tlb_bindgen::write_type_lib(&main, std::fs::File::create(wrong_libid_dir.join("dep.tlb")).unwrap()).unwrap();
+	let dep_dir = temp_dir.write_dep("dep", "DEP.TLB", "1.2");
+
+	let dependencies = winapi_tlb_bindgen::load_dependencies(&main, &[empty_dir, wrong_libid_dir, dep_dir]).unwrap();
+	assert_eq!(dependencies.iter().map(|dependency| &*dependency.name).collect::<Vec<_>>(), vec!["Dep"]);
+
+	let (out, build_result) = build(&main, &dependencies);
+	assert!(build_result.missing_types.is_empty(), "{:?}", build_result.missing_types);
+	assert!(out.contains("interface IMain(IMainVtbl): IDep(IDepVtbl) {"), "{}", out);
+	assert!(out.contains("point: DepPoint,"), "{}", out);
+	assert!(out.contains("dep: *const IDep,"), "{}", out);
+}
+
+#[test]
+fn builder_search_path() {
+	let temp_dir = TempDir::new("dependencies", "builder-search-path");
+	let main = temp_dir.main();
+	let dep_dir = temp_dir.write_dep("dep", "dep.tlb", "1.2");
+
+	let mut out = vec![];
+	let build_result =
+		winapi_tlb_bindgen::Builder::from_type_lib(main)
+		.search_path(dep_dir)
+		.missing_types(winapi_tlb_bindgen::MissingTypePolicy::Error)
+		.build(&mut out)
+		.unwrap();
+	assert!(build_result.missing_types.is_empty(), "{:?}", build_result.missing_types);
+
+	let out = String::from_utf8(out).unwrap();
+	assert!(out.contains("interface IMain(IMainVtbl): IDep(IDepVtbl) {"), "{}", out);
+}
+
+#[test]
+fn builder_files() {
+	let temp_dir = TempDir::new("dependencies", "builder-files");
+	let main_dir = temp_dir.write_dep("main", "dep.tlb", "1.2");
+	std::fs::write(main_dir.join("main.idl"), MAIN_IDL).unwrap();
+	let dep_dir = temp_dir.write_dep("dep", "dep.tlb", "1.2");
+
+	// The IDL file, the typelib that it `importlib`s, and the typelib found in the search path
+	let build_result =
+		winapi_tlb_bindgen::Builder::from_path(main_dir.join("main.idl"))
+		.search_path(&dep_dir)
+		.build(std::io::sink())
+		.unwrap();
+	assert_eq!(build_result.files, [main_dir.join("main.idl"), main_dir.join("dep.tlb"), dep_dir.join("dep.tlb")]);
+
+	let build_result =
+		winapi_tlb_bindgen::Builder::from_type_lib(temp_dir.main())
+		.build(std::io::sink())
+		.unwrap();
+	assert!(build_result.files.is_empty(), "{:?}", build_result.files);
+}
+
+#[test]
+fn versions() {
+	let temp_dir = TempDir::new("dependencies", "versions");
+	let main = temp_dir.main();
+
+	for &(version, satisfied) in &[("1.2", true), ("1.5", true), ("1.1", false), ("2.2", false)] {
+		let dir = temp_dir.write_dep(version, "dep.tlb", version);
+
+		let dependencies = winapi_tlb_bindgen::load_dependencies(&main, std::slice::from_ref(&dir)).unwrap();
+		assert_eq!(dependencies.len(), usize::from(satisfied), "{version}");
+
+		// Typelibs that are given explicitly are also matched by version
+		let dependency = winapi_tlb_bindgen::load_type_lib(&dir.join("dep.tlb")).unwrap();
+		let (_, build_result) = build(&main, &[dependency]);
+		assert_eq!(build_result.missing_types.is_empty(), satisfied, "{version}");
+	}
+}
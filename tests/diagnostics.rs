@@ -0,0 +1,171 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for the errors that are returned for typelibs that bindings can't be emitted for.
+
+mod common;
+
+use common::{find_mut, load_simple};
+
+const BACKENDS: [winapi_tlb_bindgen::Backend; 4] = [
+	winapi_tlb_bindgen::Backend::Winapi,
+	winapi_tlb_bindgen::Backend::Windows,
+	winapi_tlb_bindgen::Backend::ReprC,
+	winapi_tlb_bindgen::Backend::Com,
+];
+
+/// Builds the given typelib with every backend, and checks that each one fails
+fn build_errors(type_lib: &winapi_tlb_bindgen::types::TypeLib) -> Vec<winapi_tlb_bindgen::Error> {
+	BACKENDS.iter().map(|&backend| {
+		let result =
+			winapi_tlb_bindgen::Builder::from_type_lib(type_lib.clone())
+			.backend(backend)
+			.build(std::io::sink());
+		match result {
+			Ok(_) => panic!("{:?} emitted bindings", backend),
+			Err(err) => err,
+		}
+	}).collect()
+}
+
+#[test]
+fn unsupported_layout() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "Value").alignment = 2;
+
+	for err in build_errors(&type_lib) {
+		match &err {
+			winapi_tlb_bindgen::Error::UnsupportedLayout { location, .. } => {
+				assert_eq!(location.type_name, "Value");
+				assert_eq!(location.member_name, None);
+			},
+			err => panic!("unexpected error {:?}", err),
+		}
+
+		assert_eq!(err.to_string(), "Value: unsupported layout: union alignment is not 4 or 8");
+	}
+}
+
+#[test]
+fn unexpected_func_kind() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "Helpers").functions[0].func_kind = winapi_tlb_bindgen::types::FuncKind::Virtual;
+
+	for err in build_errors(&type_lib) {
+		match &err {
+			winapi_tlb_bindgen::Error::UnexpectedFuncKind { location, func_kind } => {
+				assert_eq!(location.type_name, "Helpers");
+				assert_eq!(location.member_name.as_deref(), Some("Add"));
+				assert_eq!(*func_kind, winapi_tlb_bindgen::types::FuncKind::Virtual);
+			},
+			err => panic!("unexpected error {:?}", err),
+		}
+
+		assert_eq!(err.to_string(), "Helpers::Add: unexpected funckind Virtual");
+	}
+}
+
+#[test]
+fn unsupported_var_type() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "Helpers").functions[0].params[1].type_ = winapi_tlb_bindgen::types::TypeDesc::Base(winapi_tlb_bindgen::types::VT_CF);
+
+	for err in build_errors(&type_lib) {
+		match &err {
+			winapi_tlb_bindgen::Error::UnsupportedVarType { location, vt } => {
+				assert_eq!(location.type_name, "Helpers");
+				assert_eq!(location.member_name.as_deref(), Some("Add"));
+				assert_eq!(location.param_index, Some(1));
+				assert_eq!(*vt, winapi_tlb_bindgen::types::VT_CF);
+			},
+			err => panic!("unexpected error {:?}", err),
+		}
+
+		assert_eq!(err.to_string(), "Helpers::Add parameter 1: VARTYPE VT_CF is not supported");
+	}
+}
+
+#[test]
+fn unsupported_enum_value() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "Color").vars[2].var_kind =
+		winapi_tlb_bindgen::types::VarKind::Const(winapi_tlb_bindgen::types::Variant::Bstr("Blue".to_string()));
+
+	for err in build_errors(&type_lib) {
+		match &err {
+			winapi_tlb_bindgen::Error::UnsupportedVarType { location, vt } => {
+				assert_eq!(location.member_name.as_deref(), Some("Blue"));
+				assert_eq!(location.param_index, None);
+				assert_eq!(*vt, winapi_tlb_bindgen::types::VT_BSTR);
+			},
+			err => panic!("unexpected error {:?}", err),
+		}
+	}
+}
+
+#[test]
+fn inconsistent() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "PPOINT").alias_type = None;
+
+	for err in build_errors(&type_lib) {
+		match &err {
+			winapi_tlb_bindgen::Error::Inconsistent { location, reason } => {
+				assert_eq!(location.type_name, "PPOINT");
+				assert_eq!(*reason, "alias has no target");
+			},
+			err => panic!("unexpected error {:?}", err),
+		}
+	}
+}
+
+#[test]
+fn self_containing_record() {
+	let mut type_lib = load_simple();
+	let point_index = type_lib.type_infos.iter().position(|type_info| type_info.name == "Point").unwrap();
+	find_mut(&mut type_lib, "Point").vars[0].type_ =
+		winapi_tlb_bindgen::types::TypeDesc::UserDefined(winapi_tlb_bindgen::types::TypeRef::Local(point_index));
+
+	// Only the windows backend looks into the fields of records, to decide whether to derive Copy
+	let err =
+		winapi_tlb_bindgen::Builder::from_type_lib(type_lib)
+		.backend(winapi_tlb_bindgen::Backend::Windows)
+		.build(std::io::sink())
+		.unwrap_err();
+	match &err {
+		winapi_tlb_bindgen::Error::Inconsistent { location, reason } => {
+			assert_eq!(location.type_name, "Point");
+			assert_eq!(*reason, "type contains itself");
+		},
+		err => panic!("unexpected error {:?}", err),
+	}
+}
+
+#[test]
+fn continue_on_error() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "Value").alignment = 2;
+	find_mut(&mut type_lib, "PPOINT").alias_type = None;
+
+	for &backend in &BACKENDS {
+		let mut out = vec![];
+		let build_result =
+			winapi_tlb_bindgen::Builder::from_type_lib(type_lib.clone())
+			.backend(backend)
+			.continue_on_error(true)
+			.build(&mut out)
+			.unwrap();
+		let out = String::from_utf8(out).unwrap();
+
+		let failed_types: Vec<_> = build_result.failed_types.iter().map(|failed_type| &*failed_type.type_name).collect();
+		assert_eq!(failed_types, ["Value", "PPOINT"], "{backend:?}");
+		assert!(matches!(build_result.failed_types[0].error, winapi_tlb_bindgen::Error::UnsupportedLayout { .. }), "{:?}", backend);
+		assert_eq!(build_result.failed_types[1].to_string(), "PPOINT was left out: PPOINT: alias has no target", "{backend:?}");
+
+		// Nothing is written for the types that failed, and everything else is
+		assert!(!out.contains("Value"), "{}", out);
+		assert!(!out.contains("PPOINT"), "{}", out);
+		assert!(out.contains("IShapeDual"), "{}", out);
+		assert!(out.contains("Area"), "{}", out);
+	}
+}
@@ -0,0 +1,77 @@
+#!/usr/bin/env python3
+# Wraps typelibs as TYPELIB resources in a minimal PE32 or PE32+ DLL.
+# Usage: mkpe.py OUT 32|64 ID=TYPELIB...
+import struct, sys
+
+def align(b, n, fill=b'\0'):
+    while len(b) % n: b += fill
+    return b
+
+def build(resources, pe32_plus=True):
+    # resources: list of (id, bytes)
+    RVA = 0x1000
+    # Layout: root dir, type dir, name dirs, data entries, string, data
+    root = 0
+    typedir = 16 + 8
+    namedirs = typedir + 16 + 8 * len(resources)
+    dataentries = namedirs + (16 + 8) * len(resources)
+    string = dataentries + 16 * len(resources)
+    name = 'TYPELIB'.encode('utf-16-le')
+    datas = align(bytearray(string) + bytearray(struct.pack('<H', 7) + name), 8)
+    data_offs = []
+    blob = bytearray()
+    for _, d in resources:
+        data_offs.append(len(datas) + len(blob))
+        blob += d
+        blob = align(blob, 8)
+    rsrc = bytearray()
+    rsrc += struct.pack('<IIHHHH', 0, 0, 0, 0, 1, 0)
+    rsrc += struct.pack('<II', 0x8000_0000 | string, 0x8000_0000 | typedir)
+    rsrc += struct.pack('<IIHHHH', 0, 0, 0, 0, 0, len(resources))
+    for i, (rid, _) in enumerate(resources):
+        rsrc += struct.pack('<II', rid, 0x8000_0000 | (namedirs + 24 * i))
+    for i in range(len(resources)):
+        rsrc += struct.pack('<IIHHHH', 0, 0, 0, 0, 0, 1)
+        rsrc += struct.pack('<II', 0x409, dataentries + 16 * i)
+    for i, (_, d) in enumerate(resources):
+        rsrc += struct.pack('<IIII', RVA + data_offs[i], len(d), 0, 0)
+    rsrc += struct.pack('<H', 7) + name
+    rsrc = align(rsrc, 8)
+    assert len(rsrc) == len(datas)
+    rsrc += blob
+    raw = align(bytearray(rsrc), 0x200)
+
+    dos = bytearray(b'MZ' + b'\0' * 0x3A + struct.pack('<I', 0x40))
+    opt_size = 240 if pe32_plus else 224
+    fh = struct.pack('<HHIIIHH', 0x8664 if pe32_plus else 0x14C, 1, 0, 0, 0, opt_size, 0x2022 if pe32_plus else 0x2102)
+    opt = bytearray(opt_size)
+    size_of_image = RVA + ((len(rsrc) + 0xFFF) & ~0xFFF)
+    if pe32_plus:
+        struct.pack_into('<H', opt, 0, 0x20B)
+        struct.pack_into('<Q', opt, 24, 0x1_8000_0000)
+        dd = 112; nrva = 108
+    else:
+        struct.pack_into('<H', opt, 0, 0x10B)
+        struct.pack_into('<I', opt, 28, 0x1000_0000)
+        dd = 96; nrva = 92
+    struct.pack_into('<II', opt, 32, 0x1000, 0x200)
+    struct.pack_into('<HH', opt, 40, 6, 0)
+    struct.pack_into('<HH', opt, 48, 6, 0)
+    struct.pack_into('<II', opt, 56, size_of_image, 0x200)
+    struct.pack_into('<H', opt, 68, 2)
+    struct.pack_into('<I', opt, nrva, 16)
+    struct.pack_into('<II', opt, dd + 2 * 8, RVA, len(rsrc))
+    sh = b'.rsrc\0\0\0' + struct.pack('<IIIIIIHHI', len(rsrc), RVA, len(raw), 0x200, 0, 0, 0, 0, 0x4000_0040)
+    hdr = dos + b'PE\0\0' + fh + opt + sh
+    hdr = align(bytearray(hdr), 0x200)
+    assert len(hdr) == 0x200
+    return bytes(hdr + raw)
+
+if __name__ == '__main__':
+    out = sys.argv[1]
+    pe32_plus = sys.argv[2] == '64'
+    resources = []
+    for arg in sys.argv[3:]:
+        rid, path = arg.split('=', 1)
+        resources.append((int(rid), open(path, 'rb').read()))
+    open(out, 'wb').write(build(resources, pe32_plus))
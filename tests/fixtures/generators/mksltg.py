@@ -0,0 +1,276 @@
+#!/usr/bin/env python3
+# Hand-crafted SLTG typelib generator for test fixtures. Writes simple-sltg.tlb, which mirrors the contents of simple.tlb.
+# Usage: mksltg.py OUT
+import struct, sys, uuid
+
+VT_I4, VT_R4, VT_R8, VT_BSTR, VT_BOOL, VT_VARIANT, VT_UNKNOWN = 3, 4, 5, 8, 11, 12, 13
+VT_VOID, VT_HRESULT, VT_PTR, VT_SAFEARRAY, VT_CARRAY, VT_USERDEFINED = 24, 25, 26, 27, 28, 29
+
+TKIND = dict(enum=0, record=1, module=2, interface=3, dispatch=4, coclass=5, alias=6, union=7)
+STDOLE = '00020430-0000-0000-c000-000000000046'
+
+# Type descriptions: ('base', vt) | ('ptr', t) | ('udt', typeref) | ('carray', t, dims) | ('safearray', t)
+# typeref: ('local', index) | ('stdole', index)
+def base(vt): return ('base', vt)
+def ptr(t): return ('ptr', t)
+def udt(r): return ('udt', r)
+
+IN, OUT, INOUT, RETVAL = 0x0000, 0x4000, 0x8000, 0x0080
+
+class NameTable:
+    def __init__(self):
+        self.data = bytearray(); self.map = {}
+    def add(self, s):
+        if s in self.map: return self.map[s]
+        self.data += b'\x7f'
+        off = len(self.data)
+        self.data += s.encode('latin-1') + b'\0'
+        self.map[s] = off
+        return off
+
+class TypeInfoBuilder:
+    def __init__(self, names, stdole_off):
+        self.names = names; self.stdole_off = stdole_off
+        self.refs = []
+    def ref(self, r):
+        if r[0] == 'local':
+            s = '*\\Rffff*#%x' % r[1]
+        else:
+            s = '*\\R%x*#%x' % (self.stdole_off, r[1])
+        if s not in self.refs: self.refs.append(s)
+        return self.refs.index(s)
+    def words(self, t, types_area, types_base):
+        k = t[0]
+        if k == 'base': return [t[1]]
+        if k == 'ptr': return [VT_PTR] + self.words(t[1], types_area, types_base)
+        if k == 'udt': return [VT_USERDEFINED, self.ref(t[1]) * 4]
+        if k == 'safearray': return [VT_SAFEARRAY, 0xffff] + self.words(t[1], types_area, types_base)
+        if k == 'carray':
+            off = types_base + len(types_area)
+            types_area += struct.pack('<HHIII', len(t[2]), 0, 0, 0, 0)
+            for d in t[2]: types_area += struct.pack('<Ii', d, 0)
+            return [VT_CARRAY, off] + self.words(t[1], types_area, types_base)
+        raise ValueError(k)
+
+def build_members(tb, ti, types_base):
+    """Returns (member bytes, tail fields) with out-of-line type data placed at types_base"""
+    names = tb.names
+    types_area = bytearray()
+    def out_of_line(t, flags=0):
+        w = tb.words(t, types_area, types_base)
+        w[0] |= flags
+        off = types_base + len(types_area)
+        types_area.extend(b''.join(struct.pack('<H', x) for x in w))
+        return off
+
+    impls = ti.get('impls', [])
+    m = bytearray()
+    for j, (r, flags) in enumerate(impls):
+        nxt = (j + 1) * 0x16 if j + 1 < len(impls) else 0xffff
+        m += struct.pack('<HHHBBHHHHHHH', 0x004a, nxt, 0xffff, flags, 0x80, 0x12, tb.ref(r), 0x4000, 0xfffe, 0xffff, 0x1d, j * 4)
+    assert len(m) <= types_base
+
+    recs = bytearray()
+    start = types_base + 0  # placeholder; fixed up by caller via two passes
+    funcs = ti.get('funcs', [])
+    vars_ = ti.get('vars', [])
+    recs_base = ti['_recs_base']
+    func_offs = []
+    for f in funcs:
+        off = recs_base + len(recs)
+        func_offs.append(off)
+        params = f.get('params', [])
+        args = bytearray()
+        for (pname, ptype, pflags) in params:
+            w = tb.words(ptype, bytearray(), 0) if ptype[0] != 'carray' else None
+            if w is not None and len(w) == 1 and pname is not None:
+                args += struct.pack('<H', names.add(pname) + 1)
+                args += struct.pack('<H', w[0] | pflags)
+            elif pname is None and w is not None:
+                w[0] |= pflags
+                args += struct.pack('<H', 0xffff) + b''.join(struct.pack('<H', x) for x in w)
+            else:
+                args += struct.pack('<HH', names.add(pname), out_of_line(ptype, pflags))
+        ret_words = tb.words(f['ret'], bytearray(), 0)
+        assert len(ret_words) == 1
+        magic = {1: 0x4c, 4: 0x44, 3: 0x53}[f['funckind']]
+        rec = struct.pack('<BBHHiHHHBBHH', magic, (f.get('invkind', 1) << 4) | 2, 0, names.add(f['name']), f.get('memid', 0x60020000 + len(func_offs) - 1),
+                          0, 0xffff, off + 0x16, (len(params) << 3) | 4, 0x80, ret_words[0], f.get('ovft', 0))
+        assert len(rec) == 0x16
+        recs += rec + args
+    var_offs = []
+    for v in vars_:
+        off = recs_base + len(recs)
+        var_offs.append(off)
+        flags = 0
+        w = tb.words(v['type'], bytearray(), 0) if v['type'][0] != 'carray' else None
+        if w is not None and len(w) == 1:
+            flags |= 0x02; type_field = w[0]
+        else:
+            type_field = out_of_line(v['type'])
+        byte_offs = v.get('offs', 0)
+        if v.get('kind') == 'const':
+            flags |= 0x10
+            if 0 <= v['value'] <= 0xffff:
+                flags |= 0x08; byte_offs = v['value']
+            else:
+                byte_offs = types_base + len(types_area)
+                types_area.extend(struct.pack('<i', v['value']))
+        elif v.get('kind') == 'dispatch':
+            flags |= 0x40
+        recs += struct.pack('<BBHHHHiHH', 0x0a, flags, 0, names.add(v['name']), byte_offs, type_field, v.get('memid', 0x40000000 + len(var_offs) - 1), 0, 0xffff)
+    # fix up next pointers
+    for lst, nxt_off in ((func_offs, 2), (var_offs, 2)):
+        for j, off in enumerate(lst):
+            nxt = lst[j + 1] if j + 1 < len(lst) else 0xffff
+            struct.pack_into('<H', recs, off - recs_base + nxt_off, nxt)
+    alias_off = 0
+    simple_alias = 0
+    if ti['kind'] == 'alias':
+        w = tb.words(ti['alias'], bytearray(), 0)
+        if len(w) == 1:
+            simple_alias = 1; alias_off = w[0]
+        else:
+            alias_off = out_of_line(ti['alias'])
+    return m, types_area, recs, dict(
+        funcs_off=func_offs[0] if func_offs else 0xffff,
+        vars_off=var_offs[0] if var_offs else 0xffff,
+        impls_off=0 if impls else 0xffff,
+        alias=alias_off, simple_alias=simple_alias)
+
+def build_typeinfo(tb, ti):
+    impls_len = 0x16 * len(ti.get('impls', []))
+    # pass 1: size of types area
+    ti['_recs_base'] = 0
+    tb_refs = list(tb.refs)
+    _, types_area, _, _ = build_members(tb, ti, impls_len)
+    ti['_recs_base'] = impls_len + len(types_area)
+    tb.refs[:] = tb_refs
+    m, types_area2, recs, tail = build_members(tb, ti, impls_len)
+    assert len(types_area2) == len(types_area)
+    members = m + types_area2 + recs
+
+    flags = ti.get('flags', 0)
+    kind = TKIND[ti['kind']]
+    hdr_len = 0x22
+    refinfo = bytearray()
+    if tb.refs:
+        refinfo += bytes([0xdf]) + b'\xff' * 0x43
+        refinfo += struct.pack('<I', len(tb.refs) * 8)
+        for _ in tb.refs: refinfo += struct.pack('<HBBHH', 1, 2, 0x40, 0xffff, 0)
+        refinfo += struct.pack('<HBI', 0xffff, 1, 0)
+        for r in tb.refs: refinfo += struct.pack('<H', len(r)) + r.encode('latin-1')
+        refinfo += bytes([0xdf])
+    href_table = hdr_len if tb.refs else 0xffffffff
+    elem_table = hdr_len + len(refinfo)
+    hdr = struct.pack('<HIIIIHHIBBBBI', 0x0501, href_table, 0xffffffff, elem_table, 0xffffffff, 1, 0, 0xfffe0000,
+                      0x02 | ((flags & 0x1f) << 3), flags >> 5, 0x02, kind, 0)
+    assert len(hdr) == hdr_len
+    memhdr = struct.pack('<HHBI', 1, 0xffff, 1, len(members))
+    nf = len(ti.get('funcs', [])); nv = len(ti.get('vars', [])); ni = len(ti.get('impls', []))
+    tail = struct.pack('<HHHHHHHHHHHHHHHHHHHHHHHHHHH',
+        nf, nv, ni, 0, tail['funcs_off'], tail['vars_off'], tail['impls_off'], 0, 0, 0,
+        tail['alias'], 0xffff, 0, 0, tail['simple_alias'], 0,
+        ti.get('size', 0), ti.get('align', 4), 0, 0, ti.get('vft', 0), 0xffff, 0xffff, 0xffff, 0xffff, 0, 0)
+    assert len(tail) == 0x36
+    return bytes(hdr + refinfo + memhdr + members + tail)
+
+def build(lib_name, lib_guid, typeinfos):
+    names = NameTable()
+    stdole_off = names.add('*\\G{%s}#2.0#0#stdole2.tlb#OLE Automation' % STDOLE.upper()) - 0  # offset of the string itself
+    blocks = []
+    for ti in typeinfos:
+        tb = TypeInfoBuilder(names, stdole_off)
+        blocks.append(build_typeinfo(tb, ti))
+    nti = len(typeinfos)
+
+    lib = bytearray()
+    lib += struct.pack('<HHHH', 0x51cc, 3, names.add(lib_name), 0xffff)
+    lib += struct.pack('<HH', 0xffff, 0xffff)
+    lib += struct.pack('<IHHIHHH', 0, 1, 0x409, 0, 0, 1, 0)
+    lib += uuid.UUID(lib_guid).bytes_le
+    lib += b'\xff' * 0x40
+    for i, ti in enumerate(typeinfos):
+        index_name = index_string(i)
+        lib += struct.pack('<HH', i, len(index_name)) + index_name.encode()
+        lib += struct.pack('<HHHH', 0xffff, 0xffff, names.add(ti['name']), 0)
+        lib += struct.pack('<HIH', 0xffff, 0, 0xffff)
+        lib += (uuid.UUID(ti['guid']).bytes_le if ti.get('guid') else b'\0' * 16)
+        lib += struct.pack('<H', TKIND[ti['kind']])
+    lib += struct.pack('<H', 3)
+    name_table_rel = len(lib) + 4
+    lib += struct.pack('<I', name_table_rel)
+    lib += struct.pack('<H', 0xffff) + b'\0' * (0x218 - 2)
+    lib += names.data
+
+    out = bytearray()
+    out += b'SLTG' + struct.pack('<HHHHIIIIII', nti + 2, 9, 0, 1, 0x000204ff, 0, 0xc0, 0x46000000, 0x44, 0xffff0000)
+    assert len(out) == 0x24
+    for i, blk in enumerate(blocks):
+        out += struct.pack('<IHH', len(blk), 13 + i * 11, i + 2)
+    out += struct.pack('<IHH', len(lib), 9, 0)
+    out += b'\x01CompObj\0dir\0'
+    for i in range(nti): out += index_string(i).encode() + b'\0'
+    out += b'\0' * 9
+    for blk in blocks: out += blk
+    out += lib
+    return bytes(out)
+
+def index_string(i):
+    return chr(ord('A') + i) + 'AAAAAAAAA'
+
+def simple():
+    COLOR, POINT, SHAPE, VALUE, PPOINT, HELPERS, ISHAPE, ISHAPEDUAL, DSHAPEEVENTS, SHAPEOBJECT = range(10)
+    HR = base(VT_HRESULT)
+    L = lambda i: ('local', i)
+    return build('Simple', '5a1e0c3a-1b2c-4d3e-8f40-112233445566', [
+        dict(kind='enum', name='Color', size=4, align=4, vars=[
+            dict(name='Red', type=base(VT_I4), kind='const', value=0),
+            dict(name='Green', type=base(VT_I4), kind='const', value=1),
+            dict(name='Blue', type=base(VT_I4), kind='const', value=-2),
+        ]),
+        dict(kind='record', name='Point', size=8, align=4, vars=[
+            dict(name='x', type=base(VT_I4), offs=0),
+            dict(name='y', type=base(VT_I4), offs=4),
+        ]),
+        dict(kind='record', name='Shape', size=48, align=4, vars=[
+            dict(name='name', type=base(VT_BSTR), offs=0),
+            dict(name='points', type=('carray', udt(L(POINT)), [4]), offs=4),
+            dict(name='next', type=ptr(udt(L(SHAPE))), offs=36),
+            dict(name='type', type=ptr(base(VT_VOID)), offs=40),
+            dict(name='color', type=udt(L(COLOR)), offs=44),
+        ]),
+        dict(kind='union', name='Value', size=8, align=8, vars=[
+            dict(name='i', type=base(VT_I4)),
+            dict(name='d', type=base(VT_R8)),
+            dict(name='p', type=udt(L(POINT))),
+        ]),
+        dict(kind='alias', name='PPOINT', size=4, align=4, alias=ptr(udt(L(POINT)))),
+        dict(kind='module', name='Helpers', funcs=[
+            dict(name='Add', funckind=3, ret=base(VT_I4), params=[('a', base(VT_I4), IN), ('b', base(VT_I4), IN)]),
+        ]),
+        # Like in midl's typelibs, an interface, dispinterface or coclass is the size of a pointer
+        dict(kind='interface', name='IShape', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000001', flags=0x1000, vft=7 * 4,
+             impls=[(('stdole', 3), 0)], funcs=[
+            dict(name='Area', funckind=1, ovft=12, ret=HR, params=[('area', ptr(base(VT_R8)), OUT | RETVAL)]),
+            dict(name='Name', funckind=1, invkind=2, ovft=16, ret=HR, params=[('name', ptr(base(VT_BSTR)), OUT | RETVAL)]),
+            dict(name='Name', funckind=1, invkind=4, ovft=20, ret=HR, params=[(None, base(VT_BSTR), IN)]),
+            dict(name='Move', funckind=1, ovft=24, ret=HR, params=[('by', ptr(udt(L(POINT))), IN), ('impl', ptr(base(VT_UNKNOWN)), INOUT)]),
+        ]),
+        dict(kind='interface', name='IShapeDual', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000002', flags=0x1000 | 0x40, vft=9 * 4,
+             impls=[(('stdole', 4), 0)], funcs=[
+            dict(name='Color', funckind=1, invkind=2, ovft=28, memid=1, ret=HR, params=[('color', ptr(udt(L(COLOR))), OUT | RETVAL)]),
+            dict(name='Scale', funckind=1, ovft=32, memid=2, ret=HR, params=[('factor', base(VT_R4), IN), ('values', ('safearray', base(VT_VARIANT)), IN)]),
+        ]),
+        dict(kind='dispatch', name='DShapeEvents', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000003', flags=0x1000, vft=7 * 4, funcs=[
+            dict(name='Moved', funckind=4, memid=1, ret=base(VT_VOID), params=[('x', base(VT_I4), IN), ('color', udt(L(COLOR)), IN)]),
+            dict(name='Renamed', funckind=4, memid=2, ret=base(VT_VOID), params=[('name', base(VT_BSTR), IN), ('cancel', ptr(base(VT_BOOL)), INOUT)]),
+        ], vars=[
+            dict(name='Visible', type=base(VT_BOOL), kind='dispatch', memid=3),
+        ]),
+        dict(kind='coclass', name='ShapeObject', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000004', flags=0x2,
+             impls=[(L(ISHAPEDUAL), 1), (L(ISHAPE), 0), (L(DSHAPEEVENTS), 3)]),
+    ])
+
+if __name__ == '__main__':
+    open(sys.argv[1], 'wb').write(simple())
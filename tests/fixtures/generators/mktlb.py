@@ -0,0 +1,251 @@
+#!/usr/bin/env python3
+# Hand-crafted MSFT typelib generator for test fixtures. Writes simple.tlb.
+# Usage: mktlb.py OUT
+import struct, sys, uuid
+
+VT_I2, VT_I4, VT_R4, VT_R8, VT_CY, VT_DATE, VT_BSTR, VT_DISPATCH, VT_ERROR, VT_BOOL, VT_VARIANT, VT_UNKNOWN = 2,3,4,5,6,7,8,9,10,11,12,13
+VT_I1, VT_UI1, VT_UI2, VT_UI4, VT_I8, VT_UI8, VT_INT, VT_UINT, VT_VOID, VT_HRESULT, VT_PTR, VT_SAFEARRAY, VT_CARRAY, VT_USERDEFINED, VT_LPSTR, VT_LPWSTR = 16,17,18,19,20,21,22,23,24,25,26,27,28,29,30,31
+
+TKIND = dict(enum=0, record=1, module=2, interface=3, dispatch=4, coclass=5, alias=6, union=7)
+
+def guid_bytes(s):
+    return uuid.UUID(s).bytes_le
+
+class Lib:
+    def __init__(self, name, guid, syskind=1):
+        self.name = name; self.guid = guid; self.syskind = syskind
+        self.guids = bytearray(); self.guid_map = {}
+        self.names = bytearray(); self.name_map = {}
+        self.strings = bytearray()
+        self.typedescs = bytearray(); self.td_map = {}
+        self.arraydescs = bytearray()
+        self.custdata = bytearray()
+        self.impfiles = bytearray()
+        self.impinfos = bytearray()
+        self.reftab = bytearray()
+        self.typeinfos = []
+        self.dispatch_href = -1
+
+    def add_guid(self, s, href=-1):
+        if s in self.guid_map: return self.guid_map[s]
+        off = len(self.guids)
+        self.guids += guid_bytes(s) + struct.pack('<ii', href, -1)
+        self.guid_map[s] = off
+        return off
+
+    def add_name(self, n, href=-1):
+        if n in self.name_map: return self.name_map[n]
+        off = len(self.names)
+        b = n.encode('latin-1')
+        self.names += struct.pack('<iiI', href, -1, len(b) | 0x3800_0000) + b
+        while len(self.names) % 4: self.names += b'\x57'
+        self.name_map[n] = off
+        return off
+
+    def add_impfile(self, guid, name, major, minor):
+        off = len(self.impfiles)
+        b = name.encode('latin-1')
+        self.impfiles += struct.pack('<iiIH', self.add_guid(guid), 0, major | (minor << 16), len(b) << 2) + b
+        while len(self.impfiles) % 4: self.impfiles += b'\x57'
+        return off
+
+    def add_impinfo(self, impfile, guid):
+        off = len(self.impinfos)
+        self.impinfos += struct.pack('<Iii', 0x0001_0000 | 0x01, impfile, self.add_guid(guid))
+        return off | 1
+
+    def base(self, vt):
+        return -0x8000_0000 | (vt << 16) | vt
+
+    def td(self, vt, inner):
+        key = (vt, inner)
+        if key in self.td_map: return self.td_map[key]
+        off = len(self.typedescs)
+        self.typedescs += struct.pack('<HHi', vt, 0x7FFE, inner)
+        self.td_map[key] = off
+        return off
+
+    def ptr(self, inner): return self.td(VT_PTR, inner)
+    def udt(self, href): return self.td(VT_USERDEFINED, href)
+    def safearray(self, inner): return self.td(VT_SAFEARRAY, inner)
+    def carray(self, elem, dims):
+        off = len(self.arraydescs)
+        self.arraydescs += struct.pack('<iHH', elem, len(dims), 0)
+        for d in dims: self.arraydescs += struct.pack('<Ii', d, 0)
+        return self.td(VT_CARRAY, off)
+
+    def value(self, v):
+        if 0 <= v < 0x0400_0000:
+            return -0x8000_0000 | (VT_I4 << 26) | v
+        off = len(self.custdata)
+        self.custdata += struct.pack('<Hi', VT_I4, v)
+        while len(self.custdata) % 4: self.custdata += b'\x57'
+        return off
+
+    def href(self, index): return index * 0x64
+
+    def add_typeinfo(self, **kw):
+        self.typeinfos.append(kw)
+        return len(self.typeinfos) - 1
+
+    def build(self):
+        ptr = 8 if self.syskind == 3 else 4
+        blocks = []
+        lib_guid_off = self.add_guid(self.guid)
+        lib_name_off = self.add_name(self.name)
+        # names / guids for typeinfos first
+        for i, ti in enumerate(self.typeinfos):
+            ti['name_off'] = self.add_name(ti['name'], self.href(i))
+            ti['guid_off'] = self.add_guid(ti['guid'], self.href(i)) if ti.get('guid') else -1
+        for ti in self.typeinfos:
+            funcs = ti.get('funcs', []); vars_ = ti.get('vars', [])
+            recs = bytearray(); ids = []; noffs = []; roffs = []
+            for f in funcs:
+                roffs.append(len(recs))
+                params = f.get('params', [])
+                plen = len(params)
+                reclen = 24 + plen * 12
+                fk = f['funckind'] | (f.get('invkind', 1) << 3) | (f.get('callconv', 4) << 8)
+                rec = struct.pack('<iiihhIhh', reclen | (len(ids) << 16), f['ret'], 0, f.get('ovft', 0), 0, fk, plen, 0)
+                for (pname, ptype, pflags) in params:
+                    rec += struct.pack('<iiI', ptype, -1 if pname is None else self.add_name(pname), pflags)
+                recs += rec
+                ids.append(f.get('memid', 0x6002_0000 + len(ids)))
+                noffs.append(-1 if f['name'] is None else self.add_name(f['name']))
+            for v in vars_:
+                roffs.append(len(recs))
+                varkind = v.get('varkind', 0)
+                recs += struct.pack('<iiihhi', 20 | (len(ids) << 16), v['type'], 0, varkind, 0, v.get('offs', 0))
+                ids.append(v.get('memid', 0x4000_0000 + len(ids)))
+                noffs.append(self.add_name(v['name']))
+            if funcs or vars_:
+                blk = struct.pack('<i', len(recs)) + recs
+                for arr in (ids, noffs, roffs):
+                    blk += b''.join(struct.pack('<i', x) for x in arr)
+                blocks.append(blk)
+            else:
+                blocks.append(None)
+            ti['nfuncs'] = len(funcs); ti['nvars'] = len(vars_)
+            if ti['kind'] == 'coclass':
+                off = len(self.reftab)
+                impls = ti.get('impls', [])
+                for j, (h, flags) in enumerate(impls):
+                    nxt = off + (j + 1) * 16 if j + 1 < len(impls) else -1
+                    self.reftab += struct.pack('<iiii', h, flags, -1, nxt)
+                ti['dt1'] = off
+        nti = len(self.typeinfos)
+        seg_dir_off = 0x54 + nti * 4
+        data_off = seg_dir_off + 15 * 16
+        segs = [bytearray() for _ in range(15)]
+        # typeinfo segment filled later (needs member offsets)
+        segs[1] = self.impinfos
+        segs[2] = self.impfiles
+        segs[3] = self.reftab
+        segs[4] = bytearray(struct.pack('<i', -1) * 0x20)
+        segs[5] = self.guids
+        segs[6] = bytearray(struct.pack('<i', -1) * 0x80)
+        segs[7] = self.names
+        segs[8] = self.strings
+        segs[9] = self.typedescs
+        segs[10] = self.arraydescs
+        segs[11] = self.custdata
+        seg0_len = nti * 0x64
+        # compute layout
+        offsets = []
+        cur = data_off + seg0_len
+        for k in range(1, 15):
+            offsets.append(cur); cur += len(segs[k])
+        seg_offsets = [data_off] + offsets
+        mem_offs = []
+        for blk in blocks:
+            if blk is None: mem_offs.append(-1)
+            else: mem_offs.append(cur); cur += len(blk)
+        ti_seg = bytearray()
+        for i, ti in enumerate(self.typeinfos):
+            kind = TKIND[ti['kind']]
+            align = ti.get('align', ptr)
+            nimpl = ti.get('nimpl', 0)
+            if ti['kind'] == 'coclass': nimpl = len(ti.get('impls', []))
+            ti_seg += struct.pack('<Iiiiiii', kind | (align << 11), mem_offs[i], 0, 0, -1, 0, ti['nfuncs'] | (ti['nvars'] << 16))
+            ti_seg += struct.pack('<iiii', 0, 0, 0, 0)
+            ti_seg += struct.pack('<iIiIiiiihHIii', ti['guid_off'], ti.get('flags', 0), ti['name_off'], 1, -1, 0, 0, -1, nimpl, ti.get('vft', 0), ti.get('size', 0), ti.get('dt1', -1), 0)
+            ti_seg += struct.pack('<ii', 0, -1)
+            assert len(ti_seg) == (i + 1) * 0x64, len(ti_seg)
+        segs[0] = ti_seg
+        out = bytearray()
+        out += b'MSFT' + struct.pack('<I', 0x0001_0002)
+        out += struct.pack('<iiiIIIi', lib_guid_off, 0, 0, self.syskind | 0x40, 1, 0x08, nti)
+        # the libguid was added late; fine since the guids segment is packed later
+        out += struct.pack('<iiiiiiiiiii', -1, 0, 0, len(self.name_map), len(self.names), lib_name_off, -1, -1, 0x20, 0x80, self.dispatch_href)
+        out += struct.pack('<i', len(self.impinfos) // 12)
+        assert len(out) == 0x54
+        for i in range(nti): out += struct.pack('<i', 0)
+        for k in range(15):
+            out += struct.pack('<IIii', seg_offsets[k] if segs[k] else 0xFFFFFFFF, len(segs[k]), -1, 0x0F)
+        for k in range(15): out += segs[k]
+        for blk in blocks:
+            if blk is not None: out += blk
+        return bytes(out)
+
+
+def simple():
+    lib = Lib('Simple', '5a1e0c3a-1b2c-4d3e-8f40-112233445566')
+    STDOLE = '00020430-0000-0000-c000-000000000046'
+    stdole = lib.add_impfile(STDOLE, 'stdole2.tlb', 2, 0)
+    iunknown = lib.add_impinfo(stdole, '00000000-0000-0000-c000-000000000046')
+    idispatch = lib.add_impinfo(stdole, '00020400-0000-0000-c000-000000000046')
+    lib.dispatch_href = idispatch
+
+    # Reserve a pre-known order so hrefs can be used
+    COLOR, POINT, SHAPE, VALUE, PPOINT, HELPERS, ISHAPE, ISHAPEDUAL, DSHAPEEVENTS, SHAPEOBJECT = range(10)
+    b = lib.base
+    HR = b(VT_HRESULT)
+
+    lib.add_typeinfo(kind='enum', name='Color', align=4, size=4, vars=[
+        dict(name='Red', type=b(VT_I4), varkind=2, offs=lib.value(0)),
+        dict(name='Green', type=b(VT_I4), varkind=2, offs=lib.value(1)),
+        dict(name='Blue', type=b(VT_I4), varkind=2, offs=lib.value(-2)),
+    ])
+    lib.add_typeinfo(kind='record', name='Point', align=4, size=8, vars=[
+        dict(name='x', type=b(VT_I4), offs=0),
+        dict(name='y', type=b(VT_I4), offs=4),
+    ])
+    lib.add_typeinfo(kind='record', name='Shape', align=4, size=48, vars=[
+        dict(name='name', type=b(VT_BSTR), offs=0),
+        dict(name='points', type=lib.carray(lib.udt(lib.href(POINT)), [4]), offs=4),
+        dict(name='next', type=lib.ptr(lib.udt(lib.href(SHAPE))), offs=36),
+        dict(name='type', type=lib.ptr(b(VT_VOID)), offs=40),
+        dict(name='color', type=lib.udt(lib.href(COLOR)), offs=44),
+    ])
+    lib.add_typeinfo(kind='union', name='Value', align=8, size=8, vars=[
+        dict(name='i', type=b(VT_I4), offs=0),
+        dict(name='d', type=b(VT_R8), offs=0),
+        dict(name='p', type=lib.udt(lib.href(POINT)), offs=0),
+    ])
+    lib.add_typeinfo(kind='alias', name='PPOINT', align=4, size=4, dt1=lib.ptr(lib.udt(lib.href(POINT))))
+    lib.add_typeinfo(kind='module', name='Helpers', funcs=[
+        dict(name='Add', funckind=3, ret=b(VT_I4), params=[('a', b(VT_I4), 1), ('b', b(VT_I4), 1)]),
+    ])
+    # Like in midl's typelibs, an interface, dispinterface or coclass is the size of a pointer
+    lib.add_typeinfo(kind='interface', name='IShape', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000001', flags=0x1000, nimpl=1, dt1=iunknown, vft=7 * 4, funcs=[
+        dict(name='Area', funckind=1, ovft=12, ret=HR, params=[('area', lib.ptr(b(VT_R8)), 0x2 | 0x8)]),
+        dict(name='Name', funckind=1, invkind=2, ovft=16, ret=HR, params=[('name', lib.ptr(b(VT_BSTR)), 0x2 | 0x8)]),
+        dict(name=None, funckind=1, invkind=4, ovft=20, ret=HR, params=[(None, b(VT_BSTR), 0x1)]),
+        dict(name='Move', funckind=1, ovft=24, ret=HR, params=[('by', lib.ptr(lib.udt(lib.href(POINT))), 0x1), ('impl', lib.ptr(b(VT_UNKNOWN)), 0x1 | 0x2)]),
+    ])
+    lib.add_typeinfo(kind='dispatch', name='IShapeDual', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000002', flags=0x1000 | 0x40, nimpl=1, dt1=idispatch, vft=9 * 4, funcs=[
+        dict(name='Color', funckind=1, invkind=2, ovft=28, memid=1, ret=HR, params=[('color', lib.ptr(lib.udt(lib.href(COLOR))), 0x2 | 0x8)]),
+        dict(name='Scale', funckind=1, ovft=32, memid=2, ret=HR, params=[('factor', b(VT_R4), 0x1), ('values', lib.safearray(b(VT_VARIANT)), 0x1)]),
+    ])
+    lib.add_typeinfo(kind='dispatch', name='DShapeEvents', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000003', flags=0x1000, nimpl=1, vft=7 * 4, funcs=[
+        dict(name='Moved', funckind=4, memid=1, ret=b(VT_VOID), params=[('x', b(VT_I4), 0x1), ('color', lib.udt(lib.href(COLOR)), 0x1)]),
+        dict(name='Renamed', funckind=4, memid=2, ret=b(VT_VOID), params=[('name', b(VT_BSTR), 0x1), ('cancel', lib.ptr(b(VT_BOOL)), 0x1 | 0x2)]),
+    ], vars=[
+        dict(name='Visible', type=b(VT_BOOL), varkind=3, memid=3),
+    ])
+    lib.add_typeinfo(kind='coclass', name='ShapeObject', size=4, guid='5a1e0c3a-1b2c-4d3e-8f40-000000000004', flags=0x2, impls=[(lib.href(ISHAPEDUAL), 1), (lib.href(ISHAPE), 0), (lib.href(DSHAPEEVENTS), 3)])
+    return lib.build()
+
+
+if __name__ == '__main__':
+    open(sys.argv[1], 'wb').write(simple())
@@ -0,0 +1,89 @@
+pub type Color = i32;
+pub const Red: Color = 0;
+pub const Green: Color = 1;
+pub const Blue: Color = -2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Shape {
+    pub name: BSTR,
+    pub points: [Point; 4],
+    pub next: *mut Shape,
+    pub type_: *mut core::ffi::c_void,
+    pub color: Color,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Value {
+    _alignment: u64,
+    pub i: i32,
+    pub d: f64,
+    pub p: Point,
+}
+
+pub type PPOINT = *mut Point;
+
+extern "system" {
+    pub fn Add(
+        a: i32,
+        b: i32,
+    ) -> i32;
+}
+
+com::interfaces! {
+    #[uuid("5A1E0C3A-1B2C-4D3E-8F40-000000000001")]
+    pub unsafe interface IShape: com::interfaces::IUnknown {
+        pub unsafe fn Area(
+            &self,
+            area: *mut f64,
+        ) -> com::sys::HRESULT;
+        pub unsafe fn get_Name(
+            &self,
+            name: *mut BSTR,
+        ) -> com::sys::HRESULT;
+        pub unsafe fn put_Name(
+            &self,
+            value: BSTR,
+        ) -> com::sys::HRESULT;
+        pub unsafe fn Move(
+            &self,
+            by: *const Point,
+            impl_: *mut Option<com::interfaces::IUnknown>,
+        ) -> com::sys::HRESULT;
+    }
+}
+
+com::interfaces! {
+    #[uuid("5A1E0C3A-1B2C-4D3E-8F40-000000000002")]
+    pub unsafe interface IShapeDual: IDispatch {
+        pub unsafe fn get_Color(
+            &self,
+            color: *mut Color,
+        ) -> com::sys::HRESULT;
+        pub unsafe fn Scale(
+            &self,
+            factor: f32,
+            values: *mut SAFEARRAY /* of VARIANT */,
+        ) -> com::sys::HRESULT;
+    }
+}
+
+com::interfaces! {
+    #[uuid("5A1E0C3A-1B2C-4D3E-8F40-000000000003")]
+    pub unsafe interface DShapeEvents: IDispatch {
+    }
+}
+
+// Implements IShapeDual
+// Implements IShape
+// Implements DShapeEvents
+pub const CLSID_ShapeObject: com::CLSID = com::CLSID { data1: 0x5a1e0c3a, data2: 0x1b2c, data3: 0x4d3e, data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04] };
+
@@ -0,0 +1,78 @@
+use winapi::{ENUM, RIDL, STRUCT};
+use winapi::ctypes::c_void;
+use winapi::shared::ntdef::HRESULT;
+use winapi::shared::wtypes::BSTR;
+use winapi::um::oaidl::{IDispatch, IDispatchVtbl, SAFEARRAY};
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl, LPUNKNOWN};
+
+ENUM!{enum Color {
+    Red = 0,
+    Green = 1,
+    Blue = 0xfffffffe,
+}}
+
+STRUCT!{struct Point {
+    x: i32,
+    y: i32,
+}}
+
+STRUCT!{struct Shape {
+    name: BSTR,
+    points: [Point; 4],
+    next: *mut Shape,
+    type_: *mut c_void,
+    color: Color,
+}}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Value {
+    _alignment: u64,
+    pub i: i32,
+    pub d: f64,
+    pub p: Point,
+}
+
+pub type PPOINT = *mut Point;
+
+extern "system" {
+    pub fn Add(
+        a: i32,
+        b: i32,
+    ) -> i32;
+}
+
+RIDL!{#[uuid(0x5a1e0c3a, 0x1b2c, 0x4d3e, 0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01)]
+interface IShape(IShapeVtbl): IUnknown(IUnknownVtbl) {
+    fn Area(
+        area: *mut f64,
+    ) -> HRESULT,
+    fn get_Name(
+        name: *mut BSTR,
+    ) -> HRESULT,
+    fn put_Name(
+        value: BSTR,
+    ) -> HRESULT,
+    fn Move(
+        by: *const Point,
+        impl_: *mut LPUNKNOWN,
+    ) -> HRESULT,
+}}
+
+RIDL!{#[uuid(0x5a1e0c3a, 0x1b2c, 0x4d3e, 0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02)]
+interface IShapeDual(IShapeDualVtbl): IDispatch(IDispatchVtbl) {
+    fn get_Color(
+        color: *mut Color,
+    ) -> HRESULT,
+    fn Scale(
+        factor: f32,
+        values: *mut SAFEARRAY /* of VARIANT */,
+    ) -> HRESULT,
+}}
+
+// Implements IShapeDual
+// Implements IShape
+// Implements DShapeEvents
+RIDL!{#[uuid(0x5a1e0c3a, 0x1b2c, 0x4d3e, 0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04)]
+class ShapeObject; }
+
@@ -0,0 +1,612 @@
+pub type HRESULT = i32;
+pub type BSTR = *mut u16;
+pub type DATE = f64;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GUID {
+    pub Data1: u32,
+    pub Data2: u16,
+    pub Data3: u16,
+    pub Data4: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CY {
+    pub int64: i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DECIMAL {
+    pub wReserved: u16,
+    pub scale: u8,
+    pub sign: u8,
+    pub Hi32: u32,
+    pub Lo64: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FILETIME {
+    pub dwLowDateTime: u32,
+    pub dwHighDateTime: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BLOB {
+    pub cbSize: u32,
+    pub pBlobData: *mut u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BSTRBLOB {
+    pub cbSize: u32,
+    pub pData: *mut u8,
+}
+
+// The counted arrays that VT_VECTORs stand for in a PROPVARIANT
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAC {
+    pub cElems: u32,
+    pub pElems: *mut i8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUB {
+    pub cElems: u32,
+    pub pElems: *mut u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAI {
+    pub cElems: u32,
+    pub pElems: *mut i16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUI {
+    pub cElems: u32,
+    pub pElems: *mut u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAL {
+    pub cElems: u32,
+    pub pElems: *mut i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUL {
+    pub cElems: u32,
+    pub pElems: *mut u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAFLT {
+    pub cElems: u32,
+    pub pElems: *mut f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CADBL {
+    pub cElems: u32,
+    pub pElems: *mut f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CACY {
+    pub cElems: u32,
+    pub pElems: *mut CY,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CADATE {
+    pub cElems: u32,
+    pub pElems: *mut DATE,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CABSTR {
+    pub cElems: u32,
+    pub pElems: *mut BSTR,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CABSTRBLOB {
+    pub cElems: u32,
+    pub pElems: *mut BSTRBLOB,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CABOOL {
+    pub cElems: u32,
+    pub pElems: *mut i16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CASCODE {
+    pub cElems: u32,
+    pub pElems: *mut i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAH {
+    pub cElems: u32,
+    pub pElems: *mut i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUH {
+    pub cElems: u32,
+    pub pElems: *mut u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CALPSTR {
+    pub cElems: u32,
+    pub pElems: *mut *mut u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CALPWSTR {
+    pub cElems: u32,
+    pub pElems: *mut *mut u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAFILETIME {
+    pub cElems: u32,
+    pub pElems: *mut FILETIME,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CACLSID {
+    pub cElems: u32,
+    pub pElems: *mut GUID,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SAFEARRAYBOUND {
+    pub cElements: u32,
+    pub lLbound: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SAFEARRAY {
+    pub cDims: u16,
+    pub fFeatures: u16,
+    pub cbElements: u32,
+    pub cLocks: u32,
+    pub pvData: *mut core::ffi::c_void,
+    pub rgsabound: [SAFEARRAYBOUND; 1],
+}
+
+// A VARIANT that holds a DECIMAL overlaps it with the whole VARIANT, so its value can be read by transmuting the VARIANT to a DECIMAL.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct VARIANT {
+    pub vt: u16,
+    pub wReserved1: u16,
+    pub wReserved2: u16,
+    pub wReserved3: u16,
+    pub data: VARIANT_data,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union VARIANT_data {
+    pub llVal: i64,
+    pub lVal: i32,
+    pub bVal: u8,
+    pub iVal: i16,
+    pub fltVal: f32,
+    pub dblVal: f64,
+    pub boolVal: i16,
+    pub scode: i32,
+    pub cyVal: CY,
+    pub date: DATE,
+    pub bstrVal: BSTR,
+    pub punkVal: *mut IUnknown,
+    pub pdispVal: *mut IDispatch,
+    pub parray: *mut SAFEARRAY,
+    pub byref: *mut core::ffi::c_void,
+    pub record: [*mut core::ffi::c_void; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DISPPARAMS {
+    pub rgvarg: *mut VARIANT,
+    pub rgdispidNamedArgs: *mut i32,
+    pub cArgs: u32,
+    pub cNamedArgs: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EXCEPINFO {
+    pub wCode: u16,
+    pub wReserved: u16,
+    pub bstrSource: BSTR,
+    pub bstrDescription: BSTR,
+    pub bstrHelpFile: BSTR,
+    pub dwHelpContext: u32,
+    pub pvReserved: *mut core::ffi::c_void,
+    pub pfnDeferredFillIn: Option<unsafe extern "system" fn(*mut EXCEPINFO) -> HRESULT>,
+    pub scode: i32,
+}
+
+pub const IID_IUnknown: GUID = GUID { Data1: 0x00000000, Data2: 0x0000, Data3: 0x0000, Data4: [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46] };
+
+#[repr(C)]
+pub struct IUnknown {
+    pub lpVtbl: *const IUnknownVtbl,
+}
+
+#[repr(C)]
+pub struct IUnknownVtbl {
+    pub QueryInterface: unsafe extern "system" fn(
+        This: *mut IUnknown,
+        riid: *const GUID,
+        ppvObject: *mut *mut core::ffi::c_void,
+    ) -> HRESULT,
+    pub AddRef: unsafe extern "system" fn(
+        This: *mut IUnknown,
+    ) -> u32,
+    pub Release: unsafe extern "system" fn(
+        This: *mut IUnknown,
+    ) -> u32,
+}
+
+impl IUnknown {
+    pub unsafe fn QueryInterface(
+        &self,
+        riid: *const GUID,
+        ppvObject: *mut *mut core::ffi::c_void,
+    ) -> HRESULT {
+        ((*self.lpVtbl).QueryInterface)(
+            (self as *const Self).cast_mut(),
+            riid,
+            ppvObject,
+        )
+    }
+
+    pub unsafe fn AddRef(
+        &self,
+    ) -> u32 {
+        ((*self.lpVtbl).AddRef)(
+            (self as *const Self).cast_mut(),
+        )
+    }
+
+    pub unsafe fn Release(
+        &self,
+    ) -> u32 {
+        ((*self.lpVtbl).Release)(
+            (self as *const Self).cast_mut(),
+        )
+    }
+}
+
+pub const IID_IDispatch: GUID = GUID { Data1: 0x00020400, Data2: 0x0000, Data3: 0x0000, Data4: [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46] };
+
+#[repr(C)]
+pub struct IDispatch {
+    pub lpVtbl: *const IDispatchVtbl,
+}
+
+#[repr(C)]
+pub struct IDispatchVtbl {
+    pub parent: IUnknownVtbl,
+    pub GetTypeInfoCount: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        pctinfo: *mut u32,
+    ) -> HRESULT,
+    pub GetTypeInfo: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        iTInfo: u32,
+        lcid: u32,
+        ppTInfo: *mut *mut core::ffi::c_void,
+    ) -> HRESULT,
+    pub GetIDsOfNames: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        riid: *const GUID,
+        rgszNames: *mut *mut u16,
+        cNames: u32,
+        lcid: u32,
+        rgDispId: *mut i32,
+    ) -> HRESULT,
+    pub Invoke: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        dispIdMember: i32,
+        riid: *const GUID,
+        lcid: u32,
+        wFlags: u16,
+        pDispParams: *mut DISPPARAMS,
+        pVarResult: *mut VARIANT,
+        pExcepInfo: *mut EXCEPINFO,
+        puArgErr: *mut u32,
+    ) -> HRESULT,
+}
+
+impl core::ops::Deref for IDispatch {
+    type Target = IUnknown;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self).cast::<IUnknown>() }
+    }
+}
+
+impl IDispatch {
+    pub unsafe fn GetTypeInfoCount(
+        &self,
+        pctinfo: *mut u32,
+    ) -> HRESULT {
+        ((*self.lpVtbl).GetTypeInfoCount)(
+            (self as *const Self).cast_mut(),
+            pctinfo,
+        )
+    }
+
+    pub unsafe fn GetTypeInfo(
+        &self,
+        iTInfo: u32,
+        lcid: u32,
+        ppTInfo: *mut *mut core::ffi::c_void,
+    ) -> HRESULT {
+        ((*self.lpVtbl).GetTypeInfo)(
+            (self as *const Self).cast_mut(),
+            iTInfo,
+            lcid,
+            ppTInfo,
+        )
+    }
+
+    pub unsafe fn GetIDsOfNames(
+        &self,
+        riid: *const GUID,
+        rgszNames: *mut *mut u16,
+        cNames: u32,
+        lcid: u32,
+        rgDispId: *mut i32,
+    ) -> HRESULT {
+        ((*self.lpVtbl).GetIDsOfNames)(
+            (self as *const Self).cast_mut(),
+            riid,
+            rgszNames,
+            cNames,
+            lcid,
+            rgDispId,
+        )
+    }
+
+    pub unsafe fn Invoke(
+        &self,
+        dispIdMember: i32,
+        riid: *const GUID,
+        lcid: u32,
+        wFlags: u16,
+        pDispParams: *mut DISPPARAMS,
+        pVarResult: *mut VARIANT,
+        pExcepInfo: *mut EXCEPINFO,
+        puArgErr: *mut u32,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Invoke)(
+            (self as *const Self).cast_mut(),
+            dispIdMember,
+            riid,
+            lcid,
+            wFlags,
+            pDispParams,
+            pVarResult,
+            pExcepInfo,
+            puArgErr,
+        )
+    }
+}
+
+pub type Color = i32;
+pub const Red: Color = 0;
+pub const Green: Color = 1;
+pub const Blue: Color = -2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Shape {
+    pub name: BSTR,
+    pub points: [Point; 4],
+    pub next: *mut Shape,
+    pub type_: *mut core::ffi::c_void,
+    pub color: Color,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Value {
+    _alignment: u64,
+    pub i: i32,
+    pub d: f64,
+    pub p: Point,
+}
+
+pub type PPOINT = *mut Point;
+
+extern "system" {
+    pub fn Add(
+        a: i32,
+        b: i32,
+    ) -> i32;
+}
+
+pub const IID_IShape: GUID = GUID { Data1: 0x5a1e0c3a, Data2: 0x1b2c, Data3: 0x4d3e, Data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01] };
+
+#[repr(C)]
+pub struct IShape {
+    pub lpVtbl: *const IShapeVtbl,
+}
+
+#[repr(C)]
+pub struct IShapeVtbl {
+    pub parent: IUnknownVtbl,
+    pub Area: unsafe extern "system" fn(
+        This: *mut IShape,
+        area: *mut f64,
+    ) -> HRESULT,
+    pub get_Name: unsafe extern "system" fn(
+        This: *mut IShape,
+        name: *mut BSTR,
+    ) -> HRESULT,
+    pub put_Name: unsafe extern "system" fn(
+        This: *mut IShape,
+        value: BSTR,
+    ) -> HRESULT,
+    pub Move: unsafe extern "system" fn(
+        This: *mut IShape,
+        by: *const Point,
+        impl_: *mut *mut IUnknown,
+    ) -> HRESULT,
+}
+
+impl core::ops::Deref for IShape {
+    type Target = IUnknown;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self).cast::<IUnknown>() }
+    }
+}
+
+impl IShape {
+    pub unsafe fn Area(
+        &self,
+        area: *mut f64,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Area)(
+            (self as *const Self).cast_mut(),
+            area,
+        )
+    }
+
+    pub unsafe fn get_Name(
+        &self,
+        name: *mut BSTR,
+    ) -> HRESULT {
+        ((*self.lpVtbl).get_Name)(
+            (self as *const Self).cast_mut(),
+            name,
+        )
+    }
+
+    pub unsafe fn put_Name(
+        &self,
+        value: BSTR,
+    ) -> HRESULT {
+        ((*self.lpVtbl).put_Name)(
+            (self as *const Self).cast_mut(),
+            value,
+        )
+    }
+
+    pub unsafe fn Move(
+        &self,
+        by: *const Point,
+        impl_: *mut *mut IUnknown,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Move)(
+            (self as *const Self).cast_mut(),
+            by,
+            impl_,
+        )
+    }
+}
+
+pub const IID_IShapeDual: GUID = GUID { Data1: 0x5a1e0c3a, Data2: 0x1b2c, Data3: 0x4d3e, Data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02] };
+
+#[repr(C)]
+pub struct IShapeDual {
+    pub lpVtbl: *const IShapeDualVtbl,
+}
+
+#[repr(C)]
+pub struct IShapeDualVtbl {
+    pub parent: IDispatchVtbl,
+    pub get_Color: unsafe extern "system" fn(
+        This: *mut IShapeDual,
+        color: *mut Color,
+    ) -> HRESULT,
+    pub Scale: unsafe extern "system" fn(
+        This: *mut IShapeDual,
+        factor: f32,
+        values: *mut SAFEARRAY /* of VARIANT */,
+    ) -> HRESULT,
+}
+
+impl core::ops::Deref for IShapeDual {
+    type Target = IDispatch;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self).cast::<IDispatch>() }
+    }
+}
+
+impl IShapeDual {
+    pub unsafe fn get_Color(
+        &self,
+        color: *mut Color,
+    ) -> HRESULT {
+        ((*self.lpVtbl).get_Color)(
+            (self as *const Self).cast_mut(),
+            color,
+        )
+    }
+
+    pub unsafe fn Scale(
+        &self,
+        factor: f32,
+        values: *mut SAFEARRAY /* of VARIANT */,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Scale)(
+            (self as *const Self).cast_mut(),
+            factor,
+            values,
+        )
+    }
+}
+
+// Implements IShapeDual
+// Implements IShape
+// Implements DShapeEvents
+pub const CLSID_ShapeObject: GUID = GUID { Data1: 0x5a1e0c3a, Data2: 0x1b2c, Data3: 0x4d3e, Data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04] };
+
@@ -0,0 +1,199 @@
+pub type Color = i32;
+pub const Red: Color = 0;
+pub const Green: Color = 1;
+pub const Blue: Color = -2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Shape {
+    pub name: BSTR,
+    pub points: [Point; 4],
+    pub next: *mut Shape,
+    pub type_: *mut core::ffi::c_void,
+    pub color: Color,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Value {
+    _alignment: u64,
+    pub i: i32,
+    pub d: f64,
+    pub p: Point,
+}
+
+pub type PPOINT = *mut Point;
+
+extern "system" {
+    pub fn Add(
+        a: i32,
+        b: i32,
+    ) -> i32;
+}
+
+pub const IID_IShape: GUID = GUID { Data1: 0x5a1e0c3a, Data2: 0x1b2c, Data3: 0x4d3e, Data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01] };
+
+#[repr(C)]
+pub struct IShape {
+    pub lpVtbl: *const IShapeVtbl,
+}
+
+#[repr(C)]
+pub struct IShapeVtbl {
+    pub parent: IUnknownVtbl,
+    pub Area: unsafe extern "system" fn(
+        This: *mut IShape,
+        area: *mut f64,
+    ) -> HRESULT,
+    pub get_Name: unsafe extern "system" fn(
+        This: *mut IShape,
+        name: *mut BSTR,
+    ) -> HRESULT,
+    pub put_Name: unsafe extern "system" fn(
+        This: *mut IShape,
+        value: BSTR,
+    ) -> HRESULT,
+    pub Move: unsafe extern "system" fn(
+        This: *mut IShape,
+        by: *const Point,
+        impl_: *mut *mut IUnknown,
+    ) -> HRESULT,
+}
+
+impl core::ops::Deref for IShape {
+    type Target = IUnknown;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self).cast::<IUnknown>() }
+    }
+}
+
+impl IShape {
+    pub unsafe fn Area(
+        &self,
+        area: *mut f64,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Area)(
+            (self as *const Self).cast_mut(),
+            area,
+        )
+    }
+
+    pub unsafe fn get_Name(
+        &self,
+        name: *mut BSTR,
+    ) -> HRESULT {
+        ((*self.lpVtbl).get_Name)(
+            (self as *const Self).cast_mut(),
+            name,
+        )
+    }
+
+    pub unsafe fn put_Name(
+        &self,
+        value: BSTR,
+    ) -> HRESULT {
+        ((*self.lpVtbl).put_Name)(
+            (self as *const Self).cast_mut(),
+            value,
+        )
+    }
+
+    pub unsafe fn Move(
+        &self,
+        by: *const Point,
+        impl_: *mut *mut IUnknown,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Move)(
+            (self as *const Self).cast_mut(),
+            by,
+            impl_,
+        )
+    }
+}
+
+pub const IID_IShapeDual: GUID = GUID { Data1: 0x5a1e0c3a, Data2: 0x1b2c, Data3: 0x4d3e, Data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02] };
+
+#[repr(C)]
+pub struct IShapeDual {
+    pub lpVtbl: *const IShapeDualVtbl,
+}
+
+#[repr(C)]
+pub struct IShapeDualVtbl {
+    pub parent: IDispatchVtbl,
+    pub get_Color: unsafe extern "system" fn(
+        This: *mut IShapeDual,
+        color: *mut Color,
+    ) -> HRESULT,
+    pub Scale: unsafe extern "system" fn(
+        This: *mut IShapeDual,
+        factor: f32,
+        values: *mut SAFEARRAY /* of VARIANT */,
+    ) -> HRESULT,
+}
+
+impl core::ops::Deref for IShapeDual {
+    type Target = IDispatch;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self).cast::<IDispatch>() }
+    }
+}
+
+impl IShapeDual {
+    pub unsafe fn get_Color(
+        &self,
+        color: *mut Color,
+    ) -> HRESULT {
+        ((*self.lpVtbl).get_Color)(
+            (self as *const Self).cast_mut(),
+            color,
+        )
+    }
+
+    pub unsafe fn Scale(
+        &self,
+        factor: f32,
+        values: *mut SAFEARRAY /* of VARIANT */,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Scale)(
+            (self as *const Self).cast_mut(),
+            factor,
+            values,
+        )
+    }
+}
+
+pub const IID_DShapeEvents: GUID = GUID { Data1: 0x5a1e0c3a, Data2: 0x1b2c, Data3: 0x4d3e, Data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03] };
+
+#[repr(C)]
+pub struct DShapeEvents {
+    pub lpVtbl: *const DShapeEventsVtbl,
+}
+
+#[repr(C)]
+pub struct DShapeEventsVtbl {
+    pub parent: IDispatchVtbl,
+}
+
+impl core::ops::Deref for DShapeEvents {
+    type Target = IDispatch;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self).cast::<IDispatch>() }
+    }
+}
+
+// Implements IShapeDual
+// Implements IShape
+// Implements DShapeEvents
+pub const CLSID_ShapeObject: GUID = GUID { Data1: 0x5a1e0c3a, Data2: 0x1b2c, Data3: 0x4d3e, Data4: [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04] };
+
@@ -0,0 +1,212 @@
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Color(pub i32);
+pub const Red: Color = Color(0);
+pub const Green: Color = Color(1);
+pub const Blue: Color = Color(-2);
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[repr(C)]
+pub struct Shape {
+    pub name: core::mem::ManuallyDrop<windows_core::BSTR>,
+    pub points: [Point; 4],
+    pub next: *mut Shape,
+    pub type_: *mut core::ffi::c_void,
+    pub color: Color,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Value {
+    _alignment: u64,
+    pub i: i32,
+    pub d: f64,
+    pub p: Point,
+}
+
+pub type PPOINT = *mut Point;
+
+unsafe extern "system" {
+    pub fn Add(
+        a: i32,
+        b: i32,
+    ) -> i32;
+}
+
+pub const IID_IShape: windows_core::GUID = windows_core::GUID::from_u128(0x5a1e0c3a_1b2c_4d3e_8f40_000000000001);
+
+#[repr(transparent)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IShape(windows_core::IUnknown);
+
+unsafe impl windows_core::Interface for IShape {
+    type Vtable = IShape_Vtbl;
+    const IID: windows_core::GUID = IID_IShape;
+}
+
+impl core::ops::Deref for IShape {
+    type Target = windows_core::IUnknown;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl IShape {
+    pub unsafe fn Area(
+        &self,
+    ) -> windows_core::Result<f64> {
+        let mut result__: f64 = core::mem::zeroed();
+        (windows_core::Interface::vtable(self).Area)(
+            windows_core::Interface::as_raw(self),
+            core::ptr::addr_of_mut!(result__),
+        ).ok().map(|()| result__)
+    }
+
+    pub unsafe fn get_Name(
+        &self,
+    ) -> windows_core::Result<windows_core::BSTR> {
+        let mut result__: windows_core::BSTR = core::mem::zeroed();
+        (windows_core::Interface::vtable(self).get_Name)(
+            windows_core::Interface::as_raw(self),
+            core::ptr::addr_of_mut!(result__),
+        ).ok().map(|()| result__)
+    }
+
+    pub unsafe fn put_Name(
+        &self,
+        value: &windows_core::BSTR,
+    ) -> windows_core::Result<()> {
+        (windows_core::Interface::vtable(self).put_Name)(
+            windows_core::Interface::as_raw(self),
+            core::mem::transmute_copy(value),
+        ).ok()
+    }
+
+    pub unsafe fn Move(
+        &self,
+        by: *const Point,
+        impl_: *mut Option<windows_core::IUnknown>,
+    ) -> windows_core::Result<()> {
+        (windows_core::Interface::vtable(self).Move)(
+            windows_core::Interface::as_raw(self),
+            by,
+            impl_.cast(),
+        ).ok()
+    }
+}
+
+#[repr(C)]
+pub struct IShape_Vtbl {
+    pub base__: windows_core::IUnknown_Vtbl,
+    pub Area: unsafe extern "system" fn(
+        this: *mut core::ffi::c_void,
+        area: *mut f64,
+    ) -> windows_core::HRESULT,
+    pub get_Name: unsafe extern "system" fn(
+        this: *mut core::ffi::c_void,
+        name: *mut windows_core::BSTR,
+    ) -> windows_core::HRESULT,
+    pub put_Name: unsafe extern "system" fn(
+        this: *mut core::ffi::c_void,
+        value: core::mem::ManuallyDrop<windows_core::BSTR>,
+    ) -> windows_core::HRESULT,
+    pub Move: unsafe extern "system" fn(
+        this: *mut core::ffi::c_void,
+        by: *const Point,
+        impl_: *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+}
+
+pub const IID_IShapeDual: windows_core::GUID = windows_core::GUID::from_u128(0x5a1e0c3a_1b2c_4d3e_8f40_000000000002);
+
+#[repr(transparent)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IShapeDual(windows::Win32::System::Com::IDispatch);
+
+unsafe impl windows_core::Interface for IShapeDual {
+    type Vtable = IShapeDual_Vtbl;
+    const IID: windows_core::GUID = IID_IShapeDual;
+}
+
+impl core::ops::Deref for IShapeDual {
+    type Target = windows::Win32::System::Com::IDispatch;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl IShapeDual {
+    pub unsafe fn get_Color(
+        &self,
+    ) -> windows_core::Result<Color> {
+        let mut result__: Color = core::mem::zeroed();
+        (windows_core::Interface::vtable(self).get_Color)(
+            windows_core::Interface::as_raw(self),
+            core::ptr::addr_of_mut!(result__),
+        ).ok().map(|()| result__)
+    }
+
+    pub unsafe fn Scale(
+        &self,
+        factor: f32,
+        values: *mut windows::Win32::System::Com::SAFEARRAY /* of windows::Win32::System::Variant::VARIANT */,
+    ) -> windows_core::Result<()> {
+        (windows_core::Interface::vtable(self).Scale)(
+            windows_core::Interface::as_raw(self),
+            factor,
+            values,
+        ).ok()
+    }
+}
+
+#[repr(C)]
+pub struct IShapeDual_Vtbl {
+    pub base__: windows::Win32::System::Com::IDispatch_Vtbl,
+    pub get_Color: unsafe extern "system" fn(
+        this: *mut core::ffi::c_void,
+        color: *mut Color,
+    ) -> windows_core::HRESULT,
+    pub Scale: unsafe extern "system" fn(
+        this: *mut core::ffi::c_void,
+        factor: f32,
+        values: *mut windows::Win32::System::Com::SAFEARRAY /* of windows::Win32::System::Variant::VARIANT */,
+    ) -> windows_core::HRESULT,
+}
+
+pub const IID_DShapeEvents: windows_core::GUID = windows_core::GUID::from_u128(0x5a1e0c3a_1b2c_4d3e_8f40_000000000003);
+
+#[repr(transparent)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DShapeEvents(windows::Win32::System::Com::IDispatch);
+
+unsafe impl windows_core::Interface for DShapeEvents {
+    type Vtable = DShapeEvents_Vtbl;
+    const IID: windows_core::GUID = IID_DShapeEvents;
+}
+
+impl core::ops::Deref for DShapeEvents {
+    type Target = windows::Win32::System::Com::IDispatch;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[repr(C)]
+pub struct DShapeEvents_Vtbl {
+    pub base__: windows::Win32::System::Com::IDispatch_Vtbl,
+}
+
+// Implements IShapeDual
+// Implements IShape
+// Implements DShapeEvents
+pub const CLSID_ShapeObject: windows_core::GUID = windows_core::GUID::from_u128(0x5a1e0c3a_1b2c_4d3e_8f40_000000000004);
+
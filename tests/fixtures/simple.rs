@@ -0,0 +1,223 @@
+ENUM!{enum Color {
+    Red = 0,
+    Green = 1,
+    Blue = 0xfffffffe,
+}}
+
+STRUCT!{struct Point {
+    x: i32,
+    y: i32,
+}}
+
+STRUCT!{struct Shape {
+    name: BSTR,
+    points: [Point; 4],
+    next: *mut Shape,
+    type_: *mut c_void,
+    color: Color,
+}}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Value {
+    _alignment: u64,
+    pub i: i32,
+    pub d: f64,
+    pub p: Point,
+}
+
+pub type PPOINT = *mut Point;
+
+extern "system" {
+    pub fn Add(
+        a: i32,
+        b: i32,
+    ) -> i32;
+}
+
+RIDL!{#[uuid(0x5a1e0c3a, 0x1b2c, 0x4d3e, 0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01)]
+interface IShape(IShapeVtbl): IUnknown(IUnknownVtbl) {
+    fn Area(
+        area: *mut f64,
+    ) -> HRESULT,
+    fn get_Name(
+        name: *mut BSTR,
+    ) -> HRESULT,
+    fn put_Name(
+        value: BSTR,
+    ) -> HRESULT,
+    fn Move(
+        by: *const Point,
+        impl_: *mut LPUNKNOWN,
+    ) -> HRESULT,
+}}
+
+RIDL!{#[uuid(0x5a1e0c3a, 0x1b2c, 0x4d3e, 0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02)]
+interface IShapeDual(IShapeDualVtbl): IDispatch(IDispatchVtbl) {
+    fn get_Color(
+        color: *mut Color,
+    ) -> HRESULT,
+    fn Scale(
+        factor: f32,
+        values: *mut SAFEARRAY /* of VARIANT */,
+    ) -> HRESULT,
+}}
+
+RIDL!{#[uuid(0x5a1e0c3a, 0x1b2c, 0x4d3e, 0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03)]
+interface DShapeEvents(DShapeEventsVtbl): IDispatch(IDispatchVtbl) {
+}}
+
+impl DShapeEvents {
+    pub unsafe fn Moved(
+        &self,
+        x: i32,
+        color: Color,
+    ) -> (HRESULT, VARIANT, EXCEPINFO, UINT) {
+        let mut args: [VARIANT; 2] = [
+            { let mut v: VARIANT = ::core::mem::uninitialized(); VariantInit(&mut v); *v.vt_mut() = 3; *v.lVal_mut() = color; v },
+            { let mut v: VARIANT = ::core::mem::uninitialized(); VariantInit(&mut v); *v.vt_mut() = 3; *v.lVal_mut() = x; v },
+        ];
+
+        let mut result: VARIANT = ::core::mem::uninitialized();
+        VariantInit(&mut result);
+
+        let mut exception_info: EXCEPINFO = ::core::mem::zeroed();
+
+        let mut error_arg: UINT = 0;
+
+        let mut disp_params = DISPPARAMS {
+            rgvarg: args.as_mut_ptr(),
+            rgdispidNamedArgs: ::core::ptr::null_mut(),
+            cArgs: 2,
+            cNamedArgs: 0,
+        };
+
+        let hr = ((*self.lpVtbl).parent.Invoke)(
+            self as *const _ as *mut _,
+            /* dispIdMember */ 1,
+            /* riid */ &IID_NULL,
+            /* lcid */ 0,
+            /* wFlags */ DISPATCH_METHOD,
+            /* pDispParams */ &mut disp_params,
+            /* pVarResult */ &mut result,
+            /* pExcepInfo */ &mut exception_info,
+            /* puArgErr */ &mut error_arg,
+        );
+
+        (hr, result, exception_info, error_arg)
+    }
+
+    pub unsafe fn Renamed(
+        &self,
+        name: BSTR,
+        cancel: *mut VARIANT_BOOL,
+    ) -> (HRESULT, VARIANT, EXCEPINFO, UINT) {
+        let mut args: [VARIANT; 2] = [
+            { let mut v: VARIANT = ::core::mem::uninitialized(); VariantInit(&mut v); *v.vt_mut() = 16395; *v.pboolVal_mut() = cancel; v },
+            { let mut v: VARIANT = ::core::mem::uninitialized(); VariantInit(&mut v); *v.vt_mut() = 8; *v.bstrVal_mut() = name; v },
+        ];
+
+        let mut result: VARIANT = ::core::mem::uninitialized();
+        VariantInit(&mut result);
+
+        let mut exception_info: EXCEPINFO = ::core::mem::zeroed();
+
+        let mut error_arg: UINT = 0;
+
+        let mut disp_params = DISPPARAMS {
+            rgvarg: args.as_mut_ptr(),
+            rgdispidNamedArgs: ::core::ptr::null_mut(),
+            cArgs: 2,
+            cNamedArgs: 0,
+        };
+
+        let hr = ((*self.lpVtbl).parent.Invoke)(
+            self as *const _ as *mut _,
+            /* dispIdMember */ 2,
+            /* riid */ &IID_NULL,
+            /* lcid */ 0,
+            /* wFlags */ DISPATCH_METHOD,
+            /* pDispParams */ &mut disp_params,
+            /* pVarResult */ &mut result,
+            /* pExcepInfo */ &mut exception_info,
+            /* puArgErr */ &mut error_arg,
+        );
+
+        (hr, result, exception_info, error_arg)
+    }
+
+    pub unsafe fn get_Visible(
+    ) -> (HRESULT, VARIANT, EXCEPINFO, UINT) {
+        let mut result: VARIANT = ::core::mem::uninitialized();
+        VariantInit(&mut result);
+
+        let mut exception_info: EXCEPINFO = ::core::mem::zeroed();
+
+        let mut error_arg: UINT = 0;
+
+        let mut disp_params = DISPPARAMS {
+            rgvarg: ::core::ptr::null_mut(),
+            rgdispidNamedArgs: ::core::ptr::null_mut(),
+            cArgs: 0,
+            cNamedArgs: 0,
+        };
+
+        let hr = ((*self.lpVtbl).parent.Invoke)(
+            self as *const _ as *mut _,
+            /* dispIdMember */ 3,
+            /* riid */ &IID_NULL,
+            /* lcid */ 0,
+            /* wFlags */ DISPATCH_PROPERTYGET,
+            /* pDispParams */ &mut disp_params,
+            /* pVarResult */ &mut result,
+            /* pExcepInfo */ &mut exception_info,
+            /* puArgErr */ &mut error_arg,
+        );
+
+        (hr, result, exception_info, error_arg)
+    }
+
+    pub unsafe fn put_Visible(
+        value: VARIANT_BOOL,
+    ) -> (HRESULT, VARIANT, EXCEPINFO, UINT) {
+        let mut args: [VARIANT; 1] = [
+            { let mut v: VARIANT = ::core::mem::uninitialized(); VariantInit(&mut v); *v.vt_mut() = 11; *v.boolVal_mut() = value; v },
+        ];
+
+        let mut result: VARIANT = ::core::mem::uninitialized();
+        VariantInit(&mut result);
+
+        let mut exception_info: EXCEPINFO = ::core::mem::zeroed();
+
+        let mut error_arg: UINT = 0;
+
+        let mut disp_params = DISPPARAMS {
+            rgvarg: args.as_mut_ptr(),
+            rgdispidNamedArgs: ::core::ptr::null_mut(),
+            cArgs: 1,
+            cNamedArgs: 0,
+        };
+
+        let hr = ((*self.lpVtbl).parent.Invoke)(
+            self as *const _ as *mut _,
+            /* dispIdMember */ 3,
+            /* riid */ &IID_NULL,
+            /* lcid */ 0,
+            /* wFlags */ DISPATCH_PROPERTYPUT,
+            /* pDispParams */ &mut disp_params,
+            /* pVarResult */ &mut result,
+            /* pExcepInfo */ &mut exception_info,
+            /* puArgErr */ &mut error_arg,
+        );
+
+        (hr, result, exception_info, error_arg)
+    }
+
+}
+
+// Implements IShapeDual
+// Implements IShape
+// Implements DShapeEvents
+RIDL!{#[uuid(0x5a1e0c3a, 0x1b2c, 0x4d3e, 0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04)]
+class ShapeObject; }
+
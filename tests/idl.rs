@@ -0,0 +1,304 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for compiling IDL files.
+//!
+//! `simple.idl` describes the same library as `simple.tlb`.
+//!
+//! `wbemcli` compiles the reduced copy of the SDK's `WbemCli.Idl` in test-wmi. `wbemcli_sdk` compiles the SDK's own, if it can be found,
+//! and checks that the reduced copy's interfaces match it.
+
+mod common;
+
+use common::{find, fixture, TempDir};
+use winapi_tlb_bindgen::types;
+
+/// Compiles the given IDL files, the first of which is the one that defines the library
+fn compile(test_name: &str, files: &[(&str, &str)]) -> Result<types::TypeLib, winapi_tlb_bindgen::Error> {
+	let dir = TempDir::new("idl", test_name);
+	for (file_name, contents) in files {
+		dir.write(file_name, contents);
+	}
+
+	winapi_tlb_bindgen::load_idl(&dir.0.join(files[0].0), types::SysKind::Win64)
+}
+
+#[test]
+fn matches_msft() {
+	let idl = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win32).unwrap();
+	let msft = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	assert_eq!(idl.name, msft.name);
+	assert_eq!(idl.guid, msft.guid);
+	assert_eq!(idl.flags, msft.flags);
+	assert_eq!(idl.imports, msft.imports);
+	assert_eq!(idl.doc_string.as_deref(), Some("A library with one of everything"));
+
+	// simple.tlb numbers functions differently, and doesn't record the entry point of its module function
+	let signature = |function: &types::Function| (
+		function.name.clone(),
+		function.func_kind,
+		function.invoke_kind,
+		function.ovft,
+		function.return_type.clone(),
+		function.params.clone(),
+	);
+
+	assert_eq!(idl.type_infos.len(), msft.type_infos.len());
+	for (idl, msft) in idl.type_infos.iter().zip(&msft.type_infos) {
+		assert_eq!(idl.name, msft.name);
+		assert_eq!(idl.typekind, msft.typekind, "{}", idl.name);
+		assert_eq!(idl.guid, msft.guid, "{}", idl.name);
+		assert_eq!(idl.vars, msft.vars, "{}", idl.name);
+		assert_eq!(idl.impl_types, msft.impl_types, "{}", idl.name);
+		assert_eq!(idl.alias_type, msft.alias_type, "{}", idl.name);
+		assert_eq!(idl.size_vft, msft.size_vft, "{}", idl.name);
+		assert_eq!((idl.size_instance, idl.alignment), (msft.size_instance, msft.alignment), "{}", idl.name);
+		assert_eq!(idl.functions.iter().map(signature).collect::<Vec<_>>(), msft.functions.iter().map(signature).collect::<Vec<_>>(), "{}", idl.name);
+	}
+}
+
+#[test]
+fn build() {
+	// `.idl` files are compiled for 64-bit Windows
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build(&fixture("simple.idl"), true, &mut out).unwrap();
+	assert!(build_result.missing_types.is_empty());
+
+	let out = String::from_utf8(out).unwrap();
+	assert!(out.contains("interface IShape(IShapeVtbl): IUnknown(IUnknownVtbl) {"), "{}", out);
+}
+
+#[test]
+fn attributes() {
+	let type_lib = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win64).unwrap();
+
+	let helpers = find(&type_lib, "Helpers");
+	assert_eq!(helpers.dll_name.as_deref(), Some("helpers.dll"));
+	assert_eq!(helpers.functions[0].entry_point, Some(types::EntryPoint::Name("Add".to_string())));
+
+	// Functions without an `id` are numbered by their level in the inheritance hierarchy and their index,
+	// and the accessors of a property share an ID
+	let ishape = find(&type_lib, "IShape");
+	let member_ids: Vec<_> = ishape.functions.iter().map(|function| function.member_id).collect();
+	assert_eq!(member_ids, [0x6001_0000, 0x6001_0001, 0x6001_0001, 0x6001_0003]);
+
+	let ishape_dual = find(&type_lib, "IShapeDual");
+	assert!(ishape_dual.is_dual());
+	assert_eq!(ishape_dual.flags & types::TYPEFLAG_FOLEAUTOMATION, types::TYPEFLAG_FOLEAUTOMATION);
+
+	let shape_object = find(&type_lib, "ShapeObject");
+	assert_eq!(shape_object.flags, types::TYPEFLAG_FCANCREATE);
+}
+
+#[test]
+fn layout() {
+	let win32 = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win32).unwrap();
+	let win64 = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win64).unwrap();
+
+	let offsets = |type_info: &types::TypeInfo| -> Vec<u32> {
+		type_info.vars.iter().map(|var| match var.var_kind {
+			types::VarKind::PerInstance(offset) => offset,
+			_ => unreachable!(),
+		}).collect()
+	};
+
+	let shape = find(&win32, "Shape");
+	assert_eq!((shape.size_instance, shape.alignment), (48, 4));
+	assert_eq!(offsets(shape), [0, 4, 36, 40, 44]);
+
+	let shape = find(&win64, "Shape");
+	assert_eq!((shape.size_instance, shape.alignment), (64, 8));
+	assert_eq!(offsets(shape), [0, 8, 40, 48, 56]);
+
+	let value = find(&win32, "Value");
+	assert_eq!((value.size_instance, value.alignment), (8, 8));
+
+	assert_eq!(find(&win32, "IShapeDual").size_vft, 9 * 4);
+	assert_eq!(find(&win64, "IShapeDual").size_vft, 9 * 8);
+}
+
+#[test]
+fn constants() {
+	let type_lib = compile("constants", &[("constants.idl", r#"
+		#define BASE 0x10
+		const long OFFSET = 2;
+
+		[uuid(00000000-0000-0000-0000-000000000001)]
+		library Constants {
+			enum Flags {
+				A = BASE,
+				B,
+				C = (A | B) << OFFSET,
+				D = ~0,
+				E = 0x80041001,
+			};
+
+			[dllname("constants.dll")]
+			module Values {
+				const double PI = 3.5;
+				const LPWSTR NAME = L"constants";
+			};
+
+			[uuid(00000000-0000-0000-0000-000000000002), oleautomation]
+			interface IDefaults : IDispatch {
+				HRESULT Method(
+					[in, defaultvalue(-1)] long a,
+					[in, defaultvalue(VARIANT_TRUE)] VARIANT_BOOL b,
+					[in, defaultvalue("x" "y")] BSTR c,
+					[in, defaultvalue(C)] enum Flags d,
+					[in, optional] VARIANT e);
+			};
+		};
+	"#)]).unwrap();
+
+	let flags = find(&type_lib, "Flags");
+	let values: Vec<_> = flags.vars.iter().map(|var| var.var_kind.clone()).collect();
+	assert_eq!(values, [
+		types::VarKind::Const(types::Variant::I4(0x10)),
+		types::VarKind::Const(types::Variant::I4(0x11)),
+		types::VarKind::Const(types::Variant::I4(0x44)),
+		types::VarKind::Const(types::Variant::I4(-1)),
+		types::VarKind::Const(types::Variant::I4(-0x7FFB_EFFF)),
+	]);
+
+	let module = find(&type_lib, "Values");
+	assert_eq!(module.vars[0].var_kind, types::VarKind::Const(types::Variant::R8(3.5)));
+	assert_eq!(module.vars[1].var_kind, types::VarKind::Const(types::Variant::Bstr("constants".to_string())));
+
+	let interface = find(&type_lib, "IDefaults");
+	assert_eq!(interface.flags, types::TYPEFLAG_FOLEAUTOMATION | types::TYPEFLAG_FDISPATCHABLE);
+	let params = &interface.functions[0].params;
+	let default_values: Vec<_> = params.iter().map(|param| param.default_value.clone()).collect();
+	assert_eq!(default_values, [
+		Some(types::Variant::I4(-1)),
+		Some(types::Variant::Bool(true)),
+		Some(types::Variant::Bstr("xy".to_string())),
+		Some(types::Variant::I4(0x44)),
+		None,
+	]);
+	assert_eq!(params[0].flags, types::PARAMFLAG_FIN | types::PARAMFLAG_FOPT | types::PARAMFLAG_FHASDEFAULT);
+	assert_eq!(params[4].flags, types::PARAMFLAG_FIN | types::PARAMFLAG_FOPT);
+}
+
+#[test]
+fn imports() {
+	// Types that are only defined in an imported file are added to the typelib when they're referenced
+	let type_lib = compile("imports", &[
+		("main.idl", r#"
+			import "types.idl";
+			import "missing.idl";
+
+			[uuid(00000000-0000-0000-0000-000000000001)]
+			library Imports {
+				[uuid(00000000-0000-0000-0000-000000000002)]
+				interface IUsesImported : IUnknown {
+					HRESULT Method([in] Imported *value, [in] REFIID riid);
+				};
+			};
+		"#),
+		("types.idl", r"
+			typedef struct tagImported {
+				GUID id;
+				DWORD flags;
+			} Imported;
+
+			typedef struct Unused {
+				long unused;
+			} Unused;
+		"),
+	]).unwrap();
+
+	let names: Vec<_> = type_lib.type_infos.iter().map(|type_info| &*type_info.name).collect();
+	assert_eq!(names, ["IUsesImported", "Imported", "tagImported"]);
+
+	let imported = find(&type_lib, "tagImported");
+	assert_eq!((imported.size_instance, imported.alignment), (20, 4));
+	assert_eq!(find(&type_lib, "Imported").alias_type, Some(types::TypeDesc::UserDefined(types::TypeRef::Local(2))));
+
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build_from_type_lib(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::default(), winapi_tlb_bindgen::Backend::Winapi, true, false, &mut out).unwrap();
+	assert!(build_result.missing_types.is_empty());
+}
+
+/// Checks the parts of `WbemCli.Idl` that test-wmi uses
+fn check_wbemcli(path: &std::path::Path) -> types::TypeLib {
+	let type_lib = winapi_tlb_bindgen::load_idl(path, types::SysKind::Win64).unwrap();
+	assert_eq!(type_lib.name, "WbemClient_v1");
+
+	// The methods that test-wmi calls are at the same vtable offsets as in the SDK
+	for &(interface, method, index) in &[
+		("IWbemLocator", "ConnectServer", 3),
+		("IWbemServices", "ExecQuery", 20),
+		("IEnumWbemClassObject", "Next", 4),
+		("IWbemClassObject", "Get", 4),
+	] {
+		let function = find(&type_lib, interface).functions.iter().find(|function| function.name == method).unwrap();
+		assert_eq!(function.ovft, index * 8, "{interface}::{method}");
+	}
+	assert_eq!(find(&type_lib, "IWbemServices").size_vft, (3 + 23) * 8);
+
+	assert_eq!(find(&type_lib, "WbemLocator").guid.to_string(), "4590F811-1D3A-11D0-891F-00AA004B2E24");
+	assert_eq!(find(&type_lib, "tag_WBEM_TIMEOUT_TYPE").vars[1].var_kind, types::VarKind::Const(types::Variant::I4(-1)));
+
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build(path, false, &mut out).unwrap();
+	assert!(build_result.missing_types.is_empty());
+	assert!(build_result.failed_types.is_empty());
+
+	type_lib
+}
+
+#[test]
+fn wbemcli() {
+	// The reduced copy of the SDK's WbemCli.Idl that test-wmi builds its bindings from
+	check_wbemcli(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test-wmi").join("WbemCli.idl"));
+}
+
+/// Finds the SDK's `WbemCli.Idl`, either at the path in the `WINAPI_TLB_BINDGEN_WBEMCLI_IDL` environment variable,
+/// or in the newest version of the Windows 10 SDK
+fn sdk_wbemcli() -> Option<std::path::PathBuf> {
+	if let Some(path) = std::env::var_os("WINAPI_TLB_BINDGEN_WBEMCLI_IDL") {
+		return Some(path.into());
+	}
+
+	let include_dir = std::path::Path::new(&std::env::var_os("ProgramFiles(x86)")?).join("Windows Kits").join("10").join("Include");
+	let mut versions: Vec<_> = std::fs::read_dir(include_dir).ok()?.filter_map(|entry| Some(entry.ok()?.path())).collect();
+	versions.sort();
+	versions.into_iter().rev().map(|version| version.join("um").join("WbemCli.Idl")).find(|path| path.is_file())
+}
+
+#[test]
+fn wbemcli_sdk() {
+	let Some(path) = sdk_wbemcli() else {
+		eprintln!("the SDK's WbemCli.Idl was not found; set WINAPI_TLB_BINDGEN_WBEMCLI_IDL to its path to run this test");
+		return;
+	};
+
+	let type_lib = check_wbemcli(&path);
+
+	// The reduced copy has the same interfaces as the SDK's
+	let reduced = winapi_tlb_bindgen::load_idl(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test-wmi").join("WbemCli.idl"), types::SysKind::Win64).unwrap();
+	for reduced in reduced.type_infos.iter().filter(|type_info| type_info.typekind == types::TypeKind::Interface) {
+		let type_info = find(&type_lib, &reduced.name);
+		assert_eq!(type_info.guid, reduced.guid, "{}", reduced.name);
+		assert_eq!(type_info.size_vft, reduced.size_vft, "{}", reduced.name);
+		let functions = |type_info: &types::TypeInfo| type_info.functions.iter().map(|function| (function.name.clone(), function.ovft)).collect::<Vec<_>>();
+		assert_eq!(functions(type_info), functions(reduced), "{}", reduced.name);
+	}
+}
+
+#[test]
+fn errors() {
+	let error = compile("errors", &[("errors.idl", "[uuid(00000000-0000-0000-0000-000000000001)]\nlibrary Errors {\n\tstruct S {\n\t\tUnknownType field;\n\t};\n};\n")]).unwrap_err();
+	match error {
+		winapi_tlb_bindgen::Error::Idl { path, line, column, message } => {
+			assert_eq!(path.file_name().unwrap(), "errors.idl");
+			assert_eq!((line, column, &*message), (4, 3, "unknown type UnknownType"));
+		},
+		error => panic!("expected Error::Idl, got {:?}", error),
+	}
+
+	let error = compile("errors-no-library", &[("errors.idl", "typedef long L;\n")]).unwrap_err();
+	assert!(error.to_string().ends_with("errors.idl:1:1: no library is defined"), "{}", error);
+}
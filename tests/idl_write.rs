@@ -0,0 +1,125 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for decompiling typelibs to IDL.
+//!
+//! The IDL is compiled back and compared with the typelib it was written from.
+
+mod common;
+
+use common::{fixture, TempDir};
+use winapi_tlb_bindgen::types;
+
+fn write(type_lib: &types::TypeLib) -> String {
+	let mut out = vec![];
+	winapi_tlb_bindgen::write_idl(type_lib, &mut out).unwrap();
+	String::from_utf8(out).unwrap()
+}
+
+/// Writes the given IDL to a file and compiles it
+fn compile(test_name: &str, idl: &str, syskind: types::SysKind) -> types::TypeLib {
+	let dir = TempDir::new("idl-write", test_name);
+	let path = dir.write("written.idl", idl);
+	winapi_tlb_bindgen::load_idl(&path, syskind).unwrap()
+}
+
+#[test]
+fn round_trip_idl() {
+	for &syskind in &[types::SysKind::Win32, types::SysKind::Win64] {
+		let type_lib = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), syskind).unwrap();
+		let written = write(&type_lib);
+		assert_eq!(compile(&format!("idl-{syskind:?}"), &written, syskind), type_lib);
+	}
+}
+
+#[test]
+fn round_trip_msft() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+	let compiled = compile("msft", &write(&type_lib), type_lib.syskind);
+
+	// The IDL records the IDs of all functions, so even the functions of the module, which simple.tlb numbers differently from midl, match.
+	// The flags don't, because midl always adds `oleautomation` to dual interfaces and simple.tlb's doesn't have it.
+	assert_eq!(compiled.type_infos.len(), type_lib.type_infos.len());
+	for (compiled, original) in compiled.type_infos.iter().zip(&type_lib.type_infos) {
+		assert_eq!(compiled.name, original.name);
+		assert_eq!(compiled.typekind, original.typekind, "{}", original.name);
+		assert_eq!(compiled.guid, original.guid, "{}", original.name);
+		assert_eq!(compiled.version, original.version, "{}", original.name);
+		assert_eq!(compiled.vars, original.vars, "{}", original.name);
+		assert_eq!(compiled.functions, original.functions, "{}", original.name);
+		assert_eq!(compiled.impl_types, original.impl_types, "{}", original.name);
+		assert_eq!(compiled.alias_type, original.alias_type, "{}", original.name);
+	}
+}
+
+#[test]
+fn syntax() {
+	let type_lib = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win64).unwrap();
+	let written = write(&type_lib);
+
+	for expected in &[
+		"library Simple {",
+		r#"    importlib("stdole2.tlb");"#,
+		"        odl,\n        uuid(5A1E0C3A-1B2C-4D3E-8F40-000000000002),\n        dual,\n        oleautomation\n    ]\n    interface IShapeDual : IDispatch {",
+		"        [id(0x60010001), propget] HRESULT Name([out, retval] BSTR* name);",
+		"        [id(0x00000002)] HRESULT Scale([in] float factor, [in] SAFEARRAY(VARIANT) values);",
+		"    typedef enum Color {\n        Red = 0,\n        Green = 1,\n        Blue = -2,\n    } Color;",
+		"        struct Point points[4];\n        struct Shape* next;",
+		"    typedef union Value {",
+		"    typedef [public] struct Point* PPOINT;",
+		r#"        [entry("Add"), id(0x60000000)] long Add([in] long a, [in] long b);"#,
+		"        properties:\n            [id(0x00000003)] VARIANT_BOOL Visible;\n        methods:",
+		"        [default] interface IShapeDual;\n        interface IShape;\n        [default, source] dispinterface DShapeEvents;",
+	] {
+		assert!(written.contains(expected), "{:?} not found in:\n{}", expected, written);
+	}
+}
+
+#[test]
+fn param_attributes() {
+	let mut type_lib = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win64).unwrap();
+	let interface = type_lib.type_infos.iter_mut().find(|type_info| type_info.name == "IShape").unwrap();
+
+	let mut params = vec![];
+	for (name, vt, default_value) in &[
+		("count", types::VT_I4, types::Variant::I4(-5)),
+		("ratio", types::VT_R8, types::Variant::R8(2.0)),
+		("label", types::VT_BSTR, types::Variant::Bstr("say \"hi\"\\\n".to_string())),
+		("enabled", types::VT_BOOL, types::Variant::Bool(true)),
+		("price", types::VT_CY, types::Variant::Cy(12345)),
+	] {
+		params.push(types::Param {
+			name: (*name).to_string(),
+			type_: types::TypeDesc::Base(*vt),
+			flags: types::PARAMFLAG_FIN | types::PARAMFLAG_FOPT | types::PARAMFLAG_FHASDEFAULT,
+			default_value: Some(default_value.clone()),
+		});
+	}
+	params.push(types::Param {
+		name: "extra".to_string(),
+		type_: types::TypeDesc::Base(types::VT_VARIANT),
+		flags: types::PARAMFLAG_FIN | types::PARAMFLAG_FOPT,
+		default_value: None,
+	});
+	params.push(types::Param {
+		name: "locale".to_string(),
+		type_: types::TypeDesc::Base(types::VT_I4),
+		flags: types::PARAMFLAG_FIN | types::PARAMFLAG_FLCID,
+		default_value: None,
+	});
+	interface.functions[0].params = params;
+
+	let written = write(&type_lib);
+	assert!(written.contains(concat!(
+		"HRESULT Area(",
+		"[in, defaultvalue(-5)] long count, ",
+		"[in, defaultvalue(2.0)] double ratio, ",
+		r#"[in, defaultvalue("say \"hi\"\\\n")] BSTR label, "#,
+		"[in, defaultvalue(-1)] VARIANT_BOOL enabled, ",
+		"[in, defaultvalue(1.2345)] CURRENCY price, ",
+		"[in, optional] VARIANT extra, ",
+		"[in, lcid] long locale);",
+	)), "{}", written);
+
+	assert_eq!(compile("param-attributes", &written, types::SysKind::Win64), type_lib);
+}
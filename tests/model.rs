@@ -0,0 +1,142 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for the typelib model returned by `load_type_lib` and consumed by `build_from_type_lib`.
+
+mod common;
+
+use common::{find, fixture};
+use winapi_tlb_bindgen::types;
+
+#[test]
+fn attributes() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	assert_eq!(type_lib.name, "Simple");
+	assert_eq!(type_lib.guid.to_string(), "5A1E0C3A-1B2C-4D3E-8F40-112233445566");
+	assert_eq!(type_lib.version, types::Version { major: 1, minor: 0 });
+	assert_eq!(type_lib.flags, types::LIBFLAG_FHASDISKIMAGE);
+	assert_eq!(type_lib.syskind, types::SysKind::Win32);
+
+	assert_eq!(type_lib.imports.len(), 1);
+	assert_eq!(type_lib.imports[0].guid, types::LIBID_STDOLE);
+	assert_eq!(type_lib.imports[0].version, types::Version { major: 2, minor: 0 });
+	assert_eq!(type_lib.imports[0].file_name, "stdole2.tlb");
+
+	let color = find(&type_lib, "Color");
+	assert_eq!(color.typekind, types::TypeKind::Enum);
+	assert_eq!(color.vars[2].name, "Blue");
+	assert_eq!(color.vars[2].var_kind, types::VarKind::Const(types::Variant::I4(-2)));
+
+	let point = find(&type_lib, "Point");
+	assert_eq!(point.vars[1].var_kind, types::VarKind::PerInstance(4));
+
+	let ishape = find(&type_lib, "IShape");
+	assert_eq!(ishape.impl_types.len(), 1);
+	assert_eq!(type_lib.get_type_name(&ishape.impl_types[0].type_ref), Some("IUnknown"));
+	assert_eq!(ishape.functions[0].call_conv, types::CallConv::StdCall);
+	assert_eq!(ishape.functions[0].params[0].flags, types::PARAMFLAG_FOUT | types::PARAMFLAG_FRETVAL);
+
+	let events = find(&type_lib, "DShapeEvents");
+	assert_eq!(events.vars[0].var_kind, types::VarKind::Dispatch);
+
+	let coclass = find(&type_lib, "ShapeObject");
+	let impl_flags: Vec<_> = coclass.impl_types.iter().map(|impl_type| impl_type.flags).collect();
+	assert_eq!(impl_flags, [
+		types::IMPLTYPEFLAG_FDEFAULT,
+		0,
+		types::IMPLTYPEFLAG_FDEFAULT | types::IMPLTYPEFLAG_FSOURCE,
+	]);
+}
+
+#[test]
+fn sltg_matches_msft() {
+	let msft = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+	let sltg = winapi_tlb_bindgen::load_type_lib(&fixture("simple-sltg.tlb")).unwrap();
+
+	assert_eq!(msft.name, sltg.name);
+	assert_eq!(msft.guid, sltg.guid);
+	assert_eq!(msft.version, sltg.version);
+	assert_eq!(msft.imports.iter().map(|import| import.guid).collect::<Vec<_>>(), sltg.imports.iter().map(|import| import.guid).collect::<Vec<_>>());
+
+	// SLTG typelibs refer to imported types by index rather than by GUID, so only compare the local parts
+	assert_eq!(msft.type_infos.len(), sltg.type_infos.len());
+	for (msft, sltg) in msft.type_infos.iter().zip(&sltg.type_infos) {
+		assert_eq!(msft.name, sltg.name);
+		assert_eq!(msft.typekind, sltg.typekind);
+		assert_eq!(msft.functions, sltg.functions, "{}", msft.name);
+		assert_eq!(msft.vars, sltg.vars, "{}", msft.name);
+		assert_eq!(msft.alias_type, sltg.alias_type, "{}", msft.name);
+	}
+}
+
+#[test]
+fn hand_built() {
+	let type_info = |name: &str, typekind, vars| types::TypeInfo {
+		name: name.to_string(),
+		doc_string: None,
+		help_context: 0,
+		guid: types::Guid::default(),
+		typekind,
+		flags: 0,
+		version: types::Version::default(),
+		size_instance: 4,
+		alignment: 4,
+		size_vft: 0,
+		functions: vec![],
+		vars,
+		impl_types: vec![],
+		alias_type: None,
+		dll_name: None,
+	};
+
+	let var = |name: &str, var_kind| types::Var {
+		name: name.to_string(),
+		doc_string: None,
+		help_context: 0,
+		member_id: 0,
+		var_kind,
+		flags: 0,
+		type_: types::TypeDesc::Base(types::VT_I4),
+	};
+
+	let type_lib = types::TypeLib {
+		name: "HandBuilt".to_string(),
+		doc_string: None,
+		help_file: None,
+		help_context: 0,
+		guid: types::Guid::default(),
+		lcid: 0,
+		version: types::Version { major: 1, minor: 0 },
+		flags: 0,
+		syskind: types::SysKind::Win64,
+		imports: vec![],
+		type_infos: vec![
+			type_info("Mode", types::TypeKind::Enum, vec![
+				var("Fast", types::VarKind::Const(types::Variant::I4(1))),
+				var("Slow", types::VarKind::Const(types::Variant::I4(2))),
+			]),
+			type_info("Options", types::TypeKind::Record, vec![
+				types::Var {
+					type_: types::TypeDesc::UserDefined(types::TypeRef::Local(0)),
+					..var("mode", types::VarKind::PerInstance(0))
+				},
+			]),
+		],
+	};
+
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build_from_type_lib(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::default(), winapi_tlb_bindgen::Backend::Winapi, true, false, &mut out).unwrap();
+	assert!(build_result.missing_types.is_empty());
+	assert_eq!(String::from_utf8(out).unwrap(), "\
+ENUM!{enum Mode {
+    Fast = 1,
+    Slow = 2,
+}}
+
+STRUCT!{struct Options {
+    mode: Mode,
+}}
+
+");
+}
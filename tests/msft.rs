@@ -0,0 +1,153 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Golden tests for bindings generated from the MSFT typelibs in `tests/fixtures`.
+//!
+//! Set the `UPDATE_GOLDEN` environment variable to regenerate the expected output after an intentional change.
+//!
+//! `stdole2` reads the OS's `stdole2.tlb`, if it can be found.
+
+mod common;
+
+use common::{check_golden, fixture, TempDir};
+
+#[test]
+fn simple() {
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build(&fixture("simple.tlb"), true, &mut out).unwrap();
+	check_golden(&out, "simple.rs");
+
+	assert!(build_result.missing_types.is_empty());
+	assert!(build_result.skipped_dispinterfaces.is_empty());
+	assert_eq!(build_result.skipped_dispinterface_of_dual_interfaces, ["IShapeDual"]);
+}
+
+#[test]
+fn simple_without_dispinterfaces() {
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build(&fixture("simple.tlb"), false, &mut out).unwrap();
+	check_golden(&out, "simple-no-dispinterfaces.rs");
+
+	assert_eq!(build_result.skipped_dispinterfaces, ["DShapeEvents"]);
+}
+
+#[test]
+fn simple_with_imports() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::build_from_type_lib(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::default(), winapi_tlb_bindgen::Backend::Winapi, false, true, &mut out).unwrap();
+	check_golden(&out, "simple-imports.rs");
+}
+
+#[test]
+fn simple_windows() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::build_from_type_lib(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::windows(), winapi_tlb_bindgen::Backend::Windows, true, false, &mut out).unwrap();
+	check_golden(&out, "simple-windows.rs");
+}
+
+#[test]
+fn simple_repr_c() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::build_from_type_lib(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::empty(), winapi_tlb_bindgen::Backend::ReprC, true, false, &mut out).unwrap();
+	check_golden(&out, "simple-repr-c.rs");
+}
+
+#[test]
+fn simple_com() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::build_from_type_lib(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::com(), winapi_tlb_bindgen::Backend::Com, true, false, &mut out).unwrap();
+	check_golden(&out, "simple-com.rs");
+}
+
+#[test]
+fn truncated() {
+	let data = std::fs::read(fixture("simple.tlb")).unwrap();
+
+	let dir = TempDir::new("msft", "truncated");
+	let path = dir.write("truncated.tlb", &data[..data.len() / 2]);
+
+	match winapi_tlb_bindgen::build(&path, true, std::io::sink()) {
+		Err(winapi_tlb_bindgen::Error::Malformed { .. }) => (),
+		result => panic!("expected Error::Malformed, got {:?}", result),
+	}
+}
+
+#[test]
+fn not_a_typelib() {
+	let result = winapi_tlb_bindgen::build(&fixture("simple.rs"), true, std::io::sink());
+	match result {
+		Err(winapi_tlb_bindgen::Error::UnsupportedFormat) => (),
+		result => panic!("expected Error::UnsupportedFormat, got {:?}", result),
+	}
+}
+
+/// The `stdole2.tlb` that Windows ships, which `midl.exe` generated, or the one that `WINAPI_TLB_BINDGEN_STDOLE2` points to.
+/// The fixtures above are written by this repository's own generators, so this is the only typelib that checks the reader against midl's output.
+fn system_stdole2() -> Option<std::path::PathBuf> {
+	if let Some(path) = std::env::var_os("WINAPI_TLB_BINDGEN_STDOLE2") {
+		return Some(path.into());
+	}
+
+	let path = std::path::Path::new(&std::env::var_os("SystemRoot")?).join("System32").join("stdole2.tlb");
+	if path.is_file() { Some(path) } else { None }
+}
+
+#[test]
+fn stdole2() {
+	let Some(path) = system_stdole2() else {
+		eprintln!("stdole2.tlb was not found; set WINAPI_TLB_BINDGEN_STDOLE2 to its path to run this test");
+		return;
+	};
+
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&path).unwrap();
+	assert_eq!(type_lib.guid, winapi_tlb_bindgen::types::LIBID_STDOLE);
+	assert_eq!(type_lib.name, "stdole");
+	assert_eq!(type_lib.version.major, 2);
+
+	// The types that references to stdole2.tlb are resolved to by index without loading it
+	let pointer_size = type_lib.syskind.pointer_size();
+	for &(index, name, guid, num_vtbl_entries) in &[
+		(0, "GUID", None, 0),
+		(3, "IUnknown", Some(winapi_tlb_bindgen::types::IID_IUNKNOWN), 3),
+		(4, "IDispatch", Some(winapi_tlb_bindgen::types::IID_IDISPATCH), 7),
+		(5, "IEnumVARIANT", Some(winapi_tlb_bindgen::types::IID_IENUMVARIANT), 7),
+	] {
+		let type_info = &type_lib.type_infos[index];
+		assert_eq!(type_info.name, name);
+		if let Some(guid) = guid {
+			assert_eq!(type_info.guid, guid, "{name}");
+		}
+		assert_eq!(type_info.size_vft, num_vtbl_entries * pointer_size, "{name}");
+	}
+
+	let idispatch = &type_lib.type_infos[4];
+	let functions: Vec<_> = idispatch.functions.iter().map(|function| (&*function.name, function.ovft)).collect();
+	assert_eq!(functions, [
+		("GetTypeInfoCount", 3 * pointer_size),
+		("GetTypeInfo", 4 * pointer_size),
+		("GetIDsOfNames", 5 * pointer_size),
+		("Invoke", 6 * pointer_size),
+	]);
+
+	// What the writer writes reads back the same
+	let mut written = vec![];
+	winapi_tlb_bindgen::write_type_lib(&type_lib, &mut written).unwrap();
+	assert_eq!(winapi_tlb_bindgen::load_type_lib_from_bytes(&written).unwrap(), type_lib);
+
+	for &backend in &[
+		winapi_tlb_bindgen::Backend::Winapi,
+		winapi_tlb_bindgen::Backend::Windows,
+		winapi_tlb_bindgen::Backend::ReprC,
+		winapi_tlb_bindgen::Backend::Com,
+	] {
+		winapi_tlb_bindgen::Builder::from_path(&path).backend(backend).continue_on_error(true).build(std::io::sink()).unwrap();
+	}
+}
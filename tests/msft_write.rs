@@ -0,0 +1,393 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for writing typelibs in the MSFT format.
+//!
+//! Each typelib is written, read back, and compared with the model it was written from.
+//! The typelib written from `simple.tlb` is also compared with `simple.tlb` structure by structure.
+
+mod common;
+
+use common::{fixture, TempDir};
+use winapi_tlb_bindgen::types;
+
+fn write(type_lib: &types::TypeLib) -> Vec<u8> {
+	let mut out = vec![];
+	winapi_tlb_bindgen::write_type_lib(type_lib, &mut out).unwrap();
+	out
+}
+
+/// Writes the given MSFT typelib to a file and reads it back
+fn read(test_name: &str, data: &[u8]) -> types::TypeLib {
+	let dir = TempDir::new("msft-write", test_name);
+	let path = dir.write("written.tlb", data);
+	winapi_tlb_bindgen::load_type_lib(&path).unwrap()
+}
+
+fn assert_round_trips(test_name: &str, type_lib: &types::TypeLib) {
+	let written = write(type_lib);
+	assert!(written.starts_with(b"MSFT"));
+
+	let read_back = read(test_name, &written);
+	assert_eq!(&read_back, type_lib);
+
+	// Writing is deterministic
+	assert_eq!(write(&read_back), written);
+}
+
+/// A field of an MSFT structure, with offsets into the name and GUID tables resolved
+#[derive(Debug, PartialEq)]
+enum Field {
+	Value(i32),
+	Name(Option<String>),
+	Guid(Option<[u8; 16]>),
+}
+
+/// The parts of an MSFT typelib that don't depend on how its tables are laid out,
+/// leaving out what `simple.tlb` doesn't fill in. See `tests/fixtures/README.md`.
+#[derive(Debug, PartialEq)]
+struct Structure {
+	header: Vec<Field>,
+	segment_lengths: Vec<usize>,
+	type_infos: Vec<Vec<Field>>,
+	members: Vec<Vec<Field>>,
+	imp_infos: Vec<Field>,
+	imp_files: Vec<Field>,
+	guids: Vec<[u8; 16]>,
+	names: Vec<String>,
+	type_descs: Vec<u8>,
+	array_descs: Vec<u8>,
+	ref_table: Vec<u8>,
+	strings: Vec<u8>,
+	custom_data: Vec<u8>,
+}
+
+const SEGMENT_TYPE_INFOS: usize = 0;
+const SEGMENT_IMP_INFOS: usize = 1;
+const SEGMENT_IMP_FILES: usize = 2;
+const SEGMENT_REF_TABLE: usize = 3;
+const SEGMENT_GUIDS: usize = 5;
+const SEGMENT_NAMES: usize = 7;
+const SEGMENT_STRINGS: usize = 8;
+const SEGMENT_TYPE_DESCS: usize = 9;
+const SEGMENT_ARRAY_DESCS: usize = 10;
+const SEGMENT_CUSTOM_DATA: usize = 11;
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+	u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+	u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn i32_at(data: &[u8], offset: usize) -> i32 {
+	i32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Decodes the structures of an MSFT typelib, following the layout documented by Wine's `typelib.h`
+struct StructureReader<'a> {
+	data: &'a [u8],
+	segments: Vec<&'a [u8]>,
+}
+
+impl<'a> StructureReader<'a> {
+	fn new(data: &'a [u8]) -> Self {
+		let num_type_infos = u32_at(data, 0x20) as usize;
+		let segment_directory_offset = 0x54 + num_type_infos * 4 + if (u32_at(data, 0x14) & 0x100) == 0 { 0 } else { 4 };
+		let segments = (0..15).map(|index| {
+			let offset = u32_at(data, segment_directory_offset + index * 16);
+			let length = u32_at(data, segment_directory_offset + index * 16 + 4) as usize;
+			if offset == 0xFFFF_FFFF { &data[..0] } else { &data[(offset as usize)..(offset as usize + length)] }
+		}).collect();
+		StructureReader { data, segments }
+	}
+
+	fn name(&self, offset: i32) -> Field {
+		Field::Name(std::convert::TryFrom::try_from(offset).ok().map(|offset: usize| {
+			let names = self.segments[SEGMENT_NAMES];
+			let len = usize::from(names[offset + 8]);
+			String::from_utf8(names[(offset + 12)..(offset + 12 + len)].to_vec()).unwrap()
+		}))
+	}
+
+	fn guid(&self, offset: i32) -> Field {
+		Field::Guid(std::convert::TryFrom::try_from(offset).ok().map(|offset: usize| {
+			let mut guid = [0; 16];
+			guid.copy_from_slice(&self.segments[SEGMENT_GUIDS][offset..(offset + 16)]);
+			guid
+		}))
+	}
+
+	fn structure(&self) -> Structure {
+		// Leave out the offset of the library's GUID, which depends on the order of the GUID table,
+		// and the number of characters in the name table, which simple.tlb doesn't count.
+		let header = (0..0x54).step_by(4).filter(|&offset| offset != 0x34).map(|offset| match offset {
+			0x08 => self.guid(i32_at(self.data, offset)),
+			0x38 => self.name(i32_at(self.data, offset)),
+			_ => Field::Value(i32_at(self.data, offset)),
+		}).collect();
+
+		let type_infos = self.segments[SEGMENT_TYPE_INFOS].chunks(0x64);
+
+		// simple.tlb doesn't fill in the low word of the flags of each import
+		let imp_infos = self.segments[SEGMENT_IMP_INFOS].chunks(12).flat_map(|imp_info| vec![
+			Field::Value(i32_at(imp_info, 0) & !0xFFFF),
+			Field::Value(i32_at(imp_info, 4)),
+			self.guid(i32_at(imp_info, 8)),
+		]).collect();
+
+		// The GUIDs and names in any order, without the hash chains. simple.tlb doesn't fill in the HREFTYPEs of the GUIDs that don't belong to a typeinfo,
+		// or the hashes and flags of the names.
+		let mut guids: Vec<_> = self.segments[SEGMENT_GUIDS].chunks(24).map(|entry| {
+			let mut guid = [0; 16];
+			guid.copy_from_slice(&entry[..16]);
+			guid
+		}).collect();
+		guids.sort_unstable();
+
+		let mut names = vec![];
+		let mut offset = 0;
+		while offset < self.segments[SEGMENT_NAMES].len() {
+			let len = usize::from(self.segments[SEGMENT_NAMES][offset + 8]);
+			names.push(String::from_utf8(self.segments[SEGMENT_NAMES][(offset + 12)..(offset + 12 + len)].to_vec()).unwrap());
+			offset += (12 + len + 3) & !3;
+		}
+		names.sort_unstable();
+
+		// simple.tlb doesn't fill in the VARTYPE that a pointer or SAFEARRAY is to in the second word of its typedesc,
+		// or the size of the elements of an array
+		let mut type_descs = self.segments[SEGMENT_TYPE_DESCS].to_vec();
+		for type_desc in type_descs.chunks_mut(8) {
+			type_desc[2..4].copy_from_slice(&[0; 2]);
+		}
+
+		let mut array_descs = self.segments[SEGMENT_ARRAY_DESCS].to_vec();
+		let mut offset = 0;
+		while offset < array_descs.len() {
+			let num_dims = usize::from(u16_at(&array_descs, offset + 4));
+			array_descs[(offset + 6)..(offset + 8)].copy_from_slice(&[0; 2]);
+			offset += 8 + num_dims * 8;
+		}
+
+		Structure {
+			header,
+			segment_lengths: self.segments.iter().map(|segment| segment.len()).collect(),
+			type_infos: type_infos.clone().map(|base| self.type_info(base)).collect(),
+			members: type_infos.map(|base| self.members(base)).collect(),
+			imp_infos,
+			imp_files: self.imp_files(),
+			guids,
+			names,
+			type_descs,
+			array_descs,
+			ref_table: self.segments[SEGMENT_REF_TABLE].to_vec(),
+			strings: self.segments[SEGMENT_STRINGS].to_vec(),
+			custom_data: self.segments[SEGMENT_CUSTOM_DATA].to_vec(),
+		}
+	}
+
+	/// Decodes the given `MSFT_TypeInfoBase`
+	fn type_info(&self, base: &[u8]) -> Vec<Field> {
+		// Leave out the offset of the member data, and the fields that simple.tlb doesn't fill in -
+		// the high bits of the typekind other than the alignment, res2, res3, res4 and datatype2.
+		(0..0x64).step_by(4).filter(|offset| ![0x04, 0x08, 0x0C, 0x10, 0x58].contains(offset)).map(|offset| match offset {
+			0x00 => Field::Value(i32_at(base, offset) & 0xF80F),
+			0x2C => self.guid(i32_at(base, offset)),
+			0x34 => self.name(i32_at(base, offset)),
+			_ => Field::Value(i32_at(base, offset)),
+		}).collect()
+	}
+
+	/// Decodes the records of the members of the typeinfo with the given `MSFT_TypeInfoBase`
+	fn members(&self, base: &[u8]) -> Vec<Field> {
+		let mut result = vec![];
+
+		let members_offset = u32_at(base, 0x04);
+		if members_offset == 0xFFFF_FFFF {
+			return result;
+		}
+
+		let num_functions = usize::from(u16_at(base, 0x18));
+		let num_vars = usize::from(u16_at(base, 0x1A));
+		let num_members = num_functions + num_vars;
+
+		let mut offset = members_offset as usize + 4;
+		for index in 0..num_members {
+			let record_len = usize::from(u16_at(self.data, offset));
+			let is_function = index < num_functions;
+			let num_params = if is_function { usize::from(u16_at(self.data, offset + 0x14)) } else { 0 };
+			let params_offset = record_len - num_params * 12;
+			for field_offset in (0..record_len).step_by(4) {
+				let value = i32_at(self.data, offset + field_offset);
+				result.push(match field_offset {
+					// simple.tlb doesn't number vars, or fill in the size of the FUNCDESC or VARDESC
+					0x00 if !is_function => Field::Value(value & 0xFFFF),
+					0x0C => Field::Value(value & 0xFFFF),
+					_ if field_offset >= params_offset && (field_offset - params_offset) % 12 == 4 => self.name(value),
+					_ => Field::Value(value),
+				});
+			}
+			offset += record_len;
+		}
+
+		// The IDs, names and offsets of the members
+		for index in 0..(num_members * 3) {
+			let value = i32_at(self.data, offset + index * 4);
+			result.push(if (num_members..(num_members * 2)).contains(&index) { self.name(value) } else { Field::Value(value) });
+		}
+
+		result
+	}
+
+	/// Decodes the `MSFT_ImpFile`s
+	fn imp_files(&self) -> Vec<Field> {
+		let imp_files = self.segments[SEGMENT_IMP_FILES];
+		let mut result = vec![];
+		let mut offset = 0;
+		while offset < imp_files.len() {
+			result.push(self.guid(i32_at(imp_files, offset)));
+			result.push(Field::Value(i32_at(imp_files, offset + 4)));
+			result.push(Field::Value(i32_at(imp_files, offset + 8)));
+			let name_len = usize::from(u16_at(imp_files, offset + 12) >> 2);
+			result.push(Field::Name(Some(String::from_utf8(imp_files[(offset + 14)..(offset + 14 + name_len)].to_vec()).unwrap())));
+			offset += (14 + name_len + 3) & !3;
+		}
+		result
+	}
+}
+
+#[test]
+fn matches_msft() {
+	let expected = std::fs::read(fixture("simple.tlb")).unwrap();
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+	let written = write(&type_lib);
+
+	assert_eq!(written.len(), expected.len());
+	assert_eq!(StructureReader::new(&written).structure(), StructureReader::new(&expected).structure());
+}
+
+#[test]
+fn round_trip_msft() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+	assert_round_trips("msft", &type_lib);
+}
+
+#[test]
+fn round_trip_sltg() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple-sltg.tlb")).unwrap();
+	assert_round_trips("sltg", &type_lib);
+}
+
+#[test]
+fn round_trip_idl() {
+	for &syskind in &[types::SysKind::Win32, types::SysKind::Win64] {
+		let type_lib = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), syskind).unwrap();
+		assert_round_trips(&format!("idl-{syskind:?}"), &type_lib);
+	}
+}
+
+#[test]
+fn build() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.json")).unwrap();
+	let written = read("build", &write(&type_lib));
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::build_from_type_lib(&written, &[], &winapi_tlb_bindgen::TypeMappings::default(), winapi_tlb_bindgen::Backend::Winapi, true, false, &mut out).unwrap();
+
+	let expected = std::fs::read_to_string(fixture("simple.rs")).unwrap();
+	assert_eq!(String::from_utf8(out).unwrap(), expected);
+}
+
+#[test]
+fn header() {
+	let type_lib = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win64).unwrap();
+	let written = write(&type_lib);
+
+	let u32_at = |offset: usize| u32::from_le_bytes([written[offset], written[offset + 1], written[offset + 2], written[offset + 3]]);
+
+	assert_eq!(u32_at(0x04), 0x0001_0002);
+	assert_eq!(u32_at(0x14) & 0xF, 3); // SYS_WIN64
+	assert_eq!(u32_at(0x18), 1); // version 1.0
+	assert_eq!(u32_at(0x20) as usize, type_lib.type_infos.len());
+	assert_eq!(u32_at(0x50), 2); // IUnknown and IDispatch from stdole2.tlb
+
+	// Every segment in the segment directory lies within the file
+	let segment_directory_offset = 0x54 + type_lib.type_infos.len() * 4;
+	for index in 0..15 {
+		let offset = u32_at(segment_directory_offset + index * 16);
+		let length = u32_at(segment_directory_offset + index * 16 + 4) as usize;
+		if offset == 0xFFFF_FFFF {
+			assert_eq!(length, 0);
+		}
+		else {
+			assert!(offset as usize + length <= written.len());
+		}
+	}
+}
+
+#[test]
+fn values() {
+	let mut type_lib = winapi_tlb_bindgen::load_idl(&fixture("simple.idl"), types::SysKind::Win32).unwrap();
+	let module = type_lib.type_infos.iter_mut().find(|type_info| type_info.typekind == types::TypeKind::Module).unwrap();
+
+	let values = vec![
+		types::Variant::Empty,
+		types::Variant::I1(-1),
+		types::Variant::I2(-2),
+		types::Variant::I4(-1),
+		types::Variant::I4(0x0400_0000),
+		types::Variant::I8(i64::MIN),
+		types::Variant::UI4(u32::MAX),
+		types::Variant::UI8(u64::MAX),
+		types::Variant::R4(1.5),
+		types::Variant::R8(-0.25),
+		types::Variant::Cy(12345),
+		types::Variant::Bool(true),
+		types::Variant::Bool(false),
+		types::Variant::Error(-0x7FFB_EFFF),
+		types::Variant::Bstr(String::new()),
+		types::Variant::Bstr("caf\u{e9}".to_string()),
+	];
+	module.vars = (0..).zip(values).map(|(i, value)| types::Var {
+		name: format!("VALUE_{i}"),
+		doc_string: None,
+		help_context: 0,
+		member_id: 0x4000_0000 + i,
+		var_kind: types::VarKind::Const(value),
+		flags: 0,
+		type_: types::TypeDesc::Base(types::VT_VARIANT),
+	}).collect();
+
+	assert_round_trips("values", &type_lib);
+}
+
+#[test]
+fn unwritable() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let check = |type_lib: &types::TypeLib, expected_name: &str| {
+		match winapi_tlb_bindgen::write_type_lib(type_lib, std::io::sink()) {
+			Err(winapi_tlb_bindgen::Error::Unwritable { name, .. }) => assert_eq!(name, expected_name),
+			result => panic!("expected Error::Unwritable, got {:?}", result),
+		}
+	};
+
+	let mut long_name = type_lib.clone();
+	long_name.type_infos[0].name = "x".repeat(256);
+	check(&long_name, &long_name.type_infos[0].name);
+
+	let mut not_ansi = type_lib.clone();
+	not_ansi.doc_string = Some("\u{2603}".to_string());
+	check(&not_ansi, "Simple");
+
+	let mut not_imported = type_lib.clone();
+	not_imported.imports.clear();
+	check(&not_imported, "IShape");
+
+	let mut other_value = type_lib;
+	let enum_ = other_value.type_infos.iter_mut().find(|type_info| type_info.typekind == types::TypeKind::Enum).unwrap();
+	enum_.vars[0].var_kind = types::VarKind::Const(types::Variant::Other(types::VT_DECIMAL));
+	let expected_name = format!("{}::{}", enum_.name, enum_.vars[0].name);
+	check(&other_value, &expected_name);
+}
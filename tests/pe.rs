@@ -0,0 +1,61 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for typelibs embedded as `TYPELIB` resources in PE images.
+//!
+//! `simple.dll` is a PE32+ image with `simple.tlb` as resources 1 and 3. `simple32.dll` is a PE32 image with `simple.tlb` as resource 1.
+
+mod common;
+
+use common::fixture;
+
+fn build(path: &std::path::Path) -> Result<String, winapi_tlb_bindgen::Error> {
+	let mut out = vec![];
+	winapi_tlb_bindgen::build(path, true, &mut out)?;
+	Ok(String::from_utf8(out).unwrap())
+}
+
+fn expected() -> String {
+	std::fs::read_to_string(fixture("simple.rs")).unwrap()
+}
+
+#[test]
+fn pe32_plus() {
+	assert_eq!(build(&fixture("simple.dll")).unwrap(), expected());
+}
+
+#[test]
+fn pe32() {
+	assert_eq!(build(&fixture("simple32.dll")).unwrap(), expected());
+}
+
+#[test]
+fn resource_index() {
+	let mut path = fixture("simple.dll").into_os_string();
+	path.push("\\3");
+	assert_eq!(build(std::path::Path::new(&path)).unwrap(), expected());
+
+	// The file that's read is the image, without the resource index
+	let build_result = winapi_tlb_bindgen::Builder::from_path(path).build(std::io::sink()).unwrap();
+	assert_eq!(build_result.files, [fixture("simple.dll")]);
+}
+
+#[test]
+fn missing_resource_index() {
+	let mut path = fixture("simple.dll").into_os_string();
+	path.push("\\2");
+	match build(std::path::Path::new(&path)) {
+		Err(winapi_tlb_bindgen::Error::TypeLibResourceNotFound(2)) => (),
+		result => panic!("expected Error::TypeLibResourceNotFound(2), got {:?}", result),
+	}
+}
+
+#[test]
+fn resource_index_on_tlb() {
+	let mut path = fixture("simple.tlb").into_os_string();
+	path.push("\\1");
+	match build(std::path::Path::new(&path)) {
+		Err(winapi_tlb_bindgen::Error::UnsupportedFormat) => (),
+		result => panic!("expected Error::UnsupportedFormat, got {:?}", result),
+	}
+}
@@ -0,0 +1,65 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for the plain `#[repr(C)]` bindings and their prelude.
+//!
+//! The golden bindings are compiled as part of this test, so they're type-checked on any host, and their layouts are checked against the typelib.
+//! Set the `UPDATE_GOLDEN` environment variable to regenerate them after an intentional change.
+
+mod common;
+
+use common::{check_golden, find, fixture, load_simple};
+
+#[allow(dead_code, non_camel_case_types, non_snake_case, non_upper_case_globals, clippy::all, clippy::pedantic)]
+mod bindings {
+	include!("fixtures/simple-repr-c-prelude.rs");
+}
+
+#[test]
+fn golden() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::build_from_type_lib(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::empty(), winapi_tlb_bindgen::Backend::ReprC, false, true, &mut out).unwrap();
+	check_golden(&out, "simple-repr-c-prelude.rs");
+}
+
+#[test]
+fn layout() {
+	let type_lib = load_simple();
+	if usize::from(type_lib.syskind.pointer_size()) != std::mem::size_of::<usize>() {
+		// The typelib is for a platform with a different pointer size than the host's
+		return;
+	}
+
+	let type_info = |name: &str| find(&type_lib, name);
+
+	for &(name, size, alignment) in &[
+		("Point", std::mem::size_of::<bindings::Point>(), std::mem::align_of::<bindings::Point>()),
+		("Shape", std::mem::size_of::<bindings::Shape>(), std::mem::align_of::<bindings::Shape>()),
+		("Value", std::mem::size_of::<bindings::Value>(), std::mem::align_of::<bindings::Value>()),
+	] {
+		let type_info = type_info(name);
+		assert_eq!(size, type_info.size_instance as usize, "size of {name}");
+		assert_eq!(alignment, usize::from(type_info.alignment), "alignment of {name}");
+	}
+
+	for &(name, size) in &[
+		("IShape", std::mem::size_of::<bindings::IShapeVtbl>()),
+		("IShapeDual", std::mem::size_of::<bindings::IShapeDualVtbl>()),
+	] {
+		assert_eq!(size, usize::from(type_info(name).size_vft), "size of the vtable of {name}");
+	}
+
+	// The sizes of the prelude's types in the Windows SDK
+	let pointer_size = std::mem::size_of::<usize>();
+	assert_eq!(std::mem::size_of::<bindings::GUID>(), 16);
+	assert_eq!(std::mem::size_of::<bindings::CY>(), 8);
+	assert_eq!(std::mem::size_of::<bindings::DECIMAL>(), 16);
+	assert_eq!(std::mem::size_of::<bindings::VARIANT>(), 8 + 2 * pointer_size);
+	assert_eq!(std::mem::align_of::<bindings::VARIANT>(), 8);
+	assert_eq!(std::mem::size_of::<bindings::SAFEARRAY>(), 16 + 2 * pointer_size);
+	assert_eq!(std::mem::size_of::<bindings::CAH>(), 2 * pointer_size);
+	assert_eq!(std::mem::size_of::<bindings::IUnknownVtbl>(), 3 * pointer_size);
+	assert_eq!(std::mem::size_of::<bindings::IDispatchVtbl>(), 7 * pointer_size);
+}
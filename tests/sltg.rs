@@ -0,0 +1,35 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for typelibs in the SLTG format.
+//!
+//! `simple-sltg.tlb` has the same contents as `simple.tlb`, so it must produce the same bindings.
+
+mod common;
+
+use common::{fixture, TempDir};
+
+#[test]
+fn simple() {
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build(&fixture("simple-sltg.tlb"), true, &mut out).unwrap();
+
+	let expected = std::fs::read_to_string(fixture("simple.rs")).unwrap();
+	assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+	assert!(build_result.missing_types.is_empty());
+	assert_eq!(build_result.skipped_dispinterface_of_dual_interfaces, ["IShapeDual"]);
+}
+
+#[test]
+fn truncated() {
+	let data = std::fs::read(fixture("simple-sltg.tlb")).unwrap();
+
+	let dir = TempDir::new("sltg", "truncated");
+	let path = dir.write("truncated.tlb", &data[..data.len() / 2]);
+
+	match winapi_tlb_bindgen::build(&path, true, std::io::sink()) {
+		Err(winapi_tlb_bindgen::Error::Malformed { .. }) => (),
+		result => panic!("expected Error::Malformed, got {:?}", result),
+	}
+}
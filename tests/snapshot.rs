@@ -0,0 +1,58 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for JSON snapshots of typelibs.
+//!
+//! `simple.json` is the snapshot of `simple.tlb`. Set the `UPDATE_GOLDEN` environment variable to regenerate it after an intentional change.
+
+mod common;
+
+use common::{check_golden, fixture, TempDir};
+
+fn read_snapshot(test_name: &str, contents: &str) -> Result<winapi_tlb_bindgen::types::TypeLib, winapi_tlb_bindgen::Error> {
+	let dir = TempDir::new("snapshot", test_name);
+	let path = dir.write("snapshot.json", contents);
+	winapi_tlb_bindgen::load_type_lib(&path)
+}
+
+#[test]
+fn write() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::write_snapshot(&type_lib, &mut out).unwrap();
+	check_golden(&out, "simple.json");
+}
+
+#[test]
+fn round_trip() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+	let snapshot = winapi_tlb_bindgen::load_type_lib(&fixture("simple.json")).unwrap();
+	assert_eq!(snapshot, type_lib);
+}
+
+#[test]
+fn build() {
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build(&fixture("simple.json"), true, &mut out).unwrap();
+
+	let expected = std::fs::read_to_string(fixture("simple.rs")).unwrap();
+	assert_eq!(String::from_utf8(out).unwrap(), expected);
+	assert_eq!(build_result.skipped_dispinterface_of_dual_interfaces, ["IShapeDual"]);
+}
+
+#[test]
+fn unsupported_version() {
+	match read_snapshot("unsupported_version", r#"{ "format_version": 999, "type_lib": null }"#) {
+		Err(winapi_tlb_bindgen::Error::UnsupportedSnapshotVersion(999)) => (),
+		result => panic!("expected Error::UnsupportedSnapshotVersion, got {:?}", result),
+	}
+}
+
+#[test]
+fn invalid() {
+	match read_snapshot("invalid", r#"{ "format_version": 1 }"#) {
+		Err(winapi_tlb_bindgen::Error::Snapshot(_)) => (),
+		result => panic!("expected Error::Snapshot, got {:?}", result),
+	}
+}
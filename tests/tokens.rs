@@ -0,0 +1,59 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for emitting bindings as a `TokenStream`.
+
+mod common;
+
+use common::fixture;
+
+#[test]
+fn all_backends() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	for &(backend, type_mappings) in &[
+		(winapi_tlb_bindgen::Backend::Winapi, winapi_tlb_bindgen::TypeMappings::default as fn() -> _),
+		(winapi_tlb_bindgen::Backend::Windows, winapi_tlb_bindgen::TypeMappings::windows),
+		(winapi_tlb_bindgen::Backend::ReprC, winapi_tlb_bindgen::TypeMappings::empty),
+		(winapi_tlb_bindgen::Backend::Com, winapi_tlb_bindgen::TypeMappings::com),
+	] {
+		for &emit_imports in &[false, true] {
+			let (tokens, _) = winapi_tlb_bindgen::build_tokens(&type_lib, &[], &type_mappings(), backend, true, emit_imports).unwrap();
+
+			// The tokens round-trip
+			let mut out = vec![];
+			winapi_tlb_bindgen::write_tokens(&tokens, false, &mut out).unwrap();
+			let reparsed: proc_macro2::TokenStream = std::str::from_utf8(&out).unwrap().parse().unwrap();
+			assert_eq!(reparsed.to_string(), tokens.to_string(), "{backend:?}, emit_imports: {emit_imports}");
+
+			// Pretty-printing drops trailing commas, so it only round-trips once the tokens have been pretty-printed
+			let mut pretty = vec![];
+			winapi_tlb_bindgen::write_tokens(&tokens, true, &mut pretty).unwrap();
+			let reparsed: proc_macro2::TokenStream = std::str::from_utf8(&pretty).unwrap().parse().unwrap();
+			let mut pretty_again = vec![];
+			winapi_tlb_bindgen::write_tokens(&reparsed, true, &mut pretty_again).unwrap();
+			assert!(pretty == pretty_again, "{:?}, emit_imports: {}", backend, emit_imports);
+		}
+	}
+}
+
+#[test]
+fn pretty() {
+	let type_lib = winapi_tlb_bindgen::load_type_lib(&fixture("simple.tlb")).unwrap();
+
+	let (tokens, _) =
+		winapi_tlb_bindgen::build_tokens(&type_lib, &[], &winapi_tlb_bindgen::TypeMappings::empty(), winapi_tlb_bindgen::Backend::ReprC, false, false).unwrap();
+	let mut out = vec![];
+	winapi_tlb_bindgen::write_tokens(&tokens, true, &mut out).unwrap();
+	let out = String::from_utf8(out).unwrap();
+	assert!(out.contains("pub struct Point {\n    pub x: i32,\n    pub y: i32,\n}\n"), "{}", out);
+}
+
+#[test]
+fn invalid() {
+	let tokens: proc_macro2::TokenStream = "fn".parse().unwrap();
+	match winapi_tlb_bindgen::write_tokens(&tokens, true, std::io::sink()) {
+		Err(winapi_tlb_bindgen::Error::Syntax(_)) => (),
+		result => panic!("expected Error::Syntax, got {:?}", result),
+	}
+}
@@ -0,0 +1,185 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for mapping types in imported typelibs to Rust paths.
+//!
+//! `Main` imports `Dep`. Both are compiled from IDL, and `Dep` is written as a `.tlb` so that `Main` can `importlib` it.
+
+mod common;
+
+use common::TempDir;
+use winapi_tlb_bindgen::{types, TypeLibSelector, TypeMappings};
+
+const DEP_LIBID: &str = "6B2F1D4B-2C3D-4E4F-9051-000000000021";
+
+const DEP_IDL: &str = r#"
+[uuid(6B2F1D4B-2C3D-4E4F-9051-000000000021), version(1.0)]
+library Dep {
+	importlib("stdole2.tlb");
+
+	typedef struct DepPoint {
+		long x;
+		long y;
+	} DepPoint;
+
+	[uuid(6B2F1D4B-2C3D-4E4F-9051-000000000022), object]
+	interface IDep : IUnknown {
+		HRESULT Foo();
+	};
+};
+"#;
+
+const MAIN_IDL: &str = r#"
+[uuid(6B2F1D4B-2C3D-4E4F-9051-000000000030), version(1.0)]
+library Main {
+	importlib("stdole2.tlb");
+	importlib("dep.tlb");
+
+	typedef struct MainRecord {
+		GUID id;
+		DepPoint point;
+	} MainRecord;
+
+	[uuid(6B2F1D4B-2C3D-4E4F-9051-000000000031), object]
+	interface IMain : IDep {
+		HRESULT Bar([in] DepPoint point, [in] IDep *dep);
+	};
+};
+"#;
+
+/// Compiles `Main` and `Dep` in a temporary directory
+fn compile(test_name: &str) -> (types::TypeLib, types::TypeLib) {
+	let dir = TempDir::new("type-mappings", test_name);
+
+	let dep = winapi_tlb_bindgen::load_idl(&dir.write("dep.idl", DEP_IDL), types::SysKind::Win64).unwrap();
+	winapi_tlb_bindgen::write_type_lib(&dep, std::fs::File::create(dir.0.join("dep.tlb")).unwrap()).unwrap();
+
+	let main = winapi_tlb_bindgen::load_idl(&dir.write("main.idl", MAIN_IDL), types::SysKind::Win64).unwrap();
+	(main, dep)
+}
+
+fn build(type_lib: &types::TypeLib, dependencies: &[types::TypeLib], type_mappings: &TypeMappings, emit_imports: bool) -> String {
+	let mut out = vec![];
+	let build_result = winapi_tlb_bindgen::build_from_type_lib(type_lib, dependencies, type_mappings, winapi_tlb_bindgen::Backend::Winapi, false, emit_imports, &mut out).unwrap();
+	assert!(build_result.missing_types.is_empty(), "{:?}", build_result.missing_types);
+	String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn defaults() {
+	let (main, dep) = compile("defaults");
+
+	let out = build(&main, std::slice::from_ref(&dep), &TypeMappings::default(), false);
+	assert!(out.contains("id: winapi::shared::guiddef::GUID,"), "{}", out);
+	assert!(out.contains("point: DepPoint,"), "{}", out);
+	assert!(out.contains("dep: *const IDep,"), "{}", out);
+
+	let out = build(&main, &[dep], &TypeMappings::empty(), false);
+	assert!(out.contains("id: GUID,"), "{}", out);
+}
+
+#[test]
+fn types() {
+	let (main, dep) = compile("types");
+
+	let mut type_mappings = TypeMappings::default();
+	type_mappings
+		.add(TypeLibSelector::Guid(DEP_LIBID.parse().unwrap()), "DepPoint", "dep_crate::Point")
+		.add(TypeLibSelector::Name("stdole".to_string()), "GUID", "my_guid::GUID");
+	let out = build(&main, &[dep], &type_mappings, false);
+	assert!(out.contains("point: dep_crate::Point,"), "{}", out);
+	assert!(out.contains("dep: *const IDep,"), "{}", out);
+	assert!(out.contains("id: my_guid::GUID,"), "{}", out);
+
+	// Parents are still emitted as bare names
+	assert!(out.contains("interface IMain(IMainVtbl): IDep(IDepVtbl) {"), "{}", out);
+}
+
+#[test]
+fn type_libs() {
+	let (main, dep) = compile("type-libs");
+
+	// Names are matched case-insensitively, and later mappings take precedence over earlier ones
+	let mut type_mappings = TypeMappings::default();
+	type_mappings
+		.add(TypeLibSelector::Name("Dep".to_string()), "IDep", "dep_crate::IDep")
+		.add_type_lib(TypeLibSelector::Name("DEP".to_string()), "dep_bindings");
+	let out = build(&main, std::slice::from_ref(&dep), &type_mappings, false);
+	assert!(out.contains("point: dep_bindings::DepPoint,"), "{}", out);
+	assert!(out.contains("dep: *const dep_bindings::IDep,"), "{}", out);
+
+	type_mappings.add(TypeLibSelector::Guid(DEP_LIBID.parse().unwrap()), "IDep", "dep_crate::IDep");
+	let out = build(&main, &[dep], &type_mappings, false);
+	assert!(out.contains("point: dep_bindings::DepPoint,"), "{}", out);
+	assert!(out.contains("dep: *const dep_crate::IDep,"), "{}", out);
+}
+
+#[test]
+fn imports() {
+	let (main, dep) = compile("imports");
+
+	let mut type_mappings = TypeMappings::default();
+	type_mappings
+		.add_type_lib(TypeLibSelector::Name("Dep".to_string()), "dep_bindings")
+		.add(TypeLibSelector::Name("Dep".to_string()), "IDep", "dep_crate::Dep");
+	let out = build(&main, &[dep], &type_mappings, true);
+
+	// Parents are imported by the paths they're mapped to
+	assert!(out.starts_with(concat!(
+		"use dep_crate::{Dep as IDep, DepVtbl as IDepVtbl};\n",
+		"use winapi::{RIDL, STRUCT};\n",
+		"use winapi::shared::ntdef::HRESULT;\n",
+		"\n",
+	)), "{}", out);
+	assert!(out.contains("interface IMain(IMainVtbl): IDep(IDepVtbl) {"), "{}", out);
+	assert!(out.contains("point: dep_bindings::DepPoint,"), "{}", out);
+}
+
+#[test]
+fn windows() {
+	let (main, dep) = compile("windows");
+
+	let mut type_mappings = TypeMappings::windows();
+	type_mappings.add_type_lib(TypeLibSelector::Name("Dep".to_string()), "dep_bindings");
+
+	let mut out = vec![];
+	let build_result =
+		winapi_tlb_bindgen::build_from_type_lib(&main, &[dep], &type_mappings, winapi_tlb_bindgen::Backend::Windows, false, false, &mut out).unwrap();
+	assert!(build_result.missing_types.is_empty(), "{:?}", build_result.missing_types);
+	let out = String::from_utf8(out).unwrap();
+
+	for expected in &[
+		"    pub id: windows_core::GUID,",
+		"    pub point: dep_bindings::DepPoint,",
+		"pub struct IMain(dep_bindings::IDep);",
+		"    pub base__: dep_bindings::IDep_Vtbl,",
+		"        dep: Option<&dep_bindings::IDep>,",
+		"            dep.map_or(core::ptr::null_mut(), windows_core::Interface::as_raw),",
+	] {
+		assert!(out.contains(expected), "{:?} not found in:\n{}", expected, out);
+	}
+}
+
+#[test]
+fn com() {
+	let (main, dep) = compile("com");
+
+	let mut type_mappings = TypeMappings::com();
+	type_mappings.add_type_lib(TypeLibSelector::Name("Dep".to_string()), "dep_bindings");
+
+	let mut out = vec![];
+	let build_result =
+		winapi_tlb_bindgen::build_from_type_lib(&main, &[dep], &type_mappings, winapi_tlb_bindgen::Backend::Com, false, false, &mut out).unwrap();
+	assert!(build_result.missing_types.is_empty(), "{:?}", build_result.missing_types);
+	let out = String::from_utf8(out).unwrap();
+
+	for expected in &[
+		"    pub id: com::sys::GUID,",
+		"    pub point: dep_bindings::DepPoint,",
+		"    pub unsafe interface IMain: dep_bindings::IDep {",
+		"            #[pass_through] point: dep_bindings::DepPoint,",
+		"            dep: Option<dep_bindings::IDep>,",
+	] {
+		assert!(out.contains(expected), "{:?} not found in:\n{}", expected, out);
+	}
+}
@@ -0,0 +1,315 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for the Rust types that each backend maps `VARTYPE`s to.
+//!
+//! Each `VARTYPE` is made the target of the `PPOINT` alias of the simple typelib, and the alias is checked in the bindings.
+
+mod common;
+
+use common::{find_mut, load_simple};
+use winapi_tlb_bindgen::{types, Backend};
+
+const BACKENDS: [Backend; 4] = [Backend::Winapi, Backend::Windows, Backend::ReprC, Backend::Com];
+
+fn build(type_lib: types::TypeLib, backend: Backend, emit_imports: bool) -> Result<(String, winapi_tlb_bindgen::BuildResult), winapi_tlb_bindgen::Error> {
+	let mut out = vec![];
+	let build_result =
+		winapi_tlb_bindgen::Builder::from_type_lib(type_lib)
+		.backend(backend)
+		.emit_imports(emit_imports)
+		.build(&mut out)?;
+	Ok((String::from_utf8(out).unwrap(), build_result))
+}
+
+/// Builds the simple typelib with `PPOINT` aliased to the given type
+fn build_alias(type_: types::TypeDesc, backend: Backend) -> Result<(String, winapi_tlb_bindgen::BuildResult), winapi_tlb_bindgen::Error> {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "PPOINT").alias_type = Some(type_);
+	build(type_lib, backend, false)
+}
+
+#[test]
+fn supported() {
+	// VARTYPE, and what it's mapped to by the winapi, windows, repr_c and com backends
+	for &(vt, expected) in &[
+		(types::VT_I2, ["i16", "i16", "i16", "i16"]),
+		(types::VT_I4, ["i32", "i32", "i32", "i32"]),
+		(types::VT_R4, ["f32", "f32", "f32", "f32"]),
+		(types::VT_R8, ["f64", "f64", "f64", "f64"]),
+		(types::VT_CY, ["CY", "windows::Win32::System::Com::CY", "CY", "CY"]),
+		(types::VT_DATE, ["DATE", "f64", "DATE", "DATE"]),
+		(types::VT_BSTR, ["BSTR", "windows_core::BSTR", "BSTR", "BSTR"]),
+		(types::VT_ERROR, ["SCODE", "i32", "i32", "i32"]),
+		(types::VT_BOOL, ["VARIANT_BOOL", "windows::Win32::Foundation::VARIANT_BOOL", "i16", "i16"]),
+		(types::VT_VARIANT, ["VARIANT", "windows::Win32::System::Variant::VARIANT", "VARIANT", "VARIANT"]),
+		(types::VT_DECIMAL, ["DECIMAL", "windows::Win32::Foundation::DECIMAL", "DECIMAL", "DECIMAL"]),
+		(types::VT_I1, ["i8", "i8", "i8", "i8"]),
+		(types::VT_UI1, ["u8", "u8", "u8", "u8"]),
+		(types::VT_UI2, ["u16", "u16", "u16", "u16"]),
+		(types::VT_UI4, ["u32", "u32", "u32", "u32"]),
+		(types::VT_I8, ["i64", "i64", "i64", "i64"]),
+		(types::VT_UI8, ["u64", "u64", "u64", "u64"]),
+		(types::VT_INT, ["INT", "i32", "i32", "i32"]),
+		(types::VT_UINT, ["UINT", "u32", "u32", "u32"]),
+		(types::VT_HRESULT, ["HRESULT", "windows_core::HRESULT", "HRESULT", "com::sys::HRESULT"]),
+		(types::VT_LPSTR, ["LPSTR", "windows_core::PSTR", "*mut u8", "*mut u8"]),
+		(types::VT_LPWSTR, ["LPCWSTR", "windows_core::PCWSTR", "*mut u16", "*mut u16"]),
+		(types::VT_INT_PTR, ["INT_PTR", "isize", "isize", "isize"]),
+		(types::VT_RECORD, ["*mut c_void", "*mut core::ffi::c_void", "*mut core::ffi::c_void", "*mut core::ffi::c_void"]),
+		(types::VT_UINT_PTR, ["UINT_PTR", "usize", "usize", "usize"]),
+		(types::VT_FILETIME, ["FILETIME", "windows::Win32::Foundation::FILETIME", "FILETIME", "FILETIME"]),
+		(types::VT_BLOB, ["BLOB", "windows::Win32::System::Com::BLOB", "BLOB", "BLOB"]),
+		(types::VT_BLOB_OBJECT, ["BLOB", "windows::Win32::System::Com::BLOB", "BLOB", "BLOB"]),
+		(types::VT_STREAM, ["LPSTREAM", "*mut core::ffi::c_void", "*mut IUnknown", "*mut core::ffi::c_void"]),
+		(types::VT_STORAGE, ["LPSTORAGE", "*mut core::ffi::c_void", "*mut IUnknown", "*mut core::ffi::c_void"]),
+		(types::VT_CLSID, ["CLSID", "windows_core::GUID", "GUID", "com::sys::GUID"]),
+		(types::VT_BSTR_BLOB, ["BSTRBLOB", "windows::Win32::System::Com::BSTRBLOB", "BSTRBLOB", "BSTRBLOB"]),
+		(types::VT_VECTOR | types::VT_I1, ["CAC", "windows::Win32::System::Com::StructuredStorage::CAC", "CAC", "CAC"]),
+		(types::VT_VECTOR | types::VT_UI1, ["CAUB", "windows::Win32::System::Com::StructuredStorage::CAUB", "CAUB", "CAUB"]),
+		(types::VT_VECTOR | types::VT_I2, ["CAI", "windows::Win32::System::Com::StructuredStorage::CAI", "CAI", "CAI"]),
+		(types::VT_VECTOR | types::VT_UI2, ["CAUI", "windows::Win32::System::Com::StructuredStorage::CAUI", "CAUI", "CAUI"]),
+		(types::VT_VECTOR | types::VT_I4, ["CAL", "windows::Win32::System::Com::StructuredStorage::CAL", "CAL", "CAL"]),
+		(types::VT_VECTOR | types::VT_UI4, ["CAUL", "windows::Win32::System::Com::StructuredStorage::CAUL", "CAUL", "CAUL"]),
+		(types::VT_VECTOR | types::VT_R4, ["CAFLT", "windows::Win32::System::Com::StructuredStorage::CAFLT", "CAFLT", "CAFLT"]),
+		(types::VT_VECTOR | types::VT_R8, ["CADBL", "windows::Win32::System::Com::StructuredStorage::CADBL", "CADBL", "CADBL"]),
+		(types::VT_VECTOR | types::VT_CY, ["CACY", "windows::Win32::System::Com::StructuredStorage::CACY", "CACY", "CACY"]),
+		(types::VT_VECTOR | types::VT_DATE, ["CADATE", "windows::Win32::System::Com::StructuredStorage::CADATE", "CADATE", "CADATE"]),
+		(types::VT_VECTOR | types::VT_BSTR, ["CABSTR", "windows::Win32::System::Com::StructuredStorage::CABSTR", "CABSTR", "CABSTR"]),
+		(types::VT_VECTOR | types::VT_BSTR_BLOB, ["CABSTRBLOB", "windows::Win32::System::Com::StructuredStorage::CABSTRBLOB", "CABSTRBLOB", "CABSTRBLOB"]),
+		(types::VT_VECTOR | types::VT_BOOL, ["CABOOL", "windows::Win32::System::Com::StructuredStorage::CABOOL", "CABOOL", "CABOOL"]),
+		(types::VT_VECTOR | types::VT_ERROR, ["CASCODE", "windows::Win32::System::Com::StructuredStorage::CASCODE", "CASCODE", "CASCODE"]),
+		(types::VT_VECTOR | types::VT_I8, ["CAH", "windows::Win32::System::Com::StructuredStorage::CAH", "CAH", "CAH"]),
+		(types::VT_VECTOR | types::VT_UI8, ["CAUH", "windows::Win32::System::Com::StructuredStorage::CAUH", "CAUH", "CAUH"]),
+		(types::VT_VECTOR | types::VT_LPSTR, ["CALPSTR", "windows::Win32::System::Com::StructuredStorage::CALPSTR", "CALPSTR", "CALPSTR"]),
+		(types::VT_VECTOR | types::VT_LPWSTR, ["CALPWSTR", "windows::Win32::System::Com::StructuredStorage::CALPWSTR", "CALPWSTR", "CALPWSTR"]),
+		(types::VT_VECTOR | types::VT_FILETIME, ["CAFILETIME", "windows::Win32::System::Com::StructuredStorage::CAFILETIME", "CAFILETIME", "CAFILETIME"]),
+		(types::VT_VECTOR | types::VT_CLSID, ["CACLSID", "windows::Win32::System::Com::StructuredStorage::CACLSID", "CACLSID", "CACLSID"]),
+		(types::VT_BYREF | types::VT_I4, ["*mut i32", "*mut i32", "*mut i32", "*mut i32"]),
+		(types::VT_BYREF | types::VT_VECTOR | types::VT_BSTR, [
+			"*mut CABSTR",
+			"*mut windows::Win32::System::Com::StructuredStorage::CABSTR",
+			"*mut CABSTR",
+			"*mut CABSTR",
+		]),
+		(types::VT_BYREF | types::VT_FILETIME, ["*mut FILETIME", "*mut windows::Win32::Foundation::FILETIME", "*mut FILETIME", "*mut FILETIME"]),
+		(types::VT_BYREF | types::VT_ARRAY | types::VT_I4, [
+			"*mut *mut SAFEARRAY /* of i32 */",
+			"*mut *mut windows::Win32::System::Com::SAFEARRAY /* of i32 */",
+			"*mut *mut SAFEARRAY /* of i32 */",
+			"*mut *mut SAFEARRAY /* of i32 */",
+		]),
+		(types::VT_ARRAY | types::VT_BSTR, [
+			"*mut SAFEARRAY /* of BSTR */",
+			"*mut windows::Win32::System::Com::SAFEARRAY /* of windows_core::BSTR */",
+			"*mut SAFEARRAY /* of BSTR */",
+			"*mut SAFEARRAY /* of BSTR */",
+		]),
+	] {
+		for (&backend, expected) in BACKENDS.iter().zip(&expected) {
+			let (out, _) = build_alias(types::TypeDesc::Base(vt), backend).unwrap();
+			let expected = format!("pub type PPOINT = {expected};");
+			assert!(out.contains(&expected), "{:?} {}: {:?} not found in:\n{}", backend, types::display_var_type(vt), expected, out);
+		}
+	}
+}
+
+#[test]
+fn unsupported() {
+	// VARTYPEs that the bindings have no type for, ones that aren't the type of a value, ones that a PROPVARIANT can't hold a vector of, and invalid combinations of flags
+	for &vt in &[
+		types::VT_EMPTY,
+		types::VT_NULL,
+		types::VT_BYREF | types::VT_NULL,
+		types::VT_SAFEARRAY,
+		types::VT_CF,
+		types::VT_VERSIONED_STREAM,
+		types::VT_VECTOR | types::VT_VARIANT,
+		types::VT_VECTOR | types::VT_CF,
+		types::VT_VECTOR | types::VT_DECIMAL,
+		types::VT_VECTOR | types::VT_UNKNOWN,
+		types::VT_BYREF | types::VT_CF,
+		types::VT_ARRAY | types::VT_VECTOR | types::VT_UI1,
+		types::VT_ILLEGAL,
+		0x7B,
+	] {
+		for &backend in &BACKENDS {
+			let Err(err) = build_alias(types::TypeDesc::Base(vt), backend) else {
+				panic!("{:?} emitted bindings for {}", backend, types::display_var_type(vt));
+			};
+			match &err {
+				winapi_tlb_bindgen::Error::UnsupportedVarType { location, vt: actual } => {
+					assert_eq!(location.type_name, "PPOINT", "{backend:?}");
+					assert_eq!(*actual & !(types::VT_BYREF | types::VT_ARRAY), vt & !(types::VT_BYREF | types::VT_ARRAY), "{backend:?}");
+				},
+				err => panic!("unexpected error {:?}", err),
+			}
+		}
+	}
+}
+
+#[test]
+fn var_type_names() {
+	for &(vt, name, displayed) in &[
+		(types::VT_I4, Some("VT_I4"), "VT_I4"),
+		(types::VT_BSTR_BLOB, Some("VT_BSTR_BLOB"), "VT_BSTR_BLOB"),
+		(types::VT_BYREF | types::VT_VARIANT, None, "VT_BYREF | VT_VARIANT"),
+		(types::VT_ARRAY | types::VT_VECTOR | types::VT_UI1, None, "VT_ARRAY | VT_VECTOR | VT_UI1"),
+		(types::VT_ILLEGAL, Some("VT_ILLEGAL"), "VT_ILLEGAL"),
+		(0x7B, None, "0x7b"),
+	] {
+		assert_eq!(types::var_type_name(vt), name);
+		assert_eq!(types::display_var_type(vt).to_string(), displayed);
+	}
+}
+
+#[test]
+fn lossy() {
+	// Type, and what it's reported to be emitted as by the winapi, windows, repr_c and com backends, if it's reported at all.
+	// Interface pointers that a backend always emits as `*mut c_void` aren't reported.
+	let cases = [
+		(types::TypeDesc::Base(types::VT_UNKNOWN), [None, None, None, None]),
+		// The repr_c prelude doesn't have `IStream` and `IStorage`
+		(types::TypeDesc::Base(types::VT_STREAM), [None, None, Some("*mut IUnknown"), None]),
+		(types::TypeDesc::Base(types::VT_STORAGE), [None, None, Some("*mut IUnknown"), None]),
+		// A VT_RECORD doesn't say which record it is
+		(types::TypeDesc::Base(types::VT_RECORD), [
+			Some("*mut c_void"),
+			Some("*mut core::ffi::c_void"),
+			Some("*mut core::ffi::c_void"),
+			Some("*mut core::ffi::c_void"),
+		]),
+		(types::TypeDesc::SafeArray(Box::new(types::TypeDesc::Base(types::VT_I4))), [
+			Some("*mut SAFEARRAY"),
+			Some("*mut windows::Win32::System::Com::SAFEARRAY"),
+			Some("*mut SAFEARRAY"),
+			Some("*mut SAFEARRAY"),
+		]),
+	];
+
+	for (type_, expected) in &cases {
+		for (&backend, expected) in BACKENDS.iter().zip(expected) {
+			let (_, build_result) = build_alias(type_.clone(), backend).unwrap();
+			let lossy: Vec<_> =
+				build_result.warnings.iter()
+				.filter(|warning| warning.location.type_name == "PPOINT")
+				.filter_map(|warning| match &warning.kind {
+					winapi_tlb_bindgen::WarningKind::LossyTypeMapping { type_, emitted_as } => Some((type_, &**emitted_as)),
+					_ => None,
+				})
+				.collect();
+			let expected: Vec<_> = expected.iter().map(|&emitted_as| (type_, emitted_as)).collect();
+			assert_eq!(lossy, expected, "{type_:?} {backend:?}");
+		}
+	}
+}
+
+#[test]
+fn interface_params() {
+	// `IShape::Move` gets a stream parameter
+	let mut type_lib = load_simple();
+	let move_ = find_mut(&mut type_lib, "IShape").functions.iter_mut().find(|function| function.name == "Move").unwrap();
+	move_.params[1].type_ = types::TypeDesc::Base(types::VT_STREAM);
+
+	let (out, _) = build(type_lib.clone(), Backend::Windows, false).unwrap();
+	assert!(out.contains("Option<&windows::Win32::System::Com::IStream>"), "{}", out);
+
+	let (out, build_result) = build(type_lib, Backend::Com, false).unwrap();
+	assert!(out.contains("impl_: Option<com::interfaces::IUnknown>,"), "{}", out);
+	assert!(build_result.warnings.iter().any(|warning| matches!(warning.kind, winapi_tlb_bindgen::WarningKind::LossyTypeMapping { .. })), "{:?}", build_result.warnings);
+}
+
+#[test]
+fn winapi_imports() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "PPOINT").alias_type = Some(types::TypeDesc::Base(types::VT_BYREF | types::VT_INT_PTR));
+
+	let (out, _) = build(type_lib.clone(), Backend::Winapi, true).unwrap();
+	assert!(out.contains("use winapi::shared::basetsd::INT_PTR;\n"), "{}", out);
+	assert!(out.contains("pub type PPOINT = *mut INT_PTR;"), "{}", out);
+
+	find_mut(&mut type_lib, "PPOINT").alias_type = Some(types::TypeDesc::Base(types::VT_VECTOR | types::VT_BSTR));
+	let (out, _) = build(type_lib, Backend::Winapi, true).unwrap();
+	assert!(out.contains("use winapi::um::propidl::CABSTR;\n"), "{}", out);
+}
+
+#[test]
+fn safe_arrays() {
+	// `IShape::Move` gets a `SAFEARRAY(IShape*)` parameter and a `SAFEARRAY(BSTR)*` parameter
+	let mut type_lib = load_simple();
+	let shape_object = find_mut(&mut type_lib, "ShapeObject");
+	let ishape = shape_object.impl_types.iter().find(|impl_type| impl_type.flags == 0).unwrap().type_ref.clone();
+	let move_ = find_mut(&mut type_lib, "IShape").functions.iter_mut().find(|function| function.name == "Move").unwrap();
+	move_.params[0].type_ = types::TypeDesc::SafeArray(Box::new(types::TypeDesc::Ptr(Box::new(types::TypeDesc::UserDefined(ishape)))));
+	move_.params[1].type_ = types::TypeDesc::Ptr(Box::new(types::TypeDesc::SafeArray(Box::new(types::TypeDesc::Base(types::VT_BSTR)))));
+
+	for (&backend, expected) in BACKENDS.iter().zip(&[
+		["by: *mut SAFEARRAY /* of *mut IShape */,", "impl_: *mut *mut SAFEARRAY /* of BSTR */,"],
+		["by: *mut windows::Win32::System::Com::SAFEARRAY /* of IShape */,", "impl_: *mut *mut windows::Win32::System::Com::SAFEARRAY /* of windows_core::BSTR */,"],
+		["by: *mut SAFEARRAY /* of *mut IShape */,", "impl_: *mut *mut SAFEARRAY /* of BSTR */,"],
+		["by: *mut SAFEARRAY /* of IShape */,", "impl_: *mut *mut SAFEARRAY /* of BSTR */,"],
+	]) {
+		let (out, build_result) = build(type_lib.clone(), backend, false).unwrap();
+		for expected in expected {
+			assert!(out.contains(expected), "{:?}: {:?} not found in:\n{}", backend, expected, out);
+		}
+		// The element type is only in a comment, so it's also reported
+		assert!(build_result.warnings.iter().any(|warning| matches!(&warning.kind, winapi_tlb_bindgen::WarningKind::LossyTypeMapping { type_: types::TypeDesc::SafeArray(_), .. })), "{:?}", build_result.warnings);
+	}
+}
+
+#[test]
+fn safe_array_dispinterface_params() {
+	// `DShapeEvents::Moved` gets `SAFEARRAY(BSTR)` and `SAFEARRAY(Color)` parameters, and `DShapeEvents::Renamed` gets a `SAFEARRAY(IShape*)` parameter.
+	// They're stored in the `VARIANT` as a `VT_ARRAY` of the `VARTYPE` of their elements.
+	let mut type_lib = load_simple();
+	let shape_object = find_mut(&mut type_lib, "ShapeObject");
+	let ishape = shape_object.impl_types.iter().find(|impl_type| impl_type.flags == 0).unwrap().type_ref.clone();
+	let events = find_mut(&mut type_lib, "DShapeEvents");
+	let color = events.functions[0].params[1].type_.clone();
+	events.functions[0].params[0].type_ = types::TypeDesc::SafeArray(Box::new(types::TypeDesc::Base(types::VT_BSTR)));
+	events.functions[0].params[1].type_ = types::TypeDesc::SafeArray(Box::new(color));
+	events.functions[1].params[0].type_ = types::TypeDesc::SafeArray(Box::new(types::TypeDesc::Ptr(Box::new(types::TypeDesc::UserDefined(ishape)))));
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::Builder::from_type_lib(type_lib)
+	.emit_dispinterfaces(true)
+	.build(&mut out)
+	.unwrap();
+	let out = String::from_utf8(out).unwrap();
+	assert!(out.contains("        x: *mut SAFEARRAY /* of BSTR */,\n"), "{}", out);
+	assert!(out.contains(&format!("*v.vt_mut() = {}; *v.parray_mut() = x;", types::VT_ARRAY | types::VT_BSTR)), "{}", out);
+	assert!(out.contains(&format!("*v.vt_mut() = {}; *v.parray_mut() = color;", types::VT_ARRAY | types::VT_I4)), "{}", out);
+	assert!(out.contains(&format!("*v.vt_mut() = {}; *v.parray_mut() = name;", types::VT_ARRAY | types::VT_UNKNOWN)), "{}", out);
+}
+
+#[test]
+fn dispinterface_retval_params() {
+	// An `[out, retval]` parameter isn't passed to `Invoke`, so it's not counted in the `DISPPARAMS` either
+	let mut type_lib = load_simple();
+	let events = find_mut(&mut type_lib, "DShapeEvents");
+	events.functions[1].params[1].flags |= types::PARAMFLAG_FRETVAL;
+	let mut retval_only = events.functions[1].clone();
+	retval_only.params.remove(0);
+	retval_only.name = "RetvalOnly".to_string();
+	retval_only.member_id = 4;
+	events.functions.push(retval_only);
+
+	let mut out = vec![];
+	winapi_tlb_bindgen::Builder::from_type_lib(type_lib)
+	.emit_dispinterfaces(true)
+	.build(&mut out)
+	.unwrap();
+	let out = String::from_utf8(out).unwrap();
+
+	let renamed = &out[out.find("pub unsafe fn Renamed(").unwrap()..];
+	let renamed = &renamed[..renamed.find("(hr, result, exception_info, error_arg)").unwrap()];
+	assert!(renamed.contains("let mut args: [VARIANT; 1] = ["), "{}", renamed);
+	assert!(renamed.contains("cArgs: 1,"), "{}", renamed);
+
+	let retval_only = &out[out.find("pub unsafe fn RetvalOnly(").unwrap()..];
+	let retval_only = &retval_only[..retval_only.find("(hr, result, exception_info, error_arg)").unwrap()];
+	assert!(!retval_only.contains("let mut args"), "{}", retval_only);
+	assert!(retval_only.contains("rgvarg: ::core::ptr::null_mut(),"), "{}", retval_only);
+	assert!(retval_only.contains("cArgs: 0,"), "{}", retval_only);
+}
@@ -0,0 +1,140 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for the warnings in `BuildResult`.
+
+mod common;
+
+use common::{find_mut, load_simple};
+use winapi_tlb_bindgen::{Location, WarningKind};
+
+fn build(type_lib: winapi_tlb_bindgen::types::TypeLib, backend: winapi_tlb_bindgen::Backend, emit_dispinterfaces: bool) -> winapi_tlb_bindgen::BuildResult {
+	winapi_tlb_bindgen::Builder::from_type_lib(type_lib)
+	.backend(backend)
+	.emit_dispinterfaces(emit_dispinterfaces)
+	.build(std::io::sink())
+	.unwrap()
+}
+
+fn location(type_name: &str, member_name: Option<&str>, param_index: Option<usize>) -> Location {
+	Location { type_name: type_name.to_string(), member_name: member_name.map(ToString::to_string), param_index }
+}
+
+#[test]
+fn warnings() {
+	for &(backend, safe_array) in &[
+		(winapi_tlb_bindgen::Backend::Winapi, "*mut SAFEARRAY"),
+		(winapi_tlb_bindgen::Backend::Windows, "*mut windows::Win32::System::Com::SAFEARRAY"),
+		(winapi_tlb_bindgen::Backend::ReprC, "*mut SAFEARRAY"),
+		(winapi_tlb_bindgen::Backend::Com, "*mut SAFEARRAY"),
+	] {
+		let build_result = build(load_simple(), backend, false);
+
+		let warnings: Vec<_> = build_result.warnings.iter().map(|warning| (&warning.kind, &warning.location)).collect();
+		assert_eq!(warnings, [
+			(
+				&WarningKind::RenamedIdentifier { original: "type".to_string(), renamed: "type_".to_string() },
+				&location("Shape", Some("type"), None),
+			),
+			(
+				&WarningKind::RenamedIdentifier { original: "impl".to_string(), renamed: "impl_".to_string() },
+				&location("IShape", Some("Move"), Some(1)),
+			),
+			(
+				&WarningKind::SkippedDispinterfaceOfDualInterface,
+				&location("IShapeDual", None, None),
+			),
+			(
+				&WarningKind::LossyTypeMapping {
+					type_: winapi_tlb_bindgen::types::TypeDesc::SafeArray(Box::new(winapi_tlb_bindgen::types::TypeDesc::Base(winapi_tlb_bindgen::types::VT_VARIANT))),
+					emitted_as: safe_array.to_string(),
+				},
+				&location("IShapeDual", Some("Scale"), Some(1)),
+			),
+			(
+				&WarningKind::SkippedDispinterface,
+				&location("DShapeEvents", None, None),
+			),
+		], "{backend:?}");
+
+		// The counterparts of the warnings that predate them are still filled in
+		assert_eq!(build_result.skipped_dispinterfaces, ["DShapeEvents"]);
+		assert_eq!(build_result.skipped_dispinterface_of_dual_interfaces, ["IShapeDual"]);
+	}
+}
+
+#[test]
+fn missing_type() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "Point").vars[1].type_ =
+		winapi_tlb_bindgen::types::TypeDesc::UserDefined(winapi_tlb_bindgen::types::TypeRef::Local(100));
+
+	let build_result = build(type_lib, winapi_tlb_bindgen::Backend::ReprC, true);
+
+	assert_eq!(build_result.missing_types.len(), 1);
+	let warning = &build_result.warnings[0];
+	assert_eq!(warning.kind, WarningKind::MissingType { type_ref: winapi_tlb_bindgen::types::TypeRef::Local(100) });
+	assert_eq!(warning.location, location("Point", Some("y"), None));
+	assert_eq!(warning.to_string(), "Point::y references type 100, which is not in the typelib");
+}
+
+#[test]
+fn dropped_restricted_member() {
+	// A dispinterface that lists the functions of IUnknown and IDispatch, like IFaxServerNotify2
+	let mut type_lib = load_simple();
+	let events = find_mut(&mut type_lib, "DShapeEvents");
+	let mut query_interface = events.functions[0].clone();
+	query_interface.name = "QueryInterface".to_string();
+	query_interface.flags = winapi_tlb_bindgen::types::FUNCFLAG_FRESTRICTED;
+	events.functions[0].ovft = 7 * 4;
+	events.functions[1].ovft = 8 * 4;
+	events.functions.insert(0, query_interface);
+
+	for &backend in &[
+		winapi_tlb_bindgen::Backend::Winapi,
+		winapi_tlb_bindgen::Backend::Windows,
+		winapi_tlb_bindgen::Backend::ReprC,
+		winapi_tlb_bindgen::Backend::Com,
+	] {
+		let build_result = build(type_lib.clone(), backend, true);
+
+		let dropped: Vec<_> =
+			build_result.warnings.iter()
+			.filter(|warning| warning.kind == WarningKind::DroppedRestrictedMember)
+			.map(|warning| &warning.location)
+			.collect();
+		assert_eq!(dropped, [&location("DShapeEvents", Some("QueryInterface"), None)], "{backend:?}");
+	}
+}
+
+#[test]
+fn summary() {
+	let mut type_lib = load_simple();
+	find_mut(&mut type_lib, "Value").alignment = 2;
+
+	let build_result =
+		winapi_tlb_bindgen::Builder::from_type_lib(type_lib)
+		.backend(winapi_tlb_bindgen::Backend::ReprC)
+		.continue_on_error(true)
+		.build(std::io::sink())
+		.unwrap();
+	let summary = build_result.summary();
+
+	assert_eq!(summary.warnings, build_result.warnings);
+	assert_eq!(summary.failed_types, [winapi_tlb_bindgen::FailedTypeSummary {
+		type_name: "Value".to_string(),
+		error: "Value: unsupported layout: union alignment is not 4 or 8".to_string(),
+	}]);
+
+	let json = serde_json::to_value(&summary).unwrap();
+	assert_eq!(json["warnings"][0], serde_json::json!({
+		"kind": "renamed_identifier",
+		"original": "type",
+		"renamed": "type_",
+		"location": { "type_name": "Shape", "member_name": "type", "param_index": null },
+	}));
+	assert_eq!(json["failed_types"][0]["type_name"], "Value");
+
+	let deserialized: winapi_tlb_bindgen::Summary = serde_json::from_value(json).unwrap();
+	assert_eq!(deserialized, summary);
+}
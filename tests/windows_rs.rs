@@ -0,0 +1,97 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for the bindings of the windows backend.
+//!
+//! The golden bindings are compiled as part of this test against the versions of the `windows` and `windows-core` crates in `Cargo.toml`,
+//! and their layouts are checked against the typelib. The golden bindings themselves are checked by `tests/msft.rs`.
+//!
+//! The `windows` crate is empty on other hosts, and `windows-core` doesn't have `BSTR` there, so there the bindings are compiled
+//! against `windows-core` and stand-ins for the few items that they use from the rest. `cargo check --tests --target x86_64-pc-windows-gnu` checks them
+//! against the `windows` crate itself from other hosts.
+
+mod common;
+
+use common::{find, load_simple};
+
+#[allow(dead_code, non_camel_case_types, non_snake_case, non_upper_case_globals, clippy::all, clippy::pedantic)]
+mod bindings {
+	#[cfg(not(windows))]
+	use super::{windows_core_stand_in as windows_core, windows_stand_in as windows};
+
+	include!("fixtures/simple-windows.rs");
+}
+
+/// `windows-core`, with the `BSTR` that it only has on Windows
+#[cfg(not(windows))]
+#[allow(clippy::upper_case_acronyms)]
+mod windows_core_stand_in {
+	pub use windows_core::*;
+
+	#[repr(transparent)]
+	pub struct BSTR(*const u16);
+}
+
+/// The items of the `windows` crate that the bindings use, declared like the `windows` crate declares them
+#[cfg(not(windows))]
+#[allow(non_camel_case_types, non_snake_case, clippy::upper_case_acronyms)]
+mod windows_stand_in {
+	pub mod Win32 {
+		pub mod System {
+			pub mod Com {
+				#[repr(transparent)]
+				#[derive(Clone, Debug, Eq, PartialEq)]
+				pub struct IDispatch(windows_core::IUnknown);
+
+				unsafe impl windows_core::Interface for IDispatch {
+					type Vtable = IDispatch_Vtbl;
+					const IID: windows_core::GUID = windows_core::GUID::from_u128(0x00020400_0000_0000_c000_000000000046);
+				}
+
+				#[repr(C)]
+				pub struct IDispatch_Vtbl {
+					pub base__: windows_core::IUnknown_Vtbl,
+					pub GetTypeInfoCount: usize,
+					pub GetTypeInfo: usize,
+					pub GetIDsOfNames: usize,
+					pub Invoke: usize,
+				}
+
+				pub struct SAFEARRAY;
+			}
+		}
+	}
+}
+
+#[test]
+fn layout() {
+	let type_lib = load_simple();
+	if usize::from(type_lib.syskind.pointer_size()) != std::mem::size_of::<usize>() {
+		// The typelib is for a platform with a different pointer size than the host's
+		return;
+	}
+
+	let type_info = |name: &str| find(&type_lib, name);
+
+	for &(name, size, alignment) in &[
+		("Point", std::mem::size_of::<bindings::Point>(), std::mem::align_of::<bindings::Point>()),
+		("Shape", std::mem::size_of::<bindings::Shape>(), std::mem::align_of::<bindings::Shape>()),
+		("Value", std::mem::size_of::<bindings::Value>(), std::mem::align_of::<bindings::Value>()),
+	] {
+		let type_info = type_info(name);
+		assert_eq!(size, type_info.size_instance as usize, "size of {name}");
+		assert_eq!(alignment, usize::from(type_info.alignment), "alignment of {name}");
+	}
+
+	for &(name, size) in &[
+		("IShape", std::mem::size_of::<bindings::IShape_Vtbl>()),
+		("IShapeDual", std::mem::size_of::<bindings::IShapeDual_Vtbl>()),
+		("DShapeEvents", std::mem::size_of::<bindings::DShapeEvents_Vtbl>()),
+	] {
+		assert_eq!(size, usize::from(type_info(name).size_vft), "size of the vtable of {name}");
+	}
+
+	assert_eq!(<bindings::IShape as windows_core::Interface>::IID, bindings::IID_IShape);
+	let guid = type_info("IShape").guid;
+	assert_eq!(bindings::IID_IShape, windows_core::GUID::from_values(guid.data1, guid.data2, guid.data3, guid.data4));
+}
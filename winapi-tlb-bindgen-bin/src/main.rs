@@ -2,38 +2,225 @@
 #![deny(clippy::all, clippy::pedantic)]
 
 #[derive(structopt::StructOpt)]
+#[allow(clippy::struct_excessive_bools)] // Each bool is a command-line flag
 struct Options {
-	#[structopt(help = "path of typelib")]
+	#[structopt(help = "path of typelib, of an IDL file, or of a JSON snapshot written by --snapshot")]
 	filename: std::path::PathBuf,
 
+	#[structopt(
+		long = "backend",
+		default_value = "winapi",
+		parse(try_from_str = "parse_backend"),
+		help = "the crate to emit bindings for, `winapi`, `windows` or `com`, or `repr-c` for plain #[repr(C)] bindings that don't depend on any crate",
+	)]
+	backend: winapi_tlb_bindgen::Backend,
+
 	#[structopt(long = "emit-dispinterfaces", help = "emit code for DISPINTERFACEs (experimental)")]
 	emit_dispinterfaces: bool,
+
+	#[structopt(long = "emit-imports", help = "emit `use` items for the winapi items and imported types that the bindings refer to, so that they compile without imports of their own; with `--backend repr-c`, emit a prelude of the ABI types instead")]
+	emit_imports: bool,
+
+	#[structopt(long = "search-path", parse(from_os_str), help = "directory to look in for the typelibs that the typelib imports types from, like stdole2.tlb; can be specified more than once")]
+	search_path: Vec<std::path::PathBuf>,
+
+	#[structopt(long = "dependency", parse(from_os_str), help = "path of a typelib that the typelib imports types from; can be specified more than once")]
+	dependencies: Vec<std::path::PathBuf>,
+
+	#[structopt(
+		long = "type-path",
+		parse(try_from_str = "parse_type_path"),
+		help = "emit the given type in an imported typelib as the given Rust path, as in `stdole:IFontDisp=my_crate::IFontDisp`; \
+			the typelib can be given by its name or LIBID; can be specified more than once",
+	)]
+	type_paths: Vec<(winapi_tlb_bindgen::TypeLibSelector, String, String)>,
+
+	#[structopt(
+		long = "type-lib-path",
+		parse(try_from_str = "parse_type_lib_path"),
+		help = "emit the types in an imported typelib as items of the given Rust module, as in `Office=office_bindings`; \
+			the typelib can be given by its name or LIBID; can be specified more than once",
+	)]
+	type_lib_paths: Vec<(winapi_tlb_bindgen::TypeLibSelector, String)>,
+
+	#[structopt(
+		long = "allowlist-type",
+		help = "only emit bindings for the types whose names match the given pattern, where `*` matches anything, and the types that they depend on; \
+			can be specified more than once",
+	)]
+	allowlist_types: Vec<String>,
+
+	#[structopt(
+		long = "allowlist-kind",
+		parse(try_from_str = "parse_type_kind"),
+		help = "only emit bindings for the types of the given kind, like `interface` or `enum`, and the types that they depend on; can be specified more than once",
+	)]
+	allowlist_kinds: Vec<winapi_tlb_bindgen::types::TypeKind>,
+
+	#[structopt(long = "blocklist-type", help = "don't emit bindings for the types whose names match the given pattern; can be specified more than once")]
+	blocklist_types: Vec<String>,
+
+	#[structopt(long = "blocklist-kind", parse(try_from_str = "parse_type_kind"), help = "don't emit bindings for the types of the given kind; can be specified more than once")]
+	blocklist_kinds: Vec<winapi_tlb_bindgen::types::TypeKind>,
+
+	#[structopt(long = "continue-on-error", help = "leave out the types that bindings can't be emitted for, instead of failing")]
+	continue_on_error: bool,
+
+	#[structopt(long = "summary", parse(from_os_str), help = "write a JSON summary of the warnings and the types that were left out to the given file")]
+	summary: Option<std::path::PathBuf>,
+
+	#[structopt(long = "snapshot", help = "write a JSON snapshot of the typelib instead of bindings")]
+	snapshot: bool,
+
+	#[structopt(long = "idl", help = "write the typelib as IDL instead of bindings")]
+	idl: bool,
+
+	#[structopt(long = "tlb", parse(from_os_str), help = "write the typelib in the MSFT format to the given file instead of bindings")]
+	tlb: Option<std::path::PathBuf>,
 }
 
 fn main() {
 	let Options {
 		filename,
+		backend,
 		emit_dispinterfaces,
+		emit_imports,
+		search_path,
+		dependencies,
+		type_paths,
+		type_lib_paths,
+		allowlist_types,
+		allowlist_kinds,
+		blocklist_types,
+		blocklist_kinds,
+		continue_on_error,
+		summary,
+		snapshot,
+		idl,
+		tlb,
 	} = structopt::StructOpt::from_args();
 
+	if let Some(tlb) = tlb {
+		let type_lib = winapi_tlb_bindgen::load_type_lib(&filename).unwrap();
+		let file = std::io::BufWriter::new(std::fs::File::create(tlb).unwrap());
+		winapi_tlb_bindgen::write_type_lib(&type_lib, file).unwrap();
+		return;
+	}
+
+	if snapshot {
+		let type_lib = winapi_tlb_bindgen::load_type_lib(&filename).unwrap();
+		let stdout = std::io::stdout();
+		winapi_tlb_bindgen::write_snapshot(&type_lib, stdout.lock()).unwrap();
+		return;
+	}
+
+	if idl {
+		let type_lib = winapi_tlb_bindgen::load_type_lib(&filename).unwrap();
+		let stdout = std::io::stdout();
+		winapi_tlb_bindgen::write_idl(&type_lib, stdout.lock()).unwrap();
+		return;
+	}
+
+	let mut type_mappings = winapi_tlb_bindgen::TypeMappings::for_backend(backend);
+	for (type_lib, type_name, path) in type_paths {
+		type_mappings.add(type_lib, type_name, path);
+	}
+	for (type_lib, module_path) in type_lib_paths {
+		type_mappings.add_type_lib(type_lib, module_path);
+	}
+
+	let mut builder =
+		winapi_tlb_bindgen::Builder::from_path(filename)
+		.backend(backend)
+		.type_mappings(type_mappings)
+		.emit_dispinterfaces(emit_dispinterfaces)
+		.emit_imports(emit_imports)
+		.continue_on_error(continue_on_error);
+
+	// Typelibs given explicitly take precedence over ones found in the search path
+	for dependency in dependencies {
+		builder = builder.dependency(winapi_tlb_bindgen::load_type_lib(&dependency).unwrap());
+	}
+	for dir in search_path {
+		builder = builder.search_path(dir);
+	}
+	for pattern in allowlist_types {
+		builder = builder.allowlist_type(pattern);
+	}
+	for typekind in allowlist_kinds {
+		builder = builder.allowlist_type_kind(typekind);
+	}
+	for pattern in blocklist_types {
+		builder = builder.blocklist_type(pattern);
+	}
+	for typekind in blocklist_kinds {
+		builder = builder.blocklist_type_kind(typekind);
+	}
+
 	let build_result = {
 		let stdout = std::io::stdout();
-		winapi_tlb_bindgen::build(&filename, emit_dispinterfaces, stdout.lock()).unwrap()
+		builder.build(stdout.lock()).unwrap()
 	};
 
-	if build_result.num_missing_types > 0 {
-		eprintln!("{} referenced types could not be found and were replaced with `__missing_type__`", build_result.num_missing_types);
+	for warning in &build_result.warnings {
+		match warning.kind {
+			winapi_tlb_bindgen::WarningKind::MissingType { .. } => eprintln!("{warning}; it was replaced with `__missing_type__`"),
+			winapi_tlb_bindgen::WarningKind::SkippedDispinterface => eprintln!("{warning}; specify --emit-dispinterfaces to emit it"),
+			_ => eprintln!("{warning}"),
+		}
 	}
 
-	if build_result.num_types_not_found > 0 {
-		eprintln!("{} types could not be found", build_result.num_types_not_found);
+	for failed_type in &build_result.failed_types {
+		eprintln!("{failed_type}");
 	}
 
-	for skipped_dispinterface in build_result.skipped_dispinterfaces {
-		eprintln!("Dispinterface {} was skipped because --emit-dispinterfaces was not specified", skipped_dispinterface);
+	if let Some(summary) = summary {
+		let file = std::io::BufWriter::new(std::fs::File::create(summary).unwrap());
+		serde_json::to_writer_pretty(file, &build_result.summary()).unwrap();
 	}
+}
+
+fn parse_backend(s: &str) -> Result<winapi_tlb_bindgen::Backend, String> {
+	match s {
+		"winapi" => Ok(winapi_tlb_bindgen::Backend::Winapi),
+		"windows" => Ok(winapi_tlb_bindgen::Backend::Windows),
+		"repr-c" => Ok(winapi_tlb_bindgen::Backend::ReprC),
+		"com" => Ok(winapi_tlb_bindgen::Backend::Com),
+		_ => Err(format!("expected `winapi`, `windows`, `repr-c` or `com`, got {s:?}")),
+	}
+}
+
+fn parse_type_kind(s: &str) -> Result<winapi_tlb_bindgen::types::TypeKind, String> {
+	match s {
+		"enum" => Ok(winapi_tlb_bindgen::types::TypeKind::Enum),
+		"record" => Ok(winapi_tlb_bindgen::types::TypeKind::Record),
+		"module" => Ok(winapi_tlb_bindgen::types::TypeKind::Module),
+		"interface" => Ok(winapi_tlb_bindgen::types::TypeKind::Interface),
+		"dispinterface" => Ok(winapi_tlb_bindgen::types::TypeKind::Dispatch),
+		"coclass" => Ok(winapi_tlb_bindgen::types::TypeKind::CoClass),
+		"alias" => Ok(winapi_tlb_bindgen::types::TypeKind::Alias),
+		"union" => Ok(winapi_tlb_bindgen::types::TypeKind::Union),
+		_ => Err(format!("expected `enum`, `record`, `module`, `interface`, `dispinterface`, `coclass`, `alias` or `union`, got {s:?}")),
+	}
+}
+
+/// Parses `typelib:type=path`
+fn parse_type_path(s: &str) -> Result<(winapi_tlb_bindgen::TypeLibSelector, String, String), String> {
+	let (type_lib, rest) = s.split_once(':').ok_or_else(|| format!("expected `typelib:type=path`, got {s:?}"))?;
+	let (type_name, path) = rest.split_once('=').ok_or_else(|| format!("expected `typelib:type=path`, got {s:?}"))?;
+	Ok((parse_type_lib_selector(type_lib), type_name.to_string(), path.to_string()))
+}
+
+/// Parses `typelib=path`
+fn parse_type_lib_path(s: &str) -> Result<(winapi_tlb_bindgen::TypeLibSelector, String), String> {
+	let (type_lib, module_path) = s.split_once('=').ok_or_else(|| format!("expected `typelib=path`, got {s:?}"))?;
+	Ok((parse_type_lib_selector(type_lib), module_path.to_string()))
+}
 
-	for skipped_dispinterface in build_result.skipped_dispinterface_of_dual_interfaces {
-		eprintln!("Dispinterface half of dual interface {} was skipped", skipped_dispinterface);
+/// A LIBID, with or without braces, or else the name of a typelib
+fn parse_type_lib_selector(s: &str) -> winapi_tlb_bindgen::TypeLibSelector {
+	match s.trim_start_matches('{').trim_end_matches('}').parse() {
+		Ok(guid) => winapi_tlb_bindgen::TypeLibSelector::Guid(guid),
+		Err(()) => winapi_tlb_bindgen::TypeLibSelector::Name(s.to_string()),
 	}
 }
@@ -0,0 +1,124 @@
+//! Selecting the types of a typelib to emit bindings for, along with the types that they depend on.
+
+use crate::types;
+
+/// A set of types of a typelib, selected by their names and typekinds
+#[derive(Debug, Default)]
+pub(crate) struct TypeSelector {
+	/// Patterns for the names of the types, where `*` matches any sequence of characters
+	pub(crate) patterns: Vec<String>,
+
+	pub(crate) typekinds: Vec<types::TypeKind>,
+}
+
+impl TypeSelector {
+	pub(crate) fn is_empty(&self) -> bool {
+		self.patterns.is_empty() && self.typekinds.is_empty()
+	}
+
+	/// Whether the given type matches any of the patterns or typekinds
+	fn matches(&self, type_info: &types::TypeInfo) -> bool {
+		self.typekinds.contains(&type_info.typekind) ||
+		self.patterns.iter().any(|pattern| matches_pattern(pattern, &type_info.name))
+	}
+}
+
+/// Gets whether to emit bindings for each type of the given typelib, by index.
+///
+/// Types in the blocklist are never selected. If the allowlist is empty, every other type is selected, including the types that only
+/// blocklisted types depend on. Otherwise the types in the allowlist are selected, along with the types that they depend on: the parents
+/// of interfaces, the interfaces that coclasses implement, the types of fields, parameters and return values, and the targets of aliases.
+/// Dependencies aren't followed through blocklisted types, so the types that only blocklisted types depend on aren't selected.
+pub(crate) fn select(type_lib: &types::TypeLib, allowlist: &TypeSelector, blocklist: &TypeSelector) -> Vec<bool> {
+	if allowlist.is_empty() {
+		return type_lib.type_infos.iter().map(|type_info| !blocklist.matches(type_info)).collect();
+	}
+
+	let mut selected = vec![false; type_lib.type_infos.len()];
+
+	let mut pending: Vec<_> =
+		type_lib.type_infos.iter().enumerate()
+		.filter(|(_, type_info)| allowlist.matches(type_info))
+		.map(|(index, _)| index)
+		.collect();
+
+	while let Some(index) = pending.pop() {
+		let Some(type_info) = type_lib.type_infos.get(index) else {
+			// A reference to a type that isn't in the typelib is emitted as `__missing_type__`
+			continue;
+		};
+
+		if selected[index] || blocklist.matches(type_info) {
+			continue;
+		}
+
+		selected[index] = true;
+
+		for impl_type in &type_info.impl_types {
+			push_type_ref(&impl_type.type_ref, &mut pending);
+		}
+
+		for function in &type_info.functions {
+			push_type_desc(&function.return_type, &mut pending);
+			for param in &function.params {
+				push_type_desc(&param.type_, &mut pending);
+			}
+		}
+
+		for var in &type_info.vars {
+			push_type_desc(&var.type_, &mut pending);
+		}
+
+		if let Some(alias_type) = &type_info.alias_type {
+			push_type_desc(alias_type, &mut pending);
+		}
+	}
+
+	selected
+}
+
+/// Records the type of the typelib that the given type refers to, if any
+fn push_type_desc(type_desc: &types::TypeDesc, pending: &mut Vec<usize>) {
+	match type_desc {
+		types::TypeDesc::Base(_) => (),
+		types::TypeDesc::Ptr(type_desc) |
+		types::TypeDesc::SafeArray(type_desc) |
+		types::TypeDesc::CArray(type_desc, _) => push_type_desc(type_desc, pending),
+		types::TypeDesc::UserDefined(type_ref) => push_type_ref(type_ref, pending),
+	}
+}
+
+/// Records the type that the given type reference refers to, if it's in the typelib
+fn push_type_ref(type_ref: &types::TypeRef, pending: &mut Vec<usize>) {
+	match type_ref {
+		types::TypeRef::Local(index) => pending.push(*index),
+
+		// Bindings for types in imported typelibs aren't emitted anyway
+		types::TypeRef::External { .. } => (),
+	}
+}
+
+/// Whether the given name matches the given pattern, where `*` matches any sequence of characters
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+	let mut parts = pattern.split('*');
+
+	// The part before the first `*` must be at the start, and the part after the last `*` must be at the end
+	let first = parts.next().unwrap_or_default();
+	let Some(mut rest) = name.strip_prefix(first) else {
+		return false;
+	};
+
+	let mut parts: Vec<_> = parts.collect();
+	let Some(last) = parts.pop() else {
+		return rest.is_empty();
+	};
+
+	for part in parts {
+		match rest.find(part) {
+			Some(index) => rest = &rest[(index + part.len())..],
+			None => return false,
+		}
+	}
+
+	rest.ends_with(last)
+}
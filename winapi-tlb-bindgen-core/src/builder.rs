@@ -0,0 +1,272 @@
+use crate::{allowlist, types, Backend, BuildResult, Context, Error, TypeMappings};
+
+/// Configures how bindings are emitted for a typelib, and emits them.
+///
+/// ```no_run
+/// # extern crate winapi_tlb_bindgen_core as winapi_tlb_bindgen;
+/// let build_result = winapi_tlb_bindgen::Builder::from_path("MsXml.Tlb")
+///     .backend(winapi_tlb_bindgen::Backend::ReprC)
+///     .filter(|type_info| type_info.name.starts_with("IXMLDOM"))
+///     .build(std::io::stdout())?;
+/// # Ok::<_, winapi_tlb_bindgen::Error>(())
+/// ```
+#[must_use]
+pub struct Builder {
+	source: Source,
+	backend: Backend,
+	type_mappings: Option<TypeMappings>,
+	dependencies: Vec<types::TypeLib>,
+	search_path: Vec<std::path::PathBuf>,
+	emit_dispinterfaces: bool,
+	emit_imports: bool,
+	allowlist: allowlist::TypeSelector,
+	blocklist: allowlist::TypeSelector,
+	filters: Vec<Filter>,
+	renames: Vec<Rename>,
+	missing_types: MissingTypePolicy,
+	continue_on_error: bool,
+}
+
+type Filter = Box<dyn Fn(&types::TypeInfo) -> bool>;
+
+type Rename = Box<dyn Fn(&types::TypeInfo) -> Option<String>>;
+
+/// Where the typelib comes from
+enum Source {
+	Path(std::path::PathBuf),
+	Bytes(Vec<u8>),
+	Reader(Box<dyn std::io::Read>),
+	TypeLib(types::TypeLib),
+}
+
+/// What to do about referenced types that can't be found
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MissingTypePolicy {
+	/// Emit `__missing_type__` in place of the type, and record it in [`BuildResult::missing_types`]
+	Placeholder,
+
+	/// Fail with [`Error::MissingTypes`] without writing any bindings
+	Error,
+}
+
+impl Builder {
+	/// Emits bindings for the typelib at the given path, which is interpreted the same way as for [`crate::build`]
+	pub fn from_path(path: impl Into<std::path::PathBuf>) -> Self {
+		Builder::new(Source::Path(path.into()))
+	}
+
+	/// Emits bindings for the typelib in the given bytes, which are interpreted the same way as for [`crate::load_type_lib_from_bytes`]
+	pub fn from_bytes(data: impl Into<Vec<u8>>) -> Self {
+		Builder::new(Source::Bytes(data.into()))
+	}
+
+	/// Emits bindings for the typelib that's read from the given reader when the bindings are built,
+	/// as for [`crate::load_type_lib_from_reader`]
+	pub fn from_reader(reader: impl std::io::Read + 'static) -> Self {
+		Builder::new(Source::Reader(Box::new(reader)))
+	}
+
+	/// Emits bindings for the given typelib, which can come from [`crate::load_type_lib`], or be constructed or modified by the caller
+	pub fn from_type_lib(type_lib: types::TypeLib) -> Self {
+		Builder::new(Source::TypeLib(type_lib))
+	}
+
+	fn new(source: Source) -> Self {
+		Builder {
+			source,
+			backend: Backend::Winapi,
+			type_mappings: None,
+			dependencies: vec![],
+			search_path: vec![],
+			emit_dispinterfaces: false,
+			emit_imports: false,
+			allowlist: allowlist::TypeSelector::default(),
+			blocklist: allowlist::TypeSelector::default(),
+			filters: vec![],
+			renames: vec![],
+			missing_types: MissingTypePolicy::Placeholder,
+			continue_on_error: false,
+		}
+	}
+
+	/// The crate to emit bindings for. The default is [`Backend::Winapi`].
+	pub fn backend(mut self, backend: Backend) -> Self {
+		self.backend = backend;
+		self
+	}
+
+	/// The Rust paths to emit types in imported typelibs as. The default is [`TypeMappings::for_backend`] of the backend.
+	pub fn type_mappings(mut self, type_mappings: TypeMappings) -> Self {
+		self.type_mappings = Some(type_mappings);
+		self
+	}
+
+	/// A typelib that the typelib imports types from. It takes precedence over typelibs found in the search path.
+	pub fn dependency(mut self, dependency: types::TypeLib) -> Self {
+		self.dependencies.push(dependency);
+		self
+	}
+
+	/// A directory to look in for the typelibs that the typelib imports types from, as for [`crate::load_dependencies`]
+	pub fn search_path(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+		self.search_path.push(dir.into());
+		self
+	}
+
+	/// Whether to emit bindings for dispinterfaces. The default is false.
+	pub fn emit_dispinterfaces(mut self, emit_dispinterfaces: bool) -> Self {
+		self.emit_dispinterfaces = emit_dispinterfaces;
+		self
+	}
+
+	/// Whether to start the bindings with the imports or prelude that they need, as for [`crate::build_from_type_lib`]. The default is false.
+	pub fn emit_imports(mut self, emit_imports: bool) -> Self {
+		self.emit_imports = emit_imports;
+		self
+	}
+
+	/// Emits bindings for the types of the typelib whose names match the given pattern, where `*` matches any sequence of characters,
+	/// along with the types that they depend on.
+	///
+	/// The dependencies are the parents of interfaces, the interfaces that coclasses implement, the types of fields, parameters
+	/// and return values, and the targets of aliases, recursively. If nothing is allowlisted, bindings are emitted for every type.
+	/// The patterns are matched against the names after [`Builder::rename`].
+	pub fn allowlist_type(mut self, pattern: impl Into<String>) -> Self {
+		self.allowlist.patterns.push(pattern.into());
+		self
+	}
+
+	/// Emits bindings for the types of the typelib of the given typekind, along with the types that they depend on, as for [`Builder::allowlist_type`]
+	pub fn allowlist_type_kind(mut self, typekind: types::TypeKind) -> Self {
+		self.allowlist.typekinds.push(typekind);
+		self
+	}
+
+	/// Doesn't emit bindings for the types of the typelib whose names match the given pattern, even if an allowlisted type depends on them.
+	///
+	/// If types are allowlisted, the types that only blocklisted types depend on aren't emitted either. If nothing is allowlisted, they're still emitted.
+	/// References to blocklisted types are still emitted by name, so they must be defined elsewhere.
+	pub fn blocklist_type(mut self, pattern: impl Into<String>) -> Self {
+		self.blocklist.patterns.push(pattern.into());
+		self
+	}
+
+	/// Doesn't emit bindings for the types of the typelib of the given typekind, as for [`Builder::blocklist_type`]
+	pub fn blocklist_type_kind(mut self, typekind: types::TypeKind) -> Self {
+		self.blocklist.typekinds.push(typekind);
+		self
+	}
+
+	/// Only emits bindings for the types of the typelib that the given function returns true for.
+	///
+	/// If this is called more than once, bindings are only emitted for the types that all the functions return true for.
+	/// Unlike [`Builder::allowlist_type`], this doesn't pull in the types that they depend on.
+	/// Types that are filtered out are still resolved when other types refer to them.
+	pub fn filter(mut self, filter: impl Fn(&types::TypeInfo) -> bool + 'static) -> Self {
+		self.filters.push(Box::new(filter));
+		self
+	}
+
+	/// Renames the types of the typelib that the given function returns a new name for.
+	///
+	/// References to the renamed types use the new names too. If this is called more than once, the functions are applied in order,
+	/// each to the result of the previous ones. Types in imported typelibs are not renamed; map them with [`Builder::type_mappings`] instead.
+	pub fn rename(mut self, rename: impl Fn(&types::TypeInfo) -> Option<String> + 'static) -> Self {
+		self.renames.push(Box::new(rename));
+		self
+	}
+
+	/// What to do about referenced types that can't be found. The default is [`MissingTypePolicy::Placeholder`].
+	pub fn missing_types(mut self, policy: MissingTypePolicy) -> Self {
+		self.missing_types = policy;
+		self
+	}
+
+	/// Whether to leave out the types that bindings can't be emitted for, instead of failing. The default is false.
+	///
+	/// The types that are left out are recorded in [`BuildResult::failed_types`] with the errors that they would have failed with.
+	/// References to them from other types are still emitted by name, so they must be defined elsewhere for the bindings to compile.
+	pub fn continue_on_error(mut self, continue_on_error: bool) -> Self {
+		self.continue_on_error = continue_on_error;
+		self
+	}
+
+	/// Emits the bindings to the given writer
+	pub fn build<W>(self, mut out: W) -> Result<BuildResult, Error> where W: std::io::Write {
+		let Builder {
+			source,
+			backend,
+			type_mappings,
+			mut dependencies,
+			search_path,
+			emit_dispinterfaces,
+			emit_imports,
+			allowlist,
+			blocklist,
+			filters,
+			renames,
+			missing_types,
+			continue_on_error,
+		} = self;
+
+		let mut files = vec![];
+
+		let mut type_lib = match source {
+			Source::Path(path) => crate::load_type_lib_with_files(&path, &mut files)?,
+			Source::Bytes(data) => crate::load_type_lib_from_bytes(&data)?,
+			Source::Reader(reader) => crate::load_type_lib_from_reader(reader)?,
+			Source::TypeLib(type_lib) => type_lib,
+		};
+
+		dependencies.extend(crate::load_dependencies_with_files(&type_lib, &search_path, &mut files)?);
+
+		for rename in &renames {
+			for type_info in &mut type_lib.type_infos {
+				if let Some(name) = rename(type_info) {
+					type_info.name = name;
+				}
+			}
+		}
+
+		let type_mappings = type_mappings.unwrap_or_else(|| TypeMappings::for_backend(backend));
+
+		let selected: Vec<_> =
+			allowlist::select(&type_lib, &allowlist, &blocklist).into_iter()
+			.zip(&type_lib.type_infos)
+			.map(|(selected, type_info)| selected && filters.iter().all(|filter| filter(type_info)))
+			.collect();
+
+		let context = Context {
+			type_lib: &type_lib,
+			dependencies: &dependencies,
+			type_mappings: &type_mappings,
+			selected: Some(&selected),
+		};
+
+		let mut build_result = match missing_types {
+			MissingTypePolicy::Placeholder => crate::write_bindings(&context, backend, emit_dispinterfaces, emit_imports, continue_on_error, out)?,
+
+			MissingTypePolicy::Error => {
+				// The bindings aren't written until it's known that no types are missing
+				let mut bindings = vec![];
+				let build_result = crate::write_bindings(&context, backend, emit_dispinterfaces, emit_imports, continue_on_error, &mut bindings)?;
+				if !build_result.missing_types.is_empty() {
+					return Err(Error::MissingTypes(build_result.missing_types));
+				}
+
+				out.write_all(&bindings)?;
+				build_result
+			},
+		};
+
+		build_result.files = files;
+		Ok(build_result)
+	}
+
+	/// Emits the bindings as a [`proc_macro2::TokenStream`], as for [`crate::build_tokens`]
+	pub fn build_tokens(self) -> Result<(proc_macro2::TokenStream, BuildResult), Error> {
+		let mut out = vec![];
+		let build_result = self.build(&mut out)?;
+		Ok((crate::parse_bindings(&out)?, build_result))
+	}
+}
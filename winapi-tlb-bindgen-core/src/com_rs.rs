@@ -0,0 +1,439 @@
+//! Bindings for the `com` crate.
+//!
+//! Each interface is declared with `com::interfaces!`, so the same bindings can be used to consume the interface and to implement it
+//! with `com::class!`. Each coclass is a `CLSID_` constant. Records, unions and enums are plain `#[repr(C)]` types.
+//!
+//! Interface pointers in methods are `Option`s of the interfaces, so that they're reference-counted, and in records they're raw pointers.
+//! Parameters that are passed by value and aren't `com::AbiTransferable`, like records and `VARIANT`s, are marked `#[pass_through]`.
+//! The bindings refer to the ABI types that `com` doesn't have by name, which must be in scope:
+//! `BSTR`, `VARIANT`, `SAFEARRAY`, `DECIMAL`, `CY`, `DATE` and an `IDispatch` interface declared with `com::interfaces!`,
+//! and `FILETIME`, `BLOB` and `BSTRBLOB` if the typelib uses those `VARTYPE`s.
+
+use crate::{counted_array_name, is_inherited_dispatch_member, resolve_type_info, sanitize_reserved, type_ref_path, type_ref_to_string, types, var_type_kind, vtbl_size, well_known_path, BuildResult, Context, Location, VarTypeKind, WarningKind, WellKnownType};
+
+/// Writes the bindings for the given type of the typelib
+pub(crate) fn write_item<W>(context: &Context<'_>, type_info: &types::TypeInfo, emit_dispinterfaces: bool, build_result: &mut BuildResult, mut out: W) -> Result<(), crate::Error> where W: std::io::Write {
+	let typekind =
+		if type_info.is_dual() {
+			// Emit the interface half of dual interfaces
+			build_result.skipped_dispinterface_of_dual_interface(&type_info.name);
+			types::TypeKind::Interface
+		}
+		else {
+			type_info.typekind
+		};
+
+	let type_name = &type_info.name;
+
+	match typekind {
+		types::TypeKind::Enum => {
+			writeln!(out, "pub type {} = i32;", type_name)?;
+
+			for member in &type_info.vars {
+				match &member.var_kind {
+					types::VarKind::Const(types::Variant::I4(value)) =>
+						writeln!(out, "pub const {}: {} = {};", sanitize_reserved(&member.name, &Location::member(type_name, &member.name), build_result), type_name, value)?,
+					types::VarKind::Const(value) =>
+						return Err(crate::Error::UnsupportedVarType { location: Location::member(type_name, &member.name), vt: value.vt() }),
+					_ => return Err(crate::Error::Inconsistent { location: Location::member(type_name, &member.name), reason: "enum member is not a constant" }),
+				}
+			}
+
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Record => {
+			writeln!(out, "#[repr(C)]")?;
+			writeln!(out, "#[derive(Clone, Copy)]")?;
+			writeln!(out, "pub struct {} {{", type_name)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				writeln!(out, "    pub {}: {},", sanitize_reserved(&field.name, &location, build_result), type_to_string(&field.type_, types::PARAMFLAG_FOUT, &location, context, build_result)?)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Module => {
+			writeln!(out, r#"extern "system" {{"#)?;
+
+			for function in &type_info.functions {
+				let location = Location::member(type_name, &function.name);
+
+				if function.func_kind != types::FuncKind::Static {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				writeln!(out, "    pub fn {}(", function.name)?;
+
+				for (param_index, param) in function.params.iter().enumerate() {
+					writeln!(out, "        {}: {},",
+						sanitize_reserved(&param.name, &location.param(param_index), build_result),
+						type_to_string(&param.type_, param.flags, &location.param(param_index), context, build_result)?)?;
+				}
+
+				match &function.return_type {
+					types::TypeDesc::Base(types::VT_VOID) => writeln!(out, "    );")?,
+					return_type => writeln!(out, "    ) -> {};", type_to_string(return_type, types::PARAMFLAG_FOUT, &location, context, build_result)?)?,
+				}
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Interface => {
+			let parent = type_info.impl_types.first().map(|impl_type| &impl_type.type_ref);
+			let parent_vtbl_size = parent.and_then(|parent| vtbl_size(parent, context)).unwrap_or(0);
+
+			let parent = parent.map(|parent| type_ref_path(parent, &Location::type_(type_name), context, build_result));
+			write_interface_start(&mut out, type_name, type_info.guid, parent.as_deref())?;
+
+			for function in &type_info.functions {
+				if function.ovft < parent_vtbl_size {
+					// Inherited from ancestors
+					continue;
+				}
+
+				let location = Location::member(type_name, &function.name);
+
+				if function.func_kind == types::FuncKind::Static || function.func_kind == types::FuncKind::Dispatch {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				let name = match function.invoke_kind {
+					types::InvokeKind::Func => function.name.clone(),
+					types::InvokeKind::PropertyGet => format!("get_{}", function.name),
+					types::InvokeKind::PropertyPut => format!("put_{}", function.name),
+					types::InvokeKind::PropertyPutRef => format!("putref_{}", function.name),
+				};
+
+				let explicit_ret_val = function.params.iter().any(|param| (param.flags & types::PARAMFLAG_FRETVAL) == types::PARAMFLAG_FRETVAL);
+				if function.invoke_kind == types::InvokeKind::PropertyGet && !explicit_ret_val {
+					let mut params = function.params.clone();
+					params.push(ret_val_param(function.return_type.clone()));
+					write_method(&mut out, &name, &params, &types::TypeDesc::Base(types::VT_HRESULT), &location, context, build_result)?;
+				}
+				else {
+					write_method(&mut out, &name, &function.params, &function.return_type, &location, context, build_result)?;
+				}
+			}
+
+			for property in &type_info.vars {
+				// Synthesize get_() and put_() functions for each property.
+
+				let location = Location::member(type_name, &property.name);
+				let property_name = sanitize_reserved(&property.name, &location, build_result);
+
+				write_method(
+					&mut out,
+					&format!("get_{}", property_name),
+					&[ret_val_param(property.type_.clone())],
+					&types::TypeDesc::Base(types::VT_HRESULT),
+					&location, context, build_result)?;
+
+				write_method(
+					&mut out,
+					&format!("put_{}", property_name),
+					&[types::Param { name: "value".to_string(), type_: property.type_.clone(), flags: types::PARAMFLAG_FIN, default_value: None }],
+					&types::TypeDesc::Base(types::VT_HRESULT),
+					&location, context, build_result)?;
+			}
+
+			write_interface_end(&mut out)?;
+		},
+
+		types::TypeKind::Dispatch => {
+			if !emit_dispinterfaces {
+				build_result.skipped_dispinterface(&type_info.name);
+				return Ok(());
+			}
+
+			// The members of a dispinterface are only reachable through IDispatch::Invoke, so only the interface itself is emitted.
+			for function in type_info.functions.iter().filter(|function| is_inherited_dispatch_member(type_info, function, context)) {
+				build_result.warn(WarningKind::DroppedRestrictedMember, Location::member(type_name, &function.name));
+			}
+
+			let parent = well_known_path("IDispatch", context);
+			write_interface_start(&mut out, type_name, type_info.guid, Some(&parent))?;
+			write_interface_end(&mut out)?;
+		},
+
+		types::TypeKind::CoClass => {
+			for parent in type_info.impl_types.iter().map(|impl_type| &impl_type.type_ref) {
+				let parent_name = type_ref_to_string(parent, &Location::type_(type_name), context, build_result);
+				writeln!(out, "// Implements {}", parent_name)?;
+			}
+
+			let guid = type_info.guid;
+			writeln!(out,
+				"pub const CLSID_{}: com::CLSID = com::CLSID {{ data1: 0x{:08x}, data2: 0x{:04x}, data3: 0x{:04x}, data4: [0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}] }};",
+				type_name,
+				guid.data1, guid.data2, guid.data3,
+				guid.data4[0], guid.data4[1], guid.data4[2], guid.data4[3],
+				guid.data4[4], guid.data4[5], guid.data4[6], guid.data4[7])?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Alias => {
+			let location = Location::type_(type_name);
+			let alias_type = type_info.alias_type.as_ref().ok_or_else(|| crate::Error::Inconsistent { location: location.clone(), reason: "alias has no target" })?;
+			writeln!(out, "pub type {} = {};", type_name, type_to_string(alias_type, types::PARAMFLAG_FOUT, &location, context, build_result)?)?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Union => {
+			let alignment = match type_info.alignment {
+				4 => "u32",
+				8 => "u64",
+				_ => return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union alignment is not 4 or 8" }),
+			};
+
+			let num_aligned_elements = type_info.size_instance.div_ceil(u32::from(type_info.alignment));
+			if num_aligned_elements == 0 {
+				return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union is empty" });
+			}
+			let wrapped_type = match num_aligned_elements {
+				1 => alignment.to_string(),
+				_ => format!("[{}; {}]", alignment, num_aligned_elements),
+			};
+
+			// The first field gives the union the size and alignment of the typelib's union.
+			writeln!(out, "#[repr(C)]")?;
+			writeln!(out, "#[derive(Clone, Copy)]")?;
+			writeln!(out, "pub union {} {{", type_name)?;
+			writeln!(out, "    _alignment: {},", wrapped_type)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				writeln!(out, "    pub {}: {},", sanitize_reserved(&field.name, &location, build_result), type_to_string(&field.type_, types::PARAMFLAG_FOUT, &location, context, build_result)?)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+	}
+
+	Ok(())
+}
+
+/// An `[out, retval]` parameter for a property getter that returns the given type
+fn ret_val_param(type_: types::TypeDesc) -> types::Param {
+	types::Param {
+		name: "value".to_string(),
+		type_: types::TypeDesc::Ptr(Box::new(type_)),
+		flags: types::PARAMFLAG_FOUT | types::PARAMFLAG_FRETVAL,
+		default_value: None,
+	}
+}
+
+fn write_interface_start<W>(mut out: W, type_name: &str, guid: types::Guid, parent: Option<&str>) -> std::io::Result<()> where W: std::io::Write {
+	writeln!(out, "com::interfaces! {{")?;
+	writeln!(out, r#"    #[uuid("{}")]"#, guid)?;
+	match parent {
+		Some(parent) => writeln!(out, "    pub unsafe interface {}: {} {{", type_name, parent)?,
+		None => writeln!(out, "    pub unsafe interface {} {{", type_name)?,
+	}
+
+	Ok(())
+}
+
+fn write_interface_end<W>(mut out: W) -> std::io::Result<()> where W: std::io::Write {
+	writeln!(out, "    }}")?;
+	writeln!(out, "}}")?;
+	writeln!(out)?;
+
+	Ok(())
+}
+
+fn write_method<W>(
+	mut out: W,
+	name: &str,
+	params: &[types::Param],
+	return_type: &types::TypeDesc,
+	location: &Location,
+	context: &Context<'_>,
+	build_result: &mut BuildResult,
+) -> Result<(), crate::Error> where W: std::io::Write {
+	writeln!(out, "        pub unsafe fn {}(", name)?;
+	writeln!(out, "            &self,")?;
+
+	for (param_index, param) in params.iter().enumerate() {
+		writeln!(out, "            {}{}: {},",
+			if is_abi_transferable(&param.type_, context) { "" } else { "#[pass_through] " },
+			sanitize_reserved(&param.name, &location.param(param_index), build_result),
+			param_type_to_string(&param.type_, param.flags, &location.param(param_index), context, build_result)?)?;
+	}
+
+	match return_type {
+		types::TypeDesc::Base(types::VT_VOID) => writeln!(out, "        );")?,
+		return_type => writeln!(out, "        ) -> {};", type_to_string(return_type, types::PARAMFLAG_FOUT, location, context, build_result)?)?,
+	}
+
+	Ok(())
+}
+
+/// Formats the given type as Rust, for a parameter of a method of an interface. Interface pointers are `Option`s of the interfaces.
+fn param_type_to_string(type_: &types::TypeDesc, param_flags: u16, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Result<String, crate::Error> {
+	if let Some(interface) = interface_path(type_, location, context, build_result) {
+		return Ok(format!("Option<{}>", interface));
+	}
+
+	Ok(match type_ {
+		types::TypeDesc::Ptr(pointee) => {
+			let pointee = param_type_to_string(pointee, param_flags, location, context, build_result)?;
+			if (param_flags & types::PARAMFLAG_FIN) == types::PARAMFLAG_FIN && (param_flags & types::PARAMFLAG_FOUT) == 0 {
+				format!("*const {}", pointee)
+			}
+			else {
+				format!("*mut {}", pointee)
+			}
+		},
+
+		_ => type_to_string(type_, param_flags, location, context, build_result)?,
+	})
+}
+
+/// Formats the given type as Rust. Interface pointers are `*mut c_void`, so that records stay `Copy`.
+fn type_to_string(type_: &types::TypeDesc, param_flags: u16, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Result<String, crate::Error> {
+	Ok(match type_ {
+		types::TypeDesc::Ptr(pointee) => {
+			if let types::TypeDesc::UserDefined(type_ref) = &**pointee {
+				if is_interface(type_ref, context) {
+					return Ok("*mut core::ffi::c_void".to_string());
+				}
+			}
+
+			if (param_flags & types::PARAMFLAG_FIN) == types::PARAMFLAG_FIN && (param_flags & types::PARAMFLAG_FOUT) == 0 {
+				// [in] => *const
+				format!("*const {}", type_to_string(pointee, param_flags, location, context, build_result)?)
+			}
+			else {
+				// [in, out] => *mut
+				// [] => *mut (Some functions like IXMLError::GetErrorInfo don't annotate [out] on their out parameter)
+				format!("*mut {}", type_to_string(pointee, param_flags, location, context, build_result)?)
+			}
+		},
+
+		types::TypeDesc::SafeArray(element) => {
+			// The element type of the SAFEARRAY isn't part of the Rust type, so it's written next to it in a comment,
+			// and reported for the bindings that don't keep comments, like the ones from `build_tokens`
+			let element = match interface_path(element, location, context, build_result) {
+				Some(interface) => interface,
+				None => type_to_string(element, 0, location, context, build_result)?,
+			};
+			let type_name = "*mut SAFEARRAY";
+			build_result.lossy_type_mapping(type_, type_name, location);
+			format!("{} /* of {} */", type_name, element)
+		},
+
+		types::TypeDesc::CArray(element, dimensions) => {
+			let mut type_name = type_to_string(element, param_flags, location, context, build_result)?;
+
+			for dimension in dimensions {
+				type_name = format!("[{}; {}]", type_name, dimension);
+			}
+
+			type_name
+		},
+
+		types::TypeDesc::UserDefined(type_ref) => type_ref_path(type_ref, location, context, build_result),
+
+		types::TypeDesc::Base(vt) => match var_type_kind(*vt, location)? {
+			VarTypeKind::Expanded(type_) => type_to_string(&type_, param_flags, location, context, build_result)?,
+
+			VarTypeKind::Record => {
+				let type_name = "*mut core::ffi::c_void";
+				build_result.lossy_type_mapping(type_, type_name, location);
+				type_name.to_string()
+			},
+
+			VarTypeKind::WellKnown(well_known) => well_known_type_path(well_known).to_string(),
+		},
+	})
+}
+
+/// Whether the given type implements `com::AbiTransferable`, that is whether it's a primitive, a pointer or an interface
+fn is_abi_transferable(type_: &types::TypeDesc, context: &Context<'_>) -> bool {
+	match type_ {
+		types::TypeDesc::Ptr(_) |
+		types::TypeDesc::SafeArray(_) => true,
+
+		types::TypeDesc::CArray(..) => false,
+
+		types::TypeDesc::UserDefined(type_ref) => match resolve_type_info(type_ref, context) {
+			Some(type_info) => match type_info.typekind {
+				types::TypeKind::Enum => true,
+				types::TypeKind::Alias => type_info.alias_type.as_ref().is_some_and(|alias_type| is_abi_transferable(alias_type, context)),
+				_ => false,
+			},
+
+			None => false,
+		},
+
+		types::TypeDesc::Base(vt) =>
+			counted_array_name(*vt).is_none() &&
+			!matches!(*vt, types::VT_CY | types::VT_VARIANT | types::VT_DECIMAL | types::VT_FILETIME | types::VT_BLOB | types::VT_BLOB_OBJECT | types::VT_BSTR_BLOB),
+	}
+}
+
+/// Whether the given type reference refers to an interface or dispinterface
+fn is_interface(type_ref: &types::TypeRef, context: &Context<'_>) -> bool {
+	match resolve_type_info(type_ref, context) {
+		Some(type_info) => matches!(type_info.typekind, types::TypeKind::Interface | types::TypeKind::Dispatch),
+
+		// Only the well-known interfaces of stdole2.tlb have a vtable size without their typelib
+		None => context.type_lib.get_vtbl_size(type_ref).is_some(),
+	}
+}
+
+/// If the given type is a pointer to an interface, gets the path of the interface
+fn interface_path(type_: &types::TypeDesc, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Option<String> {
+	match type_ {
+		types::TypeDesc::Base(types::VT_DISPATCH) => Some(well_known_path("IDispatch", context)),
+		types::TypeDesc::Base(types::VT_UNKNOWN) => Some(well_known_path("IUnknown", context)),
+		types::TypeDesc::Base(types::VT_STREAM | types::VT_STREAMED_OBJECT | types::VT_STORAGE | types::VT_STORED_OBJECT) => {
+			// `com` doesn't have `IStream` and `IStorage`, so they're passed as the `IUnknown` that they derive from
+			let path = well_known_path("IUnknown", context);
+			build_result.lossy_type_mapping(type_, &path, location);
+			Some(path)
+		},
+		types::TypeDesc::Ptr(pointee) => match &**pointee {
+			types::TypeDesc::UserDefined(type_ref) if is_interface(type_ref, context) => Some(type_ref_path(type_ref, location, context, build_result)),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+fn well_known_type_path(well_known: WellKnownType) -> &'static str {
+	match well_known {
+		WellKnownType::Primitive(type_name) |
+		WellKnownType::CountedArray(type_name) => type_name,
+		WellKnownType::Void => "core::ffi::c_void",
+		WellKnownType::Int |
+		WellKnownType::Error => "i32",
+		WellKnownType::UInt => "u32",
+		WellKnownType::IntPtr => "isize",
+		WellKnownType::UIntPtr => "usize",
+		WellKnownType::Bool => "i16",
+		WellKnownType::Currency => "CY",
+		WellKnownType::Date => "DATE",
+		WellKnownType::Decimal => "DECIMAL",
+		WellKnownType::HResult => "com::sys::HRESULT",
+		WellKnownType::Bstr => "BSTR",
+		WellKnownType::BstrBlob => "BSTRBLOB",
+		WellKnownType::LpStr => "*mut u8",
+		WellKnownType::LpWStr => "*mut u16",
+		WellKnownType::Variant => "VARIANT",
+		WellKnownType::FileTime => "FILETIME",
+		WellKnownType::Blob => "BLOB",
+		WellKnownType::Clsid => "com::sys::GUID",
+		WellKnownType::Dispatch |
+		WellKnownType::Unknown |
+		WellKnownType::Stream |
+		WellKnownType::Storage => "*mut core::ffi::c_void",
+	}
+}
@@ -0,0 +1,190 @@
+/// Error kind
+#[derive(Debug)]
+pub enum Error {
+	/// An IO error while reading the typelib, or while writing the bindgen output to the [`std::io::Write`] given to [`crate::build`]
+	Io(std::io::Error),
+
+	/// The typelib is not in a format that this crate can read
+	UnsupportedFormat,
+
+	/// The PE image does not contain a `TYPELIB` resource with the given index
+	TypeLibResourceNotFound(u16),
+
+	/// The typelib is corrupt
+	Malformed {
+		/// The offset in the typelib at which the problem was detected
+		offset: usize,
+
+		/// A description of the problem
+		reason: &'static str,
+	},
+
+	/// The JSON snapshot is invalid
+	Snapshot(serde_json::Error),
+
+	/// The JSON snapshot was written with a different version of the snapshot format
+	UnsupportedSnapshotVersion(u32),
+
+	/// The IDL file could not be compiled
+	Idl {
+		/// The file that contains the problem. This is the given IDL file or a file that it imports.
+		path: std::path::PathBuf,
+		line: usize,
+		column: usize,
+
+		/// A description of the problem
+		message: String,
+	},
+
+	/// The typelib cannot be written in the MSFT format, such as because a name is longer than the format allows
+	Unwritable {
+		/// The typelib, typeinfo or member that cannot be written
+		name: String,
+
+		/// A description of the problem
+		reason: &'static str,
+	},
+
+	/// The emitted bindings, or the tokens given to [`crate::write_tokens`], are not a valid Rust file
+	Syntax(syn::Error),
+
+	/// Referenced types could not be found, and the [`crate::Builder`] was configured with [`crate::MissingTypePolicy::Error`]
+	MissingTypes(Vec<crate::MissingType>),
+
+	/// A type, member or parameter has a `VARTYPE` that bindings can't be emitted for
+	UnsupportedVarType {
+		location: Location,
+		vt: crate::types::VarType,
+	},
+
+	/// A type is used where its typekind isn't valid, such as a dispinterface parameter of a user-defined type that isn't an enum
+	UnexpectedTypeKind {
+		location: Location,
+		typekind: crate::types::TypeKind,
+	},
+
+	/// A function has a funckind that isn't valid for its type, such as a virtual function of a module
+	UnexpectedFuncKind {
+		location: Location,
+		func_kind: crate::types::FuncKind,
+	},
+
+	/// A type has a size or alignment that bindings can't be emitted for
+	UnsupportedLayout {
+		location: Location,
+
+		/// A description of the problem
+		reason: &'static str,
+	},
+
+	/// A type doesn't have what its typekind requires, such as an alias without a target or a dispinterface whose parent isn't `IDispatch`
+	Inconsistent {
+		location: Location,
+
+		/// A description of the problem
+		reason: &'static str,
+	},
+}
+
+/// Where in the typelib a problem was found
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Location {
+	/// The name of the type
+	pub type_name: String,
+
+	/// The name of the function, property, field or constant of the type, if the problem is in one
+	pub member_name: Option<String>,
+
+	/// The index of the parameter of the function, if the problem is in one
+	pub param_index: Option<usize>,
+}
+
+impl Location {
+	pub(crate) fn type_(type_name: &str) -> Self {
+		Location { type_name: type_name.to_string(), member_name: None, param_index: None }
+	}
+
+	pub(crate) fn member(type_name: &str, member_name: &str) -> Self {
+		Location { type_name: type_name.to_string(), member_name: Some(member_name.to_string()), param_index: None }
+	}
+
+	/// The location of the parameter with the given index of the function at this location
+	pub(crate) fn param(&self, param_index: usize) -> Self {
+		Location { param_index: Some(param_index), ..self.clone() }
+	}
+
+	/// The type or member, like `IFoo` or `IFoo::Bar`, as in [`crate::MissingType::referrer`]
+	pub(crate) fn referrer(&self) -> String {
+		match &self.member_name {
+			Some(member_name) => format!("{}::{}", self.type_name, member_name),
+			None => self.type_name.clone(),
+		}
+	}
+}
+
+impl std::fmt::Display for Location {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.referrer())?;
+		if let Some(param_index) = self.param_index {
+			write!(f, " parameter {}", param_index)?;
+		}
+		Ok(())
+	}
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::Io(err) => write!(f, "I/O error: {}", err),
+			Error::UnsupportedFormat => write!(f, "typelib is not in a supported format"),
+			Error::TypeLibResourceNotFound(index) => write!(f, "image does not contain a TYPELIB resource with index {}", index),
+			Error::Malformed { offset, reason } => write!(f, "malformed typelib at offset 0x{:08x}: {}", offset, reason),
+			Error::Snapshot(err) => write!(f, "invalid snapshot: {}", err),
+			Error::UnsupportedSnapshotVersion(version) =>
+				write!(f, "snapshot has format version {} but only version {} is supported", version, crate::SNAPSHOT_FORMAT_VERSION),
+			Error::Idl { path, line, column, message } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+			Error::Unwritable { name, reason } => write!(f, "cannot write {}: {}", name, reason),
+			Error::Syntax(err) => write!(f, "bindings are not valid Rust: {}", err),
+			Error::MissingTypes(missing_types) => {
+				write!(f, "{} referenced types could not be found", missing_types.len())?;
+				for missing_type in missing_types {
+					write!(f, "; {}", missing_type)?;
+				}
+				Ok(())
+			},
+			Error::UnsupportedVarType { location, vt } => write!(f, "{}: VARTYPE {} is not supported", location, crate::types::display_var_type(*vt)),
+			Error::UnexpectedTypeKind { location, typekind } => write!(f, "{}: unexpected typekind {:?}", location, typekind),
+			Error::UnexpectedFuncKind { location, func_kind } => write!(f, "{}: unexpected funckind {:?}", location, func_kind),
+			Error::UnsupportedLayout { location, reason } => write!(f, "{}: unsupported layout: {}", location, reason),
+			Error::Inconsistent { location, reason } => write!(f, "{}: {}", location, reason),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Io(err) => Some(err),
+			Error::Snapshot(err) => Some(err),
+			Error::Syntax(err) => Some(err),
+			Error::UnsupportedFormat |
+			Error::TypeLibResourceNotFound(_) |
+			Error::Malformed { .. } |
+			Error::UnsupportedSnapshotVersion(_) |
+			Error::Idl { .. } |
+			Error::Unwritable { .. } |
+			Error::MissingTypes(_) |
+			Error::UnsupportedVarType { .. } |
+			Error::UnexpectedTypeKind { .. } |
+			Error::UnexpectedFuncKind { .. } |
+			Error::UnsupportedLayout { .. } |
+			Error::Inconsistent { .. } => None,
+		}
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(err: std::io::Error) -> Self {
+		Error::Io(err)
+	}
+}
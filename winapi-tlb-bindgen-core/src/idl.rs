@@ -0,0 +1,2562 @@
+//! A compiler for IDL files, so that bindings can be generated without running `midl.exe` first.
+//!
+//! This understands the subset of MIDL that ends up in a typelib - the `library` block, and the interfaces, dispinterfaces, coclasses,
+//! modules, enums, structs, unions and typedefs that it defines or references - and lays out types the way `midl.exe` does.
+//!
+//! Preprocessor directives are ignored, except for `#define`s of constants. Files named by `import` are only parsed
+//! when a type can't be found in the IDL file itself, in the builtin types, or in the typelibs named by `importlib`.
+//!
+//! It can also decompile a typelib back to IDL, so that what's in a typelib can be read without `oleview.exe`.
+
+use crate::types;
+
+/// Whether the file at the given path should be compiled as IDL rather than read as a typelib.
+pub(crate) fn is_idl(filename: &std::path::Path) -> bool {
+	filename.extension().and_then(std::ffi::OsStr::to_str).is_some_and(|extension| extension.eq_ignore_ascii_case("idl") || extension.eq_ignore_ascii_case("odl"))
+}
+
+/// Compiles the IDL file at the given path into the typelib that `midl.exe` would generate for the given platform.
+/// The paths of the files that were read, including imported IDL files and typelibs, are added to `files`.
+pub(crate) fn read(filename: &std::path::Path, syskind: types::SysKind, files: &mut Vec<std::path::PathBuf>) -> Result<types::TypeLib, crate::Error> {
+	let mut compiler = Compiler::new(syskind);
+
+	let items = compiler.parse_file(filename)?;
+
+	let mut library = None;
+	for item in items {
+		match item {
+			Item::Def(def) => compiler.define(def)?,
+			Item::Library(l) =>
+				if library.is_some() {
+					return Err(compiler.error(l.location, "only one library can be defined"));
+				}
+				else {
+					library = Some(l);
+				},
+			Item::ImportLib(_, _) | Item::Forward(_, _) => (),
+		}
+	}
+
+	let Library { attributes, name, location, items } = library.ok_or_else(|| compiler.error(Location { file: 0, line: 1, column: 1 }, "no library is defined"))?;
+
+	let mut members = vec![];
+	for item in items {
+		match item {
+			Item::Def(def) => {
+				members.push((def.name.clone(), def.location));
+				compiler.define(def)?;
+			},
+			Item::Forward(name, location) => members.push((name, location)),
+			Item::ImportLib(type_lib_name, location) => compiler.import_lib(&type_lib_name, location)?,
+			Item::Library(l) => return Err(compiler.error(l.location, "libraries cannot be nested")),
+		}
+	}
+
+	// Everything in the library block is in the typelib, in order. Types defined outside it are added as they're referenced.
+	for (name, location) in &members {
+		if compiler.local(name).is_none() {
+			return Err(compiler.error(*location, format!("{} is not defined", name)));
+		}
+	}
+
+	let mut index = 0;
+	while index < compiler.names.len() {
+		compiler.build(index)?;
+		index += 1;
+	}
+
+	let guid = attributes.uuid.ok_or_else(|| compiler.error(location, format!("library {} does not have a uuid", name)))?;
+
+	files.extend(compiler.files);
+	files.extend(compiler.imported_type_lib_files);
+
+	Ok(types::TypeLib {
+		name,
+		doc_string: attributes.help_string.clone(),
+		help_file: attributes.help_file.clone(),
+		help_context: attributes.help_context.unwrap_or(0),
+		guid,
+		lcid: attributes.lcid.unwrap_or(0),
+		version: attributes.version.unwrap_or_default(),
+		flags: attributes.flags(LIBFLAGS) | types::LIBFLAG_FHASDISKIMAGE,
+		syskind,
+		imports: compiler.imports,
+		type_infos: compiler.type_infos.into_iter().map(Option::unwrap).collect(),
+	})
+}
+
+/// Where something is in an IDL file
+#[derive(Clone, Copy, Debug)]
+struct Location {
+	/// Index into [`Compiler::files`]
+	file: usize,
+	line: usize,
+	column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+	Ident(String),
+	Number(String),
+	Str(String),
+	Char(i64),
+	Punct(&'static str),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+	kind: TokenKind,
+	location: Location,
+}
+
+/// Longer punctuation first, so that it is matched in preference to its prefixes
+const PUNCTUATION: &[&str] = &[
+	"...", "<<", ">>", "::",
+	"(", ")", "[", "]", "{", "}", ";", ",", ":", "=", "*", "&", "|", "^", "~", "!", "+", "-", "/", "%", "<", ">", "?", ".",
+];
+
+/// The tokens of a file, and the `#define`s in it
+struct Lexed {
+	tokens: Vec<Token>,
+	defines: Vec<(String, Vec<Token>)>,
+}
+
+fn lex(source: &str, file: usize, path: &std::path::Path) -> Result<Lexed, crate::Error> {
+	let error = |location: Location, message: &str| crate::Error::Idl { path: path.to_owned(), line: location.line, column: location.column, message: message.to_string() };
+
+	let bytes = source.as_bytes();
+
+	let mut tokens = vec![];
+	let mut defines = vec![];
+
+	let mut pos = 0;
+	let mut line = 1;
+	let mut line_start = 0;
+	let mut at_line_start = true;
+
+	while pos < bytes.len() {
+		let location = Location { file, line, column: pos - line_start + 1 };
+
+		match bytes[pos] {
+			b'\n' => {
+				pos += 1;
+				line += 1;
+				line_start = pos;
+				at_line_start = true;
+			},
+
+			b' ' | b'\t' | b'\r' | 0x0C => pos += 1,
+
+			b'/' if bytes.get(pos + 1) == Some(&b'/') =>
+				while pos < bytes.len() && bytes[pos] != b'\n' {
+					pos += 1;
+				},
+
+			b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+				pos += 2;
+				loop {
+					match bytes.get(pos) {
+						Some(b'*') if bytes.get(pos + 1) == Some(&b'/') => {
+							pos += 2;
+							break;
+						},
+						Some(b'\n') => {
+							pos += 1;
+							line += 1;
+							line_start = pos;
+						},
+						Some(_) => pos += 1,
+						None => return Err(error(location, "unterminated comment")),
+					}
+				}
+			},
+
+			b'#' if at_line_start => {
+				// A preprocessor directive, which runs to the end of the line unless the line ends with a backslash
+				let start = pos + 1;
+				while pos < bytes.len() && !(bytes[pos] == b'\n' && bytes[pos - 1] != b'\\') {
+					if bytes[pos] == b'\n' {
+						line += 1;
+						line_start = pos + 1;
+					}
+					pos += 1;
+				}
+
+				let directive = source[start..pos].replace("\\\n", " ");
+				let directive = directive.trim_start();
+				if let Some(definition) = directive.strip_prefix("define") {
+					let definition = definition.trim_start();
+					let name_len = definition.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(definition.len());
+					let (name, value) = definition.split_at(name_len);
+
+					// Function-like macros can't be expanded, and values that don't lex are of no use either
+					if !name.is_empty() && !value.starts_with('(') {
+						if let Ok(value) = lex(value, file, path) {
+							let value = value.tokens.into_iter().map(|token| Token { location, ..token }).collect();
+							defines.push((name.to_string(), value));
+						}
+					}
+				}
+			},
+
+			b'"' => {
+				let (s, end) = lex_string(bytes, pos + 1, b'"').ok_or_else(|| error(location, "unterminated string"))?;
+				tokens.push(Token { kind: TokenKind::Str(s), location });
+				pos = end;
+				at_line_start = false;
+			},
+
+			b'L' if bytes.get(pos + 1) == Some(&b'"') => {
+				let (s, end) = lex_string(bytes, pos + 2, b'"').ok_or_else(|| error(location, "unterminated string"))?;
+				tokens.push(Token { kind: TokenKind::Str(s), location });
+				pos = end;
+				at_line_start = false;
+			},
+
+			b'\'' => {
+				let (s, end) = lex_string(bytes, pos + 1, b'\'').ok_or_else(|| error(location, "unterminated character"))?;
+				let mut chars = s.chars();
+				let (Some(c), None) = (chars.next(), chars.next()) else {
+					return Err(error(location, "character literals must contain exactly one character"));
+				};
+				tokens.push(Token { kind: TokenKind::Char(i64::from(u32::from(c))), location });
+				pos = end;
+				at_line_start = false;
+			},
+
+			b if b.is_ascii_alphabetic() || b == b'_' => {
+				let start = pos;
+				while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+					pos += 1;
+				}
+				tokens.push(Token { kind: TokenKind::Ident(source[start..pos].to_string()), location });
+				at_line_start = false;
+			},
+
+			b if b.is_ascii_digit() => {
+				// Also consumes the parts of unquoted GUIDs that start with digits, like `11d0`
+				let start = pos;
+				while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_' || bytes[pos] == b'.') {
+					pos += 1;
+				}
+				tokens.push(Token { kind: TokenKind::Number(source[start..pos].to_string()), location });
+				at_line_start = false;
+			},
+
+			_ => {
+				let punct = PUNCTUATION.iter().find(|punct| source[pos..].starts_with(**punct)).ok_or_else(|| error(location, "unexpected character"))?;
+				tokens.push(Token { kind: TokenKind::Punct(punct), location });
+				pos += punct.len();
+				at_line_start = false;
+			},
+		}
+	}
+
+	Ok(Lexed { tokens, defines })
+}
+
+/// Lexes the contents of a string or character literal that starts at the given position, up to the given terminator.
+///
+/// Returns the contents, and the position after the terminator.
+fn lex_string(bytes: &[u8], mut pos: usize, terminator: u8) -> Option<(String, usize)> {
+	let mut result = vec![];
+
+	loop {
+		match *bytes.get(pos)? {
+			b'\n' => return None,
+			b if b == terminator => return Some((String::from_utf8_lossy(&result).into_owned(), pos + 1)),
+			b'\\' => {
+				pos += 1;
+				match *bytes.get(pos)? {
+					b'n' => result.push(b'\n'),
+					b't' => result.push(b'\t'),
+					b'r' => result.push(b'\r'),
+					b'0' => result.push(b'\0'),
+					b => result.push(b),
+				}
+				pos += 1;
+			},
+			b => {
+				result.push(b);
+				pos += 1;
+			},
+		}
+	}
+}
+
+/// The value of a constant expression
+#[derive(Clone, Debug)]
+enum Value {
+	Int(i64),
+	Float(f64),
+	Str(String),
+}
+
+/// The attributes in square brackets before a definition, member or parameter
+#[derive(Clone, Debug, Default)]
+struct Attributes {
+	uuid: Option<types::Guid>,
+	version: Option<types::Version>,
+	lcid: Option<u32>,
+	help_string: Option<String>,
+	help_file: Option<String>,
+	help_context: Option<u32>,
+	id: Option<i32>,
+	dll_name: Option<String>,
+	entry: Option<types::EntryPoint>,
+	default_value: Option<Value>,
+
+	/// Attributes without arguments, like `dual` and `propget`
+	words: Vec<String>,
+}
+
+impl Attributes {
+	fn has(&self, word: &str) -> bool {
+		self.words.iter().any(|w| w == word)
+	}
+
+	fn flags(&self, table: &[(&str, u16)]) -> u16 {
+		table.iter()
+			.filter(|(word, _)| self.has(word))
+			.fold(0, |flags, (_, flag)| flags | flag)
+	}
+}
+
+const LIBFLAGS: &[(&str, u16)] = &[
+	("restricted", types::LIBFLAG_FRESTRICTED),
+	("control", types::LIBFLAG_FCONTROL),
+	("hidden", types::LIBFLAG_FHIDDEN),
+];
+
+const TYPEFLAGS: &[(&str, u16)] = &[
+	("appobject", types::TYPEFLAG_FAPPOBJECT),
+	("licensed", types::TYPEFLAG_FLICENSED),
+	("predeclid", types::TYPEFLAG_FPREDECLID),
+	("hidden", types::TYPEFLAG_FHIDDEN),
+	("control", types::TYPEFLAG_FCONTROL),
+	("dual", types::TYPEFLAG_FDUAL),
+	("nonextensible", types::TYPEFLAG_FNONEXTENSIBLE),
+	("oleautomation", types::TYPEFLAG_FOLEAUTOMATION),
+	("restricted", types::TYPEFLAG_FRESTRICTED),
+	("aggregatable", types::TYPEFLAG_FAGGREGATABLE),
+	("replaceable", types::TYPEFLAG_FREPLACEABLE),
+	("proxy", types::TYPEFLAG_FPROXY),
+];
+
+const FUNCFLAGS: &[(&str, u16)] = &[
+	("restricted", types::FUNCFLAG_FRESTRICTED),
+	("source", types::FUNCFLAG_FSOURCE),
+	("bindable", types::FUNCFLAG_FBINDABLE),
+	("requestedit", types::FUNCFLAG_FREQUESTEDIT),
+	("displaybind", types::FUNCFLAG_FDISPLAYBIND),
+	("defaultbind", types::FUNCFLAG_FDEFAULTBIND),
+	("hidden", types::FUNCFLAG_FHIDDEN),
+	("usesgetlasterror", types::FUNCFLAG_FUSESGETLASTERROR),
+	("defaultcollelem", types::FUNCFLAG_FDEFAULTCOLLELEM),
+	("uidefault", types::FUNCFLAG_FUIDEFAULT),
+	("nonbrowsable", types::FUNCFLAG_FNONBROWSABLE),
+	("replaceable", types::FUNCFLAG_FREPLACEABLE),
+	("immediatebind", types::FUNCFLAG_FIMMEDIATEBIND),
+];
+
+const VARFLAGS: &[(&str, u16)] = &[
+	("readonly", types::VARFLAG_FREADONLY),
+	("source", types::VARFLAG_FSOURCE),
+	("bindable", types::VARFLAG_FBINDABLE),
+	("requestedit", types::VARFLAG_FREQUESTEDIT),
+	("displaybind", types::VARFLAG_FDISPLAYBIND),
+	("defaultbind", types::VARFLAG_FDEFAULTBIND),
+	("hidden", types::VARFLAG_FHIDDEN),
+	("restricted", types::VARFLAG_FRESTRICTED),
+	("defaultcollelem", types::VARFLAG_FDEFAULTCOLLELEM),
+	("uidefault", types::VARFLAG_FUIDEFAULT),
+	("nonbrowsable", types::VARFLAG_FNONBROWSABLE),
+	("replaceable", types::VARFLAG_FREPLACEABLE),
+	("immediatebind", types::VARFLAG_FIMMEDIATEBIND),
+];
+
+const PARAMFLAGS: &[(&str, u16)] = &[
+	("in", types::PARAMFLAG_FIN),
+	("out", types::PARAMFLAG_FOUT),
+	("lcid", types::PARAMFLAG_FLCID),
+	("retval", types::PARAMFLAG_FRETVAL),
+	("optional", types::PARAMFLAG_FOPT),
+];
+
+const IMPLTYPEFLAGS: &[(&str, u16)] = &[
+	("default", types::IMPLTYPEFLAG_FDEFAULT),
+	("source", types::IMPLTYPEFLAG_FSOURCE),
+	("restricted", types::IMPLTYPEFLAG_FRESTRICTED),
+	("defaultvtable", types::IMPLTYPEFLAG_FDEFAULTVTABLE),
+];
+
+/// A type as written in the IDL, such as `const BSTR *`
+#[derive(Clone, Debug)]
+struct TypeExpr {
+	base: BaseType,
+	pointers: usize,
+
+	/// Array dimensions, outermost first
+	dimensions: Vec<u32>,
+	location: Location,
+}
+
+#[derive(Clone, Debug)]
+enum BaseType {
+	Named(String),
+	SafeArray(Box<TypeExpr>),
+}
+
+/// A top-level or library-level item
+#[derive(Debug)]
+enum Item {
+	Library(Library),
+	Def(Def),
+	ImportLib(String, Location),
+
+	/// A forward declaration like `interface IFoo;`, which puts the type in the typelib when it appears in the library block
+	Forward(String, Location),
+}
+
+#[derive(Debug)]
+struct Library {
+	attributes: Attributes,
+	name: String,
+	location: Location,
+	items: Vec<Item>,
+}
+
+/// The definition of something that becomes a typeinfo
+#[derive(Clone, Debug)]
+struct Def {
+	attributes: Attributes,
+	name: String,
+	location: Location,
+	kind: DefKind,
+}
+
+#[derive(Clone, Debug)]
+enum DefKind {
+	Interface { parent: Option<(String, Location)>, methods: Vec<Method> },
+	DispInterface { properties: Vec<Field>, methods: Vec<Method>, interface: Option<(String, Location)> },
+	CoClass { interfaces: Vec<(Attributes, String, Location)> },
+	Module { methods: Vec<Method>, constants: Vec<Constant> },
+	Enum { members: Vec<EnumMember> },
+	Record { fields: Vec<Field> },
+	Union { fields: Vec<Field> },
+	Alias { type_: TypeExpr },
+}
+
+#[derive(Clone, Debug)]
+struct Method {
+	attributes: Attributes,
+	return_type: TypeExpr,
+	call_conv: Option<types::CallConv>,
+	name: String,
+	params: Vec<Param>,
+}
+
+#[derive(Clone, Debug)]
+struct Param {
+	attributes: Attributes,
+	type_: TypeExpr,
+	name: Option<String>,
+	location: Location,
+}
+
+/// A field of a struct or union, or a property of a dispinterface
+#[derive(Clone, Debug)]
+struct Field {
+	attributes: Attributes,
+	type_: TypeExpr,
+	name: String,
+}
+
+#[derive(Clone, Debug)]
+struct EnumMember {
+	attributes: Attributes,
+	name: String,
+	value: i64,
+}
+
+#[derive(Clone, Debug)]
+struct Constant {
+	attributes: Attributes,
+	type_: TypeExpr,
+	name: String,
+	value: Value,
+}
+
+struct Parser<'a> {
+	tokens: Vec<Token>,
+	pos: usize,
+	path: &'a std::path::Path,
+
+	/// The location reported for errors at the end of the file
+	end: Location,
+
+	/// `#define`s, `const`s and enum members, for evaluating constant expressions
+	constants: &'a mut std::collections::HashMap<String, Value>,
+
+	/// The files named by `import`
+	imports: Vec<String>,
+}
+
+impl Parser<'_> {
+	fn error(&self, location: Location, message: impl Into<String>) -> crate::Error {
+		crate::Error::Idl { path: self.path.to_owned(), line: location.line, column: location.column, message: message.into() }
+	}
+
+	fn peek(&self) -> Option<&TokenKind> {
+		self.tokens.get(self.pos).map(|token| &token.kind)
+	}
+
+	fn peek_at(&self, offset: usize) -> Option<&TokenKind> {
+		self.tokens.get(self.pos + offset).map(|token| &token.kind)
+	}
+
+	fn location(&self) -> Location {
+		self.tokens.get(self.pos).map_or(self.end, |token| token.location)
+	}
+
+	fn next(&mut self) -> Result<Token, crate::Error> {
+		let token = self.tokens.get(self.pos).cloned().ok_or_else(|| self.error(self.end, "unexpected end of file"))?;
+		self.pos += 1;
+		Ok(token)
+	}
+
+	fn is_punct(&self, punct: &str) -> bool {
+		matches!(self.peek(), Some(TokenKind::Punct(p)) if *p == punct)
+	}
+
+	fn is_ident(&self, ident: &str) -> bool {
+		matches!(self.peek(), Some(TokenKind::Ident(i)) if i == ident)
+	}
+
+	fn eat_punct(&mut self, punct: &str) -> bool {
+		let result = self.is_punct(punct);
+		if result {
+			self.pos += 1;
+		}
+		result
+	}
+
+	fn eat_ident(&mut self, ident: &str) -> bool {
+		let result = self.is_ident(ident);
+		if result {
+			self.pos += 1;
+		}
+		result
+	}
+
+	fn expect_punct(&mut self, punct: &str) -> Result<(), crate::Error> {
+		if self.eat_punct(punct) {
+			Ok(())
+		}
+		else {
+			Err(self.error(self.location(), format!("expected `{}`", punct)))
+		}
+	}
+
+	fn ident(&mut self) -> Result<(String, Location), crate::Error> {
+		let location = self.location();
+		match self.next()?.kind {
+			TokenKind::Ident(ident) => Ok((ident, location)),
+			_ => Err(self.error(location, "expected an identifier")),
+		}
+	}
+
+	fn string(&mut self) -> Result<String, crate::Error> {
+		let location = self.location();
+		let TokenKind::Str(mut result) = self.next()?.kind else {
+			return Err(self.error(location, "expected a string"));
+		};
+
+		// Adjacent strings are concatenated
+		while let Some(TokenKind::Str(s)) = self.peek() {
+			result.push_str(s);
+			self.pos += 1;
+		}
+
+		Ok(result)
+	}
+
+	/// Skips tokens up to and including the `)` that matches an already-consumed `(`
+	fn skip_parenthesized(&mut self) -> Result<(), crate::Error> {
+		let mut depth = 1;
+		while depth > 0 {
+			match self.next()?.kind {
+				TokenKind::Punct("(") => depth += 1,
+				TokenKind::Punct(")") => depth -= 1,
+				_ => (),
+			}
+		}
+		Ok(())
+	}
+
+	/// Items at the top level of a file, or in a library block
+	fn items(&mut self, in_library: bool) -> Result<Vec<Item>, crate::Error> {
+		let mut items = vec![];
+
+		loop {
+			if in_library && self.eat_punct("}") {
+				break;
+			}
+			if self.peek().is_none() {
+				if in_library {
+					return Err(self.error(self.end, "expected `}`"));
+				}
+				break;
+			}
+
+			if self.eat_punct(";") || self.skip_directive()? {
+				continue;
+			}
+
+			if self.eat_ident("import") {
+				loop {
+					let file_name = self.string()?;
+					self.imports.push(file_name);
+					if !self.eat_punct(",") {
+						break;
+					}
+				}
+				self.expect_punct(";")?;
+				continue;
+			}
+
+			if self.eat_ident("importlib") {
+				let location = self.location();
+				self.expect_punct("(")?;
+				let file_name = self.string()?;
+				self.expect_punct(")")?;
+				self.expect_punct(";")?;
+				items.push(Item::ImportLib(file_name, location));
+				continue;
+			}
+
+			if self.is_ident("const") {
+				self.constant(Attributes::default())?;
+				continue;
+			}
+
+			if self.is_ident("typedef") {
+				items.extend(self.typedef()?.into_iter().map(Item::Def));
+				continue;
+			}
+
+			let attributes = self.attributes()?;
+			let (keyword, location) = self.ident()?;
+			match &*keyword {
+				"library" if !in_library => {
+					let (name, _) = self.ident()?;
+					self.expect_punct("{")?;
+					let library_items = self.items(true)?;
+					self.eat_punct(";");
+					items.push(Item::Library(Library { attributes, name, location, items: library_items }));
+				},
+
+				"interface" | "dispinterface" | "coclass" if matches!(self.peek_at(1), Some(TokenKind::Punct(";"))) => {
+					let (name, location) = self.ident()?;
+					self.expect_punct(";")?;
+					items.push(Item::Forward(name, location));
+				},
+
+				"interface" => items.push(Item::Def(self.interface(attributes)?)),
+				"dispinterface" => items.push(Item::Def(self.dispinterface(attributes)?)),
+				"coclass" => items.push(Item::Def(self.coclass(attributes)?)),
+				"module" => items.push(Item::Def(self.module(attributes)?)),
+
+				"struct" | "union" | "enum" => {
+					let (name, location) = self.ident()?;
+					if self.is_punct("{") {
+						let kind = self.definition_body(&keyword)?;
+						items.push(Item::Def(Def { attributes, name, location, kind }));
+					}
+					else {
+						items.push(Item::Forward(name, location));
+					}
+					self.expect_punct(";")?;
+				},
+
+				_ => return Err(self.error(location, format!("unexpected `{}`", keyword))),
+			}
+		}
+
+		Ok(items)
+	}
+
+	/// Skips `cpp_quote(...)` and `midl_pragma warning(...)`, which don't affect the typelib
+	fn skip_directive(&mut self) -> Result<bool, crate::Error> {
+		if self.eat_ident("cpp_quote") {
+			self.expect_punct("(")?;
+			self.skip_parenthesized()?;
+			Ok(true)
+		}
+		else if self.eat_ident("midl_pragma") {
+			let _ = self.ident()?;
+			self.expect_punct("(")?;
+			self.skip_parenthesized()?;
+			Ok(true)
+		}
+		else {
+			Ok(false)
+		}
+	}
+
+	fn attributes(&mut self) -> Result<Attributes, crate::Error> {
+		let mut attributes = Attributes::default();
+
+		if !self.eat_punct("[") {
+			return Ok(attributes);
+		}
+
+		if self.eat_punct("]") {
+			return Ok(attributes);
+		}
+
+		loop {
+			let (name, location) = self.ident()?;
+
+			if self.eat_punct("(") {
+				match &*name {
+					"uuid" => {
+						let uuid =
+							if let Some(TokenKind::Str(_)) = self.peek() {
+								self.string()?
+							}
+							else {
+								// An unquoted GUID is lexed as a sequence of identifiers, numbers and `-`s
+								let mut uuid = String::new();
+								while !self.is_punct(")") {
+									match self.next()?.kind {
+										TokenKind::Ident(s) | TokenKind::Number(s) => uuid.push_str(&s),
+										TokenKind::Punct("-") => uuid.push('-'),
+										_ => return Err(self.error(location, "invalid uuid")),
+									}
+								}
+								uuid
+							};
+						attributes.uuid = Some(uuid.parse().map_err(|()| self.error(location, "invalid uuid"))?);
+					},
+
+					"version" => {
+						let version_location = self.location();
+						let TokenKind::Number(version) = self.next()?.kind else {
+							return Err(self.error(version_location, "invalid version"));
+						};
+						let mut parts = version.splitn(2, '.').map(str::parse);
+						let version = match (parts.next(), parts.next()) {
+							(Some(Ok(major)), None) => types::Version { major, minor: 0 },
+							(Some(Ok(major)), Some(Ok(minor))) => types::Version { major, minor },
+							_ => return Err(self.error(version_location, "invalid version")),
+						};
+						attributes.version = Some(version);
+					},
+
+					"lcid" => attributes.lcid = Some(self.int_expr()? as u32),
+					"helpstring" => attributes.help_string = Some(self.string()?),
+					"helpfile" => attributes.help_file = Some(self.string()?),
+					"helpcontext" => attributes.help_context = Some(self.int_expr()? as u32),
+					"id" => attributes.id = Some(self.int_expr()? as i32),
+					"dllname" => attributes.dll_name = Some(self.string()?),
+
+					"entry" =>
+						attributes.entry = Some(
+							if let Some(TokenKind::Str(_)) = self.peek() {
+								types::EntryPoint::Name(self.string()?)
+							}
+							else {
+								types::EntryPoint::Ordinal(self.int_expr()? as u16)
+							}),
+
+					"defaultvalue" => attributes.default_value = Some(self.expr()?),
+
+					// Other attributes with arguments, like `size_is(...)` and `custom(...)`, don't affect the typelib
+					_ => {
+						self.skip_parenthesized()?;
+						if !self.eat_punct(",") {
+							self.expect_punct("]")?;
+							break;
+						}
+						continue;
+					},
+				}
+
+				self.expect_punct(")")?;
+			}
+			else {
+				attributes.words.push(name);
+			}
+
+			if !self.eat_punct(",") {
+				self.expect_punct("]")?;
+				break;
+			}
+		}
+
+		Ok(attributes)
+	}
+
+	/// Parses a type up to but not including the declarator
+	fn type_expr(&mut self) -> Result<TypeExpr, crate::Error> {
+		let location = self.location();
+
+		self.skip_qualifiers();
+
+		let base =
+			if self.eat_ident("SAFEARRAY") {
+				self.expect_punct("(")?;
+				let element = self.type_expr()?;
+				self.expect_punct(")")?;
+				BaseType::SafeArray(Box::new(element))
+			}
+			else {
+				let (first, first_location) = self.ident()?;
+				let name = match &*first {
+					"struct" | "union" | "enum" | "interface" | "dispinterface" | "coclass" => {
+						let (name, _) = self.ident()?;
+						if self.is_punct("{") {
+							return Err(self.error(first_location, format!("nested {} definitions are not supported", first)));
+						}
+						name
+					},
+
+					"unsigned" | "signed" => {
+						let mut name =
+							match self.peek() {
+								Some(TokenKind::Ident(next)) if is_integer_type(next) => self.ident()?.0,
+								_ => "int".to_string(),
+							};
+						if name == "long" && self.eat_ident("long") {
+							name = "hyper".to_string();
+						}
+						else if (name == "long" || name == "short") && self.is_ident("int") {
+							self.pos += 1;
+						}
+
+						if first == "unsigned" {
+							format!("unsigned {}", name)
+						}
+						else {
+							name
+						}
+					},
+
+					"long" if self.eat_ident("long") => "hyper".to_string(),
+
+					"long" | "short" if self.is_ident("int") => {
+						self.pos += 1;
+						first
+					},
+
+					_ => first,
+				};
+
+				BaseType::Named(name)
+			};
+
+		self.skip_qualifiers();
+
+		let mut pointers = 0;
+		while self.eat_punct("*") {
+			pointers += 1;
+			self.skip_qualifiers();
+		}
+
+		Ok(TypeExpr { base, pointers, dimensions: vec![], location })
+	}
+
+	fn skip_qualifiers(&mut self) {
+		while let Some(TokenKind::Ident(ident)) = self.peek() {
+			if matches!(&**ident, "const" | "volatile" | "__RPC_FAR" | "__ptr32" | "__ptr64") {
+				self.pos += 1;
+			}
+			else {
+				break;
+			}
+		}
+	}
+
+	/// Parses a declarator, like the `*ppFoo` in `IFoo *ppFoo` or the `points[4]` in `Point points[4]`, and applies it to the given type.
+	///
+	/// The name is optional, since parameters may omit it.
+	fn declarator(&mut self, mut type_: TypeExpr) -> Result<(Option<(String, Location)>, TypeExpr), crate::Error> {
+		while self.eat_punct("*") {
+			type_.pointers += 1;
+			self.skip_qualifiers();
+		}
+
+		let name =
+			if let Some(TokenKind::Ident(_)) = self.peek() {
+				Some(self.ident()?)
+			}
+			else {
+				None
+			};
+
+		while self.eat_punct("[") {
+			let location = self.location();
+			if self.is_punct("]") {
+				return Err(self.error(location, "arrays without a size are not supported"));
+			}
+			let dimension = self.int_expr()?;
+			let dimension = <u32 as std::convert::TryFrom<_>>::try_from(dimension).map_err(|_| self.error(location, "invalid array size"))?;
+			type_.dimensions.push(dimension);
+			self.expect_punct("]")?;
+		}
+
+		Ok((name, type_))
+	}
+
+	fn named_declarator(&mut self, type_: TypeExpr) -> Result<(String, Location, TypeExpr), crate::Error> {
+		let location = self.location();
+		match self.declarator(type_)? {
+			(Some((name, location)), type_) => Ok((name, location, type_)),
+			(None, _) => Err(self.error(location, "expected a name")),
+		}
+	}
+
+	fn typedef(&mut self) -> Result<Vec<Def>, crate::Error> {
+		let typedef_location = self.location();
+		self.pos += 1; // typedef
+
+		let mut attributes = self.attributes()?;
+		self.skip_qualifiers();
+
+		// A typedef of a struct, union or enum may define it in place
+		let definition =
+			match (self.peek(), self.peek_at(1), self.peek_at(2)) {
+				(Some(TokenKind::Ident(keyword)), Some(TokenKind::Punct("{")), _) if matches!(&**keyword, "struct" | "union" | "enum") => {
+					let keyword = keyword.clone();
+					self.pos += 1;
+					let kind = self.definition_body(&keyword)?;
+					Some((None, kind))
+				},
+
+				(Some(TokenKind::Ident(keyword)), Some(TokenKind::Ident(tag)), Some(TokenKind::Punct("{"))) if matches!(&**keyword, "struct" | "union" | "enum") => {
+					let keyword = keyword.clone();
+					let tag = (tag.clone(), self.tokens[self.pos + 1].location);
+					self.pos += 2;
+					let kind = self.definition_body(&keyword)?;
+					Some((Some(tag), kind))
+				},
+
+				_ => None,
+			};
+
+		let mut declarators = vec![];
+		let base_type =
+			if definition.is_some() {
+				// Filled in below, once the name of the definition is known
+				TypeExpr { base: BaseType::Named(String::new()), pointers: 0, dimensions: vec![], location: typedef_location }
+			}
+			else {
+				self.type_expr()?
+			};
+		loop {
+			declarators.push(self.named_declarator(base_type.clone())?);
+			if !self.eat_punct(",") {
+				break;
+			}
+		}
+		self.expect_punct(";")?;
+
+		let mut defs = vec![];
+
+		if let Some((tag, kind)) = definition {
+			// Like midl, the definition is named after its tag if it has one, else after the first declarator.
+			let (name, location) = match tag {
+				Some(tag) => tag,
+				None => match declarators.first() {
+					Some((name, location, type_)) if type_.pointers == 0 && type_.dimensions.is_empty() => (name.clone(), *location),
+					_ => return Err(self.error(typedef_location, "anonymous definitions must be named by the first declarator")),
+				},
+			};
+
+			for (_, _, type_) in &mut declarators {
+				type_.base = BaseType::Named(name.clone());
+			}
+
+			defs.push(Def { attributes: std::mem::take(&mut attributes), name, location, kind });
+		}
+
+		for (name, location, type_) in declarators {
+			// `typedef struct Foo {...} Foo;` and `typedef interface IFoo IFoo;` don't define aliases
+			if matches!(&type_.base, BaseType::Named(base) if *base == name) && type_.pointers == 0 && type_.dimensions.is_empty() {
+				continue;
+			}
+
+			defs.push(Def { attributes: attributes.clone(), name, location, kind: DefKind::Alias { type_ } });
+		}
+
+		Ok(defs)
+	}
+
+	/// Parses the `{...}` body of a struct, union or enum
+	fn definition_body(&mut self, keyword: &str) -> Result<DefKind, crate::Error> {
+		self.expect_punct("{")?;
+
+		if keyword == "enum" {
+			let mut members = vec![];
+			let mut next_value = 0_i64;
+
+			while !self.eat_punct("}") {
+				let attributes = self.attributes()?;
+				let (name, _) = self.ident()?;
+				let value =
+					if self.eat_punct("=") {
+						self.int_expr()?
+					}
+					else {
+						next_value
+					};
+				next_value = value.wrapping_add(1);
+
+				self.constants.insert(name.clone(), Value::Int(value));
+				members.push(EnumMember { attributes, name, value });
+
+				if !self.eat_punct(",") {
+					self.expect_punct("}")?;
+					break;
+				}
+			}
+
+			Ok(DefKind::Enum { members })
+		}
+		else {
+			let mut fields = vec![];
+
+			while !self.eat_punct("}") {
+				let attributes = self.attributes()?;
+				let type_ = self.type_expr()?;
+				loop {
+					let (name, _, type_) = self.named_declarator(type_.clone())?;
+					fields.push(Field { attributes: attributes.clone(), type_, name });
+					if !self.eat_punct(",") {
+						break;
+					}
+				}
+				self.expect_punct(";")?;
+			}
+
+			if keyword == "union" {
+				Ok(DefKind::Union { fields })
+			}
+			else {
+				Ok(DefKind::Record { fields })
+			}
+		}
+	}
+
+	fn constant(&mut self, attributes: Attributes) -> Result<Constant, crate::Error> {
+		self.pos += 1; // const
+		let type_ = self.type_expr()?;
+		let (name, _, type_) = self.named_declarator(type_)?;
+		self.expect_punct("=")?;
+		let value = self.expr()?;
+		self.expect_punct(";")?;
+
+		self.constants.insert(name.clone(), value.clone());
+		Ok(Constant { attributes, type_, name, value })
+	}
+
+	fn interface(&mut self, attributes: Attributes) -> Result<Def, crate::Error> {
+		let (name, location) = self.ident()?;
+
+		let parent =
+			if self.eat_punct(":") {
+				Some(self.ident()?)
+			}
+			else {
+				None
+			};
+
+		self.expect_punct("{")?;
+
+		let mut methods = vec![];
+		while !self.eat_punct("}") {
+			if self.eat_punct(";") || self.skip_directive()? {
+				continue;
+			}
+
+			if self.is_ident("const") {
+				self.constant(Attributes::default())?;
+				continue;
+			}
+
+			if self.is_ident("typedef") {
+				return Err(self.error(self.location(), "typedefs inside interfaces are not supported"));
+			}
+
+			let attributes = self.attributes()?;
+			methods.push(self.method(attributes)?);
+		}
+		self.eat_punct(";");
+
+		Ok(Def { attributes, name, location, kind: DefKind::Interface { parent, methods } })
+	}
+
+	fn dispinterface(&mut self, attributes: Attributes) -> Result<Def, crate::Error> {
+		let (name, location) = self.ident()?;
+
+		self.expect_punct("{")?;
+
+		let mut properties = vec![];
+		let mut methods = vec![];
+		let mut interface = None;
+
+		if self.eat_ident("interface") {
+			interface = Some(self.ident()?);
+			self.expect_punct(";")?;
+			self.expect_punct("}")?;
+		}
+		else {
+			if self.eat_ident("properties") {
+				self.expect_punct(":")?;
+				while !self.is_ident("methods") && !self.is_punct("}") {
+					let attributes = self.attributes()?;
+					let type_ = self.type_expr()?;
+					let (name, _, type_) = self.named_declarator(type_)?;
+					self.expect_punct(";")?;
+					properties.push(Field { attributes, type_, name });
+				}
+			}
+
+			if self.eat_ident("methods") {
+				self.expect_punct(":")?;
+				while !self.is_punct("}") {
+					let attributes = self.attributes()?;
+					methods.push(self.method(attributes)?);
+				}
+			}
+
+			self.expect_punct("}")?;
+		}
+		self.eat_punct(";");
+
+		Ok(Def { attributes, name, location, kind: DefKind::DispInterface { properties, methods, interface } })
+	}
+
+	fn coclass(&mut self, attributes: Attributes) -> Result<Def, crate::Error> {
+		let (name, location) = self.ident()?;
+
+		self.expect_punct("{")?;
+
+		let mut interfaces = vec![];
+		while !self.eat_punct("}") {
+			let attributes = self.attributes()?;
+			if !self.eat_ident("interface") && !self.eat_ident("dispinterface") {
+				return Err(self.error(self.location(), "expected `interface` or `dispinterface`"));
+			}
+			let (name, location) = self.ident()?;
+			self.expect_punct(";")?;
+			interfaces.push((attributes, name, location));
+		}
+		self.eat_punct(";");
+
+		Ok(Def { attributes, name, location, kind: DefKind::CoClass { interfaces } })
+	}
+
+	fn module(&mut self, attributes: Attributes) -> Result<Def, crate::Error> {
+		let (name, location) = self.ident()?;
+
+		self.expect_punct("{")?;
+
+		let mut methods = vec![];
+		let mut constants = vec![];
+		while !self.eat_punct("}") {
+			if self.eat_punct(";") || self.skip_directive()? {
+				continue;
+			}
+
+			let attributes = self.attributes()?;
+			if self.is_ident("const") {
+				constants.push(self.constant(attributes)?);
+			}
+			else {
+				methods.push(self.method(attributes)?);
+			}
+		}
+		self.eat_punct(";");
+
+		Ok(Def { attributes, name, location, kind: DefKind::Module { methods, constants } })
+	}
+
+	fn method(&mut self, attributes: Attributes) -> Result<Method, crate::Error> {
+		let return_type = self.type_expr()?;
+
+		let mut call_conv = None;
+		while let Some(TokenKind::Ident(ident)) = self.peek() {
+			call_conv = Some(match &**ident {
+				"__stdcall" | "_stdcall" | "STDMETHODCALLTYPE" | "WINAPI" | "CALLBACK" => types::CallConv::StdCall,
+				"__cdecl" | "_cdecl" | "cdecl" => types::CallConv::CDecl,
+				"__fastcall" | "_fastcall" => types::CallConv::FastCall,
+				"__pascal" | "_pascal" | "pascal" => types::CallConv::Pascal,
+				_ => break,
+			});
+			self.pos += 1;
+		}
+
+		let (name, _) = self.ident()?;
+
+		self.expect_punct("(")?;
+
+		let mut params = vec![];
+		if self.is_ident("void") && matches!(self.peek_at(1), Some(TokenKind::Punct(")"))) {
+			self.pos += 1;
+		}
+		if !self.eat_punct(")") {
+			loop {
+				let attributes = self.attributes()?;
+				let location = self.location();
+				if self.is_punct("...") {
+					return Err(self.error(location, "variadic functions are not supported"));
+				}
+				let type_ = self.type_expr()?;
+				let (name, type_) = self.declarator(type_)?;
+				params.push(Param { attributes, type_, name: name.map(|(name, _)| name), location });
+
+				if !self.eat_punct(",") {
+					self.expect_punct(")")?;
+					break;
+				}
+			}
+		}
+
+		self.expect_punct(";")?;
+
+		Ok(Method { attributes, return_type, call_conv, name, params })
+	}
+
+	fn int_expr(&mut self) -> Result<i64, crate::Error> {
+		let location = self.location();
+		match self.expr()? {
+			Value::Int(value) => Ok(value),
+			_ => Err(self.error(location, "expected an integer")),
+		}
+	}
+
+	/// Parses and evaluates a constant expression
+	fn expr(&mut self) -> Result<Value, crate::Error> {
+		self.binary_expr(0)
+	}
+
+	fn binary_expr(&mut self, min_precedence: u8) -> Result<Value, crate::Error> {
+		const OPERATORS: &[(&str, u8)] = &[
+			("|", 0),
+			("^", 1),
+			("&", 2),
+			("<<", 3), (">>", 3),
+			("+", 4), ("-", 4),
+			("*", 5), ("/", 5), ("%", 5),
+		];
+
+		let mut left = self.unary_expr()?;
+
+		loop {
+			let location = self.location();
+			let (op, precedence) = match self.peek() {
+				Some(TokenKind::Punct(op)) => match OPERATORS.iter().find(|(o, precedence)| o == op && *precedence >= min_precedence) {
+					Some(&(op, precedence)) => (op, precedence),
+					None => break,
+				},
+				_ => break,
+			};
+			self.pos += 1;
+
+			let right = self.binary_expr(precedence + 1)?;
+
+			left = match (left, right) {
+				(Value::Int(left), Value::Int(right)) => Value::Int(match op {
+					"|" => left | right,
+					"^" => left ^ right,
+					"&" => left & right,
+					"<<" => left.wrapping_shl(right as u32),
+					">>" => left.wrapping_shr(right as u32),
+					"+" => left.wrapping_add(right),
+					"-" => left.wrapping_sub(right),
+					"*" => left.wrapping_mul(right),
+					"/" | "%" if right == 0 => return Err(self.error(location, "division by zero")),
+					"/" => left.wrapping_div(right),
+					"%" => left.wrapping_rem(right),
+					_ => unreachable!(),
+				}),
+
+				(left, right) => {
+					let (left, right) = match (left, right) {
+						(Value::Int(left), Value::Float(right)) => (left as f64, right),
+						(Value::Float(left), Value::Int(right)) => (left, right as f64),
+						(Value::Float(left), Value::Float(right)) => (left, right),
+						_ => return Err(self.error(location, format!("`{}` cannot be applied to these operands", op))),
+					};
+					Value::Float(match op {
+						"+" => left + right,
+						"-" => left - right,
+						"*" => left * right,
+						"/" => left / right,
+						_ => return Err(self.error(location, format!("`{}` cannot be applied to floating-point operands", op))),
+					})
+				},
+			};
+		}
+
+		Ok(left)
+	}
+
+	fn unary_expr(&mut self) -> Result<Value, crate::Error> {
+		let location = self.location();
+		match self.next()?.kind {
+			TokenKind::Punct("-") => match self.unary_expr()? {
+				Value::Int(value) => Ok(Value::Int(value.wrapping_neg())),
+				Value::Float(value) => Ok(Value::Float(-value)),
+				Value::Str(_) => Err(self.error(location, "`-` cannot be applied to a string")),
+			},
+
+			TokenKind::Punct("+") => self.unary_expr(),
+
+			TokenKind::Punct("~") => match self.unary_expr()? {
+				Value::Int(value) => Ok(Value::Int(!value)),
+				_ => Err(self.error(location, "`~` can only be applied to an integer")),
+			},
+
+			TokenKind::Punct("!") => match self.unary_expr()? {
+				Value::Int(value) => Ok(Value::Int((value == 0).into())),
+				_ => Err(self.error(location, "`!` can only be applied to an integer")),
+			},
+
+			TokenKind::Punct("(") => {
+				let value = self.expr()?;
+				self.expect_punct(")")?;
+				Ok(value)
+			},
+
+			TokenKind::Number(number) => parse_number(&number).ok_or_else(|| self.error(location, format!("invalid number `{}`", number))),
+
+			TokenKind::Char(value) => Ok(Value::Int(value)),
+
+			TokenKind::Str(mut s) => {
+				while let Some(TokenKind::Str(next)) = self.peek() {
+					s.push_str(next);
+					self.pos += 1;
+				}
+				Ok(Value::Str(s))
+			},
+
+			TokenKind::Ident(ident) => self.constants.get(&ident).cloned().ok_or_else(|| self.error(location, format!("unknown constant `{}`", ident))),
+
+			TokenKind::Punct(punct) => Err(self.error(location, format!("unexpected `{}`", punct))),
+		}
+	}
+}
+
+fn is_integer_type(name: &str) -> bool {
+	matches!(name, "char" | "short" | "int" | "long" | "hyper" | "small" | "__int8" | "__int16" | "__int32" | "__int64")
+}
+
+fn parse_number(number: &str) -> Option<Value> {
+	let lower = number.to_ascii_lowercase();
+
+	if let Some(hex) = lower.strip_prefix("0x") {
+		let hex = hex.trim_end_matches(['u', 'l']);
+		return u64::from_str_radix(hex, 16).ok().map(|value| Value::Int(value as i64));
+	}
+
+	if lower.contains('.') || lower.contains('e') {
+		let float = lower.trim_end_matches(['f', 'l']);
+		return float.parse().ok().map(Value::Float);
+	}
+
+	let int = lower.trim_end_matches(['u', 'l']);
+	if int.len() > 1 && int.starts_with('0') {
+		return u64::from_str_radix(&int[1..], 8).ok().map(|value| Value::Int(value as i64));
+	}
+
+	int.parse::<u64>().ok().map(|value| Value::Int(value as i64))
+}
+
+/// Constants that IDL files use but that are `#define`d in headers rather than in IDL
+const WELL_KNOWN_CONSTANTS: &[(&str, i64)] = &[
+	("FALSE", 0),
+	("TRUE", 1),
+	("NULL", 0),
+	("VARIANT_FALSE", 0),
+	("VARIANT_TRUE", -1),
+	("DISPID_UNKNOWN", -1),
+	("DISPID_VALUE", 0),
+	("DISPID_PROPERTYPUT", -3),
+	("DISPID_NEWENUM", -4),
+	("DISPID_EVALUATE", -5),
+	("DISPID_CONSTRUCTOR", -6),
+	("DISPID_DESTRUCTOR", -7),
+	("DISPID_COLLECT", -8),
+];
+
+/// The `VARTYPE`s of the base types and of common typedefs from the Windows SDK headers
+fn builtin_type(name: &str) -> Option<types::TypeDesc> {
+	let vt = match name {
+		"boolean" | "byte" | "BYTE" | "BOOLEAN" | "UCHAR" | "unsigned char" | "unsigned small" | "unsigned __int8" => types::VT_UI1,
+		"char" | "CHAR" | "small" | "__int8" => types::VT_I1,
+		"short" | "SHORT" | "__int16" => types::VT_I2,
+		"unsigned short" | "unsigned __int16" | "USHORT" | "WORD" | "wchar_t" | "WCHAR" | "OLECHAR" => types::VT_UI2,
+		"long" | "LONG" | "__int32" | "BOOL" | "DISPID" | "MEMBERID" => types::VT_I4,
+		"unsigned long" | "unsigned __int32" | "ULONG" | "DWORD" | "LCID" => types::VT_UI4,
+		"int" | "INT" => types::VT_INT,
+		"unsigned int" | "UINT" => types::VT_UINT,
+		"hyper" | "__int64" | "LONGLONG" | "LONG64" | "INT64" => types::VT_I8,
+		"unsigned hyper" | "unsigned __int64" | "ULONGLONG" | "ULONG64" | "UINT64" => types::VT_UI8,
+		"float" | "FLOAT" => types::VT_R4,
+		"double" | "DOUBLE" => types::VT_R8,
+		"HRESULT" => types::VT_HRESULT,
+		"SCODE" => types::VT_ERROR,
+		"BSTR" => types::VT_BSTR,
+		"VARIANT" | "VARIANTARG" => types::VT_VARIANT,
+		"VARIANT_BOOL" => types::VT_BOOL,
+		"CY" | "CURRENCY" => types::VT_CY,
+		"DATE" => types::VT_DATE,
+		"DECIMAL" => types::VT_DECIMAL,
+		"LPSTR" | "LPCSTR" => types::VT_LPSTR,
+		"LPWSTR" | "LPCWSTR" | "LPOLESTR" | "LPCOLESTR" => types::VT_LPWSTR,
+		"LPUNKNOWN" => types::VT_UNKNOWN,
+		"LPDISPATCH" => types::VT_DISPATCH,
+		"void" | "VOID" => types::VT_VOID,
+		"LPVOID" | "PVOID" => return Some(types::TypeDesc::Ptr(Box::new(types::TypeDesc::Base(types::VT_VOID)))),
+		_ => return None,
+	};
+	Some(types::TypeDesc::Base(vt))
+}
+
+/// An interface that another interface can derive from
+struct Parent {
+	type_ref: types::TypeRef,
+	num_vtbl_entries: u16,
+
+	/// The depth of the parent in the inheritance hierarchy, where `IUnknown` is 0
+	level: u16,
+	dispatchable: bool,
+}
+
+/// The index of `GUID` in `stdole2.tlb`
+const STDOLE_GUID_INDEX: u32 = 0;
+
+struct Compiler {
+	syskind: types::SysKind,
+
+	/// The IDL files that have been parsed, for reporting errors. The first one is the file being compiled.
+	files: Vec<std::path::PathBuf>,
+
+	constants: std::collections::HashMap<String, Value>,
+
+	/// Definitions in the file being compiled
+	defs: std::collections::HashMap<String, Def>,
+
+	/// Definitions in `import`ed files
+	imported_defs: std::collections::HashMap<String, Def>,
+
+	/// `import`ed files that haven't been parsed yet
+	pending_imports: std::collections::VecDeque<std::path::PathBuf>,
+
+	/// Typelibs named by `importlib`, other than `stdole2.tlb`
+	imported_type_libs: Vec<types::TypeLib>,
+
+	/// The files that the typelibs named by `importlib` were read from
+	imported_type_lib_files: Vec<std::path::PathBuf>,
+
+	imports: Vec<types::ImportedTypeLib>,
+
+	/// The names of the types in the typelib, by index
+	names: Vec<String>,
+	indices: std::collections::HashMap<String, usize>,
+	type_infos: Vec<Option<types::TypeInfo>>,
+
+	/// The levels of the interfaces in the typelib in the inheritance hierarchy, for numbering their functions
+	interface_levels: std::collections::HashMap<usize, u16>,
+
+	/// Types whose typeinfos are being built, to detect types that contain themselves
+	building: std::collections::HashSet<usize>,
+}
+
+impl Compiler {
+	fn new(syskind: types::SysKind) -> Self {
+		Compiler {
+			syskind,
+			files: vec![],
+			constants: WELL_KNOWN_CONSTANTS.iter().map(|&(name, value)| (name.to_string(), Value::Int(value))).collect(),
+			defs: std::collections::HashMap::new(),
+			imported_defs: std::collections::HashMap::new(),
+			pending_imports: std::collections::VecDeque::new(),
+			imported_type_libs: vec![],
+			imported_type_lib_files: vec![],
+			imports: vec![],
+			names: vec![],
+			indices: std::collections::HashMap::new(),
+			type_infos: vec![],
+			interface_levels: std::collections::HashMap::new(),
+			building: std::collections::HashSet::new(),
+		}
+	}
+
+	fn error(&self, location: Location, message: impl Into<String>) -> crate::Error {
+		crate::Error::Idl { path: self.files[location.file].clone(), line: location.line, column: location.column, message: message.into() }
+	}
+
+	fn pointer_size(&self) -> u16 {
+		self.syskind.pointer_size()
+	}
+
+	/// Parses the given file, and queues the files that it imports.
+	fn parse_file(&mut self, filename: &std::path::Path) -> Result<Vec<Item>, crate::Error> {
+		let source = std::fs::read(filename)?;
+		let source = String::from_utf8_lossy(&source);
+
+		let file = self.files.len();
+		self.files.push(filename.to_owned());
+
+		let Lexed { tokens, defines } = lex(&source, file, filename)?;
+
+		let end_line = source.lines().count().max(1);
+		let end = Location { file, line: end_line, column: 1 };
+
+		// `#define`s are assumed to come before their uses, and ones whose values aren't constant expressions are ignored.
+		for (name, value) in defines {
+			let mut parser = Parser { tokens: value, pos: 0, path: filename, end, constants: &mut self.constants, imports: vec![] };
+			if let Ok(value) = parser.expr() {
+				if parser.peek().is_none() {
+					self.constants.insert(name, value);
+				}
+			}
+		}
+
+		let mut parser = Parser { tokens, pos: 0, path: filename, end, constants: &mut self.constants, imports: vec![] };
+		let items = parser.items(false)?;
+		let imports = parser.imports;
+
+		let dir = filename.parent().unwrap_or_else(|| std::path::Path::new(""));
+		for import in imports {
+			// Imports that can't be found, like the SDK's `oaidl.idl` on hosts without the SDK, are satisfied by the builtin types.
+			let path = dir.join(&import);
+			if path.is_file() && !self.files.contains(&path) && !self.pending_imports.contains(&path) {
+				self.pending_imports.push_back(path);
+			}
+		}
+
+		Ok(items)
+	}
+
+	/// Parses the next pending imported file. Returns false if there are none.
+	fn parse_next_import(&mut self) -> Result<bool, crate::Error> {
+		let Some(path) = self.pending_imports.pop_front() else {
+			return Ok(false);
+		};
+
+		let items = self.parse_file(&path)?;
+		for item in items {
+			if let Item::Def(def) = item {
+				self.imported_defs.entry(def.name.clone()).or_insert(def);
+			}
+		}
+
+		Ok(true)
+	}
+
+	fn define(&mut self, def: Def) -> Result<(), crate::Error> {
+		if self.defs.contains_key(&def.name) {
+			return Err(self.error(def.location, format!("{} is defined more than once", def.name)));
+		}
+
+		self.defs.insert(def.name.clone(), def);
+		Ok(())
+	}
+
+	fn import_lib(&mut self, file_name: &str, location: Location) -> Result<(), crate::Error> {
+		if file_name.eq_ignore_ascii_case("stdole2.tlb") || file_name.eq_ignore_ascii_case("stdole32.tlb") {
+			// The types that typelibs use from stdole are built in
+			if !self.imports.iter().any(|import| import.guid == types::LIBID_STDOLE) {
+				let major = if file_name.eq_ignore_ascii_case("stdole2.tlb") { 2 } else { 1 };
+				self.imports.push(types::ImportedTypeLib {
+					guid: types::LIBID_STDOLE,
+					version: types::Version { major, minor: 0 },
+					lcid: 0,
+					file_name: file_name.to_string(),
+				});
+			}
+			return Ok(());
+		}
+
+		let dir = self.files[location.file].parent().unwrap_or_else(|| std::path::Path::new("")).to_owned();
+		let path = dir.join(file_name);
+		if !path.is_file() {
+			return Err(self.error(location, format!("could not find {}", file_name)));
+		}
+
+		let type_lib = crate::load_type_lib_with_files(&path, &mut self.imported_type_lib_files)?;
+		self.imports.push(types::ImportedTypeLib {
+			guid: type_lib.guid,
+			version: type_lib.version,
+			lcid: type_lib.lcid,
+			file_name: file_name.to_string(),
+		});
+		self.imported_type_libs.push(type_lib);
+
+		Ok(())
+	}
+
+	/// A reference to a type in `stdole2.tlb`. The import is added if the IDL file didn't `importlib` it.
+	fn stdole_type(&mut self, type_: types::ImportedType) -> types::TypeRef {
+		if !self.imports.iter().any(|import| import.guid == types::LIBID_STDOLE) {
+			self.imports.push(types::ImportedTypeLib {
+				guid: types::LIBID_STDOLE,
+				version: types::Version { major: 2, minor: 0 },
+				lcid: 0,
+				file_name: "stdole2.tlb".to_string(),
+			});
+		}
+
+		types::TypeRef::External { lib_guid: Some(types::LIBID_STDOLE), type_ }
+	}
+
+	/// Finds a type in the typelibs named by `importlib`
+	fn imported_type(&self, name: &str) -> Option<(types::TypeRef, &types::TypeInfo, u16)> {
+		self.imported_type_libs.iter().find_map(|type_lib| {
+			let (index, type_info) = type_lib.type_infos.iter().enumerate().find(|(_, type_info)| type_info.name == name)?;
+			let type_ =
+				if type_info.guid == types::Guid::default() {
+					types::ImportedType::Index(index as u32)
+				}
+				else {
+					types::ImportedType::Guid(type_info.guid)
+				};
+			Some((types::TypeRef::External { lib_guid: Some(type_lib.guid), type_ }, type_info, type_lib.syskind.pointer_size()))
+		})
+	}
+
+	/// Gets the index of the type with the given name in the typelib, adding it if it's defined but not yet added.
+	fn local(&mut self, name: &str) -> Option<usize> {
+		if let Some(&index) = self.indices.get(name) {
+			return Some(index);
+		}
+
+		if !self.defs.contains_key(name) && !self.imported_defs.contains_key(name) {
+			return None;
+		}
+
+		let index = self.names.len();
+		self.names.push(name.to_string());
+		self.indices.insert(name.to_string(), index);
+		self.type_infos.push(None);
+		Some(index)
+	}
+
+	/// Resolves a type name that isn't defined in the IDL file being compiled.
+	fn resolve_name(&mut self, name: &str, location: Location) -> Result<types::TypeDesc, crate::Error> {
+		if let Some(type_) = builtin_type(name) {
+			return Ok(type_);
+		}
+
+		match name {
+			"GUID" | "IID" | "CLSID" =>
+				return Ok(types::TypeDesc::UserDefined(self.stdole_type(types::ImportedType::Index(STDOLE_GUID_INDEX)))),
+			"REFGUID" | "REFIID" | "REFCLSID" =>
+				return Ok(types::TypeDesc::Ptr(Box::new(types::TypeDesc::UserDefined(self.stdole_type(types::ImportedType::Index(STDOLE_GUID_INDEX)))))),
+			"IUnknown" =>
+				return Ok(types::TypeDesc::UserDefined(self.stdole_type(types::ImportedType::Guid(types::IID_IUNKNOWN)))),
+			"IDispatch" =>
+				return Ok(types::TypeDesc::UserDefined(self.stdole_type(types::ImportedType::Guid(types::IID_IDISPATCH)))),
+			"IEnumVARIANT" =>
+				return Ok(types::TypeDesc::UserDefined(self.stdole_type(types::ImportedType::Guid(types::IID_IENUMVARIANT)))),
+			_ => (),
+		}
+
+		if let Some((type_ref, _, _)) = self.imported_type(name) {
+			return Ok(types::TypeDesc::UserDefined(type_ref));
+		}
+
+		loop {
+			if let Some(index) = self.local(name) {
+				return Ok(types::TypeDesc::UserDefined(types::TypeRef::Local(index)));
+			}
+
+			if !self.parse_next_import()? {
+				return Err(self.error(location, format!("unknown type {}", name)));
+			}
+		}
+	}
+
+	fn resolve_type(&mut self, type_: &TypeExpr) -> Result<types::TypeDesc, crate::Error> {
+		let mut pointers = type_.pointers;
+
+		let mut result = match &type_.base {
+			BaseType::SafeArray(element) => types::TypeDesc::SafeArray(Box::new(self.resolve_type(element)?)),
+
+			BaseType::Named(name) =>
+				if self.defs.contains_key(name) {
+					types::TypeDesc::UserDefined(types::TypeRef::Local(self.local(name).unwrap()))
+				}
+				else if pointers > 0 && (name == "IUnknown" || name == "IDispatch") {
+					// `IUnknown *` and `IDispatch *` have their own VARTYPEs
+					pointers -= 1;
+					types::TypeDesc::Base(if name == "IUnknown" { types::VT_UNKNOWN } else { types::VT_DISPATCH })
+				}
+				else {
+					self.resolve_name(name, type_.location)?
+				},
+		};
+
+		for _ in 0..pointers {
+			result = types::TypeDesc::Ptr(Box::new(result));
+		}
+
+		if !type_.dimensions.is_empty() {
+			result = types::TypeDesc::CArray(Box::new(result), type_.dimensions.clone());
+		}
+
+		Ok(result)
+	}
+
+	/// Resolves the name of an interface that another interface derives from, or that a coclass implements
+	fn resolve_interface(&mut self, name: &str, location: Location) -> Result<Parent, crate::Error> {
+		match name {
+			"IUnknown" if !self.defs.contains_key(name) => Ok(Parent {
+				type_ref: self.stdole_type(types::ImportedType::Guid(types::IID_IUNKNOWN)),
+				num_vtbl_entries: 3,
+				level: 0,
+				dispatchable: false,
+			}),
+
+			"IDispatch" if !self.defs.contains_key(name) => Ok(Parent {
+				type_ref: self.stdole_type(types::ImportedType::Guid(types::IID_IDISPATCH)),
+				num_vtbl_entries: 7,
+				level: 1,
+				dispatchable: true,
+			}),
+
+			_ => match self.resolve_name_or_local(name, location)? {
+				types::TypeDesc::UserDefined(types::TypeRef::Local(index)) => {
+					self.build(index)?;
+					let type_info = self.type_infos[index].as_ref().unwrap();
+					Ok(Parent {
+						type_ref: types::TypeRef::Local(index),
+						num_vtbl_entries: type_info.size_vft / self.pointer_size(),
+						level: self.interface_levels.get(&index).copied().unwrap_or(0),
+						dispatchable: (type_info.flags & types::TYPEFLAG_FDISPATCHABLE) == types::TYPEFLAG_FDISPATCHABLE,
+					})
+				},
+
+				types::TypeDesc::UserDefined(type_ref) => {
+					let (_, type_info, pointer_size) = self.imported_type(name).ok_or_else(|| self.error(location, format!("{} is not an interface", name)))?;
+					Ok(Parent {
+						type_ref,
+						num_vtbl_entries: type_info.size_vft / pointer_size,
+						level: 1,
+						dispatchable: (type_info.flags & types::TYPEFLAG_FDISPATCHABLE) == types::TYPEFLAG_FDISPATCHABLE,
+					})
+				},
+
+				_ => Err(self.error(location, format!("{} is not an interface", name))),
+			},
+		}
+	}
+
+	fn resolve_name_or_local(&mut self, name: &str, location: Location) -> Result<types::TypeDesc, crate::Error> {
+		if self.defs.contains_key(name) {
+			Ok(types::TypeDesc::UserDefined(types::TypeRef::Local(self.local(name).unwrap())))
+		}
+		else {
+			self.resolve_name(name, location)
+		}
+	}
+
+	/// Builds the typeinfo of the type with the given index, if it hasn't been built already
+	fn build(&mut self, index: usize) -> Result<(), crate::Error> {
+		if self.type_infos[index].is_some() {
+			return Ok(());
+		}
+
+		let name = &self.names[index];
+		let def = self.defs.get(name).or_else(|| self.imported_defs.get(name)).unwrap().clone();
+
+		if !self.building.insert(index) {
+			return Err(self.error(def.location, format!("{} contains itself", def.name)));
+		}
+
+		let type_info = self.type_info(index, &def)?;
+
+		self.building.remove(&index);
+		self.type_infos[index] = Some(type_info);
+		Ok(())
+	}
+
+	fn type_info(&mut self, index: usize, def: &Def) -> Result<types::TypeInfo, crate::Error> {
+		let pointer_size = self.pointer_size();
+
+		let mut type_info = types::TypeInfo {
+			name: def.name.clone(),
+			doc_string: def.attributes.help_string.clone(),
+			help_context: def.attributes.help_context.unwrap_or(0),
+			guid: def.attributes.uuid.unwrap_or_default(),
+			typekind: types::TypeKind::Alias,
+			flags: def.attributes.flags(TYPEFLAGS),
+			version: def.attributes.version.unwrap_or_default(),
+			size_instance: u32::from(pointer_size),
+			alignment: pointer_size,
+			size_vft: 0,
+			functions: vec![],
+			vars: vec![],
+			impl_types: vec![],
+			alias_type: None,
+			dll_name: None,
+		};
+
+		match &def.kind {
+			DefKind::Interface { parent, methods } => {
+				type_info.typekind = types::TypeKind::Interface;
+
+				let parent = match parent {
+					Some((parent, location)) => Some(self.resolve_interface(parent, *location)?),
+					None => None,
+				};
+
+				let (num_parent_vtbl_entries, level) = parent.as_ref().map_or((0, 0), |parent| (parent.num_vtbl_entries, parent.level + 1));
+				self.interface_levels.insert(index, level);
+
+				if let Some(parent) = parent {
+					if parent.dispatchable {
+						type_info.flags |= types::TYPEFLAG_FDISPATCHABLE;
+					}
+					type_info.impl_types.push(types::ImplType { type_ref: parent.type_ref, flags: 0 });
+				}
+
+				if def.attributes.has("dual") {
+					// Dual interfaces are stored as dispinterfaces whose functions are those of the interface half
+					type_info.typekind = types::TypeKind::Dispatch;
+					type_info.flags |= types::TYPEFLAG_FOLEAUTOMATION | types::TYPEFLAG_FDISPATCHABLE;
+				}
+
+				type_info.functions = self.functions(methods, types::FuncKind::PureVirtual, num_parent_vtbl_entries, level)?;
+				type_info.size_vft = (num_parent_vtbl_entries + methods.len() as u16) * pointer_size;
+			},
+
+			DefKind::DispInterface { properties, methods, interface } => {
+				type_info.typekind = types::TypeKind::Dispatch;
+				type_info.flags |= types::TYPEFLAG_FDISPATCHABLE;
+				type_info.size_vft = 7 * pointer_size;
+				type_info.impl_types.push(types::ImplType { type_ref: self.stdole_type(types::ImportedType::Guid(types::IID_IDISPATCH)), flags: 0 });
+
+				for (i, property) in properties.iter().enumerate() {
+					type_info.vars.push(types::Var {
+						name: property.name.clone(),
+						doc_string: property.attributes.help_string.clone(),
+						help_context: property.attributes.help_context.unwrap_or(0),
+						member_id: property.attributes.id.unwrap_or(0x4000_0000 + i as i32),
+						var_kind: types::VarKind::Dispatch,
+						flags: property.attributes.flags(VARFLAGS),
+						type_: self.resolve_type(&property.type_)?,
+					});
+				}
+
+				type_info.functions =
+					if let Some((interface, location)) = interface {
+						// The dispinterface exposes the functions of the interface
+						let interface = match self.resolve_interface(interface, *location)?.type_ref {
+							types::TypeRef::Local(index) => self.type_infos[index].as_ref().unwrap(),
+							types::TypeRef::External { .. } => return Err(self.error(*location, "the interface of a dispinterface must be defined in the IDL file")),
+						};
+						interface.functions.iter().map(|function| types::Function { func_kind: types::FuncKind::Dispatch, ovft: 0, ..function.clone() }).collect()
+					}
+					else {
+						let mut functions = self.functions(methods, types::FuncKind::Dispatch, 0, 2)?;
+						for function in &mut functions {
+							function.ovft = 0;
+						}
+						functions
+					};
+			},
+
+			DefKind::CoClass { interfaces } => {
+				type_info.typekind = types::TypeKind::CoClass;
+				if !def.attributes.has("noncreatable") {
+					type_info.flags |= types::TYPEFLAG_FCANCREATE;
+				}
+
+				for (attributes, name, location) in interfaces {
+					let interface = self.resolve_interface(name, *location)?;
+					type_info.impl_types.push(types::ImplType { type_ref: interface.type_ref, flags: attributes.flags(IMPLTYPEFLAGS) });
+				}
+			},
+
+			DefKind::Module { methods, constants } => {
+				type_info.typekind = types::TypeKind::Module;
+				type_info.size_instance = 0;
+				type_info.dll_name.clone_from(&def.attributes.dll_name);
+
+				type_info.functions = self.functions(methods, types::FuncKind::Static, 0, 0)?;
+				for function in &mut type_info.functions {
+					function.ovft = 0;
+				}
+
+				for (i, constant) in constants.iter().enumerate() {
+					let type_ = self.resolve_type(&constant.type_)?;
+					let value = self.variant(&constant.value, &type_, constant.type_.location)?;
+					type_info.vars.push(types::Var {
+						name: constant.name.clone(),
+						doc_string: constant.attributes.help_string.clone(),
+						help_context: constant.attributes.help_context.unwrap_or(0),
+						member_id: constant.attributes.id.unwrap_or(0x4000_0000 + i as i32),
+						var_kind: types::VarKind::Const(value),
+						flags: constant.attributes.flags(VARFLAGS),
+						type_,
+					});
+				}
+			},
+
+			DefKind::Enum { members } => {
+				type_info.typekind = types::TypeKind::Enum;
+				type_info.size_instance = 4;
+				type_info.alignment = 4;
+
+				type_info.vars = members.iter().enumerate().map(|(i, member)| types::Var {
+					name: member.name.clone(),
+					doc_string: member.attributes.help_string.clone(),
+					help_context: member.attributes.help_context.unwrap_or(0),
+					member_id: member.attributes.id.unwrap_or(0x4000_0000 + i as i32),
+					var_kind: types::VarKind::Const(types::Variant::I4(member.value as i32)),
+					flags: member.attributes.flags(VARFLAGS),
+					type_: types::TypeDesc::Base(types::VT_I4),
+				}).collect();
+			},
+
+			DefKind::Record { fields } | DefKind::Union { fields } => {
+				let is_union = matches!(def.kind, DefKind::Union { .. });
+				type_info.typekind = if is_union { types::TypeKind::Union } else { types::TypeKind::Record };
+
+				let mut size = 0_u32;
+				let mut alignment = 1_u16;
+
+				for (i, field) in fields.iter().enumerate() {
+					let type_ = self.resolve_type(&field.type_)?;
+					let (field_size, field_alignment) = self.layout(&type_, field.type_.location)?;
+
+					let offset =
+						if is_union {
+							size = size.max(field_size);
+							0
+						}
+						else {
+							let offset = align_up(size, field_alignment);
+							size = offset + field_size;
+							offset
+						};
+					alignment = alignment.max(field_alignment);
+
+					type_info.vars.push(types::Var {
+						name: field.name.clone(),
+						doc_string: field.attributes.help_string.clone(),
+						help_context: field.attributes.help_context.unwrap_or(0),
+						member_id: field.attributes.id.unwrap_or(0x4000_0000 + i as i32),
+						var_kind: types::VarKind::PerInstance(offset),
+						flags: field.attributes.flags(VARFLAGS),
+						type_,
+					});
+				}
+
+				type_info.size_instance = align_up(size, alignment);
+				type_info.alignment = alignment;
+			},
+
+			DefKind::Alias { type_ } => {
+				let alias_type = self.resolve_type(type_)?;
+				let (size, alignment) = self.layout(&alias_type, type_.location)?;
+				type_info.size_instance = size;
+				type_info.alignment = alignment;
+				type_info.alias_type = Some(alias_type);
+			},
+		}
+
+		Ok(type_info)
+	}
+
+	/// Converts the methods of an interface, dispinterface or module to functions
+	fn functions(&mut self, methods: &[Method], func_kind: types::FuncKind, num_parent_vtbl_entries: u16, level: u16) -> Result<Vec<types::Function>, crate::Error> {
+		let mut functions: Vec<types::Function> = vec![];
+
+		for (i, method) in methods.iter().enumerate() {
+			let attributes = &method.attributes;
+
+			let invoke_kind =
+				if attributes.has("propget") {
+					types::InvokeKind::PropertyGet
+				}
+				else if attributes.has("propput") {
+					types::InvokeKind::PropertyPut
+				}
+				else if attributes.has("propputref") {
+					types::InvokeKind::PropertyPutRef
+				}
+				else {
+					types::InvokeKind::Func
+				};
+
+			// Like midl, the accessors of a property share the member ID of the first one
+			let member_id = attributes.id.unwrap_or_else(||
+				functions.iter()
+				.find(|function| function.name == method.name && function.invoke_kind != types::InvokeKind::Func && invoke_kind != types::InvokeKind::Func)
+				.map_or(0x6000_0000 | (i32::from(level) << 16) | i as i32, |function| function.member_id));
+
+			let mut params = vec![];
+			for param in &method.params {
+				let name = param.name.clone().ok_or_else(|| self.error(param.location, format!("a parameter of {} does not have a name", method.name)))?;
+				let type_ = self.resolve_type(&param.type_)?;
+
+				let mut flags = param.attributes.flags(PARAMFLAGS);
+				if (flags & (types::PARAMFLAG_FIN | types::PARAMFLAG_FOUT)) == 0 {
+					// Parameters are [in] by default
+					flags |= types::PARAMFLAG_FIN;
+				}
+
+				let default_value = match &param.attributes.default_value {
+					Some(value) => {
+						flags |= types::PARAMFLAG_FOPT | types::PARAMFLAG_FHASDEFAULT;
+						Some(self.variant(value, &type_, param.location)?)
+					},
+					None => None,
+				};
+
+				params.push(types::Param { name, type_, flags, default_value });
+			}
+
+			functions.push(types::Function {
+				name: method.name.clone(),
+				doc_string: attributes.help_string.clone(),
+				help_context: attributes.help_context.unwrap_or(0),
+				member_id,
+				func_kind,
+				invoke_kind,
+				call_conv: method.call_conv.unwrap_or(types::CallConv::StdCall),
+				flags: attributes.flags(FUNCFLAGS),
+				ovft: (num_parent_vtbl_entries + i as u16) * self.pointer_size(),
+				return_type: self.resolve_type(&method.return_type)?,
+				params,
+				entry_point: attributes.entry.clone(),
+			});
+		}
+
+		Ok(functions)
+	}
+
+	/// Converts the value of a constant or default value to a value of the given type
+	fn variant(&mut self, value: &Value, type_: &types::TypeDesc, location: Location) -> Result<types::Variant, crate::Error> {
+		let vt = match type_ {
+			types::TypeDesc::UserDefined(types::TypeRef::Local(index)) => {
+				// Enums and aliases of base types
+				self.build(*index)?;
+				let type_info = self.type_infos[*index].as_ref().unwrap();
+				match (&type_info.typekind, &type_info.alias_type) {
+					(types::TypeKind::Enum, _) => types::VT_I4,
+					(types::TypeKind::Alias, Some(alias_type)) => return self.variant(value, &alias_type.clone(), location),
+					_ => return Err(self.error(location, format!("{} cannot have a constant value", type_info.name))),
+				}
+			},
+			types::TypeDesc::Ptr(pointee) if matches!(value, Value::Int(0)) => {
+				// NULL pointers. Default values of pointer parameters describe the pointee, so use its type.
+				return self.variant(value, &pointee.clone(), location);
+			},
+			type_ => type_.vt(),
+		};
+
+		let result = match (value, vt) {
+			(Value::Str(s), types::VT_BSTR | types::VT_LPSTR | types::VT_LPWSTR | types::VT_VARIANT) => types::Variant::Bstr(s.clone()),
+
+			(Value::Int(value), types::VT_VARIANT) => types::Variant::I4(*value as i32),
+			(Value::Int(value), types::VT_BOOL) => types::Variant::Bool(*value != 0),
+			(Value::Int(value), types::VT_CY) => types::Variant::Cy(value.wrapping_mul(10000)),
+			(Value::Int(value), types::VT_R4) => types::Variant::R4(*value as f32),
+			(Value::Int(value), types::VT_R8) => types::Variant::R8(*value as f64),
+			(Value::Int(value), types::VT_DATE) => types::Variant::Date(*value as f64),
+			(Value::Int(value), vt @ (
+				types::VT_I1 | types::VT_I2 | types::VT_I4 | types::VT_I8 |
+				types::VT_UI1 | types::VT_UI2 | types::VT_UI4 | types::VT_UI8 |
+				types::VT_INT | types::VT_UINT | types::VT_ERROR | types::VT_HRESULT
+			)) => types::Variant::from_bits(if vt == types::VT_HRESULT { types::VT_ERROR } else { vt }, *value as u64),
+
+			(Value::Float(value), types::VT_R4) => types::Variant::R4(*value as f32),
+			(Value::Float(value), types::VT_R8 | types::VT_VARIANT) => types::Variant::R8(*value),
+			(Value::Float(value), types::VT_DATE) => types::Variant::Date(*value),
+			(Value::Float(value), types::VT_CY) => types::Variant::Cy((value * 10000.0) as i64),
+
+			_ => return Err(self.error(location, "the value does not match the type")),
+		};
+
+		Ok(result)
+	}
+
+	/// The size and alignment of a value of the given type
+	fn layout(&mut self, type_: &types::TypeDesc, location: Location) -> Result<(u32, u16), crate::Error> {
+		// midl packs structs to 8 bytes by default
+		const MAX_ALIGNMENT: u16 = 8;
+
+		let pointer_size = self.pointer_size();
+
+		let (size, alignment) = match type_ {
+			types::TypeDesc::Base(vt) => match *vt {
+				types::VT_I1 | types::VT_UI1 => (1, 1),
+				types::VT_I2 | types::VT_UI2 | types::VT_BOOL => (2, 2),
+				types::VT_I4 | types::VT_UI4 | types::VT_INT | types::VT_UINT | types::VT_R4 | types::VT_ERROR | types::VT_HRESULT => (4, 4),
+				types::VT_I8 | types::VT_UI8 | types::VT_R8 | types::VT_CY | types::VT_DATE => (8, 8),
+				types::VT_DECIMAL => (16, 8),
+				types::VT_VARIANT => (if self.syskind == types::SysKind::Win64 { 24 } else { 16 }, 8),
+				types::VT_BSTR | types::VT_LPSTR | types::VT_LPWSTR | types::VT_UNKNOWN | types::VT_DISPATCH => (u32::from(pointer_size), pointer_size),
+				_ => return Err(self.error(location, "this type cannot be used by value")),
+			},
+
+			types::TypeDesc::Ptr(_) | types::TypeDesc::SafeArray(_) => (u32::from(pointer_size), pointer_size),
+
+			types::TypeDesc::CArray(element, dimensions) => {
+				let (size, alignment) = self.layout(element, location)?;
+				(dimensions.iter().fold(size, |size, dimension| size * dimension), alignment)
+			},
+
+			types::TypeDesc::UserDefined(types::TypeRef::Local(index)) => {
+				self.build(*index)?;
+				let type_info = self.type_infos[*index].as_ref().unwrap();
+				(type_info.size_instance, type_info.alignment)
+			},
+
+			types::TypeDesc::UserDefined(types::TypeRef::External { lib_guid, type_ }) =>
+				if *lib_guid == Some(types::LIBID_STDOLE) {
+					match type_ {
+						types::ImportedType::Index(STDOLE_GUID_INDEX) => (16, 4),
+						_ => (u32::from(pointer_size), pointer_size),
+					}
+				}
+				else {
+					let type_info =
+						self.imported_type_libs.iter()
+						.filter(|type_lib| Some(type_lib.guid) == *lib_guid)
+						.find_map(|type_lib| match type_ {
+							types::ImportedType::Guid(guid) => type_lib.type_infos.iter().find(|type_info| type_info.guid == *guid),
+							types::ImportedType::Index(index) => type_lib.type_infos.get(*index as usize),
+						})
+						.ok_or_else(|| self.error(location, "imported type not found"))?;
+					(type_info.size_instance, type_info.alignment)
+				},
+		};
+
+		Ok((size, alignment.clamp(1, MAX_ALIGNMENT)))
+	}
+}
+
+fn align_up(offset: u32, alignment: u16) -> u32 {
+	let alignment = u32::from(alignment.max(1));
+	offset.div_ceil(alignment) * alignment
+}
+
+/// Writes the given typelib as IDL, the way `oleview.exe` decompiles a typelib.
+///
+/// The IDL compiles back to the same typelib, except for what IDL can't express, like the offsets of fields and vtable slots,
+/// which are recomputed by the compiler, and the names of types in typelibs other than `stdole2.tlb`.
+pub(crate) fn write<W>(type_lib: &types::TypeLib, mut out: W) -> Result<(), crate::Error> where W: std::io::Write {
+	// Forward-declare interfaces outside the library, so that they can be referenced before they're defined
+	// without changing the order of the types in the library.
+	let mut have_forward_declarations = false;
+	for type_info in &type_lib.type_infos {
+		match type_info.typekind {
+			types::TypeKind::Interface => writeln!(out, "interface {};", type_info.name)?,
+			types::TypeKind::Dispatch if type_info.is_dual() => writeln!(out, "interface {};", type_info.name)?,
+			types::TypeKind::Dispatch => writeln!(out, "dispinterface {};", type_info.name)?,
+			_ => continue,
+		}
+		have_forward_declarations = true;
+	}
+	if have_forward_declarations {
+		writeln!(out)?;
+	}
+
+	let mut attributes = vec![format!("uuid({})", type_lib.guid), version_attribute(type_lib.version)];
+	if type_lib.lcid != 0 {
+		attributes.push(format!("lcid(0x{:04x})", type_lib.lcid));
+	}
+	attributes.extend(type_lib.doc_string.as_deref().map(|doc_string| format!("helpstring({})", quote(doc_string))));
+	attributes.extend(type_lib.help_file.as_deref().map(|help_file| format!("helpfile({})", quote(help_file))));
+	if type_lib.help_context != 0 {
+		attributes.push(format!("helpcontext(0x{:08x})", type_lib.help_context));
+	}
+	attributes.extend(flag_words(type_lib.flags, LIBFLAGS));
+	write_attribute_block(&mut out, "", &attributes)?;
+
+	writeln!(out, "library {} {{", type_lib.name)?;
+
+	for import in &type_lib.imports {
+		writeln!(out, "    importlib({});", quote(&import.file_name))?;
+	}
+
+	for type_info in &type_lib.type_infos {
+		writeln!(out)?;
+		write_type_info(&mut out, type_lib, type_info)?;
+	}
+
+	writeln!(out, "}};")?;
+
+	Ok(())
+}
+
+fn write_type_info<W>(mut out: W, type_lib: &types::TypeLib, type_info: &types::TypeInfo) -> Result<(), crate::Error> where W: std::io::Write {
+	let mut attributes = vec![];
+	if type_info.guid != types::Guid::default() {
+		attributes.push(format!("uuid({})", type_info.guid));
+	}
+	if type_info.version != types::Version::default() {
+		attributes.push(version_attribute(type_info.version));
+	}
+	attributes.extend(type_info.doc_string.as_deref().map(|doc_string| format!("helpstring({})", quote(doc_string))));
+	if type_info.help_context != 0 {
+		attributes.push(format!("helpcontext(0x{:08x})", type_info.help_context));
+	}
+	attributes.extend(flag_words(type_info.flags, TYPEFLAGS));
+
+	let name = &type_info.name;
+
+	// Dual interfaces are written as interfaces with the `dual` attribute
+	let typekind = if type_info.is_dual() { types::TypeKind::Interface } else { type_info.typekind };
+
+	match typekind {
+		types::TypeKind::Enum | types::TypeKind::Record | types::TypeKind::Union => {
+			let keyword = match typekind {
+				types::TypeKind::Enum => "enum",
+				types::TypeKind::Record => "struct",
+				_ => "union",
+			};
+			writeln!(out, "    typedef {}{} {} {{", attribute_list(&attributes), keyword, name)?;
+
+			for (i, var) in type_info.vars.iter().enumerate() {
+				let attributes = attribute_list(&var_attributes(var, i));
+				if typekind == types::TypeKind::Enum {
+					writeln!(out, "        {}{} = {},", attributes, var.name, const_value(var))?;
+				}
+				else {
+					let (type_name, suffix) = type_to_idl(&var.type_, type_lib);
+					writeln!(out, "        {}{} {}{};", attributes, type_name, var.name, suffix)?;
+				}
+			}
+
+			writeln!(out, "    }} {};", name)?;
+		},
+
+		types::TypeKind::Alias => {
+			attributes.insert(0, "public".to_string());
+			let alias_type = type_info.alias_type.as_ref().unwrap_or(&types::TypeDesc::Base(types::VT_VOID));
+			let (type_name, suffix) = type_to_idl(alias_type, type_lib);
+			writeln!(out, "    typedef {}{} {}{};", attribute_list(&attributes), type_name, name, suffix)?;
+		},
+
+		types::TypeKind::Module => {
+			attributes.extend(type_info.dll_name.as_deref().map(|dll_name| format!("dllname({})", quote(dll_name))));
+			write_attribute_block(&mut out, "    ", &attributes)?;
+			writeln!(out, "    module {} {{", name)?;
+
+			for function in &type_info.functions {
+				write_function(&mut out, type_lib, function, "        ")?;
+			}
+
+			for (i, var) in type_info.vars.iter().enumerate() {
+				let (type_name, suffix) = type_to_idl(&var.type_, type_lib);
+				writeln!(out, "        {}const {} {}{} = {};", attribute_list(&var_attributes(var, i)), type_name, var.name, suffix, const_value(var))?;
+			}
+
+			writeln!(out, "    }};")?;
+		},
+
+		types::TypeKind::Interface => {
+			attributes.insert(0, "odl".to_string());
+			write_attribute_block(&mut out, "    ", &attributes)?;
+
+			match type_info.impl_types.first() {
+				Some(parent) => writeln!(out, "    interface {} : {} {{", name, type_ref_to_idl(&parent.type_ref, type_lib))?,
+				None => writeln!(out, "    interface {} {{", name)?,
+			}
+
+			for function in &type_info.functions {
+				write_function(&mut out, type_lib, function, "        ")?;
+			}
+
+			writeln!(out, "    }};")?;
+		},
+
+		types::TypeKind::Dispatch => {
+			write_attribute_block(&mut out, "    ", &attributes)?;
+			writeln!(out, "    dispinterface {} {{", name)?;
+
+			writeln!(out, "        properties:")?;
+			for (i, var) in type_info.vars.iter().enumerate() {
+				let (type_name, suffix) = type_to_idl(&var.type_, type_lib);
+				writeln!(out, "            {}{} {}{};", attribute_list(&var_attributes(var, i)), type_name, var.name, suffix)?;
+			}
+
+			writeln!(out, "        methods:")?;
+			for function in &type_info.functions {
+				write_function(&mut out, type_lib, function, "            ")?;
+			}
+
+			writeln!(out, "    }};")?;
+		},
+
+		types::TypeKind::CoClass => {
+			if (type_info.flags & types::TYPEFLAG_FCANCREATE) == 0 {
+				attributes.push("noncreatable".to_string());
+			}
+			write_attribute_block(&mut out, "    ", &attributes)?;
+			writeln!(out, "    coclass {} {{", name)?;
+
+			for impl_type in &type_info.impl_types {
+				let keyword = match type_lib.get_type_info(&impl_type.type_ref) {
+					Some(type_info) if type_info.typekind == types::TypeKind::Dispatch && !type_info.is_dual() => "dispinterface",
+					_ => "interface",
+				};
+				let attributes: Vec<_> = flag_words(impl_type.flags, IMPLTYPEFLAGS).collect();
+				writeln!(out, "        {}{} {};", attribute_list(&attributes), keyword, type_ref_to_idl(&impl_type.type_ref, type_lib))?;
+			}
+
+			writeln!(out, "    }};")?;
+		},
+	}
+
+	Ok(())
+}
+
+fn write_function<W>(mut out: W, type_lib: &types::TypeLib, function: &types::Function, indent: &str) -> Result<(), crate::Error> where W: std::io::Write {
+	let mut attributes = vec![];
+	match &function.entry_point {
+		Some(types::EntryPoint::Name(name)) => attributes.push(format!("entry({})", quote(name))),
+		Some(types::EntryPoint::Ordinal(ordinal)) => attributes.push(format!("entry({})", ordinal)),
+		None => (),
+	}
+	attributes.push(format!("id(0x{:08x})", function.member_id));
+	match function.invoke_kind {
+		types::InvokeKind::Func => (),
+		types::InvokeKind::PropertyGet => attributes.push("propget".to_string()),
+		types::InvokeKind::PropertyPut => attributes.push("propput".to_string()),
+		types::InvokeKind::PropertyPutRef => attributes.push("propputref".to_string()),
+	}
+	attributes.extend(flag_words(function.flags, FUNCFLAGS));
+	attributes.extend(function.doc_string.as_deref().map(|doc_string| format!("helpstring({})", quote(doc_string))));
+	if function.help_context != 0 {
+		attributes.push(format!("helpcontext(0x{:08x})", function.help_context));
+	}
+
+	let call_conv = match function.call_conv {
+		types::CallConv::StdCall => "",
+		types::CallConv::CDecl => "__cdecl ",
+		types::CallConv::FastCall => "__fastcall ",
+		types::CallConv::Pascal => "__pascal ",
+
+		// IDL has no keywords for the other calling conventions
+		types::CallConv::MacPascal => "/* CC_MACPASCAL */ ",
+		types::CallConv::FpFastCall => "/* CC_FPFASTCALL */ ",
+		types::CallConv::SysCall => "/* CC_SYSCALL */ ",
+		types::CallConv::MpwCDecl => "/* CC_MPWCDECL */ ",
+		types::CallConv::MpwPascal => "/* CC_MPWPASCAL */ ",
+	};
+
+	let (return_type, _) = type_to_idl(&function.return_type, type_lib);
+
+	let params: Vec<_> = function.params.iter().enumerate().map(|(i, param)| {
+		let mut attributes: Vec<_> = flag_words(param.flags, PARAMFLAGS).filter(|word| word != "optional").collect();
+		match &param.default_value {
+			Some(default_value) if (param.flags & types::PARAMFLAG_FHASDEFAULT) == types::PARAMFLAG_FHASDEFAULT =>
+				attributes.push(format!("defaultvalue({})", variant_to_idl(default_value))),
+			_ if (param.flags & types::PARAMFLAG_FOPT) == types::PARAMFLAG_FOPT => attributes.push("optional".to_string()),
+			_ => (),
+		}
+
+		let (type_name, suffix) = type_to_idl(&param.type_, type_lib);
+
+		// Parameters of functions in typelibs don't always have names, but they must in IDL
+		let name = if param.name.is_empty() { format!("param{}", i) } else { param.name.clone() };
+
+		format!("{}{} {}{}", attribute_list(&attributes), type_name, name, suffix)
+	}).collect();
+
+	writeln!(out, "{}{}{} {}{}({});", indent, attribute_list(&attributes), return_type, call_conv, function.name, params.join(", "))?;
+
+	Ok(())
+}
+
+fn var_attributes(var: &types::Var, index: usize) -> Vec<String> {
+	let mut attributes = vec![];
+
+	// The IDs of dispinterface properties are always written, since they're what clients use to access them.
+	// Other IDs are only written if they aren't the ones that the compiler would assign.
+	if var.var_kind == types::VarKind::Dispatch || var.member_id != 0x4000_0000 + index as i32 {
+		attributes.push(format!("id(0x{:08x})", var.member_id));
+	}
+	attributes.extend(flag_words(var.flags, VARFLAGS));
+	attributes.extend(var.doc_string.as_deref().map(|doc_string| format!("helpstring({})", quote(doc_string))));
+	if var.help_context != 0 {
+		attributes.push(format!("helpcontext(0x{:08x})", var.help_context));
+	}
+
+	attributes
+}
+
+fn const_value(var: &types::Var) -> String {
+	match &var.var_kind {
+		types::VarKind::Const(value) => variant_to_idl(value),
+		_ => "0 /* not a constant */".to_string(),
+	}
+}
+
+/// Writes a multi-line attribute list, like the ones before libraries, interfaces and coclasses
+fn write_attribute_block<W>(mut out: W, indent: &str, attributes: &[String]) -> std::io::Result<()> where W: std::io::Write {
+	if attributes.is_empty() {
+		return Ok(());
+	}
+
+	writeln!(out, "{}[", indent)?;
+	for (i, attribute) in attributes.iter().enumerate() {
+		writeln!(out, "{}    {}{}", indent, attribute, if i + 1 < attributes.len() { "," } else { "" })?;
+	}
+	writeln!(out, "{}]", indent)?;
+
+	Ok(())
+}
+
+/// Formats a single-line attribute list, like the ones before members and parameters, followed by a space
+fn attribute_list(attributes: &[String]) -> String {
+	if attributes.is_empty() {
+		String::new()
+	}
+	else {
+		format!("[{}] ", attributes.join(", "))
+	}
+}
+
+fn flag_words<'a>(flags: u16, table: &'a [(&'a str, u16)]) -> impl Iterator<Item = String> + 'a {
+	table.iter()
+		.filter(move |(_, flag)| (flags & flag) == *flag)
+		.map(|(word, _)| (*word).to_string())
+}
+
+fn version_attribute(version: types::Version) -> String {
+	format!("version({}.{})", version.major, version.minor)
+}
+
+fn quote(s: &str) -> String {
+	let mut result = String::with_capacity(s.len() + 2);
+	result.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			'\n' => result.push_str("\\n"),
+			'\t' => result.push_str("\\t"),
+			'\r' => result.push_str("\\r"),
+			'\0' => result.push_str("\\0"),
+			c => result.push(c),
+		}
+	}
+	result.push('"');
+	result
+}
+
+/// Formats a constant value or default value as an expression that [`Compiler::variant`] converts back to the same value
+fn variant_to_idl(value: &types::Variant) -> String {
+	fn float(value: f64) -> String {
+		// `Display` never uses an exponent, but an integral value has to be given a fractional part to be parsed as a float
+		let s = value.to_string();
+		if s.contains('.') { s } else { format!("{}.0", s) }
+	}
+
+	match value {
+		types::Variant::Empty | types::Variant::Null => "0".to_string(),
+		types::Variant::I1(value) => value.to_string(),
+		types::Variant::I2(value) => value.to_string(),
+		types::Variant::I4(value) | types::Variant::Int(value) | types::Variant::Error(value) => value.to_string(),
+		types::Variant::I8(value) => value.to_string(),
+		types::Variant::UI1(value) => value.to_string(),
+		types::Variant::UI2(value) => value.to_string(),
+		types::Variant::UI4(value) | types::Variant::UInt(value) => value.to_string(),
+		types::Variant::UI8(value) => value.to_string(),
+		types::Variant::R4(value) => float(f64::from(*value)),
+		types::Variant::R8(value) | types::Variant::Date(value) => float(*value),
+		types::Variant::Cy(value) =>
+			if value % 10000 == 0 {
+				(value / 10000).to_string()
+			}
+			else {
+				float(*value as f64 / 10000.0)
+			},
+		types::Variant::Bool(value) => if *value { "-1".to_string() } else { "0".to_string() },
+		types::Variant::Bstr(value) => quote(value),
+		types::Variant::Other(vt) => format!("0 /* VARTYPE {} */", vt),
+	}
+}
+
+/// Formats a type as IDL. C arrays are formatted as a type and a suffix that goes after the name of the declarator.
+fn type_to_idl(type_: &types::TypeDesc, type_lib: &types::TypeLib) -> (String, String) {
+	match type_ {
+		types::TypeDesc::Base(vt) => (base_type_to_idl(*vt).to_string(), String::new()),
+
+		types::TypeDesc::Ptr(pointee) => {
+			let (type_name, suffix) = type_to_idl(pointee, type_lib);
+			(format!("{}*", type_name), suffix)
+		},
+
+		types::TypeDesc::SafeArray(element) => {
+			let (type_name, suffix) = type_to_idl(element, type_lib);
+			(format!("SAFEARRAY({}{})", type_name, suffix), String::new())
+		},
+
+		types::TypeDesc::CArray(element, dimensions) => {
+			let (type_name, mut suffix) = type_to_idl(element, type_lib);
+			for dimension in dimensions.iter().rev() {
+				suffix.insert_str(0, &format!("[{}]", dimension));
+			}
+			(type_name, suffix)
+		},
+
+		types::TypeDesc::UserDefined(type_ref) => {
+			// Structs and unions are referred to by their tags, so that they can be referenced before they're defined, like by a pointer to themselves
+			let keyword = match type_lib.get_type_info(type_ref).map(|type_info| type_info.typekind) {
+				Some(types::TypeKind::Record) => "struct ",
+				Some(types::TypeKind::Union) => "union ",
+				_ => "",
+			};
+			(format!("{}{}", keyword, type_ref_to_idl(type_ref, type_lib)), String::new())
+		},
+	}
+}
+
+fn type_ref_to_idl<'a>(type_ref: &types::TypeRef, type_lib: &'a types::TypeLib) -> &'a str {
+	type_lib.get_type_name(type_ref).unwrap_or("__missing_type__")
+}
+
+/// The inverse of [`builtin_type`]
+fn base_type_to_idl(vt: types::VarType) -> &'static str {
+	match vt {
+		types::VT_I1 => "char",
+		types::VT_UI1 => "unsigned char",
+		types::VT_I2 => "short",
+		types::VT_UI2 => "unsigned short",
+		types::VT_I4 => "long",
+		types::VT_UI4 => "unsigned long",
+		types::VT_INT => "int",
+		types::VT_UINT => "unsigned int",
+		types::VT_I8 => "__int64",
+		types::VT_UI8 => "unsigned __int64",
+		types::VT_R4 => "float",
+		types::VT_R8 => "double",
+		types::VT_HRESULT => "HRESULT",
+		types::VT_ERROR => "SCODE",
+		types::VT_BSTR => "BSTR",
+		types::VT_VARIANT => "VARIANT",
+		types::VT_BOOL => "VARIANT_BOOL",
+		types::VT_CY => "CURRENCY",
+		types::VT_DATE => "DATE",
+		types::VT_DECIMAL => "DECIMAL",
+		types::VT_LPSTR => "LPSTR",
+		types::VT_LPWSTR => "LPWSTR",
+		types::VT_UNKNOWN => "IUnknown*",
+		types::VT_DISPATCH => "IDispatch*",
+		types::VT_VOID => "void",
+		_ => "__missing_type__",
+	}
+}
@@ -0,0 +1,750 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_possible_wrap,
+	clippy::cast_precision_loss,
+	clippy::cast_sign_loss,
+	clippy::cognitive_complexity,
+	clippy::missing_errors_doc,
+	clippy::missing_panics_doc,
+	clippy::must_use_candidate,
+	clippy::too_many_lines,
+	clippy::uninlined_format_args,
+	clippy::use_self,
+)]
+
+mod allowlist;
+mod builder;
+mod com_rs;
+mod error;
+mod idl;
+mod msft;
+mod pe;
+mod repr_c;
+mod sltg;
+mod snapshot;
+mod type_mappings;
+pub mod types;
+mod warning;
+mod winapi;
+mod windows_rs;
+
+pub use builder::{Builder, MissingTypePolicy};
+pub use error::{Error, Location};
+pub use type_mappings::{TypeLibSelector, TypeMappings};
+pub use snapshot::FORMAT_VERSION as SNAPSHOT_FORMAT_VERSION;
+pub use warning::{FailedTypeSummary, Summary, Warning, WarningKind};
+
+/// The crate that bindings are emitted for
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Backend {
+	/// `winapi` 0.3, using its `RIDL!`, `STRUCT!` and `ENUM!` macros
+	Winapi,
+
+	/// The `windows` and `windows-core` crates, with a vtable struct and an implementation of `windows_core::Interface` for each interface
+	///
+	/// Use [`TypeMappings::windows`] so that types from `stdole2.tlb` are emitted as the `windows` types.
+	Windows,
+
+	/// Plain `#[repr(C)]` structs for interfaces and their vtables, which don't depend on any crate
+	///
+	/// The bindings refer to a handful of ABI types like `GUID`, `HRESULT` and `IUnknownVtbl` by name, which must be in scope.
+	/// Use [`TypeMappings::empty`] so that types from `stdole2.tlb` are emitted by their names too.
+	/// The ABI types can come from `winapi`, or from the prelude written by [`write_prelude`].
+	ReprC,
+
+	/// The `com` crate, with a `com::interfaces!` block for each interface and a `CLSID_` constant for each coclass
+	///
+	/// Use [`TypeMappings::com`] so that `GUID` and `IUnknown` are emitted as the `com` types. The bindings refer to the other ABI types
+	/// that they use by name, like `BSTR` and `IDispatch`, which must be in scope.
+	Com,
+}
+
+/// The result of running [`build`]
+#[derive(Debug)]
+pub struct BuildResult {
+	/// The references to types that could not be found, each with the type or member that it's in. The references were replaced with `__missing_type__`.
+	pub missing_types: Vec<MissingType>,
+
+	/// The names of the dispinterfaces that were skipped because the `emit_dispinterfaces` parameter of [`build`] was false
+	pub skipped_dispinterfaces: Vec<String>,
+
+	/// The names of the dual interfaces whose dispinterface half was skipped, and only whose interface half was emitted
+	pub skipped_dispinterface_of_dual_interfaces: Vec<String>,
+
+	/// The types that bindings could not be emitted for, and were left out because the [`Builder`] was configured with [`Builder::continue_on_error`]
+	pub failed_types: Vec<FailedType>,
+
+	/// Everything about the bindings that might need attention, including the missing types and skipped dispinterfaces above
+	pub warnings: Vec<Warning>,
+
+	/// The files that the [`Builder`] read the typelib and its dependencies from, including the files that an IDL file imports,
+	/// for a build script to print `cargo:rerun-if-changed` for. Empty for [`build_from_type_lib`], which doesn't read any files.
+	pub files: Vec<std::path::PathBuf>,
+}
+
+impl BuildResult {
+	/// Gets a summary of the warnings and failed types that can be serialized
+	pub fn summary(&self) -> Summary {
+		Summary {
+			warnings: self.warnings.clone(),
+			failed_types:
+				self.failed_types.iter()
+				.map(|failed_type| FailedTypeSummary { type_name: failed_type.type_name.clone(), error: failed_type.error.to_string() })
+				.collect(),
+		}
+	}
+
+	/// Records a warning, unless the same warning was already recorded
+	fn warn(&mut self, kind: WarningKind, location: Location) {
+		let warning = Warning { kind, location };
+		if !self.warnings.contains(&warning) {
+			self.warnings.push(warning);
+		}
+	}
+
+	/// Records that the given type reference could not be resolved
+	fn missing_type(&mut self, type_ref: &types::TypeRef, location: &Location) -> MissingType {
+		let missing_type = MissingType { referrer: location.referrer(), type_ref: type_ref.clone() };
+		self.missing_types.push(missing_type.clone());
+		self.warn(WarningKind::MissingType { type_ref: type_ref.clone() }, location.clone());
+		missing_type
+	}
+
+	fn skipped_dispinterface(&mut self, type_name: &str) {
+		self.skipped_dispinterfaces.push(type_name.to_string());
+		self.warn(WarningKind::SkippedDispinterface, Location::type_(type_name));
+	}
+
+	fn skipped_dispinterface_of_dual_interface(&mut self, type_name: &str) {
+		self.skipped_dispinterface_of_dual_interfaces.push(type_name.to_string());
+		self.warn(WarningKind::SkippedDispinterfaceOfDualInterface, Location::type_(type_name));
+	}
+
+	/// Records that the given type was emitted as the given Rust type, which doesn't capture all of it
+	fn lossy_type_mapping(&mut self, type_: &types::TypeDesc, emitted_as: &str, location: &Location) {
+		self.warn(WarningKind::LossyTypeMapping { type_: type_.clone(), emitted_as: emitted_as.to_string() }, location.clone());
+	}
+}
+
+/// A type that bindings could not be emitted for
+#[derive(Debug)]
+pub struct FailedType {
+	/// The name of the type
+	pub type_name: String,
+
+	/// Why bindings could not be emitted for the type
+	pub error: Error,
+}
+
+impl std::fmt::Display for FailedType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} was left out: {}", self.type_name, self.error)
+	}
+}
+
+/// A reference to a type that could not be found
+#[derive(Clone, Debug)]
+pub struct MissingType {
+	/// The type or member that references the type, like `IFoo` or `IFoo::Bar`
+	pub referrer: String,
+
+	/// The reference to the type. For a type in an imported typelib, this has the LIBID of that typelib.
+	pub type_ref: types::TypeRef,
+}
+
+impl std::fmt::Display for MissingType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.type_ref {
+			types::TypeRef::Local(index) => write!(f, "{} references type {}, which is not in the typelib", self.referrer, index),
+
+			types::TypeRef::External { lib_guid, type_ } => {
+				write!(f, "{} references ", self.referrer)?;
+				match type_ {
+					types::ImportedType::Guid(guid) => write!(f, "type {{{}}}", guid)?,
+					types::ImportedType::Index(index) => write!(f, "type {}", index)?,
+				}
+				match lib_guid {
+					Some(lib_guid) => write!(f, " in typelib {{{}}}, which was not found", lib_guid),
+					None => write!(f, " in an unknown typelib, which was not found"),
+				}
+			},
+		}
+	}
+}
+
+/// Parses the typelib at the given path and emits bindings to the given writer.
+///
+/// The path can be a `.tlb` file, or a PE image (`.dll`, `.ocx`, `.exe`, `.olb`) with an embedded `TYPELIB` resource.
+/// Like `LoadTypeLibEx`, a resource index other than 1 can be selected by appending it to the path, as in `foo.dll\3`
+///
+/// The path can also be a JSON snapshot written by [`write_snapshot`], or an `.idl` / `.odl` file, which is compiled for 64-bit Windows
+/// the same way `midl.exe` would. Use [`load_idl`] to compile it for a different platform.
+///
+/// The typelib is parsed without using any Windows APIs, so this works on any host.
+///
+/// Types from `stdole2.tlb` are emitted as the paths of their `winapi` bindings, as in [`TypeMappings::default`].
+/// Use a [`Builder`] to resolve types from other typelibs, to map them to other paths, and to configure the bindings in other ways.
+pub fn build<W>(filename: &std::path::Path, emit_dispinterfaces: bool, out: W) -> Result<BuildResult, Error> where W: std::io::Write {
+	Builder::from_path(filename).emit_dispinterfaces(emit_dispinterfaces).build(out)
+}
+
+/// Parses the typelib at the given path into a [`types::TypeLib`].
+///
+/// The path is interpreted the same way as for [`build`].
+pub fn load_type_lib(filename: &std::path::Path) -> Result<types::TypeLib, Error> {
+	load_type_lib_with_files(filename, &mut vec![])
+}
+
+/// Parses the typelib at the given path like [`load_type_lib`], and adds the paths of the files that were read for it to `files`
+fn load_type_lib_with_files(filename: &std::path::Path, files: &mut Vec<std::path::PathBuf>) -> Result<types::TypeLib, Error> {
+	if idl::is_idl(filename) {
+		return idl::read(filename, types::SysKind::Win64, files);
+	}
+
+	let (filename, resource_index) = pe::split_resource_index(filename);
+	let data = std::fs::read(&filename)?;
+	files.push(filename.into_owned());
+	read_type_lib(&data, resource_index)
+}
+
+/// Parses the given bytes into a [`types::TypeLib`].
+///
+/// The bytes can be a typelib, a PE image with an embedded `TYPELIB` resource, or a JSON snapshot written by [`write_snapshot`].
+/// The format is detected from the bytes. The typelib in resource 1 of an image is used, as for a path without a resource index in [`build`].
+pub fn load_type_lib_from_bytes(data: &[u8]) -> Result<types::TypeLib, Error> {
+	read_type_lib(data, None)
+}
+
+/// Reads the given reader to the end, and parses what it read into a [`types::TypeLib`] the same way as [`load_type_lib_from_bytes`]
+pub fn load_type_lib_from_reader<R>(mut reader: R) -> Result<types::TypeLib, Error> where R: std::io::Read {
+	let mut data = vec![];
+	reader.read_to_end(&mut data)?;
+	read_type_lib(&data, None)
+}
+
+/// Parses the given typelib, PE image or JSON snapshot. The resource index is only valid for a PE image.
+fn read_type_lib(data: &[u8], resource_index: Option<u16>) -> Result<types::TypeLib, Error> {
+	let data =
+		if pe::is_pe(data) {
+			pe::find_type_lib(data, resource_index.unwrap_or(pe::DEFAULT_RESOURCE_INDEX))?
+		}
+		else if resource_index.is_some() {
+			// Only images have resources
+			return Err(Error::UnsupportedFormat);
+		}
+		else {
+			data
+		};
+
+	if msft::is_msft(data) {
+		msft::read(data)
+	}
+	else if sltg::is_sltg(data) {
+		sltg::read(data)
+	}
+	else if snapshot::is_snapshot(data) {
+		snapshot::read(data)
+	}
+	else {
+		Err(Error::UnsupportedFormat)
+	}
+}
+
+/// Compiles the IDL file at the given path into the typelib that `midl.exe` would generate for the given platform.
+///
+/// Files named by `importlib` other than `stdole2.tlb` are loaded relative to the IDL file. Files named by `import` are parsed
+/// if they exist relative to the IDL file, and otherwise the types they would have defined must be ones that this crate knows about,
+/// like `BSTR` and `IUnknown`.
+pub fn load_idl(filename: &std::path::Path, syskind: types::SysKind) -> Result<types::TypeLib, Error> {
+	idl::read(filename, syskind, &mut vec![])
+}
+
+/// Writes a JSON snapshot of the given typelib to the given writer.
+///
+/// The snapshot can be passed to [`build`] or [`load_type_lib`] in place of the original typelib.
+/// It records [`SNAPSHOT_FORMAT_VERSION`], and snapshots with a different version are rejected when they are read.
+pub fn write_snapshot<W>(type_lib: &types::TypeLib, out: W) -> Result<(), Error> where W: std::io::Write {
+	snapshot::write(type_lib, out)
+}
+
+/// Writes the given typelib to the given writer in the MSFT format, which is the format that `midl.exe` produces.
+///
+/// The typelib can come from [`load_type_lib`] or [`load_idl`], or be constructed or modified by the caller. This can be used to compile
+/// an IDL file or a JSON snapshot into a `.tlb` without `midl.exe`, or to produce the typelib of a COM server written in Rust.
+pub fn write_type_lib<W>(type_lib: &types::TypeLib, out: W) -> Result<(), Error> where W: std::io::Write {
+	msft::write(type_lib, out)
+}
+
+/// Writes the given typelib to the given writer as IDL, the way `oleview.exe` decompiles a typelib.
+///
+/// This shows what is really in a typelib before bindings are generated for it. The IDL can be compiled back with [`load_idl`].
+pub fn write_idl<W>(type_lib: &types::TypeLib, out: W) -> Result<(), Error> where W: std::io::Write {
+	idl::write(type_lib, out)
+}
+
+/// Finds and loads the typelibs that the given typelib imports types from, so that they can be passed to [`build_from_type_lib`].
+///
+/// Each imported typelib is looked for in the given directories, in order, by the file name that it had when the typelib was created,
+/// like `stdole2.tlb` or `mso.dll`. A file is only used if it [satisfies](types::ImportedTypeLib::is_satisfied_by) the import,
+/// that is if it has the same LIBID and a compatible version. Imported typelibs that aren't found are skipped.
+pub fn load_dependencies(type_lib: &types::TypeLib, search_path: &[std::path::PathBuf]) -> Result<Vec<types::TypeLib>, Error> {
+	load_dependencies_with_files(type_lib, search_path, &mut vec![])
+}
+
+/// Finds and loads the dependencies of the given typelib like [`load_dependencies`], and adds the paths of the files that were read for them to `files`
+fn load_dependencies_with_files(type_lib: &types::TypeLib, search_path: &[std::path::PathBuf], files: &mut Vec<std::path::PathBuf>) -> Result<Vec<types::TypeLib>, Error> {
+	let mut dependencies = vec![];
+
+	for import in &type_lib.imports {
+		// The file name can be the full path of the file on the machine that the typelib was created on
+		let file_name = import.file_name.rsplit(['\\', '/']).next().unwrap_or(&import.file_name);
+
+		for dir in search_path {
+			let Some(path) = find_file(dir, file_name)? else {
+				continue;
+			};
+
+			let dependency = load_type_lib_with_files(&path, files)?;
+			if import.is_satisfied_by(&dependency) {
+				dependencies.push(dependency);
+				break;
+			}
+		}
+	}
+
+	Ok(dependencies)
+}
+
+/// Finds the file with the given name in the given directory. Like on Windows, the name is matched case-insensitively.
+fn find_file(dir: &std::path::Path, file_name: &str) -> Result<Option<std::path::PathBuf>, Error> {
+	let path = dir.join(file_name);
+	if path.is_file() {
+		return Ok(Some(path));
+	}
+
+	let entries = match std::fs::read_dir(dir) {
+		Ok(entries) => entries,
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+		Err(err) => return Err(err.into()),
+	};
+
+	for entry in entries {
+		let entry = entry?;
+		if entry.file_name().to_str().is_some_and(|name| name.eq_ignore_ascii_case(file_name)) && entry.path().is_file() {
+			return Ok(Some(entry.path()));
+		}
+	}
+
+	Ok(None)
+}
+
+/// Writes the ABI types that bindings for [`Backend::ReprC`] refer to by name, like `GUID`, `VARIANT` and `IUnknownVtbl`, to the given writer.
+///
+/// They don't depend on any crate, so bindings that use them compile on any host, not just on Windows.
+/// The prelude is also written at the start of the bindings by [`build_from_type_lib`] if its `emit_imports` parameter is true.
+pub fn write_prelude<W>(out: W) -> Result<(), Error> where W: std::io::Write {
+	Ok(repr_c::write_prelude(out)?)
+}
+
+/// Emits bindings for the given typelib to the given writer.
+///
+/// The typelib can come from [`load_type_lib`], or be constructed or modified by the caller.
+///
+/// References to types in the typelibs that it imports are resolved using the given typelibs, which can come from [`load_dependencies`].
+/// Types in `stdole2.tlb` that almost every typelib references, like `IDispatch`, are resolved even if `stdole2.tlb` isn't given.
+/// The types are emitted as the Rust paths that they're mapped to by the given [`TypeMappings`], or else by their names.
+///
+/// If `emit_imports` is true, the bindings start with `use` items for the `winapi` items and the parents of interfaces that they refer to by name,
+/// so that they compile without any imports of their own. Bindings for [`Backend::Windows`] always refer to items by their full paths.
+/// Bindings for [`Backend::ReprC`] start with the prelude written by [`write_prelude`] instead, so they compile on any host.
+pub fn build_from_type_lib<W>(
+	type_lib: &types::TypeLib,
+	dependencies: &[types::TypeLib],
+	type_mappings: &TypeMappings,
+	backend: Backend,
+	emit_dispinterfaces: bool,
+	emit_imports: bool,
+	out: W,
+) -> Result<BuildResult, Error> where W: std::io::Write {
+	let context = Context { type_lib, dependencies, type_mappings, selected: None };
+	write_bindings(&context, backend, emit_dispinterfaces, emit_imports, false, out)
+}
+
+/// Emits bindings for the typelib of the given context to the given writer.
+///
+/// If `continue_on_error` is true, types that bindings can't be emitted for are left out and recorded in [`BuildResult::failed_types`].
+fn write_bindings<W>(
+	context: &Context<'_>,
+	backend: Backend,
+	emit_dispinterfaces: bool,
+	emit_imports: bool,
+	continue_on_error: bool,
+	mut out: W,
+) -> Result<BuildResult, Error> where W: std::io::Write {
+	let mut build_result = BuildResult {
+		missing_types: vec![],
+		skipped_dispinterfaces: vec![],
+		skipped_dispinterface_of_dual_interfaces: vec![],
+		failed_types: vec![],
+		warnings: vec![],
+		files: vec![],
+	};
+
+	match backend {
+		Backend::Winapi =>
+			if emit_imports {
+				// The imports depend on what the items refer to, so the items are written first.
+				// The names that a type refers to only count if its bindings are written, so they're collected separately for each type.
+				let mut items = vec![];
+				let mut referenced_names = std::collections::BTreeSet::new();
+				write_items(context, continue_on_error, &mut build_result, &mut items, |type_info, build_result, item| {
+					let mut item_referenced_names = std::collections::BTreeSet::new();
+					winapi::write_item(context, type_info, emit_dispinterfaces, &mut item_referenced_names, build_result, item)?;
+					referenced_names.append(&mut item_referenced_names);
+					Ok(())
+				})?;
+				winapi::write_imports(context, &referenced_names, &mut out)?;
+				out.write_all(&items)?;
+			}
+			else {
+				write_items(context, continue_on_error, &mut build_result, &mut out, |type_info, build_result, item|
+					winapi::write_item(context, type_info, emit_dispinterfaces, &mut std::collections::BTreeSet::new(), build_result, item))?;
+			},
+
+		Backend::Windows => write_items(context, continue_on_error, &mut build_result, &mut out, |type_info, build_result, item|
+			windows_rs::write_item(context, type_info, emit_dispinterfaces, build_result, item))?,
+
+		Backend::ReprC => {
+			if emit_imports {
+				repr_c::write_prelude(&mut out)?;
+			}
+
+			write_items(context, continue_on_error, &mut build_result, &mut out, |type_info, build_result, item|
+				repr_c::write_item(context, type_info, emit_dispinterfaces, build_result, item))?;
+		},
+
+		Backend::Com => write_items(context, continue_on_error, &mut build_result, &mut out, |type_info, build_result, item|
+			com_rs::write_item(context, type_info, emit_dispinterfaces, build_result, item))?,
+	}
+
+	Ok(build_result)
+}
+
+/// Writes the bindings for each type of the typelib of the given context with the given function of a backend
+fn write_items<W, F>(context: &Context<'_>, continue_on_error: bool, build_result: &mut BuildResult, mut out: W, mut write_item: F) -> Result<(), Error>
+where W: std::io::Write, F: FnMut(&types::TypeInfo, &mut BuildResult, &mut Vec<u8>) -> Result<(), Error> {
+	// Each type is written to a buffer first, so that nothing is written for a type that fails partway through
+	let mut item = vec![];
+
+	for type_info in context.type_infos() {
+		item.clear();
+
+		match write_item(type_info, build_result, &mut item) {
+			Ok(()) => out.write_all(&item)?,
+			Err(error) if continue_on_error => build_result.failed_types.push(FailedType { type_name: type_info.name.clone(), error }),
+			Err(error) => return Err(error),
+		}
+	}
+
+	Ok(())
+}
+
+/// Emits bindings for the given typelib as a [`proc_macro2::TokenStream`], for a proc macro or for post-processing with `syn`.
+///
+/// The parameters are the same as for [`build_from_type_lib`]. The backends write the bindings as text, which is then parsed as a Rust file,
+/// so bindings that aren't valid Rust are an [`Error::Syntax`] rather than tokens that fail to compile later.
+/// Comments in the bindings aren't part of the tokens. Use [`write_tokens`] to write the tokens out.
+pub fn build_tokens(
+	type_lib: &types::TypeLib,
+	dependencies: &[types::TypeLib],
+	type_mappings: &TypeMappings,
+	backend: Backend,
+	emit_dispinterfaces: bool,
+	emit_imports: bool,
+) -> Result<(proc_macro2::TokenStream, BuildResult), Error> {
+	let mut out = vec![];
+	let build_result = build_from_type_lib(type_lib, dependencies, type_mappings, backend, emit_dispinterfaces, emit_imports, &mut out)?;
+	Ok((parse_bindings(&out)?, build_result))
+}
+
+/// Parses the given bindings as a Rust file
+fn parse_bindings(bindings: &[u8]) -> Result<proc_macro2::TokenStream, Error> {
+	let file = syn::parse_file(std::str::from_utf8(bindings).expect("bindings are written as UTF-8")).map_err(Error::Syntax)?;
+	Ok(quote::ToTokens::into_token_stream(file))
+}
+
+/// Writes the given tokens, such as the ones from [`build_tokens`], to the given writer.
+///
+/// If `pretty` is true, the tokens are formatted with `prettyplease`, which requires them to be a valid Rust file.
+/// The contents of macro invocations like `RIDL!` are left mostly on one line. Otherwise the tokens are written as they're displayed.
+pub fn write_tokens<W>(tokens: &proc_macro2::TokenStream, pretty: bool, mut out: W) -> Result<(), Error> where W: std::io::Write {
+	if pretty {
+		let file: syn::File = syn::parse2(tokens.clone()).map_err(Error::Syntax)?;
+		out.write_all(prettyplease::unparse(&file).as_bytes())?;
+	}
+	else {
+		writeln!(out, "{}", tokens)?;
+	}
+
+	Ok(())
+}
+
+fn sanitize_reserved(s: &str, location: &Location, build_result: &mut BuildResult) -> String {
+	let renamed = match s {
+		"impl" => "impl_",
+		"type" => "type_",
+		_ => return s.to_string(),
+	};
+
+	build_result.warn(WarningKind::RenamedIdentifier { original: s.to_string(), renamed: renamed.to_string() }, location.clone());
+	renamed.to_string()
+}
+
+/// The typelib that bindings are being emitted for, and what's needed to resolve the types that it references
+struct Context<'a> {
+	type_lib: &'a types::TypeLib,
+	dependencies: &'a [types::TypeLib],
+	type_mappings: &'a TypeMappings,
+
+	/// Whether to emit bindings for each type of the typelib, by index, or `None` to emit bindings for all of them
+	selected: Option<&'a [bool]>,
+}
+
+impl<'a> Context<'a> {
+	/// The types of the typelib that bindings are emitted for
+	fn type_infos(&self) -> impl Iterator<Item = &'a types::TypeInfo> + '_ {
+		self.type_lib.type_infos.iter().enumerate()
+		.filter(move |&(index, _)| self.selected.map_or(true, |selected| selected[index]))
+		.map(|(_, type_info)| type_info)
+	}
+}
+
+/// Gets the name of the type that the given type reference refers to, in the typelib or in the typelibs that it imports.
+///
+/// If the type can't be found, it's recorded in [`BuildResult::missing_types`] and `__missing_type__` is returned.
+fn type_ref_to_string<'a>(type_ref: &types::TypeRef, location: &Location, context: &Context<'a>, build_result: &mut BuildResult) -> &'a str {
+	if let Some(type_name) = context.type_lib.get_type_name(type_ref) {
+		return type_name;
+	}
+
+	if let Some((_, type_info)) = context.type_lib.get_imported_type_info(type_ref, context.dependencies) {
+		return &type_info.name;
+	}
+
+	build_result.missing_type(type_ref, location);
+	"__missing_type__"
+}
+
+/// Gets the size in bytes of the vtable of the interface that the given type reference refers to, in the typelib or in the typelibs that it imports.
+fn vtbl_size(type_ref: &types::TypeRef, context: &Context<'_>) -> Option<u16> {
+	context.type_lib.get_vtbl_size(type_ref).or_else(|| {
+		let (dependency, type_info) = context.type_lib.get_imported_type_info(type_ref, context.dependencies)?;
+		Some(type_info.size_vft / dependency.syskind.pointer_size() * context.type_lib.syskind.pointer_size())
+	})
+}
+
+/// Whether the given member of a dispinterface is one of the restricted members of `IUnknown` and `IDispatch` that it lists, like `IFaxServerNotify2` does.
+/// None of the backends emit these, since the dispinterface derives from `IDispatch` already.
+fn is_inherited_dispatch_member(type_info: &types::TypeInfo, function: &types::Function, context: &Context<'_>) -> bool {
+	// 3 from IUnknown + 4 from IDispatch
+	function.ovft < 7 * context.type_lib.syskind.pointer_size() &&
+	type_info.functions.iter().any(|function| function.ovft > 0)
+}
+
+/// Gets the typeinfo that the given type reference refers to, in the typelib or in the typelibs that it imports
+fn resolve_type_info<'a>(type_ref: &types::TypeRef, context: &Context<'a>) -> Option<&'a types::TypeInfo> {
+	context.type_lib.get_type_info(type_ref)
+	.or_else(|| context.type_lib.get_imported_type_info(type_ref, context.dependencies).map(|(_, type_info)| type_info))
+}
+
+/// Gets the Rust path that the given type in an imported typelib is mapped to by [`TypeMappings`], if any
+fn mapped_type_path(type_ref: &types::TypeRef, context: &Context<'_>) -> Option<String> {
+	if let types::TypeRef::Local(_) = type_ref {
+		return None;
+	}
+
+	let (lib_guid, lib_name, type_name) =
+		if let Some((dependency, type_info)) = context.type_lib.get_imported_type_info(type_ref, context.dependencies) {
+			(dependency.guid, &*dependency.name, &*type_info.name)
+		}
+		else {
+			// The well-known types of stdole2.tlb can be mapped without it
+			(types::LIBID_STDOLE, "stdole", context.type_lib.get_type_name(type_ref)?)
+		};
+
+	context.type_mappings.get(lib_guid, lib_name, type_name)
+}
+
+/// Gets the path that the given type is mapped to, or else its name
+fn type_ref_path(type_ref: &types::TypeRef, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> String {
+	mapped_type_path(type_ref, context)
+	.unwrap_or_else(|| type_ref_to_string(type_ref, location, context, build_result).to_string())
+}
+
+/// Splits the `VT_BYREF` and `VT_ARRAY` flags of a base type into the pointer or `SAFEARRAY` that they stand for,
+/// so that `VT_BYREF | VT_I4` is mapped the same way as a `VT_PTR` to `VT_I4`. Returns `None` if the type has neither flag.
+fn expand_var_type_flags(vt: types::VarType) -> Option<types::TypeDesc> {
+	if (vt & types::VT_RESERVED) == types::VT_RESERVED {
+		// Includes VT_ILLEGAL
+		None
+	}
+	else if (vt & types::VT_BYREF) == types::VT_BYREF {
+		let pointee = vt & !types::VT_BYREF;
+		Some(types::TypeDesc::Ptr(Box::new(expand_var_type_flags(pointee).unwrap_or(types::TypeDesc::Base(pointee)))))
+	}
+	else if (vt & (types::VT_ARRAY | types::VT_VECTOR)) == types::VT_ARRAY {
+		Some(types::TypeDesc::SafeArray(Box::new(types::TypeDesc::Base(vt & !types::VT_ARRAY))))
+	}
+	else {
+		None
+	}
+}
+
+/// Gets the name of the counted array that the given `VT_VECTOR` type stands for in a `PROPVARIANT`, like `CAL` for `VT_VECTOR | VT_I4`.
+/// Returns `None` if the type isn't a `VT_VECTOR`, or if it's a vector of `PROPVARIANT`s or `CLIPDATA`, which the bindings have no type for.
+fn counted_array_name(vt: types::VarType) -> Option<&'static str> {
+	if (vt & !types::VT_TYPEMASK) != types::VT_VECTOR {
+		return None;
+	}
+
+	Some(match vt & types::VT_TYPEMASK {
+		types::VT_I1 => "CAC",
+		types::VT_UI1 => "CAUB",
+		types::VT_I2 => "CAI",
+		types::VT_UI2 => "CAUI",
+		types::VT_I4 => "CAL",
+		types::VT_UI4 => "CAUL",
+		types::VT_R4 => "CAFLT",
+		types::VT_R8 => "CADBL",
+		types::VT_CY => "CACY",
+		types::VT_DATE => "CADATE",
+		types::VT_BSTR => "CABSTR",
+		types::VT_BSTR_BLOB => "CABSTRBLOB",
+		types::VT_BOOL => "CABOOL",
+		types::VT_ERROR => "CASCODE",
+		types::VT_I8 => "CAH",
+		types::VT_UI8 => "CAUH",
+		types::VT_LPSTR => "CALPSTR",
+		types::VT_LPWSTR => "CALPWSTR",
+		types::VT_FILETIME => "CAFILETIME",
+		types::VT_CLSID => "CACLSID",
+		_ => return None,
+	})
+}
+
+/// What a base type stands for in the bindings
+pub(crate) enum VarTypeKind {
+	/// The pointer or `SAFEARRAY` that the `VT_BYREF` or `VT_ARRAY` flag of the type stands for
+	Expanded(types::TypeDesc),
+
+	/// A `VT_RECORD`. It doesn't say which record it is, so it's emitted as an untyped pointer to the record, like the `pvRecord` of a `VARIANT`,
+	/// and reported as a lossy mapping.
+	Record,
+
+	/// A type that the backends refer to by its path
+	WellKnown(WellKnownType),
+}
+
+/// A type that the backends refer to by its path. The backends only differ in how they spell the path of each of these.
+#[derive(Clone, Copy)]
+pub(crate) enum WellKnownType {
+	/// A type that all the backends emit as the same Rust primitive, like `i32` for `VT_I4`
+	Primitive(&'static str),
+
+	/// The counted array of a `PROPVARIANT` that a `VT_VECTOR` type stands for, like `CAL` for `VT_VECTOR | VT_I4`
+	CountedArray(&'static str),
+
+	Void,
+	Int,
+	UInt,
+	IntPtr,
+	UIntPtr,
+	Error,
+	Bool,
+	Currency,
+	Date,
+	Decimal,
+	HResult,
+	Bstr,
+	BstrBlob,
+	LpStr,
+	LpWStr,
+	Variant,
+	FileTime,
+	Blob,
+	Clsid,
+
+	/// An `IDispatch` pointer
+	Dispatch,
+
+	/// An `IUnknown` pointer
+	Unknown,
+
+	/// An `IStream` pointer
+	Stream,
+
+	/// An `IStorage` pointer
+	Storage,
+}
+
+/// Classifies the given base type for the backends. `location` is where the type is used, for reporting the types that the bindings have no type for.
+fn var_type_kind(vt: types::VarType, location: &Location) -> Result<VarTypeKind, Error> {
+	if let Some(type_) = expand_var_type_flags(vt) {
+		return Ok(VarTypeKind::Expanded(type_));
+	}
+
+	if let Some(type_name) = counted_array_name(vt) {
+		return Ok(VarTypeKind::WellKnown(WellKnownType::CountedArray(type_name)));
+	}
+
+	Ok(VarTypeKind::WellKnown(match vt {
+		types::VT_I1 => WellKnownType::Primitive("i8"),
+		types::VT_UI1 => WellKnownType::Primitive("u8"),
+		types::VT_I2 => WellKnownType::Primitive("i16"),
+		types::VT_UI2 => WellKnownType::Primitive("u16"),
+		types::VT_I4 => WellKnownType::Primitive("i32"),
+		types::VT_UI4 => WellKnownType::Primitive("u32"),
+		types::VT_I8 => WellKnownType::Primitive("i64"),
+		types::VT_UI8 => WellKnownType::Primitive("u64"),
+		types::VT_R4 => WellKnownType::Primitive("f32"),
+		types::VT_R8 => WellKnownType::Primitive("f64"),
+		types::VT_VOID => WellKnownType::Void,
+		types::VT_INT => WellKnownType::Int,
+		types::VT_UINT => WellKnownType::UInt,
+		types::VT_INT_PTR => WellKnownType::IntPtr,
+		types::VT_UINT_PTR => WellKnownType::UIntPtr,
+		types::VT_ERROR => WellKnownType::Error,
+		types::VT_BOOL => WellKnownType::Bool,
+		types::VT_CY => WellKnownType::Currency,
+		types::VT_DATE => WellKnownType::Date,
+		types::VT_DECIMAL => WellKnownType::Decimal,
+		types::VT_HRESULT => WellKnownType::HResult,
+		types::VT_BSTR => WellKnownType::Bstr,
+		types::VT_BSTR_BLOB => WellKnownType::BstrBlob,
+		types::VT_LPSTR => WellKnownType::LpStr,
+		types::VT_LPWSTR => WellKnownType::LpWStr,
+		types::VT_VARIANT => WellKnownType::Variant,
+		types::VT_FILETIME => WellKnownType::FileTime,
+		types::VT_BLOB |
+		types::VT_BLOB_OBJECT => WellKnownType::Blob,
+		types::VT_CLSID => WellKnownType::Clsid,
+		types::VT_DISPATCH => WellKnownType::Dispatch,
+		types::VT_UNKNOWN => WellKnownType::Unknown,
+		types::VT_STREAM |
+		types::VT_STREAMED_OBJECT => WellKnownType::Stream,
+		types::VT_STORAGE |
+		types::VT_STORED_OBJECT => WellKnownType::Storage,
+		types::VT_RECORD => return Ok(VarTypeKind::Record),
+		// Includes VT_EMPTY and VT_NULL, which only say that a `VARIANT` holds no value, and aren't the type of a value themselves,
+		// and a VT_SAFEARRAY without the element type that a `TypeDesc::SafeArray` has, which would be passed by pointer and not by value
+		vt => return Err(Error::UnsupportedVarType { location: location.clone(), vt }),
+	}))
+}
+
+/// Gets the path of the given type in `stdole2.tlb`
+fn well_known_path(type_name: &str, context: &Context<'_>) -> String {
+	context.type_mappings.get(types::LIBID_STDOLE, "stdole", type_name)
+	.unwrap_or_else(|| type_name.to_string())
+}
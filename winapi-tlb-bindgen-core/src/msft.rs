@@ -0,0 +1,1603 @@
+//! Reader and writer for typelibs in the MSFT format, which is what `midl.exe` and `ICreateTypeLib2` produce.
+
+use std::convert::TryFrom;
+
+use crate::types;
+
+const MAGIC: &[u8; 4] = b"MSFT";
+
+const HEADER_SIZE: usize = 0x54;
+const HELPDLLFLAG: u32 = 0x100;
+const TYPE_INFO_BASE_SIZE: usize = 0x64;
+const IMPINFO_OFFSET_IS_GUID: u32 = 0x0001_0000;
+
+const FUNC_RECORD_FIXED_SIZE: usize = 0x18;
+const FKCCIC_HAS_DEFAULT_VALUES: u32 = 0x1000;
+const FKCCIC_ENTRY_IS_ORDINAL: u32 = 0x2000;
+const VAR_RECORD_FIXED_SIZE: usize = 0x14;
+
+const MAX_TYPE_DESC_DEPTH: usize = 64;
+
+pub(crate) fn is_msft(data: &[u8]) -> bool {
+	data.starts_with(MAGIC)
+}
+
+/// Parses the MSFT typelib in the given buffer.
+pub(crate) fn read(data: &[u8]) -> Result<types::TypeLib, crate::Error> {
+	let reader = Reader::new(data)?;
+
+	let version = reader.u32(0x18)?;
+	let imports = reader.imp_files()?;
+	let type_infos = (0..reader.num_type_infos).map(|index| reader.type_info(index)).collect::<Result<_, _>>()?;
+
+	Ok(types::TypeLib {
+		name: reader.name(reader.i32(0x38)?)?,
+		doc_string: reader.string(reader.i32(0x24)?)?,
+		help_file: reader.string(reader.i32(0x3C)?)?,
+		help_context: reader.u32(0x2C)?,
+		guid: reader.guid(reader.i32(0x08)?)?.unwrap_or_default(),
+		lcid: reader.u32(0x0C)?,
+		version: split_version(version),
+		flags: reader.u16(0x1C)?,
+		syskind: reader.syskind,
+		imports,
+		type_infos,
+	})
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Segment {
+	offset: usize,
+	length: usize,
+}
+
+#[derive(Debug, Default)]
+struct Segments {
+	type_infos: Segment,
+	imp_infos: Segment,
+	imp_files: Segment,
+	ref_table: Segment,
+	guids: Segment,
+	names: Segment,
+	strings: Segment,
+	type_descs: Segment,
+	array_descs: Segment,
+	custom_data: Segment,
+}
+
+struct Reader<'a> {
+	data: &'a [u8],
+	syskind: types::SysKind,
+	num_type_infos: usize,
+	dispatch_href: i32,
+	segments: Segments,
+}
+
+impl<'a> Reader<'a> {
+	fn new(data: &'a [u8]) -> Result<Self, crate::Error> {
+		if !is_msft(data) {
+			return Err(malformed(0, "missing MSFT signature"));
+		}
+
+		let mut reader = Reader {
+			data,
+			syskind: types::SysKind::Win32,
+			num_type_infos: 0,
+			dispatch_href: -1,
+			segments: Segments::default(),
+		};
+
+		let var_flags = reader.u32(0x14)?;
+		reader.syskind = match var_flags & 0xF {
+			0 => types::SysKind::Win16,
+			1 => types::SysKind::Win32,
+			2 => types::SysKind::Mac,
+			3 => types::SysKind::Win64,
+			_ => return Err(malformed(0x14, "unknown SYSKIND")),
+		};
+
+		let num_type_infos = reader.i32(0x20)?;
+		reader.num_type_infos = usize::try_from(num_type_infos).map_err(|_| malformed(0x20, "negative typeinfo count"))?;
+		reader.dispatch_href = reader.i32(0x4C)?;
+
+		// The header is followed by an optional help DLL string offset, then the offsets of each typeinfo, then the segment directory.
+		let mut segment_directory_offset = HEADER_SIZE + reader.num_type_infos * 4;
+		if (var_flags & HELPDLLFLAG) == HELPDLLFLAG {
+			segment_directory_offset += 4;
+		}
+
+		let segment = |index: usize| -> Result<Segment, crate::Error> {
+			let offset = segment_directory_offset + index * 16;
+			Ok(Segment {
+				offset: reader.u32(offset)? as usize,
+				length: reader.u32(offset + 4)? as usize,
+			})
+		};
+
+		let segments = Segments {
+			type_infos: segment(0)?,
+			imp_infos: segment(1)?,
+			imp_files: segment(2)?,
+			ref_table: segment(3)?,
+			guids: segment(5)?,
+			names: segment(7)?,
+			strings: segment(8)?,
+			type_descs: segment(9)?,
+			array_descs: segment(10)?,
+			custom_data: segment(11)?,
+		};
+		reader.segments = segments;
+
+		if reader.segments.type_infos.length < reader.num_type_infos * TYPE_INFO_BASE_SIZE {
+			return Err(malformed(segment_directory_offset, "typeinfo segment is too small"));
+		}
+
+		Ok(reader)
+	}
+
+	fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], crate::Error> {
+		offset.checked_add(len)
+			.and_then(|end| self.data.get(offset..end))
+			.ok_or_else(|| malformed(offset, "unexpected end of file"))
+	}
+
+	fn u16(&self, offset: usize) -> Result<u16, crate::Error> {
+		let bytes = self.bytes(offset, 2)?;
+		Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn i16(&self, offset: usize) -> Result<i16, crate::Error> {
+		let bytes = self.bytes(offset, 2)?;
+		Ok(i16::from_le_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn u32(&self, offset: usize) -> Result<u32, crate::Error> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn i32(&self, offset: usize) -> Result<i32, crate::Error> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn guid(&self, offset: i32) -> Result<Option<types::Guid>, crate::Error> {
+		let Some(offset) = segment_offset(self.segments.guids, offset) else { return Ok(None); };
+
+		let data4 = self.bytes(offset + 8, 8)?;
+		Ok(Some(types::Guid {
+			data1: self.u32(offset)?,
+			data2: self.u16(offset + 4)?,
+			data3: self.u16(offset + 6)?,
+			data4: [data4[0], data4[1], data4[2], data4[3], data4[4], data4[5], data4[6], data4[7]],
+		}))
+	}
+
+	fn name(&self, offset: i32) -> Result<String, crate::Error> {
+		let offset = segment_offset(self.segments.names, offset).ok_or_else(|| malformed(self.segments.names.offset, "missing name"))?;
+
+		// hreftype: i32, next_hash: i32, then the length of the name in the low byte of the third i32
+		let len = self.u32(offset + 8)? & 0xFF;
+		Ok(decode_ansi(self.bytes(offset + 12, len as usize)?))
+	}
+
+	/// A string in the string table, such as a help string. The string is prefixed with its length.
+	fn string(&self, offset: i32) -> Result<Option<String>, crate::Error> {
+		let Some(offset) = segment_offset(self.segments.strings, offset) else { return Ok(None); };
+
+		let len = self.u16(offset)?;
+		Ok(Some(decode_ansi(self.bytes(offset + 2, usize::from(len))?)))
+	}
+
+	/// Parses the `MSFT_ImpFile`s that describe the typelibs this typelib imports types from.
+	fn imp_files(&self) -> Result<Vec<types::ImportedTypeLib>, crate::Error> {
+		let mut result = vec![];
+
+		// LIBID offset, LCID, version, length of the file name (shifted left by 2), file name, padding to a multiple of 4
+		let mut relative_offset = 0;
+		while relative_offset < self.segments.imp_files.length {
+			let offset = self.segments.imp_files.offset + relative_offset;
+			let guid = self.guid(self.i32(offset)?)?.ok_or_else(|| malformed(offset, "imported typelib has no LIBID"))?;
+			let lcid = self.u32(offset + 4)?;
+			let version = split_version(self.u32(offset + 8)?);
+			let file_name_len = usize::from(self.u16(offset + 12)? >> 2);
+			let file_name = decode_ansi(self.bytes(offset + 14, file_name_len)?);
+
+			result.push(types::ImportedTypeLib { guid, version, lcid, file_name });
+
+			relative_offset = (relative_offset + 14 + file_name_len + 3) & !3;
+		}
+
+		Ok(result)
+	}
+
+	/// Decodes a value that is either packed into the given `i32` itself, or stored in the custom data segment.
+	fn value(&self, encoded: i32) -> Result<types::Variant, crate::Error> {
+		if encoded < 0 {
+			let vt = ((encoded as u32 & 0x7C00_0000) >> 26) as types::VarType;
+			let value = encoded as u32 & 0x03FF_FFFF;
+			return Ok(types::Variant::from_bits(vt, u64::from(value)));
+		}
+
+		let offset = segment_offset(self.segments.custom_data, encoded).ok_or_else(|| malformed(self.segments.custom_data.offset, "invalid value offset"))?;
+		let vt = self.u16(offset)?;
+		match vt {
+			types::VT_BSTR => {
+				// The length of the string, or -1 for a null string, followed by the string
+				let len = self.i32(offset + 2)?;
+				let value = match usize::try_from(len) {
+					Ok(len) => decode_ansi(self.bytes(offset + 6, len)?),
+					Err(_) => String::new(),
+				};
+				Ok(types::Variant::Bstr(value))
+			},
+
+			// Values of less than 4 bytes are stored in 4 bytes
+			types::VT_EMPTY | types::VT_NULL |
+			types::VT_I1 | types::VT_UI1 | types::VT_I2 | types::VT_UI2 | types::VT_I4 | types::VT_UI4 |
+			types::VT_INT | types::VT_UINT | types::VT_R4 | types::VT_BOOL | types::VT_ERROR =>
+				Ok(types::Variant::from_bits(vt, u64::from(self.u32(offset + 2)?))),
+
+			types::VT_I8 | types::VT_UI8 | types::VT_R8 | types::VT_CY | types::VT_DATE => {
+				let low = u64::from(self.u32(offset + 2)?);
+				let high = u64::from(self.u32(offset + 6)?);
+				Ok(types::Variant::from_bits(vt, low | (high << 32)))
+			},
+
+			vt => Ok(types::Variant::Other(vt)),
+		}
+	}
+
+	fn type_desc(&self, encoded: i32, depth: usize) -> Result<types::TypeDesc, crate::Error> {
+		if encoded < 0 {
+			// Base types are packed into the value itself
+			return Ok(types::TypeDesc::Base(encoded as types::VarType));
+		}
+
+		if depth > MAX_TYPE_DESC_DEPTH {
+			return Err(malformed(self.segments.type_descs.offset, "type descriptions are nested too deeply"));
+		}
+
+		let offset = segment_offset(self.segments.type_descs, encoded).ok_or_else(|| malformed(self.segments.type_descs.offset, "invalid type description offset"))?;
+		let vt = self.u16(offset)? & 0x0FFF;
+		let inner = self.i32(offset + 4)?;
+
+		Ok(match vt {
+			types::VT_PTR => types::TypeDesc::Ptr(Box::new(self.type_desc(inner, depth + 1)?)),
+
+			types::VT_SAFEARRAY => types::TypeDesc::SafeArray(Box::new(self.type_desc(inner, depth + 1)?)),
+
+			types::VT_CARRAY => {
+				let offset = segment_offset(self.segments.array_descs, inner).ok_or_else(|| malformed(offset, "invalid array description offset"))?;
+				let element = self.type_desc(self.i32(offset)?, depth + 1)?;
+				let num_dimensions = self.u16(offset + 4)?;
+				let dimensions =
+					(0..usize::from(num_dimensions))
+					.map(|dimension| self.u32(offset + 8 + dimension * 8))
+					.collect::<Result<_, _>>()?;
+				types::TypeDesc::CArray(Box::new(element), dimensions)
+			},
+
+			types::VT_USERDEFINED => types::TypeDesc::UserDefined(self.type_ref(inner)?),
+
+			vt => types::TypeDesc::Base(vt),
+		})
+	}
+
+	fn type_ref(&self, href: i32) -> Result<types::TypeRef, crate::Error> {
+		if href < 0 {
+			return Err(malformed(0, "invalid HREFTYPE"));
+		}
+
+		if (href & 1) == 1 {
+			// Offset of an MSFT_ImpInfo in the imported types segment
+			let offset = segment_offset(self.segments.imp_infos, href & !3).ok_or_else(|| malformed(self.segments.imp_infos.offset, "invalid import offset"))?;
+			let flags = self.u32(offset)?;
+			let imp_file_offset = self.i32(offset + 4)?;
+			let guid_or_index = self.i32(offset + 8)?;
+
+			// MSFT_ImpFile starts with the offset of the LIBID in the GUID table
+			let lib_guid = match segment_offset(self.segments.imp_files, imp_file_offset) {
+				Some(imp_file_offset) => self.guid(self.i32(imp_file_offset)?)?,
+				None => None,
+			};
+
+			let type_ =
+				if (flags & IMPINFO_OFFSET_IS_GUID) == IMPINFO_OFFSET_IS_GUID {
+					let guid = self.guid(guid_or_index)?.ok_or_else(|| malformed(offset + 8, "invalid import GUID offset"))?;
+					types::ImportedType::Guid(guid)
+				}
+				else {
+					let index = u32::try_from(guid_or_index).map_err(|_| malformed(offset + 8, "invalid import index"))?;
+					types::ImportedType::Index(index)
+				};
+
+			Ok(types::TypeRef::External { lib_guid, type_ })
+		}
+		else {
+			// Offset of a typeinfo in the typeinfo segment
+			let index = href as usize / TYPE_INFO_BASE_SIZE;
+			if index >= self.num_type_infos {
+				return Err(malformed(self.segments.type_infos.offset, "HREFTYPE refers to a non-existent typeinfo"));
+			}
+
+			Ok(types::TypeRef::Local(index))
+		}
+	}
+
+	fn type_info(&self, index: usize) -> Result<types::TypeInfo, crate::Error> {
+		let offset = self.segments.type_infos.offset + index * TYPE_INFO_BASE_SIZE;
+
+		let typekind_and_alignment = self.u32(offset)?;
+		let typekind = match typekind_and_alignment & 0xF {
+			0 => types::TypeKind::Enum,
+			1 => types::TypeKind::Record,
+			2 => types::TypeKind::Module,
+			3 => types::TypeKind::Interface,
+			4 => types::TypeKind::Dispatch,
+			5 => types::TypeKind::CoClass,
+			6 => types::TypeKind::Alias,
+			7 => types::TypeKind::Union,
+			_ => return Err(malformed(offset, "unknown TYPEKIND")),
+		};
+		let alignment = ((typekind_and_alignment >> 11) & 0x1F) as u16;
+
+		let member_offset = self.i32(offset + 0x04)?;
+		let num_elements = self.u32(offset + 0x18)?;
+		let num_functions = (num_elements & 0xFFFF) as usize;
+		let num_vars = (num_elements >> 16) as usize;
+		let guid = self.guid(self.i32(offset + 0x2C)?)?.unwrap_or_default();
+		let flags = self.u32(offset + 0x30)? as u16;
+		let name = self.name(self.i32(offset + 0x34)?)?;
+		let version = split_version(self.u32(offset + 0x38)?);
+		let doc_string = self.string(self.i32(offset + 0x3C)?)?;
+		let help_context = self.u32(offset + 0x44)?;
+		let num_impl_types = self.i16(offset + 0x4C)?;
+		let size_vft = self.u16(offset + 0x4E)?;
+		let size_instance = self.u32(offset + 0x50)?;
+		let data_type1 = self.i32(offset + 0x54)?;
+
+		let mut impl_types = vec![];
+		let mut alias_type = None;
+		let mut dll_name = None;
+
+		match typekind {
+			types::TypeKind::CoClass => {
+				// `data_type1` is the offset of a linked list of MSFT_RefRecord in the reference table
+				let mut ref_offset = data_type1;
+				for _ in 0..num_impl_types {
+					let Some(offset) = segment_offset(self.segments.ref_table, ref_offset) else { break; };
+					impl_types.push(types::ImplType {
+						type_ref: self.type_ref(self.i32(offset)?)?,
+						flags: self.u32(offset + 4)? as u16,
+					});
+					ref_offset = self.i32(offset + 12)?;
+				}
+			},
+
+			types::TypeKind::Dispatch =>
+				// `data_type1` is the parent of the interface half of a dual interface.
+				// Otherwise all dispinterfaces inherit from IDispatch.
+				if data_type1 != -1 {
+					impl_types.push(types::ImplType { type_ref: self.type_ref(data_type1)?, flags: 0 });
+				}
+				else if num_impl_types > 0 && self.dispatch_href != -1 {
+					impl_types.push(types::ImplType { type_ref: self.type_ref(self.dispatch_href)?, flags: 0 });
+				},
+
+			types::TypeKind::Alias =>
+				alias_type = Some(self.type_desc(data_type1, 0)?),
+
+			types::TypeKind::Module =>
+				// `data_type1` is the offset of the DLL name in the string table
+				dll_name = self.string(data_type1)?,
+
+			_ =>
+				if num_impl_types > 0 && data_type1 != -1 {
+					impl_types.push(types::ImplType { type_ref: self.type_ref(data_type1)?, flags: 0 });
+				},
+		}
+
+		let (functions, vars) =
+			if num_functions + num_vars > 0 {
+				let member_offset = usize::try_from(member_offset).map_err(|_| malformed(offset + 0x04, "invalid member offset"))?;
+				self.members(member_offset, num_functions, num_vars)?
+			}
+			else {
+				(vec![], vec![])
+			};
+
+		Ok(types::TypeInfo {
+			name,
+			doc_string,
+			help_context,
+			guid,
+			typekind,
+			flags,
+			version,
+			size_instance,
+			alignment,
+			size_vft,
+			functions,
+			vars,
+			impl_types,
+			alias_type,
+			dll_name,
+		})
+	}
+
+	/// Parses the member data block of a typeinfo.
+	///
+	/// The block starts with the total length of the member records, followed by the records themselves (functions first, then vars).
+	/// This is followed by three arrays with one entry per member - the member IDs, the offsets of the names in the name table,
+	/// and the offsets of the records relative to the start of the records.
+	fn members(&self, offset: usize, num_functions: usize, num_vars: usize) -> Result<(Vec<types::Function>, Vec<types::Var>), crate::Error> {
+		let records_offset = offset + 4;
+		let records_len = self.u32(offset)? as usize;
+		let num_members = num_functions + num_vars;
+		let member_ids_offset = records_offset + records_len;
+		let names_offset = member_ids_offset + num_members * 4;
+		let record_offsets_offset = names_offset + num_members * 4;
+
+		let mut functions: Vec<types::Function> = Vec::with_capacity(num_functions);
+		for i in 0..num_functions {
+			let member_id = self.i32(member_ids_offset + i * 4)?;
+			let name_offset = self.i32(names_offset + i * 4)?;
+			let record_offset = records_offset + self.u32(record_offsets_offset + i * 4)? as usize;
+
+			let function = self.function(record_offset, member_id, name_offset, functions.last())?;
+			functions.push(function);
+		}
+
+		let mut vars = Vec::with_capacity(num_vars);
+		for i in num_functions..num_members {
+			let member_id = self.i32(member_ids_offset + i * 4)?;
+			let name_offset = self.i32(names_offset + i * 4)?;
+			let record_offset = records_offset + self.u32(record_offsets_offset + i * 4)? as usize;
+
+			let var = self.var(record_offset, member_id, name_offset)?;
+			vars.push(var);
+		}
+
+		Ok((functions, vars))
+	}
+
+	fn function(&self, offset: usize, member_id: i32, name_offset: i32, previous: Option<&types::Function>) -> Result<types::Function, crate::Error> {
+		let record_len = (self.u32(offset)? & 0xFFFF) as usize;
+		let data_type = self.i32(offset + 4)?;
+		let flags = self.u32(offset + 8)? as u16;
+		let ovft = self.u16(offset + 12)? & !1;
+		let fkccic = self.u32(offset + 16)?;
+		let num_params = self.u16(offset + 20)? as usize;
+
+		let func_kind = match fkccic & 0x7 {
+			0 => types::FuncKind::Virtual,
+			1 => types::FuncKind::PureVirtual,
+			2 => types::FuncKind::NonVirtual,
+			3 => types::FuncKind::Static,
+			4 => types::FuncKind::Dispatch,
+			_ => return Err(malformed(offset + 16, "unknown FUNCKIND")),
+		};
+
+		let invoke_kind = match (fkccic >> 3) & 0xF {
+			1 => types::InvokeKind::Func,
+			2 => types::InvokeKind::PropertyGet,
+			4 => types::InvokeKind::PropertyPut,
+			8 => types::InvokeKind::PropertyPutRef,
+			_ => return Err(malformed(offset + 16, "unknown INVOKEKIND")),
+		};
+
+		let call_conv = match (fkccic >> 8) & 0xF {
+			0 => types::CallConv::FastCall,
+			1 => types::CallConv::CDecl,
+			2 => types::CallConv::Pascal,
+			3 => types::CallConv::MacPascal,
+			4 => types::CallConv::StdCall,
+			5 => types::CallConv::FpFastCall,
+			6 => types::CallConv::SysCall,
+			7 => types::CallConv::MpwCDecl,
+			8 => types::CallConv::MpwPascal,
+			_ => return Err(malformed(offset + 16, "unknown CALLCONV")),
+		};
+
+		// The name is omitted for the second function of a propget / propput pair
+		let name = match previous {
+			Some(previous) if name_offset == -1 && previous.invoke_kind != types::InvokeKind::Func && invoke_kind != types::InvokeKind::Func =>
+				previous.name.clone(),
+			_ => self.name(name_offset)?,
+		};
+
+		// The fixed part of the record is followed by a variable number of optional attributes, then the default values of the parameters
+		// if there are any, then an MSFT_ParameterInfo for each parameter.
+		let params_offset =
+			(offset + record_len).checked_sub(num_params * 12)
+			.ok_or_else(|| malformed(offset, "function record is too small for its parameters"))?;
+
+		let default_values_offset =
+			if (fkccic & FKCCIC_HAS_DEFAULT_VALUES) == FKCCIC_HAS_DEFAULT_VALUES {
+				let default_values_offset =
+					params_offset.checked_sub(num_params * 4)
+					.ok_or_else(|| malformed(offset, "function record is too small for its default values"))?;
+				Some(default_values_offset)
+			}
+			else {
+				None
+			};
+
+		let optional_attributes_end = default_values_offset.unwrap_or(params_offset);
+		let optional_attribute = |index: usize| -> Result<Option<i32>, crate::Error> {
+			let attribute_offset = offset + FUNC_RECORD_FIXED_SIZE + index * 4;
+			if attribute_offset + 4 <= optional_attributes_end {
+				Ok(Some(self.i32(attribute_offset)?))
+			}
+			else {
+				Ok(None)
+			}
+		};
+
+		let help_context = optional_attribute(0)?.map_or(0, |help_context| help_context as u32);
+		let doc_string = match optional_attribute(1)? {
+			Some(doc_string_offset) => self.string(doc_string_offset)?,
+			None => None,
+		};
+		let entry_point = match optional_attribute(2)? {
+			Some(entry) if (fkccic & FKCCIC_ENTRY_IS_ORDINAL) == FKCCIC_ENTRY_IS_ORDINAL => Some(types::EntryPoint::Ordinal(entry as u16)),
+			Some(entry) => self.string(entry)?.map(types::EntryPoint::Name),
+			None => None,
+		};
+
+		let params = (0..num_params).map(|index| {
+			let param_offset = params_offset + index * 12;
+			let type_ = self.type_desc(self.i32(param_offset)?, 0)?;
+			let name_offset = self.i32(param_offset + 4)?;
+			let flags = self.u32(param_offset + 8)? as u16;
+
+			let default_value = match default_values_offset {
+				Some(default_values_offset) if (flags & types::PARAMFLAG_FHASDEFAULT) == types::PARAMFLAG_FHASDEFAULT =>
+					Some(self.value(self.i32(default_values_offset + index * 4)?)?),
+				_ => None,
+			};
+
+			let name =
+				if name_offset == -1 {
+					if index == num_params - 1 && matches!(invoke_kind, types::InvokeKind::PropertyPut | types::InvokeKind::PropertyPutRef) {
+						// The parameter to "put" was omitted.
+						"value".to_string()
+					}
+					else {
+						format!("arg{}", index)
+					}
+				}
+				else {
+					self.name(name_offset)?
+				};
+
+			Ok(types::Param { name, type_, flags, default_value })
+		}).collect::<Result<_, crate::Error>>()?;
+
+		Ok(types::Function {
+			name,
+			doc_string,
+			help_context,
+			member_id,
+			func_kind,
+			invoke_kind,
+			call_conv,
+			flags,
+			ovft,
+			return_type: self.type_desc(data_type, 0)?,
+			params,
+			entry_point,
+		})
+	}
+
+	fn var(&self, offset: usize, member_id: i32, name_offset: i32) -> Result<types::Var, crate::Error> {
+		let record_len = (self.u32(offset)? & 0xFFFF) as usize;
+		let data_type = self.i32(offset + 4)?;
+		let flags = self.u32(offset + 8)? as u16;
+		let value_or_offset = self.i32(offset + 16)?;
+
+		let var_kind = match self.u16(offset + 12)? {
+			0 => types::VarKind::PerInstance(value_or_offset as u32),
+			1 => types::VarKind::Static,
+			2 => types::VarKind::Const(self.value(value_or_offset)?),
+			3 => types::VarKind::Dispatch,
+			_ => return Err(malformed(offset + 12, "unknown VARKIND")),
+		};
+
+		// The fixed part of the record is followed by optional attributes
+		let help_context =
+			if record_len >= VAR_RECORD_FIXED_SIZE + 4 {
+				self.u32(offset + VAR_RECORD_FIXED_SIZE)?
+			}
+			else {
+				0
+			};
+		let doc_string =
+			if record_len >= VAR_RECORD_FIXED_SIZE + 8 {
+				self.string(self.i32(offset + VAR_RECORD_FIXED_SIZE + 4)?)?
+			}
+			else {
+				None
+			};
+
+		Ok(types::Var {
+			name: self.name(name_offset)?,
+			doc_string,
+			help_context,
+			member_id,
+			var_kind,
+			flags,
+			type_: self.type_desc(data_type, 0)?,
+		})
+	}
+}
+
+/// Serializes the given typelib in the MSFT format.
+///
+/// The file is laid out the way `midl.exe` lays it out - the header, the offsets of each typeinfo, the segment directory,
+/// the segments, and then the member data of each typeinfo.
+pub(crate) fn write<W>(type_lib: &types::TypeLib, mut out: W) -> Result<(), crate::Error> where W: std::io::Write {
+	let data = Writer::new(type_lib).write()?;
+	out.write_all(&data)?;
+	Ok(())
+}
+
+const NUM_SEGMENTS: usize = 15;
+const GUID_HASH_TABLE_LEN: usize = 0x20;
+const NAME_HASH_TABLE_LEN: usize = 0x80;
+
+/// Flags in the second byte of the length of a name, which `ITypeComp` uses to bind names without searching every typeinfo.
+const NAME_FLAGS_TYPE_INFO: u8 = 0x38;
+const NAME_FLAG_UNIQUE_MEMBER: u8 = 0x10;
+const NAME_FLAG_GLOBAL_MEMBER: u8 = 0x20;
+
+/// Sizes of the 32-bit structures that `ITypeInfo::GetFuncDesc` and `ITypeInfo::GetVarDesc` reconstitute from a record.
+/// Each record stores the total size of the structures it expands to.
+const FUNCDESC_SIZE: u32 = 52;
+const ELEMDESC_SIZE: u32 = 16;
+const PARAMDESCEX_SIZE: u32 = 24;
+const VARDESC_SIZE: u32 = 36;
+const TYPEDESC_SIZE: u32 = 8;
+const ARRAYDESC_SIZE: u32 = 20;
+const SAFEARRAYBOUND_SIZE: u32 = 8;
+const VARIANT_SIZE: u32 = 16;
+
+/// Records and strings are padded with this byte, like `midl.exe` does.
+const PADDING: u8 = 0x57;
+
+struct Writer<'a> {
+	type_lib: &'a types::TypeLib,
+
+	type_infos: Vec<u8>,
+	imp_infos: Vec<u8>,
+	imp_files: Vec<u8>,
+	ref_table: Vec<u8>,
+	guid_hash_table: [i32; GUID_HASH_TABLE_LEN],
+	guids: Vec<u8>,
+	name_hash_table: [i32; NAME_HASH_TABLE_LEN],
+	names: Vec<u8>,
+	strings: Vec<u8>,
+	type_descs: Vec<u8>,
+	array_descs: Vec<u8>,
+	custom_data: Vec<u8>,
+
+	/// The member data block of each typeinfo
+	members: Vec<Vec<u8>>,
+
+	num_names: u32,
+	num_name_chars: u32,
+	num_imp_infos: u32,
+	dispatch_href: Option<i32>,
+
+	guid_offsets: std::collections::HashMap<types::Guid, i32>,
+	name_offsets: std::collections::HashMap<Vec<u8>, i32>,
+	string_offsets: std::collections::HashMap<Vec<u8>, i32>,
+	type_desc_offsets: std::collections::HashMap<(types::VarType, i32), i32>,
+	imp_file_offsets: Vec<(types::Guid, i32)>,
+	imp_info_offsets: Vec<(i32, types::ImportedType, i32)>,
+}
+
+impl<'a> Writer<'a> {
+	fn new(type_lib: &'a types::TypeLib) -> Self {
+		Writer {
+			type_lib,
+
+			type_infos: vec![],
+			imp_infos: vec![],
+			imp_files: vec![],
+			ref_table: vec![],
+			guid_hash_table: [-1; GUID_HASH_TABLE_LEN],
+			guids: vec![],
+			name_hash_table: [-1; NAME_HASH_TABLE_LEN],
+			names: vec![],
+			strings: vec![],
+			type_descs: vec![],
+			array_descs: vec![],
+			custom_data: vec![],
+
+			members: vec![],
+
+			num_names: 0,
+			num_name_chars: 0,
+			num_imp_infos: 0,
+			dispatch_href: None,
+
+			guid_offsets: std::collections::HashMap::new(),
+			name_offsets: std::collections::HashMap::new(),
+			string_offsets: std::collections::HashMap::new(),
+			type_desc_offsets: std::collections::HashMap::new(),
+			imp_file_offsets: vec![],
+			imp_info_offsets: vec![],
+		}
+	}
+
+	fn write(mut self) -> Result<Vec<u8>, crate::Error> {
+		let type_lib = self.type_lib;
+		let lib_error = |reason| unwritable(&type_lib.name, reason);
+
+		let guid = self.guid(type_lib.guid, -2);
+		let name = self.name(&type_lib.name).map_err(lib_error)?;
+		let doc_string = self.optional_string(type_lib.doc_string.as_deref()).map_err(lib_error)?;
+		let help_file = self.optional_string(type_lib.help_file.as_deref()).map_err(lib_error)?;
+		for import in &type_lib.imports {
+			self.imp_file(import).map_err(lib_error)?;
+		}
+
+		// Allocate the names of the typeinfos before any member names, so that members that share a name with a typeinfo
+		// are not marked as unique.
+		let mut type_info_names = Vec::with_capacity(type_lib.type_infos.len());
+		for (index, type_info) in type_lib.type_infos.iter().enumerate() {
+			let name = self.name(&type_info.name).map_err(|reason| unwritable(&type_info.name, reason))?;
+			let entry = name as usize;
+			self.names[entry..(entry + 4)].copy_from_slice(&type_info_href(index).to_le_bytes());
+			self.names[entry + 9] = NAME_FLAGS_TYPE_INFO;
+			type_info_names.push(name);
+		}
+
+		for (index, (type_info, name)) in type_lib.type_infos.iter().zip(type_info_names).enumerate() {
+			self.type_info(index, type_info, name)?;
+		}
+
+		let guid_hash_table: Vec<u8> = self.guid_hash_table.iter().flat_map(|offset| offset.to_le_bytes()).collect();
+		let name_hash_table: Vec<u8> = self.name_hash_table.iter().flat_map(|offset| offset.to_le_bytes()).collect();
+
+		let num_type_infos = type_lib.type_infos.len();
+		let segments_offset = HEADER_SIZE + num_type_infos * 4 + NUM_SEGMENTS * 16;
+
+		// The member data blocks follow the segments, so patch their offsets into the typeinfos before laying out the segments.
+		let mut members_offset =
+			segments_offset +
+			self.type_infos.len() + guid_hash_table.len() + self.guids.len() + self.ref_table.len() + self.imp_infos.len() +
+			self.imp_files.len() + name_hash_table.len() + self.names.len() + self.strings.len() + self.type_descs.len() +
+			self.array_descs.len() + self.custom_data.len();
+		for (index, members) in self.members.iter().enumerate() {
+			let offset = index * TYPE_INFO_BASE_SIZE + 0x04;
+			self.type_infos[offset..(offset + 4)].copy_from_slice(&(members_offset as u32).to_le_bytes());
+			members_offset += members.len();
+		}
+
+		// The segments in the order that they're written in, along with their index in the segment directory
+		let segments: [(usize, &[u8]); 12] = [
+			(0, &self.type_infos),
+			(4, &guid_hash_table),
+			(5, &self.guids),
+			(3, &self.ref_table),
+			(1, &self.imp_infos),
+			(2, &self.imp_files),
+			(6, &name_hash_table),
+			(7, &self.names),
+			(8, &self.strings),
+			(9, &self.type_descs),
+			(10, &self.array_descs),
+			(11, &self.custom_data),
+		];
+
+		let mut segment_directory = [(-1_i32, 0_usize); NUM_SEGMENTS];
+		let mut segment_offset = segments_offset;
+		for &(index, segment) in &segments {
+			if !segment.is_empty() {
+				segment_directory[index] = (segment_offset as i32, segment.len());
+				segment_offset += segment.len();
+			}
+		}
+
+		let syskind = match type_lib.syskind {
+			types::SysKind::Win16 => 0,
+			types::SysKind::Win32 => 1,
+			types::SysKind::Mac => 2,
+			types::SysKind::Win64 => 3,
+		};
+		let var_flags = syskind | 0x40 | if help_file == -1 { 0 } else { 0x10 };
+
+		let mut data = Vec::with_capacity(members_offset);
+		data.extend_from_slice(MAGIC);
+		push_u32(&mut data, 0x0001_0002);
+		push_i32(&mut data, guid);
+		push_u32(&mut data, type_lib.lcid);
+		push_u32(&mut data, type_lib.lcid);
+		push_u32(&mut data, var_flags);
+		push_u32(&mut data, join_version(type_lib.version));
+		push_u32(&mut data, u32::from(type_lib.flags));
+		push_u32(&mut data, num_type_infos as u32);
+		push_i32(&mut data, doc_string);
+		push_u32(&mut data, 0); // help string context
+		push_u32(&mut data, type_lib.help_context);
+		push_u32(&mut data, self.num_names);
+		push_u32(&mut data, self.num_name_chars);
+		push_i32(&mut data, name);
+		push_i32(&mut data, help_file);
+		push_i32(&mut data, -1); // custom data
+		push_u32(&mut data, 0x20);
+		push_u32(&mut data, 0x80);
+		push_i32(&mut data, self.dispatch_href.unwrap_or(-1));
+		push_u32(&mut data, self.num_imp_infos);
+
+		for index in 0..num_type_infos {
+			push_i32(&mut data, type_info_href(index));
+		}
+
+		for &(offset, length) in &segment_directory {
+			push_i32(&mut data, offset);
+			push_u32(&mut data, length as u32);
+			push_i32(&mut data, -1);
+			push_u32(&mut data, 0x0F);
+		}
+
+		for &(_, segment) in &segments {
+			data.extend_from_slice(segment);
+		}
+
+		for members in &self.members {
+			data.extend_from_slice(members);
+		}
+
+		Ok(data)
+	}
+
+	fn type_info(&mut self, index: usize, type_info: &types::TypeInfo, name: i32) -> Result<(), crate::Error> {
+		let error = |reason| unwritable(&type_info.name, reason);
+
+		let href = type_info_href(index);
+		let guid = if type_info.guid == types::Guid::default() { -1 } else { self.guid(type_info.guid, href) };
+		let doc_string = self.optional_string(type_info.doc_string.as_deref()).map_err(error)?;
+
+		let (typekind, inherits) = match type_info.typekind {
+			types::TypeKind::Enum => (0, false),
+			types::TypeKind::Record => (1, false),
+			types::TypeKind::Module => (2, false),
+			types::TypeKind::Interface => (3, true),
+			types::TypeKind::Dispatch => (4, true),
+			types::TypeKind::CoClass => (5, true),
+			types::TypeKind::Alias => (6, false),
+			types::TypeKind::Union => (7, false),
+		};
+		if !inherits && !type_info.impl_types.is_empty() {
+			return Err(error("only interfaces, dispinterfaces and coclasses can have implemented types"));
+		}
+
+		let data_type1 = match type_info.typekind {
+			types::TypeKind::CoClass => {
+				// A linked list of MSFT_RefRecord in the reference table
+				let first = if type_info.impl_types.is_empty() { -1 } else { self.ref_table.len() as i32 };
+				for (i, impl_type) in type_info.impl_types.iter().enumerate() {
+					let href = self.type_ref(&impl_type.type_ref).map_err(error)?;
+					let next = if i + 1 == type_info.impl_types.len() { -1 } else { self.ref_table.len() as i32 + 16 };
+					push_i32(&mut self.ref_table, href);
+					push_u32(&mut self.ref_table, u32::from(impl_type.flags));
+					push_i32(&mut self.ref_table, -1); // custom data
+					push_i32(&mut self.ref_table, next);
+				}
+				first
+			},
+
+			types::TypeKind::Dispatch if !type_info.is_dual() => {
+				// All dispinterfaces inherit from the IDispatch in the header
+				match &*type_info.impl_types {
+					[] => (),
+					[impl_type] => {
+						let href = self.type_ref(&impl_type.type_ref).map_err(error)?;
+						match self.dispatch_href {
+							Some(dispatch_href) if dispatch_href != href => return Err(error("all dispinterfaces must inherit from the same IDispatch")),
+							_ => self.dispatch_href = Some(href),
+						}
+					},
+					_ => return Err(error("dispinterfaces can only inherit from one interface")),
+				}
+				-1
+			},
+
+			types::TypeKind::Alias => {
+				let alias_type = type_info.alias_type.as_ref().ok_or_else(|| error("alias does not have an aliased type"))?;
+				self.type_desc(alias_type).map_err(error)?
+			},
+
+			types::TypeKind::Module => self.optional_string(type_info.dll_name.as_deref()).map_err(error)?,
+
+			_ => match &*type_info.impl_types {
+				[] => -1,
+				[impl_type] => self.type_ref(&impl_type.type_ref).map_err(error)?,
+				_ => return Err(error("interfaces can only inherit from one interface")),
+			},
+		};
+
+		// For interfaces, the number of functions in the vtable and the depth of the inheritance chain including the interface itself
+		let data_type2 =
+			if matches!(type_info.typekind, types::TypeKind::Interface) || type_info.is_dual() {
+				let pointer_size = self.type_lib.syskind.pointer_size();
+				(u32::from(type_info.size_vft / pointer_size) << 16) | self.inheritance_depth(type_info)
+			}
+			else {
+				0
+			};
+
+		let num_functions = u16::try_from(type_info.functions.len()).map_err(|_| error("has too many functions"))?;
+		let num_vars = u16::try_from(type_info.vars.len()).map_err(|_| error("has too many variables"))?;
+		let num_impl_types = i16::try_from(type_info.impl_types.len()).map_err(|_| error("has too many implemented types"))?;
+
+		let members = self.members(href, type_info)?;
+		self.members.push(members);
+
+		// midl.exe fills these reserved fields with values that grow with the number of members
+		let num_members = type_info.functions.len() + type_info.vars.len();
+		let reserved2: usize =
+			type_info.functions.iter().map(|function| 0x40 + function.params.len() * 0x10).sum::<usize>() +
+			type_info.vars.len() * 0x40;
+		let reserved3 = if num_members == 0 { -1 } else { ((num_members - 1) * 0x38) as i32 };
+
+		let alignment = u32::from(type_info.alignment) & 0x1F;
+
+		let type_infos = &mut self.type_infos;
+		push_u32(type_infos, typekind | 0x20 | (alignment << 6) | (alignment << 11) | ((index as u32) << 16));
+		push_i32(type_infos, -1); // member data offset, patched later
+		push_u32(type_infos, reserved2 as u32);
+		push_i32(type_infos, reserved3);
+		push_u32(type_infos, 3);
+		push_u32(type_infos, 0);
+		push_u32(type_infos, u32::from(num_functions) | (u32::from(num_vars) << 16));
+		push_u32(type_infos, 0);
+		push_u32(type_infos, 0);
+		push_u32(type_infos, 0);
+		push_u32(type_infos, 0);
+		push_i32(type_infos, guid);
+		push_u32(type_infos, u32::from(type_info.flags));
+		push_i32(type_infos, name);
+		push_u32(type_infos, join_version(type_info.version));
+		push_i32(type_infos, doc_string);
+		push_u32(type_infos, 0); // help string context
+		push_u32(type_infos, type_info.help_context);
+		push_i32(type_infos, -1); // custom data
+		type_infos.extend_from_slice(&num_impl_types.to_le_bytes());
+		push_u16(type_infos, type_info.size_vft);
+		push_u32(type_infos, type_info.size_instance);
+		push_i32(type_infos, data_type1);
+		push_u32(type_infos, data_type2);
+		push_u32(type_infos, 0);
+		push_i32(type_infos, -1);
+
+		Ok(())
+	}
+
+	fn inheritance_depth(&self, type_info: &types::TypeInfo) -> u32 {
+		let mut depth: u32 = 1;
+		let mut parent = type_info.impl_types.first();
+		while let Some(impl_type) = parent {
+			depth += 1;
+			parent = match &impl_type.type_ref {
+				// Guard against cycles in malformed models
+				types::TypeRef::Local(index) if depth as usize <= self.type_lib.type_infos.len() =>
+					self.type_lib.type_infos.get(*index).and_then(|type_info| type_info.impl_types.first()),
+
+				types::TypeRef::External { type_: types::ImportedType::Guid(types::IID_IDISPATCH), .. } => {
+					// IDispatch inherits from IUnknown
+					depth += 1;
+					None
+				},
+
+				_ => None,
+			};
+		}
+		depth
+	}
+
+	/// Writes the member data block of a typeinfo. This is the inverse of [`Reader::members`]
+	fn members(&mut self, type_info_href: i32, type_info: &types::TypeInfo) -> Result<Vec<u8>, crate::Error> {
+		if type_info.functions.is_empty() && type_info.vars.is_empty() {
+			return Ok(vec![]);
+		}
+
+		let mut records = vec![];
+		let mut member_ids = vec![];
+		let mut names = vec![];
+		let mut record_offsets = vec![];
+
+		for (index, function) in type_info.functions.iter().enumerate() {
+			record_offsets.push(records.len() as u32);
+			let name =
+				self.function(&mut records, index, function, type_info_href, type_info.typekind)
+				.map_err(|reason| unwritable(&format!("{}::{}", type_info.name, function.name), reason))?;
+			member_ids.push(function.member_id);
+
+			// Like midl, omit the name of the second function of a propget / propput pair
+			let previous = index.checked_sub(1).map(|previous| &type_info.functions[previous]);
+			match previous {
+				Some(previous) if previous.name == function.name && previous.invoke_kind != types::InvokeKind::Func && function.invoke_kind != types::InvokeKind::Func =>
+					names.push(-1),
+				_ => names.push(name),
+			}
+		}
+
+		for (index, var) in type_info.vars.iter().enumerate() {
+			record_offsets.push(records.len() as u32);
+			let name =
+				self.var(&mut records, index, var, type_info_href, type_info.typekind)
+				.map_err(|reason| unwritable(&format!("{}::{}", type_info.name, var.name), reason))?;
+			member_ids.push(var.member_id);
+			names.push(name);
+		}
+
+		let mut result = Vec::with_capacity(4 + records.len() + member_ids.len() * 12);
+		push_u32(&mut result, records.len() as u32);
+		result.extend_from_slice(&records);
+		for member_id in member_ids {
+			push_i32(&mut result, member_id);
+		}
+		for name in names {
+			push_i32(&mut result, name);
+		}
+		for record_offset in record_offsets {
+			push_u32(&mut result, record_offset);
+		}
+		Ok(result)
+	}
+
+	/// Writes the record of a function, and returns the offset of its name
+	fn function(
+		&mut self,
+		records: &mut Vec<u8>,
+		index: usize,
+		function: &types::Function,
+		type_info_href: i32,
+		typekind: types::TypeKind,
+	) -> Result<i32, &'static str> {
+		let is_module = typekind == types::TypeKind::Module;
+		let name = self.member_name(&function.name, type_info_href, is_module, is_module)?;
+		let return_type = self.type_desc(&function.return_type)?;
+		let mut desc_size = FUNCDESC_SIZE + decoded_size(&function.return_type);
+
+		let func_kind = match function.func_kind {
+			types::FuncKind::Virtual => 0,
+			types::FuncKind::PureVirtual => 1,
+			types::FuncKind::NonVirtual => 2,
+			types::FuncKind::Static => 3,
+			types::FuncKind::Dispatch => 4,
+		};
+		let invoke_kind = match function.invoke_kind {
+			types::InvokeKind::Func => 1,
+			types::InvokeKind::PropertyGet => 2,
+			types::InvokeKind::PropertyPut => 4,
+			types::InvokeKind::PropertyPutRef => 8,
+		};
+		let call_conv = match function.call_conv {
+			types::CallConv::FastCall => 0,
+			types::CallConv::CDecl => 1,
+			types::CallConv::Pascal => 2,
+			types::CallConv::MacPascal => 3,
+			types::CallConv::StdCall => 4,
+			types::CallConv::FpFastCall => 5,
+			types::CallConv::SysCall => 6,
+			types::CallConv::MpwCDecl => 7,
+			types::CallConv::MpwPascal => 8,
+		};
+		let mut fkccic = func_kind | (invoke_kind << 3) | (call_conv << 8);
+
+		// Optional attributes are only written up to the last one that is set
+		let doc_string = self.optional_string(function.doc_string.as_deref())?;
+		let entry = match &function.entry_point {
+			Some(types::EntryPoint::Name(entry)) => Some(self.string(entry)?),
+			Some(types::EntryPoint::Ordinal(ordinal)) => {
+				fkccic |= FKCCIC_ENTRY_IS_ORDINAL;
+				Some(i32::from(*ordinal))
+			},
+			None => None,
+		};
+		let mut optional_attributes = vec![function.help_context as i32, doc_string, entry.unwrap_or(-1)];
+		let num_optional_attributes =
+			if entry.is_some() { 3 }
+			else if doc_string != -1 { 2 }
+			else { usize::from(function.help_context != 0) };
+		optional_attributes.truncate(num_optional_attributes);
+
+		let has_default_values = function.params.iter().any(|param| param.default_value.is_some());
+		if has_default_values {
+			fkccic |= FKCCIC_HAS_DEFAULT_VALUES;
+		}
+
+		let mut default_values = vec![];
+		let mut params = vec![];
+		let mut num_optional_params: u16 = 0;
+		for (param_index, param) in function.params.iter().enumerate() {
+			let default_value = match &param.default_value {
+				Some(default_value) => {
+					desc_size += PARAMDESCEX_SIZE;
+					self.value(default_value)?
+				},
+				None => -1,
+			};
+			if has_default_values {
+				push_i32(&mut default_values, default_value);
+			}
+
+			// Like midl, omit the name of the parameter to "put". The reader names it "value".
+			let name =
+				if param_index == function.params.len() - 1 && matches!(function.invoke_kind, types::InvokeKind::PropertyPut | types::InvokeKind::PropertyPutRef) {
+					-1
+				}
+				else {
+					self.name(&param.name)?
+				};
+			push_i32(&mut params, self.type_desc(&param.type_)?);
+			push_i32(&mut params, name);
+			push_u32(&mut params, u32::from(param.flags));
+			desc_size += ELEMDESC_SIZE + decoded_size(&param.type_);
+
+			if (param.flags & (types::PARAMFLAG_FOPT | types::PARAMFLAG_FHASDEFAULT)) == types::PARAMFLAG_FOPT {
+				num_optional_params += 1;
+			}
+		}
+
+		let num_params = u16::try_from(function.params.len()).map_err(|_| "has too many parameters")?;
+		let record_len = FUNC_RECORD_FIXED_SIZE + optional_attributes.len() * 4 + default_values.len() + params.len();
+		let record_len = u16::try_from(record_len).map_err(|_| "has a record that is too large")?;
+		let desc_size = u16::try_from(desc_size).map_err(|_| "has a FUNCDESC that is too large")?;
+
+		push_u32(records, u32::from(record_len) | ((index as u32) << 16));
+		push_i32(records, return_type);
+		push_u32(records, u32::from(function.flags));
+		push_u16(records, function.ovft);
+		push_u16(records, desc_size);
+		push_u32(records, fkccic);
+		push_u16(records, num_params);
+		push_u16(records, num_optional_params);
+		for optional_attribute in optional_attributes {
+			push_i32(records, optional_attribute);
+		}
+		records.extend_from_slice(&default_values);
+		records.extend_from_slice(&params);
+
+		Ok(name)
+	}
+
+	/// Writes the record of a var, and returns the offset of its name
+	fn var(
+		&mut self,
+		records: &mut Vec<u8>,
+		index: usize,
+		var: &types::Var,
+		type_info_href: i32,
+		typekind: types::TypeKind,
+	) -> Result<i32, &'static str> {
+		let name = self.member_name(&var.name, type_info_href, typekind != types::TypeKind::Dispatch, typekind == types::TypeKind::Enum)?;
+		let data_type = self.type_desc(&var.type_)?;
+		let mut desc_size = VARDESC_SIZE + decoded_size(&var.type_);
+
+		let (var_kind, value_or_offset) = match &var.var_kind {
+			types::VarKind::PerInstance(offset) => (0, *offset as i32),
+			types::VarKind::Static => (1, 0),
+			types::VarKind::Const(value) => {
+				desc_size += VARIANT_SIZE;
+				(2, self.value(value)?)
+			},
+			types::VarKind::Dispatch => (3, 0),
+		};
+
+		let doc_string = self.optional_string(var.doc_string.as_deref())?;
+		let optional_attributes: &[i32] =
+			if doc_string != -1 { &[var.help_context as i32, doc_string] }
+			else if var.help_context != 0 { &[var.help_context as i32] }
+			else { &[] };
+
+		let record_len = (VAR_RECORD_FIXED_SIZE + optional_attributes.len() * 4) as u32;
+		let desc_size = u16::try_from(desc_size).map_err(|_| "has a VARDESC that is too large")?;
+
+		push_u32(records, record_len | ((index as u32) << 16));
+		push_i32(records, data_type);
+		push_u32(records, u32::from(var.flags));
+		push_u16(records, var_kind);
+		push_u16(records, desc_size);
+		push_i32(records, value_or_offset);
+		for &optional_attribute in optional_attributes {
+			push_i32(records, optional_attribute);
+		}
+
+		Ok(name)
+	}
+
+	/// Adds the given GUID to the GUID table if it isn't already in it, and returns its offset.
+	///
+	/// `href` is the HREFTYPE of the type with this GUID, -2 for the typelib itself, or 2 for an imported typelib.
+	fn guid(&mut self, guid: types::Guid, href: i32) -> i32 {
+		if let Some(&offset) = self.guid_offsets.get(&guid) {
+			return offset;
+		}
+
+		let offset = self.guids.len() as i32;
+		let bucket = hash_guid(guid);
+		push_u32(&mut self.guids, guid.data1);
+		push_u16(&mut self.guids, guid.data2);
+		push_u16(&mut self.guids, guid.data3);
+		self.guids.extend_from_slice(&guid.data4);
+		push_i32(&mut self.guids, href);
+		push_i32(&mut self.guids, self.guid_hash_table[bucket]);
+		self.guid_hash_table[bucket] = offset;
+
+		self.guid_offsets.insert(guid, offset);
+		offset
+	}
+
+	/// Adds the given name to the name table if it isn't already in it, and returns its offset.
+	fn name(&mut self, name: &str) -> Result<i32, &'static str> {
+		let name = encode_ansi(name)?;
+		if let Some(&offset) = self.name_offsets.get(&name) {
+			return Ok(offset);
+		}
+
+		let len = u8::try_from(name.len()).map_err(|_| "has a name that is longer than 255 characters")?;
+		let hash = hash_name(&name);
+		let bucket = usize::from(hash & 0x7F);
+
+		// hreftype: i32, next_hash: i32, length, flags, hash, name
+		let offset = self.names.len() as i32;
+		push_i32(&mut self.names, -1);
+		push_i32(&mut self.names, self.name_hash_table[bucket]);
+		self.names.push(len);
+		self.names.push(0);
+		push_u16(&mut self.names, hash);
+		self.names.extend_from_slice(&name);
+		pad(&mut self.names, 0);
+		self.name_hash_table[bucket] = offset;
+
+		self.num_names += 1;
+		self.num_name_chars += u32::from(len);
+		self.name_offsets.insert(name, offset);
+		Ok(offset)
+	}
+
+	/// Adds the name of a function or var to the name table, and records the typeinfo that it belongs to.
+	///
+	/// A name that only belongs to one typeinfo is marked as unique if `unique` is set, and names of module and enum members
+	/// are marked as global since they can be used without qualifying them with the name of their typeinfo.
+	fn member_name(&mut self, name: &str, type_info_href: i32, unique: bool, global: bool) -> Result<i32, &'static str> {
+		let offset = self.name(name)?;
+		let entry = offset as usize;
+
+		if self.names[entry..(entry + 4)] == (-1_i32).to_le_bytes() {
+			self.names[entry..(entry + 4)].copy_from_slice(&type_info_href.to_le_bytes());
+			if unique {
+				self.names[entry + 9] |= NAME_FLAG_UNIQUE_MEMBER;
+			}
+		}
+		else {
+			self.names[entry + 9] &= !NAME_FLAG_UNIQUE_MEMBER;
+		}
+
+		if global {
+			self.names[entry + 9] |= NAME_FLAG_GLOBAL_MEMBER;
+		}
+
+		Ok(offset)
+	}
+
+	/// Adds the given string to the string table if it isn't already in it, and returns its offset.
+	fn string(&mut self, s: &str) -> Result<i32, &'static str> {
+		let s = encode_ansi(s)?;
+		if let Some(&offset) = self.string_offsets.get(&s) {
+			return Ok(offset);
+		}
+
+		let len = u16::try_from(s.len()).map_err(|_| "has a string that is longer than 65535 characters")?;
+
+		let offset = self.strings.len() as i32;
+		push_u16(&mut self.strings, len);
+		self.strings.extend_from_slice(&s);
+		pad(&mut self.strings, offset as usize + 8);
+
+		self.string_offsets.insert(s, offset);
+		Ok(offset)
+	}
+
+	fn optional_string(&mut self, s: Option<&str>) -> Result<i32, &'static str> {
+		match s {
+			Some(s) => self.string(s),
+			None => Ok(-1),
+		}
+	}
+
+	/// Writes an `MSFT_ImpFile` for the given imported typelib. This is the inverse of [`Reader::imp_files`]
+	fn imp_file(&mut self, import: &types::ImportedTypeLib) -> Result<(), &'static str> {
+		let guid = self.guid(import.guid, 2);
+		let file_name = encode_ansi(&import.file_name)?;
+		let file_name_len =
+			u16::try_from(file_name.len()).ok()
+			.filter(|&len| len <= 0x3FFF)
+			.ok_or("has an imported typelib whose file name is too long")?;
+
+		let offset = self.imp_files.len() as i32;
+		push_i32(&mut self.imp_files, guid);
+		push_u32(&mut self.imp_files, import.lcid);
+		push_u32(&mut self.imp_files, join_version(import.version));
+		push_u16(&mut self.imp_files, file_name_len << 2);
+		self.imp_files.extend_from_slice(&file_name);
+		pad(&mut self.imp_files, 0);
+
+		self.imp_file_offsets.push((import.guid, offset));
+		Ok(())
+	}
+
+	/// Returns the HREFTYPE of the given type, adding an `MSFT_ImpInfo` for it if it's an imported type.
+	fn type_ref(&mut self, type_ref: &types::TypeRef) -> Result<i32, &'static str> {
+		match type_ref {
+			types::TypeRef::Local(index) =>
+				if *index < self.type_lib.type_infos.len() {
+					Ok(type_info_href(*index))
+				}
+				else {
+					Err("refers to a typeinfo that does not exist")
+				},
+
+			types::TypeRef::External { lib_guid, type_ } => {
+				let imp_file =
+					lib_guid.and_then(|lib_guid| self.imp_file_offsets.iter().find(|(guid, _)| *guid == lib_guid))
+					.map(|&(_, offset)| offset)
+					.ok_or("refers to a type in a typelib that is not imported")?;
+
+				if let Some(&(_, _, offset)) = self.imp_info_offsets.iter().find(|(file, imported, _)| *file == imp_file && imported == type_) {
+					return Ok(offset | 1);
+				}
+
+				let offset = self.imp_infos.len() as i32;
+				let (flags, guid_or_index) = match *type_ {
+					types::ImportedType::Guid(guid) => (IMPINFO_OFFSET_IS_GUID, self.guid(guid, offset | 1)),
+					types::ImportedType::Index(index) => (0, i32::try_from(index).map_err(|_| "refers to an imported type with an invalid index")?),
+				};
+				push_u32(&mut self.imp_infos, flags);
+				push_i32(&mut self.imp_infos, imp_file);
+				push_i32(&mut self.imp_infos, guid_or_index);
+
+				self.num_imp_infos += 1;
+				self.imp_info_offsets.push((imp_file, *type_, offset));
+				Ok(offset | 1)
+			},
+		}
+	}
+
+	/// Encodes the given type, adding entries to the type description table for types that aren't base types.
+	/// This is the inverse of [`Reader::type_desc`]
+	fn type_desc(&mut self, type_desc: &types::TypeDesc) -> Result<i32, &'static str> {
+		let (vt, inner) = match type_desc {
+			// Base types are packed into the value itself
+			types::TypeDesc::Base(vt) => return Ok((0x8000_0000 | (u32::from(*vt) << 16) | u32::from(*vt)) as i32),
+
+			types::TypeDesc::Ptr(inner) => (types::VT_PTR, self.type_desc(inner)?),
+
+			types::TypeDesc::SafeArray(inner) => (types::VT_SAFEARRAY, self.type_desc(inner)?),
+
+			types::TypeDesc::CArray(element, dimensions) => {
+				let element = self.type_desc(element)?;
+				let num_dimensions = u16::try_from(dimensions.len()).map_err(|_| "has an array with too many dimensions")?;
+
+				// The element type, the number of dimensions, the size of the bounds, then the number of elements and lower bound of each dimension
+				let array_desc = self.array_descs.len() as i32;
+				push_i32(&mut self.array_descs, element);
+				push_u16(&mut self.array_descs, num_dimensions);
+				push_u16(&mut self.array_descs, num_dimensions.wrapping_mul(8));
+				for &dimension in dimensions {
+					push_u32(&mut self.array_descs, dimension);
+					push_u32(&mut self.array_descs, 0);
+				}
+
+				// Arrays are not shared, like midl.exe
+				let offset = self.type_descs.len() as i32;
+				push_u16(&mut self.type_descs, types::VT_CARRAY);
+				push_u16(&mut self.type_descs, 0x7FFE);
+				push_i32(&mut self.type_descs, array_desc);
+				return Ok(offset);
+			},
+
+			types::TypeDesc::UserDefined(type_ref) => (types::VT_USERDEFINED, self.type_ref(type_ref)?),
+		};
+
+		if let Some(&offset) = self.type_desc_offsets.get(&(vt, inner)) {
+			return Ok(offset);
+		}
+
+		// The high word describes the inner type - the inner base type combined with VT_BYREF or VT_ARRAY,
+		// 0x7FFF for user-defined types and pointers to them, or 0x7FFE for anything else.
+		let mix =
+			if vt == types::VT_USERDEFINED {
+				0x7FFF
+			}
+			else if inner < 0 {
+				let inner_vt = (inner >> 16) as u16;
+				if vt == types::VT_PTR { (inner_vt & 0x3FFF) | types::VT_BYREF } else { (inner_vt & 0x0FFF) | types::VT_ARRAY }
+			}
+			else {
+				let inner = inner as usize;
+				if self.type_descs[(inner + 2)..(inner + 4)] == [0xFF, 0x7F] { 0x7FFF } else { 0x7FFE }
+			};
+
+		let offset = self.type_descs.len() as i32;
+		push_u16(&mut self.type_descs, vt);
+		push_u16(&mut self.type_descs, mix);
+		push_i32(&mut self.type_descs, inner);
+
+		self.type_desc_offsets.insert((vt, inner), offset);
+		Ok(offset)
+	}
+
+	/// Encodes the given value, either into the returned `i32` itself or into the custom data segment.
+	/// This is the inverse of [`Reader::value`]
+	fn value(&mut self, value: &types::Variant) -> Result<i32, &'static str> {
+		let (vt, bits, len) = match value {
+			types::Variant::Empty => (types::VT_EMPTY, 0, 4),
+			types::Variant::Null => (types::VT_NULL, 0, 4),
+			types::Variant::I1(value) => (types::VT_I1, u64::from(*value as u8), 4),
+			types::Variant::I2(value) => (types::VT_I2, u64::from(*value as u16), 4),
+			types::Variant::I4(value) => (types::VT_I4, u64::from(*value as u32), 4),
+			types::Variant::I8(value) => (types::VT_I8, *value as u64, 8),
+			types::Variant::UI1(value) => (types::VT_UI1, u64::from(*value), 4),
+			types::Variant::UI2(value) => (types::VT_UI2, u64::from(*value), 4),
+			types::Variant::UI4(value) => (types::VT_UI4, u64::from(*value), 4),
+			types::Variant::UI8(value) => (types::VT_UI8, *value, 8),
+			types::Variant::Int(value) => (types::VT_INT, u64::from(*value as u32), 4),
+			types::Variant::UInt(value) => (types::VT_UINT, u64::from(*value), 4),
+			types::Variant::R4(value) => (types::VT_R4, u64::from(value.to_bits()), 4),
+			types::Variant::R8(value) => (types::VT_R8, value.to_bits(), 8),
+			types::Variant::Cy(value) => (types::VT_CY, *value as u64, 8),
+			types::Variant::Date(value) => (types::VT_DATE, value.to_bits(), 8),
+			types::Variant::Bool(value) => (types::VT_BOOL, if *value { 0xFFFF } else { 0 }, 4), // VARIANT_TRUE is -1
+			types::Variant::Error(value) => (types::VT_ERROR, u64::from(*value as u32), 4),
+
+			types::Variant::Bstr(value) => {
+				let value = encode_ansi(value)?;
+				let len = i32::try_from(value.len()).map_err(|_| "has a string value that is too long")?;
+
+				let offset = self.custom_data.len() as i32;
+				push_u16(&mut self.custom_data, types::VT_BSTR);
+				push_i32(&mut self.custom_data, len);
+				self.custom_data.extend_from_slice(&value);
+				pad(&mut self.custom_data, 0);
+				return Ok(offset);
+			},
+
+			types::Variant::Other(_) => return Err("has a value of a type that cannot be stored in a typelib"),
+		};
+
+		// Small enough values are packed into the value itself, along with the VARTYPE
+		if len == 4 && bits <= 0x03FF_FFFF {
+			return Ok((0x8000_0000 | (u32::from(vt) << 26) | bits as u32) as i32);
+		}
+
+		let offset = self.custom_data.len() as i32;
+		push_u16(&mut self.custom_data, vt);
+		push_u32(&mut self.custom_data, bits as u32);
+		if len == 8 {
+			push_u32(&mut self.custom_data, (bits >> 32) as u32);
+		}
+		pad(&mut self.custom_data, 0);
+		Ok(offset)
+	}
+}
+
+/// The HREFTYPE of a typeinfo is the offset of its `MSFT_TypeInfoBase` in the typeinfo segment.
+fn type_info_href(index: usize) -> i32 {
+	(index * TYPE_INFO_BASE_SIZE) as i32
+}
+
+/// The size that the given type adds to a reconstituted `FUNCDESC` or `VARDESC`
+fn decoded_size(type_desc: &types::TypeDesc) -> u32 {
+	match type_desc {
+		types::TypeDesc::Base(_) | types::TypeDesc::UserDefined(_) => 0,
+		types::TypeDesc::Ptr(inner) | types::TypeDesc::SafeArray(inner) => TYPEDESC_SIZE + decoded_size(inner),
+		types::TypeDesc::CArray(element, dimensions) =>
+			ARRAYDESC_SIZE + (dimensions.len().saturating_sub(1) as u32) * SAFEARRAYBOUND_SIZE + decoded_size(element),
+	}
+}
+
+/// The hash that the GUID table is bucketed by.
+fn hash_guid(guid: types::Guid) -> usize {
+	let words = [
+		guid.data1 as u16,
+		(guid.data1 >> 16) as u16,
+		guid.data2,
+		guid.data3,
+		u16::from_le_bytes([guid.data4[0], guid.data4[1]]),
+		u16::from_le_bytes([guid.data4[2], guid.data4[3]]),
+		u16::from_le_bytes([guid.data4[4], guid.data4[5]]),
+		u16::from_le_bytes([guid.data4[6], guid.data4[7]]),
+	];
+	usize::from(words.iter().fold(0, |hash, word| hash ^ word)) % GUID_HASH_TABLE_LEN
+}
+
+/// The low word of `LHashValOfNameSysA` for Western European languages, which the name table stores with each name.
+fn hash_name(name: &[u8]) -> u16 {
+	// How characters above 0x7F are folded before hashing. Characters below 0x80 are just uppercased.
+	const HIGH_CHARS: [u8; 0x80] = [
+		0x7F, 0x7F, 0x82, 0x46, 0x84, 0x85, 0x86, 0x87, 0x7F, 0x89, 0x53, 0x8B, 0x8C, 0x7F, 0x7F, 0x7F,
+		0x7F, 0x91, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x7F, 0x99, 0x53, 0x9B, 0x8C, 0x7F, 0x7F, 0x59,
+		0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xAB, 0xAC, 0xAD, 0xAE, 0xAF,
+		0xB0, 0xB1, 0xB2, 0xB3, 0xB4, 0xB5, 0xB6, 0xB7, 0xB8, 0xB9, 0xBA, 0xBB, 0xBC, 0xBD, 0xBE, 0xBF,
+		0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0xC6, 0x43, 0x45, 0x45, 0x45, 0x45, 0x49, 0x49, 0x49, 0x49,
+		0xD0, 0x4E, 0x4F, 0x4F, 0x4F, 0x4F, 0x4F, 0xD7, 0x4F, 0x55, 0x55, 0x55, 0x55, 0x59, 0xDE, 0xDF,
+		0x41, 0x41, 0x41, 0x41, 0x41, 0x41, 0xC6, 0x43, 0x45, 0x45, 0x45, 0x45, 0x49, 0x49, 0x49, 0x49,
+		0xD0, 0x4E, 0x4F, 0x4F, 0x4F, 0x4F, 0x4F, 0xF7, 0x4F, 0x55, 0x55, 0x55, 0x55, 0x59, 0xDE, 0x59,
+	];
+
+	let hash = name.iter().fold(0x0DEA_DBEE_u32, |hash, &c| {
+		let c = if c < 0x80 { c.to_ascii_uppercase() } else { HIGH_CHARS[usize::from(c - 0x80)] };
+		hash.wrapping_mul(37).wrapping_add(u32::from(c))
+	});
+	(hash % 65599) as u16
+}
+
+/// The inverse of [`decode_ansi`]
+fn encode_ansi(s: &str) -> Result<Vec<u8>, &'static str> {
+	s.chars()
+		.map(|c| u8::try_from(u32::from(c)).map_err(|_| "has a name or string with characters that are not in the ANSI codepage"))
+		.collect()
+}
+
+/// Pads the given buffer to a multiple of 4 bytes, and to at least the given length.
+fn pad(buf: &mut Vec<u8>, min_len: usize) {
+	while buf.len() < min_len || buf.len() % 4 != 0 {
+		buf.push(PADDING);
+	}
+}
+
+fn push_u16(buf: &mut Vec<u8>, value: u16) {
+	buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(buf: &mut Vec<u8>, value: u32) {
+	buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_i32(buf: &mut Vec<u8>, value: i32) {
+	buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn unwritable(name: &str, reason: &'static str) -> crate::Error {
+	crate::Error::Unwritable { name: name.to_string(), reason }
+}
+
+/// Versions are stored with the major version in the low word and the minor version in the high word.
+fn split_version(version: u32) -> types::Version {
+	types::Version {
+		major: (version & 0xFFFF) as u16,
+		minor: (version >> 16) as u16,
+	}
+}
+
+/// The inverse of [`split_version`]
+fn join_version(version: types::Version) -> u32 {
+	u32::from(version.major) | (u32::from(version.minor) << 16)
+}
+
+fn segment_offset(segment: Segment, offset: i32) -> Option<usize> {
+	let offset = usize::try_from(offset).ok()?;
+	if offset < segment.length {
+		Some(segment.offset + offset)
+	}
+	else {
+		None
+	}
+}
+
+/// Strings in MSFT typelibs are in the ANSI codepage. Assume it's Windows-1252, which is mostly the same as Latin-1.
+fn decode_ansi(bytes: &[u8]) -> String {
+	bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+fn malformed(offset: usize, reason: &'static str) -> crate::Error {
+	crate::Error::Malformed { offset, reason }
+}
@@ -0,0 +1,250 @@
+//! Extracts `TYPELIB` resources from PE / PE32+ images, such as `.dll`, `.ocx`, `.exe` and `.olb` files.
+
+use std::convert::TryFrom;
+
+const DOS_MAGIC: &[u8; 2] = b"MZ";
+const PE_SIGNATURE: &[u8; 4] = b"PE\0\0";
+
+const OPTIONAL_HEADER_MAGIC_PE32: u16 = 0x010B;
+const OPTIONAL_HEADER_MAGIC_PE32_PLUS: u16 = 0x020B;
+
+const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+
+const RESOURCE_TYPE_TYPELIB: &str = "TYPELIB";
+
+/// The typelib that `LoadTypeLibEx` loads from an image when no resource index is given
+pub(crate) const DEFAULT_RESOURCE_INDEX: u16 = 1;
+
+/// Resource directories nest type, name and language. Anything deeper than that is corrupt.
+const MAX_RESOURCE_DIRECTORY_DEPTH: usize = 3;
+
+pub(crate) fn is_pe(data: &[u8]) -> bool {
+	data.starts_with(DOS_MAGIC)
+}
+
+/// Returns the contents of the `TYPELIB` resource with the given index in the given PE image.
+pub(crate) fn find_type_lib(data: &[u8], index: u16) -> Result<&[u8], crate::Error> {
+	let image = Image::new(data)?;
+
+	let Some(resource_directory_rva) = image.resource_directory_rva else { return Err(crate::Error::TypeLibResourceNotFound(index)); };
+	let resource_directory_offset = image.rva_to_offset(resource_directory_rva)?;
+
+	let resources = Resources { image: &image, base: resource_directory_offset };
+
+	let Some(types_entry) = resources.find_entry(0, 0, &ResourceId::Name(RESOURCE_TYPE_TYPELIB))? else {
+		return Err(crate::Error::TypeLibResourceNotFound(index));
+	};
+	let types_entry = resources.directory(types_entry, 1)?;
+
+	let Some(names_entry) = resources.find_entry(types_entry, 1, &ResourceId::Id(index))? else {
+		return Err(crate::Error::TypeLibResourceNotFound(index));
+	};
+	let names_entry = resources.directory(names_entry, 2)?;
+
+	// Use the first language. Typelibs are not localized this way in practice.
+	let Some(language_entry) = resources.first_entry(names_entry, 2)? else {
+		return Err(crate::Error::TypeLibResourceNotFound(index));
+	};
+	if (language_entry & 0x8000_0000) != 0 {
+		return Err(malformed(resources.base, "resource directory is nested too deeply"));
+	}
+
+	// IMAGE_RESOURCE_DATA_ENTRY
+	let data_entry_offset = resources.base + language_entry as usize;
+	let data_rva = image.u32(data_entry_offset)?;
+	let data_size = image.u32(data_entry_offset + 4)?;
+	let data_offset = image.rva_to_offset(data_rva)?;
+	image.bytes(data_offset, data_size as usize)
+}
+
+/// Splits a `LoadTypeLib`-style path like `foo.dll\3` into the path of the image and the index of the `TYPELIB` resource.
+///
+/// The path is returned unchanged if it names an existing file or does not end in a resource index.
+pub(crate) fn split_resource_index(path: &std::path::Path) -> (std::borrow::Cow<'_, std::path::Path>, Option<u16>) {
+	if path.is_file() {
+		return (path.into(), None);
+	}
+
+	let Some(path_str) = path.to_str() else { return (path.into(), None); };
+	let Some((image_path, index)) = path_str.rsplit_once(['\\', '/']) else { return (path.into(), None); };
+	if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+		return (path.into(), None);
+	}
+
+	match index.parse() {
+		Ok(index) => (std::path::PathBuf::from(image_path).into(), Some(index)),
+		Err(_) => (path.into(), None),
+	}
+}
+
+struct Image<'a> {
+	data: &'a [u8],
+	sections: Vec<Section>,
+	resource_directory_rva: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Section {
+	virtual_address: u32,
+	raw_data_size: u32,
+	raw_data_offset: u32,
+}
+
+impl<'a> Image<'a> {
+	fn new(data: &'a [u8]) -> Result<Self, crate::Error> {
+		if !is_pe(data) {
+			return Err(malformed(0, "missing MZ signature"));
+		}
+
+		let mut image = Image {
+			data,
+			sections: vec![],
+			resource_directory_rva: None,
+		};
+
+		let pe_header_offset = image.u32(0x3C)? as usize;
+		if image.bytes(pe_header_offset, 4)? != PE_SIGNATURE {
+			return Err(malformed(pe_header_offset, "missing PE signature"));
+		}
+
+		// IMAGE_FILE_HEADER
+		let file_header_offset = pe_header_offset + 4;
+		let num_sections = image.u16(file_header_offset + 2)?;
+		let optional_header_size = image.u16(file_header_offset + 16)?;
+
+		// IMAGE_OPTIONAL_HEADER32 / IMAGE_OPTIONAL_HEADER64
+		let optional_header_offset = file_header_offset + 20;
+		let (num_data_directories_offset, data_directories_offset) = match image.u16(optional_header_offset)? {
+			OPTIONAL_HEADER_MAGIC_PE32 => (optional_header_offset + 92, optional_header_offset + 96),
+			OPTIONAL_HEADER_MAGIC_PE32_PLUS => (optional_header_offset + 108, optional_header_offset + 112),
+			_ => return Err(malformed(optional_header_offset, "unknown optional header magic")),
+		};
+
+		let num_data_directories = image.u32(num_data_directories_offset)? as usize;
+		if num_data_directories > IMAGE_DIRECTORY_ENTRY_RESOURCE {
+			let resource_directory_rva = image.u32(data_directories_offset + IMAGE_DIRECTORY_ENTRY_RESOURCE * 8)?;
+			if resource_directory_rva != 0 {
+				image.resource_directory_rva = Some(resource_directory_rva);
+			}
+		}
+
+		// IMAGE_SECTION_HEADER
+		let section_headers_offset = optional_header_offset + usize::from(optional_header_size);
+		image.sections = (0..usize::from(num_sections)).map(|index| {
+			let offset = section_headers_offset + index * 40;
+			Ok(Section {
+				virtual_address: image.u32(offset + 12)?,
+				raw_data_size: image.u32(offset + 16)?,
+				raw_data_offset: image.u32(offset + 20)?,
+			})
+		}).collect::<Result<_, crate::Error>>()?;
+
+		Ok(image)
+	}
+
+	fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], crate::Error> {
+		offset.checked_add(len)
+			.and_then(|end| self.data.get(offset..end))
+			.ok_or_else(|| malformed(offset, "unexpected end of file"))
+	}
+
+	fn u16(&self, offset: usize) -> Result<u16, crate::Error> {
+		let bytes = self.bytes(offset, 2)?;
+		Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn u32(&self, offset: usize) -> Result<u32, crate::Error> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn rva_to_offset(&self, rva: u32) -> Result<usize, crate::Error> {
+		self.sections.iter()
+			.find_map(|section| {
+				let offset_in_section = rva.checked_sub(section.virtual_address)?;
+				// Some linkers leave the virtual size as zero, so only the raw data size is checked.
+				if offset_in_section < section.raw_data_size {
+					usize::try_from(section.raw_data_offset.checked_add(offset_in_section)?).ok()
+				}
+				else {
+					None
+				}
+			})
+			.ok_or_else(|| malformed(0, "RVA is not in any section"))
+	}
+}
+
+enum ResourceId<'a> {
+	Id(u16),
+	Name(&'a str),
+}
+
+struct Resources<'a, 'b> {
+	image: &'b Image<'a>,
+	base: usize,
+}
+
+impl Resources<'_, '_> {
+	/// Resolves an entry's `OffsetToData` that is expected to point to a subdirectory.
+	fn directory(&self, entry: u32, depth: usize) -> Result<u32, crate::Error> {
+		if (entry & 0x8000_0000) == 0 || depth >= MAX_RESOURCE_DIRECTORY_DEPTH {
+			return Err(malformed(self.base, "expected a resource subdirectory"));
+		}
+
+		Ok(entry & 0x7FFF_FFFF)
+	}
+
+	/// Iterates over the entries of the `IMAGE_RESOURCE_DIRECTORY` at the given offset, returning the `OffsetToData` of the first one that matches.
+	fn find_entry(&self, directory: u32, depth: usize, id: &ResourceId<'_>) -> Result<Option<u32>, crate::Error> {
+		if depth >= MAX_RESOURCE_DIRECTORY_DEPTH {
+			return Err(malformed(self.base, "resource directory is nested too deeply"));
+		}
+
+		let directory_offset = self.base + directory as usize;
+		let num_named_entries = usize::from(self.image.u16(directory_offset + 12)?);
+		let num_id_entries = usize::from(self.image.u16(directory_offset + 14)?);
+
+		for index in 0..(num_named_entries + num_id_entries) {
+			let entry_offset = directory_offset + 16 + index * 8;
+			let name = self.image.u32(entry_offset)?;
+			let offset_to_data = self.image.u32(entry_offset + 4)?;
+
+			let matches = match id {
+				ResourceId::Id(id) => (name & 0x8000_0000) == 0 && name == u32::from(*id),
+				ResourceId::Name(id) => (name & 0x8000_0000) != 0 && self.name_eq(name & 0x7FFF_FFFF, id)?,
+			};
+			if matches {
+				return Ok(Some(offset_to_data));
+			}
+		}
+
+		Ok(None)
+	}
+
+	fn first_entry(&self, directory: u32, depth: usize) -> Result<Option<u32>, crate::Error> {
+		if depth >= MAX_RESOURCE_DIRECTORY_DEPTH {
+			return Err(malformed(self.base, "resource directory is nested too deeply"));
+		}
+
+		let directory_offset = self.base + directory as usize;
+		let num_entries = usize::from(self.image.u16(directory_offset + 12)?) + usize::from(self.image.u16(directory_offset + 14)?);
+		if num_entries == 0 {
+			return Ok(None);
+		}
+
+		Ok(Some(self.image.u32(directory_offset + 16 + 4)?))
+	}
+
+	/// Resource names are `IMAGE_RESOURCE_DIR_STRING_U` - a length-prefixed UTF-16 string. They are compared case-insensitively.
+	fn name_eq(&self, offset: u32, expected: &str) -> Result<bool, crate::Error> {
+		let offset = self.base + offset as usize;
+		let len = usize::from(self.image.u16(offset)?);
+		let bytes = self.image.bytes(offset + 2, len * 2)?;
+		let name = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]]));
+		Ok(std::char::decode_utf16(name).map(|c| c.map(|c| c.to_ascii_uppercase())).eq(expected.chars().map(Ok)))
+	}
+}
+
+fn malformed(offset: usize, reason: &'static str) -> crate::Error {
+	crate::Error::Malformed { offset, reason }
+}
@@ -0,0 +1,862 @@
+//! Plain `#[repr(C)]` bindings that don't depend on the macros of any crate.
+//!
+//! Each interface is a `#[repr(C)]` struct holding a pointer to its `#[repr(C)]` vtable struct, with the same layout and names as
+//! the ones that `winapi`'s `RIDL!` macro generates. The bindings refer to a handful of ABI types by name, which must be in scope:
+//! `GUID`, `HRESULT`, `BSTR`, `VARIANT`, `SAFEARRAY`, `DECIMAL`, `CY`, `DATE`, `IUnknown`, `IUnknownVtbl`, `IDispatch` and `IDispatchVtbl`,
+//! and `FILETIME`, `BLOB` and `BSTRBLOB` if the typelib uses those `VARTYPE`s.
+//! They can come from `winapi`, or from the prelude that's written with the bindings if `emit_imports` is set, which doesn't depend on any crate.
+
+use crate::{is_inherited_dispatch_member, sanitize_reserved, type_ref_path, type_ref_to_string, types, var_type_kind, vtbl_size, well_known_path, BuildResult, Context, Location, VarTypeKind, WarningKind, WellKnownType};
+
+/// Writes the bindings for the given type of the typelib
+pub(crate) fn write_item<W>(context: &Context<'_>, type_info: &types::TypeInfo, emit_dispinterfaces: bool, build_result: &mut BuildResult, mut out: W) -> Result<(), crate::Error> where W: std::io::Write {
+	let typekind =
+		if type_info.is_dual() {
+			// Emit the interface half of dual interfaces
+			build_result.skipped_dispinterface_of_dual_interface(&type_info.name);
+			types::TypeKind::Interface
+		}
+		else {
+			type_info.typekind
+		};
+
+	let type_name = &type_info.name;
+
+	match typekind {
+		types::TypeKind::Enum => {
+			writeln!(out, "pub type {} = i32;", type_name)?;
+
+			for member in &type_info.vars {
+				match &member.var_kind {
+					types::VarKind::Const(types::Variant::I4(value)) =>
+						writeln!(out, "pub const {}: {} = {};", sanitize_reserved(&member.name, &Location::member(type_name, &member.name), build_result), type_name, value)?,
+					types::VarKind::Const(value) =>
+						return Err(crate::Error::UnsupportedVarType { location: Location::member(type_name, &member.name), vt: value.vt() }),
+					_ => return Err(crate::Error::Inconsistent { location: Location::member(type_name, &member.name), reason: "enum member is not a constant" }),
+				}
+			}
+
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Record => {
+			writeln!(out, "#[repr(C)]")?;
+			writeln!(out, "#[derive(Clone, Copy)]")?;
+			writeln!(out, "pub struct {} {{", type_name)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				writeln!(out, "    pub {}: {},", sanitize_reserved(&field.name, &location, build_result), type_to_string(&field.type_, types::PARAMFLAG_FOUT, &location, context, build_result)?)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Module => {
+			writeln!(out, r#"extern "system" {{"#)?;
+
+			for function in &type_info.functions {
+				let location = Location::member(type_name, &function.name);
+
+				if function.func_kind != types::FuncKind::Static {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				writeln!(out, "    pub fn {}(", function.name)?;
+
+				for (param_index, param) in function.params.iter().enumerate() {
+					writeln!(out, "        {}: {},",
+						sanitize_reserved(&param.name, &location.param(param_index), build_result),
+						type_to_string(&param.type_, param.flags, &location.param(param_index), context, build_result)?)?;
+				}
+
+				match &function.return_type {
+					types::TypeDesc::Base(types::VT_VOID) => writeln!(out, "    );")?,
+					return_type => writeln!(out, "    ) -> {};", type_to_string(return_type, types::PARAMFLAG_FOUT, &location, context, build_result)?)?,
+				}
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Interface => {
+			let mut functions = vec![];
+
+			let parent = type_info.impl_types.first().map(|impl_type| &impl_type.type_ref);
+			let parent_vtbl_size = parent.and_then(|parent| vtbl_size(parent, context)).unwrap_or(0);
+
+			for function in &type_info.functions {
+				if function.ovft < parent_vtbl_size {
+					// Inherited from ancestors
+					continue;
+				}
+
+				let location = Location::member(type_name, &function.name);
+
+				if function.func_kind == types::FuncKind::Static || function.func_kind == types::FuncKind::Dispatch {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				let name = match function.invoke_kind {
+					types::InvokeKind::Func => function.name.clone(),
+					types::InvokeKind::PropertyGet => format!("get_{}", function.name),
+					types::InvokeKind::PropertyPut => format!("put_{}", function.name),
+					types::InvokeKind::PropertyPutRef => format!("putref_{}", function.name),
+				};
+
+				let explicit_ret_val = function.params.iter().any(|param| (param.flags & types::PARAMFLAG_FRETVAL) == types::PARAMFLAG_FRETVAL);
+				if function.invoke_kind == types::InvokeKind::PropertyGet && !explicit_ret_val {
+					let mut params = function.params.clone();
+					params.push(ret_val_param(function.return_type.clone()));
+					functions.push(Function::new(name, &params, &types::TypeDesc::Base(types::VT_HRESULT), &location, context, build_result)?);
+				}
+				else {
+					functions.push(Function::new(name, &function.params, &function.return_type, &location, context, build_result)?);
+				}
+			}
+
+			for property in &type_info.vars {
+				// Synthesize get_() and put_() functions for each property.
+
+				let location = Location::member(type_name, &property.name);
+				let property_name = sanitize_reserved(&property.name, &location, build_result);
+
+				functions.push(Function::new(
+					format!("get_{}", property_name),
+					&[ret_val_param(property.type_.clone())],
+					&types::TypeDesc::Base(types::VT_HRESULT),
+					&location, context, build_result)?);
+
+				functions.push(Function::new(
+					format!("put_{}", property_name),
+					&[types::Param { name: "value".to_string(), type_: property.type_.clone(), flags: types::PARAMFLAG_FIN, default_value: None }],
+					&types::TypeDesc::Base(types::VT_HRESULT),
+					&location, context, build_result)?);
+			}
+
+			let parent = parent.map(|parent| type_ref_path(parent, &Location::type_(type_name), context, build_result));
+			write_interface(&mut out, type_name, type_info.guid, parent.as_deref(), &functions)?;
+		},
+
+		types::TypeKind::Dispatch => {
+			if !emit_dispinterfaces {
+				build_result.skipped_dispinterface(&type_info.name);
+				return Ok(());
+			}
+
+			// The members of a dispinterface are only reachable through IDispatch::Invoke, so only the interface itself is emitted.
+			for function in type_info.functions.iter().filter(|function| is_inherited_dispatch_member(type_info, function, context)) {
+				build_result.warn(WarningKind::DroppedRestrictedMember, Location::member(type_name, &function.name));
+			}
+
+			let parent = well_known_path("IDispatch", context);
+			write_interface(&mut out, type_name, type_info.guid, Some(&parent), &[])?;
+		},
+
+		types::TypeKind::CoClass => {
+			for parent in type_info.impl_types.iter().map(|impl_type| &impl_type.type_ref) {
+				let parent_name = type_ref_to_string(parent, &Location::type_(type_name), context, build_result);
+				writeln!(out, "// Implements {}", parent_name)?;
+			}
+
+			writeln!(out, "pub const CLSID_{}: GUID = {};", type_name, guid_to_string(type_info.guid))?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Alias => {
+			let location = Location::type_(type_name);
+			let alias_type = type_info.alias_type.as_ref().ok_or_else(|| crate::Error::Inconsistent { location: location.clone(), reason: "alias has no target" })?;
+			writeln!(out, "pub type {} = {};", type_name, type_to_string(alias_type, types::PARAMFLAG_FOUT, &location, context, build_result)?)?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Union => {
+			let alignment = match type_info.alignment {
+				4 => "u32",
+				8 => "u64",
+				_ => return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union alignment is not 4 or 8" }),
+			};
+
+			let num_aligned_elements = type_info.size_instance.div_ceil(u32::from(type_info.alignment));
+			if num_aligned_elements == 0 {
+				return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union is empty" });
+			}
+			let wrapped_type = match num_aligned_elements {
+				1 => alignment.to_string(),
+				_ => format!("[{}; {}]", alignment, num_aligned_elements),
+			};
+
+			// The first field gives the union the size and alignment of the typelib's union.
+			writeln!(out, "#[repr(C)]")?;
+			writeln!(out, "#[derive(Clone, Copy)]")?;
+			writeln!(out, "pub union {} {{", type_name)?;
+			writeln!(out, "    _alignment: {},", wrapped_type)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				writeln!(out, "    pub {}: {},", sanitize_reserved(&field.name, &location, build_result), type_to_string(&field.type_, types::PARAMFLAG_FOUT, &location, context, build_result)?)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+	}
+
+	Ok(())
+}
+
+/// A function in the vtable of an interface
+struct Function {
+	name: String,
+
+	/// The names and types of the parameters
+	params: Vec<(String, String)>,
+
+	/// The type that the function returns, if it returns a value
+	return_type: Option<String>,
+}
+
+impl Function {
+	fn new(
+		name: String,
+		params: &[types::Param],
+		return_type: &types::TypeDesc,
+		location: &Location,
+		context: &Context<'_>,
+		build_result: &mut BuildResult,
+	) -> Result<Self, crate::Error> {
+		let params =
+			params.iter().enumerate()
+			.map(|(param_index, param)| Ok((sanitize_reserved(&param.name, &location.param(param_index), build_result), type_to_string(&param.type_, param.flags, &location.param(param_index), context, build_result)?)))
+			.collect::<Result<_, crate::Error>>()?;
+
+		let return_type = match return_type {
+			types::TypeDesc::Base(types::VT_VOID) => None,
+			return_type => Some(type_to_string(return_type, types::PARAMFLAG_FOUT, location, context, build_result)?),
+		};
+
+		Ok(Function { name, params, return_type })
+	}
+}
+
+/// An `[out, retval]` parameter for a property getter that returns the given type
+fn ret_val_param(type_: types::TypeDesc) -> types::Param {
+	types::Param {
+		name: "value".to_string(),
+		type_: types::TypeDesc::Ptr(Box::new(type_)),
+		flags: types::PARAMFLAG_FOUT | types::PARAMFLAG_FRETVAL,
+		default_value: None,
+	}
+}
+
+/// Writes the IID constant, the struct, the vtable struct, and the methods that call through the vtable of an interface
+fn write_interface<W>(mut out: W, type_name: &str, guid: types::Guid, parent: Option<&str>, functions: &[Function]) -> std::io::Result<()> where W: std::io::Write {
+	writeln!(out, "pub const IID_{}: GUID = {};", type_name, guid_to_string(guid))?;
+	writeln!(out)?;
+
+	writeln!(out, "#[repr(C)]")?;
+	writeln!(out, "pub struct {} {{", type_name)?;
+	writeln!(out, "    pub lpVtbl: *const {}Vtbl,", type_name)?;
+	writeln!(out, "}}")?;
+	writeln!(out)?;
+
+	writeln!(out, "#[repr(C)]")?;
+	writeln!(out, "pub struct {}Vtbl {{", type_name)?;
+	if let Some(parent) = parent {
+		writeln!(out, "    pub parent: {}Vtbl,", parent)?;
+	}
+	for function in functions {
+		writeln!(out, r#"    pub {}: unsafe extern "system" fn("#, function.name)?;
+		writeln!(out, "        This: *mut {},", type_name)?;
+		for (name, type_) in &function.params {
+			writeln!(out, "        {}: {},", name, type_)?;
+		}
+		match &function.return_type {
+			Some(return_type) => writeln!(out, "    ) -> {},", return_type)?,
+			None => writeln!(out, "    ),")?,
+		}
+	}
+	writeln!(out, "}}")?;
+	writeln!(out)?;
+
+	if let Some(parent) = parent {
+		writeln!(out, "impl core::ops::Deref for {} {{", type_name)?;
+		writeln!(out, "    type Target = {};", parent)?;
+		writeln!(out)?;
+		writeln!(out, "    fn deref(&self) -> &Self::Target {{")?;
+		writeln!(out, "        unsafe {{ &*(self as *const Self).cast::<{}>() }}", parent)?;
+		writeln!(out, "    }}")?;
+		writeln!(out, "}}")?;
+		writeln!(out)?;
+	}
+
+	if !functions.is_empty() {
+		writeln!(out, "impl {} {{", type_name)?;
+
+		for (i, function) in functions.iter().enumerate() {
+			if i > 0 {
+				writeln!(out)?;
+			}
+
+			writeln!(out, "    pub unsafe fn {}(", function.name)?;
+			writeln!(out, "        &self,")?;
+			for (name, type_) in &function.params {
+				writeln!(out, "        {}: {},", name, type_)?;
+			}
+			match &function.return_type {
+				Some(return_type) => writeln!(out, "    ) -> {} {{", return_type)?,
+				None => writeln!(out, "    ) {{")?,
+			}
+
+			writeln!(out, "        ((*self.lpVtbl).{})(", function.name)?;
+			writeln!(out, "            (self as *const Self).cast_mut(),")?;
+			for (name, _) in &function.params {
+				writeln!(out, "            {},", name)?;
+			}
+			writeln!(out, "        )")?;
+
+			writeln!(out, "    }}")?;
+		}
+
+		writeln!(out, "}}")?;
+		writeln!(out)?;
+	}
+
+	Ok(())
+}
+
+fn guid_to_string(guid: types::Guid) -> String {
+	format!("GUID {{ Data1: 0x{:08x}, Data2: 0x{:04x}, Data3: 0x{:04x}, Data4: [0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}] }}",
+		guid.data1, guid.data2, guid.data3,
+		guid.data4[0], guid.data4[1], guid.data4[2], guid.data4[3],
+		guid.data4[4], guid.data4[5], guid.data4[6], guid.data4[7])
+}
+
+fn type_to_string(type_: &types::TypeDesc, param_flags: u16, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Result<String, crate::Error> {
+	Ok(match type_ {
+		types::TypeDesc::Ptr(pointee) =>
+			if (param_flags & types::PARAMFLAG_FIN) == types::PARAMFLAG_FIN && (param_flags & types::PARAMFLAG_FOUT) == 0 {
+				// [in] => *const
+				format!("*const {}", type_to_string(pointee, param_flags, location, context, build_result)?)
+			}
+			else {
+				// [in, out] => *mut
+				// [] => *mut (Some functions like IXMLError::GetErrorInfo don't annotate [out] on their out parameter)
+				format!("*mut {}", type_to_string(pointee, param_flags, location, context, build_result)?)
+			},
+
+		types::TypeDesc::SafeArray(element) => {
+			// The element type of the SAFEARRAY isn't part of the Rust type, so it's written next to it in a comment,
+			// and reported for the bindings that don't keep comments, like the ones from `build_tokens`
+			let element = type_to_string(element, 0, location, context, build_result)?;
+			let type_name = "*mut SAFEARRAY";
+			build_result.lossy_type_mapping(type_, type_name, location);
+			format!("{} /* of {} */", type_name, element)
+		},
+
+		types::TypeDesc::CArray(element, dimensions) => {
+			let mut type_name = type_to_string(element, param_flags, location, context, build_result)?;
+
+			for dimension in dimensions {
+				type_name = format!("[{}; {}]", type_name, dimension);
+			}
+
+			type_name
+		},
+
+		types::TypeDesc::UserDefined(type_ref) => type_ref_path(type_ref, location, context, build_result),
+
+		types::TypeDesc::Base(vt) => match var_type_kind(*vt, location)? {
+			VarTypeKind::Expanded(type_) => type_to_string(&type_, param_flags, location, context, build_result)?,
+
+			VarTypeKind::Record => {
+				let type_name = "*mut core::ffi::c_void";
+				build_result.lossy_type_mapping(type_, type_name, location);
+				type_name.to_string()
+			},
+
+			VarTypeKind::WellKnown(well_known) => {
+				let type_name = well_known_type_path(well_known, context);
+				if let WellKnownType::Stream | WellKnownType::Storage = well_known {
+					build_result.lossy_type_mapping(type_, &type_name, location);
+				}
+				type_name
+			},
+		},
+	})
+}
+
+fn well_known_type_path(well_known: WellKnownType, context: &Context<'_>) -> String {
+	let path = match well_known {
+		WellKnownType::Primitive(type_name) |
+		WellKnownType::CountedArray(type_name) => type_name,
+		WellKnownType::Void => "core::ffi::c_void",
+		WellKnownType::Int |
+		WellKnownType::Error => "i32",
+		WellKnownType::UInt => "u32",
+		WellKnownType::IntPtr => "isize",
+		WellKnownType::UIntPtr => "usize",
+		WellKnownType::Bool => "i16",
+		WellKnownType::Currency => "CY",
+		WellKnownType::Date => "DATE",
+		WellKnownType::Decimal => "DECIMAL",
+		WellKnownType::HResult => "HRESULT",
+		WellKnownType::Bstr => "BSTR",
+		WellKnownType::BstrBlob => "BSTRBLOB",
+		WellKnownType::LpStr => "*mut u8",
+		WellKnownType::LpWStr => "*mut u16",
+		WellKnownType::Variant => "VARIANT",
+		WellKnownType::FileTime => "FILETIME",
+		WellKnownType::Blob => "BLOB",
+		WellKnownType::Clsid => "GUID",
+		WellKnownType::Dispatch => return format!("*mut {}", well_known_path("IDispatch", context)),
+		// The prelude doesn't have `IStream` and `IStorage`, so they're emitted as the `IUnknown` that they derive from
+		WellKnownType::Unknown |
+		WellKnownType::Stream |
+		WellKnownType::Storage => return format!("*mut {}", well_known_path("IUnknown", context)),
+	};
+	path.to_string()
+}
+
+/// Writes the ABI types that the bindings refer to by name
+pub(crate) fn write_prelude<W>(mut out: W) -> std::io::Result<()> where W: std::io::Write {
+	out.write_all(PRELUDE.as_bytes())
+}
+
+/// The ABI types that the bindings refer to by name, laid out the same way as in the Windows SDK
+const PRELUDE: &str = r#"pub type HRESULT = i32;
+pub type BSTR = *mut u16;
+pub type DATE = f64;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GUID {
+    pub Data1: u32,
+    pub Data2: u16,
+    pub Data3: u16,
+    pub Data4: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CY {
+    pub int64: i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DECIMAL {
+    pub wReserved: u16,
+    pub scale: u8,
+    pub sign: u8,
+    pub Hi32: u32,
+    pub Lo64: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FILETIME {
+    pub dwLowDateTime: u32,
+    pub dwHighDateTime: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BLOB {
+    pub cbSize: u32,
+    pub pBlobData: *mut u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BSTRBLOB {
+    pub cbSize: u32,
+    pub pData: *mut u8,
+}
+
+// The counted arrays that VT_VECTORs stand for in a PROPVARIANT
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAC {
+    pub cElems: u32,
+    pub pElems: *mut i8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUB {
+    pub cElems: u32,
+    pub pElems: *mut u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAI {
+    pub cElems: u32,
+    pub pElems: *mut i16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUI {
+    pub cElems: u32,
+    pub pElems: *mut u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAL {
+    pub cElems: u32,
+    pub pElems: *mut i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUL {
+    pub cElems: u32,
+    pub pElems: *mut u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAFLT {
+    pub cElems: u32,
+    pub pElems: *mut f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CADBL {
+    pub cElems: u32,
+    pub pElems: *mut f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CACY {
+    pub cElems: u32,
+    pub pElems: *mut CY,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CADATE {
+    pub cElems: u32,
+    pub pElems: *mut DATE,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CABSTR {
+    pub cElems: u32,
+    pub pElems: *mut BSTR,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CABSTRBLOB {
+    pub cElems: u32,
+    pub pElems: *mut BSTRBLOB,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CABOOL {
+    pub cElems: u32,
+    pub pElems: *mut i16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CASCODE {
+    pub cElems: u32,
+    pub pElems: *mut i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAH {
+    pub cElems: u32,
+    pub pElems: *mut i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAUH {
+    pub cElems: u32,
+    pub pElems: *mut u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CALPSTR {
+    pub cElems: u32,
+    pub pElems: *mut *mut u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CALPWSTR {
+    pub cElems: u32,
+    pub pElems: *mut *mut u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CAFILETIME {
+    pub cElems: u32,
+    pub pElems: *mut FILETIME,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CACLSID {
+    pub cElems: u32,
+    pub pElems: *mut GUID,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SAFEARRAYBOUND {
+    pub cElements: u32,
+    pub lLbound: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SAFEARRAY {
+    pub cDims: u16,
+    pub fFeatures: u16,
+    pub cbElements: u32,
+    pub cLocks: u32,
+    pub pvData: *mut core::ffi::c_void,
+    pub rgsabound: [SAFEARRAYBOUND; 1],
+}
+
+// A VARIANT that holds a DECIMAL overlaps it with the whole VARIANT, so its value can be read by transmuting the VARIANT to a DECIMAL.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct VARIANT {
+    pub vt: u16,
+    pub wReserved1: u16,
+    pub wReserved2: u16,
+    pub wReserved3: u16,
+    pub data: VARIANT_data,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union VARIANT_data {
+    pub llVal: i64,
+    pub lVal: i32,
+    pub bVal: u8,
+    pub iVal: i16,
+    pub fltVal: f32,
+    pub dblVal: f64,
+    pub boolVal: i16,
+    pub scode: i32,
+    pub cyVal: CY,
+    pub date: DATE,
+    pub bstrVal: BSTR,
+    pub punkVal: *mut IUnknown,
+    pub pdispVal: *mut IDispatch,
+    pub parray: *mut SAFEARRAY,
+    pub byref: *mut core::ffi::c_void,
+    pub record: [*mut core::ffi::c_void; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DISPPARAMS {
+    pub rgvarg: *mut VARIANT,
+    pub rgdispidNamedArgs: *mut i32,
+    pub cArgs: u32,
+    pub cNamedArgs: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EXCEPINFO {
+    pub wCode: u16,
+    pub wReserved: u16,
+    pub bstrSource: BSTR,
+    pub bstrDescription: BSTR,
+    pub bstrHelpFile: BSTR,
+    pub dwHelpContext: u32,
+    pub pvReserved: *mut core::ffi::c_void,
+    pub pfnDeferredFillIn: Option<unsafe extern "system" fn(*mut EXCEPINFO) -> HRESULT>,
+    pub scode: i32,
+}
+
+pub const IID_IUnknown: GUID = GUID { Data1: 0x00000000, Data2: 0x0000, Data3: 0x0000, Data4: [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46] };
+
+#[repr(C)]
+pub struct IUnknown {
+    pub lpVtbl: *const IUnknownVtbl,
+}
+
+#[repr(C)]
+pub struct IUnknownVtbl {
+    pub QueryInterface: unsafe extern "system" fn(
+        This: *mut IUnknown,
+        riid: *const GUID,
+        ppvObject: *mut *mut core::ffi::c_void,
+    ) -> HRESULT,
+    pub AddRef: unsafe extern "system" fn(
+        This: *mut IUnknown,
+    ) -> u32,
+    pub Release: unsafe extern "system" fn(
+        This: *mut IUnknown,
+    ) -> u32,
+}
+
+impl IUnknown {
+    pub unsafe fn QueryInterface(
+        &self,
+        riid: *const GUID,
+        ppvObject: *mut *mut core::ffi::c_void,
+    ) -> HRESULT {
+        ((*self.lpVtbl).QueryInterface)(
+            (self as *const Self).cast_mut(),
+            riid,
+            ppvObject,
+        )
+    }
+
+    pub unsafe fn AddRef(
+        &self,
+    ) -> u32 {
+        ((*self.lpVtbl).AddRef)(
+            (self as *const Self).cast_mut(),
+        )
+    }
+
+    pub unsafe fn Release(
+        &self,
+    ) -> u32 {
+        ((*self.lpVtbl).Release)(
+            (self as *const Self).cast_mut(),
+        )
+    }
+}
+
+pub const IID_IDispatch: GUID = GUID { Data1: 0x00020400, Data2: 0x0000, Data3: 0x0000, Data4: [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46] };
+
+#[repr(C)]
+pub struct IDispatch {
+    pub lpVtbl: *const IDispatchVtbl,
+}
+
+#[repr(C)]
+pub struct IDispatchVtbl {
+    pub parent: IUnknownVtbl,
+    pub GetTypeInfoCount: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        pctinfo: *mut u32,
+    ) -> HRESULT,
+    pub GetTypeInfo: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        iTInfo: u32,
+        lcid: u32,
+        ppTInfo: *mut *mut core::ffi::c_void,
+    ) -> HRESULT,
+    pub GetIDsOfNames: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        riid: *const GUID,
+        rgszNames: *mut *mut u16,
+        cNames: u32,
+        lcid: u32,
+        rgDispId: *mut i32,
+    ) -> HRESULT,
+    pub Invoke: unsafe extern "system" fn(
+        This: *mut IDispatch,
+        dispIdMember: i32,
+        riid: *const GUID,
+        lcid: u32,
+        wFlags: u16,
+        pDispParams: *mut DISPPARAMS,
+        pVarResult: *mut VARIANT,
+        pExcepInfo: *mut EXCEPINFO,
+        puArgErr: *mut u32,
+    ) -> HRESULT,
+}
+
+impl core::ops::Deref for IDispatch {
+    type Target = IUnknown;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self).cast::<IUnknown>() }
+    }
+}
+
+impl IDispatch {
+    pub unsafe fn GetTypeInfoCount(
+        &self,
+        pctinfo: *mut u32,
+    ) -> HRESULT {
+        ((*self.lpVtbl).GetTypeInfoCount)(
+            (self as *const Self).cast_mut(),
+            pctinfo,
+        )
+    }
+
+    pub unsafe fn GetTypeInfo(
+        &self,
+        iTInfo: u32,
+        lcid: u32,
+        ppTInfo: *mut *mut core::ffi::c_void,
+    ) -> HRESULT {
+        ((*self.lpVtbl).GetTypeInfo)(
+            (self as *const Self).cast_mut(),
+            iTInfo,
+            lcid,
+            ppTInfo,
+        )
+    }
+
+    pub unsafe fn GetIDsOfNames(
+        &self,
+        riid: *const GUID,
+        rgszNames: *mut *mut u16,
+        cNames: u32,
+        lcid: u32,
+        rgDispId: *mut i32,
+    ) -> HRESULT {
+        ((*self.lpVtbl).GetIDsOfNames)(
+            (self as *const Self).cast_mut(),
+            riid,
+            rgszNames,
+            cNames,
+            lcid,
+            rgDispId,
+        )
+    }
+
+    pub unsafe fn Invoke(
+        &self,
+        dispIdMember: i32,
+        riid: *const GUID,
+        lcid: u32,
+        wFlags: u16,
+        pDispParams: *mut DISPPARAMS,
+        pVarResult: *mut VARIANT,
+        pExcepInfo: *mut EXCEPINFO,
+        puArgErr: *mut u32,
+    ) -> HRESULT {
+        ((*self.lpVtbl).Invoke)(
+            (self as *const Self).cast_mut(),
+            dispIdMember,
+            riid,
+            lcid,
+            wFlags,
+            pDispParams,
+            pVarResult,
+            pExcepInfo,
+            puArgErr,
+        )
+    }
+}
+
+"#;
@@ -0,0 +1,864 @@
+//! Reader for typelibs in the SLTG format, which is what older tools like the VB5 / VB6 compilers produce.
+//!
+//! The format is undocumented. The layout used here follows what Wine's `typelib.c` has worked out.
+//!
+//! An SLTG typelib is a header, followed by a table of blocks that form a linked list, followed by one block per typeinfo.
+//! The last block in the list is the library block, which contains the library attributes, the name and GUID of each typeinfo,
+//! and the name table.
+
+use crate::types;
+
+const MAGIC: &[u8; 4] = b"SLTG";
+const COMPOBJ_MAGIC: &[u8; 8] = b"CompObj\0";
+const DIR_MAGIC: &[u8; 4] = b"dir\0";
+
+const HEADER_SIZE: usize = 0x24;
+const BLOCK_ENTRY_SIZE: usize = 8;
+const MAGIC_SIZE: usize = 13;
+const INDEX_STRING_SIZE: usize = 11;
+const PAD_SIZE: usize = 9;
+
+const LIB_BLOCK_MAGIC: u16 = 0x51CC;
+const TYPE_INFO_HEADER_MAGIC: u16 = 0x0501;
+const REF_MAGIC: u8 = 0xDF;
+const IMPL_MAGIC: u16 = 0x004A;
+
+const FUNCTION_FLAGS_PRESENT: u8 = 0x20;
+const FUNCTION_MAGIC: u8 = 0x4C;
+const DISPATCH_FUNCTION_MAGIC: u8 = 0x44;
+const STATIC_FUNCTION_MAGIC: u8 = 0x53;
+
+const VAR_MAGIC: u8 = 0x0A;
+const VAR_WITH_FLAGS_MAGIC: u8 = 0x2A;
+
+/// `SLTG_MemberHeader`
+const MEMBER_HEADER_SIZE: usize = 9;
+
+/// `SLTG_ImplInfo`
+const IMPL_INFO_SIZE: usize = 0x16;
+
+/// Offset of the `SAFEARRAYBOUND`s in the `SAFEARRAY` that describes a `VT_CARRAY`
+const SAFEARRAY_BOUNDS_OFFSET: usize = 16;
+
+/// Names are stored as offsets into a table, after a hash table and some other data whose purpose isn't known.
+const NAME_TABLE_PREFIX_SIZE: usize = 0x218;
+const NAME_TABLE_EXTENDED_PREFIX_SIZE: usize = 0x20;
+
+/// Marks an internal reference in a reference string of the form `*\R<lib offset>*#<type index>`
+const INTERNAL_REF_LIB_OFFSET: u32 = 0xFFFF;
+
+const MAX_TYPE_DESC_DEPTH: usize = 64;
+
+pub(crate) fn is_sltg(data: &[u8]) -> bool {
+	data.starts_with(MAGIC)
+}
+
+/// Parses the SLTG typelib in the given buffer.
+pub(crate) fn read(data: &[u8]) -> Result<types::TypeLib, crate::Error> {
+	let reader = Reader { data };
+
+	if !is_sltg(data) {
+		return Err(malformed(0, "missing SLTG signature"));
+	}
+
+	let num_blocks = usize::from(reader.u16(0x04)?);
+	let first_block = usize::from(reader.u16(0x0A)?);
+	let num_type_infos = num_blocks.checked_sub(2).ok_or_else(|| malformed(0x04, "too few blocks"))?;
+	let num_block_entries = num_blocks - 1;
+
+	let magic_offset = HEADER_SIZE + num_block_entries * BLOCK_ENTRY_SIZE;
+	if reader.u8(magic_offset)? != 0x01 ||
+		reader.bytes(magic_offset + 1, COMPOBJ_MAGIC.len())? != COMPOBJ_MAGIC ||
+		reader.bytes(magic_offset + 9, DIR_MAGIC.len())? != DIR_MAGIC
+	{
+		return Err(malformed(magic_offset, "missing CompObj magic"));
+	}
+
+	// The blocks are laid out in the file in the order of the linked list, starting at the first block.
+	let mut blocks = Vec::with_capacity(num_block_entries);
+	let mut block_offset = magic_offset + MAGIC_SIZE + num_type_infos * INDEX_STRING_SIZE + PAD_SIZE;
+	let mut block_index = first_block;
+	loop {
+		if block_index == 0 || block_index > num_block_entries || blocks.len() == num_block_entries {
+			return Err(malformed(HEADER_SIZE, "invalid block list"));
+		}
+
+		let entry_offset = HEADER_SIZE + (block_index - 1) * BLOCK_ENTRY_SIZE;
+		let len = reader.u32(entry_offset)? as usize;
+		let index_string_offset = magic_offset + usize::from(reader.u16(entry_offset + 4)?);
+		blocks.push((block_offset, index_string_offset));
+
+		block_index = usize::from(reader.u16(entry_offset + 6)?);
+		if block_index == 0 {
+			break;
+		}
+
+		block_offset = block_offset.checked_add(len).ok_or_else(|| malformed(entry_offset, "invalid block length"))?;
+	}
+
+	let (lib_block_offset, _) = blocks.pop().unwrap();
+	if blocks.len() != num_type_infos {
+		return Err(malformed(HEADER_SIZE, "block list does not contain one block per typeinfo"));
+	}
+
+	let (lib_block, mut offset) = reader.lib_block(lib_block_offset)?;
+
+	// The library attributes are followed by 0x40 bytes whose purpose isn't known, then by an SLTG_OtherTypeInfo for each typeinfo.
+	offset += 0x40;
+	let mut other_type_infos = Vec::with_capacity(num_type_infos);
+	for _ in 0..num_type_infos {
+		let (other_type_info, next) = reader.other_type_info(offset)?;
+		other_type_infos.push(other_type_info);
+		offset = next;
+	}
+
+	// The name table is at an offset stored after the last SLTG_OtherTypeInfo, relative to the start of the library block.
+	let mut name_table_offset = lib_block_offset + reader.u32(offset + 2)? as usize;
+	if reader.u16(name_table_offset)? == 0x0200 {
+		name_table_offset += NAME_TABLE_EXTENDED_PREFIX_SIZE;
+	}
+	name_table_offset += NAME_TABLE_PREFIX_SIZE;
+
+	let reader = TypeInfoReader {
+		reader,
+		name_table_offset,
+		imports: std::cell::RefCell::default(),
+	};
+
+	let type_infos =
+		blocks.into_iter().zip(other_type_infos)
+		.map(|((block_offset, index_string_offset), other_type_info)| {
+			if reader.c_str(index_string_offset)? != other_type_info.index_name {
+				return Err(malformed(index_string_offset, "index string of typeinfo block does not match library block"));
+			}
+
+			reader.type_info(block_offset, &other_type_info)
+		})
+		.collect::<Result<_, _>>()?;
+
+	Ok(types::TypeLib {
+		name: reader.name(lib_block.name_offset)?,
+		doc_string: lib_block.doc_string,
+		help_file: lib_block.help_file,
+		help_context: lib_block.help_context,
+		guid: lib_block.guid,
+		lcid: lib_block.lcid,
+		version: lib_block.version,
+		flags: lib_block.flags,
+		syskind: lib_block.syskind,
+		imports: reader.imports.into_inner(),
+		type_infos,
+	})
+}
+
+/// The library attributes in `SLTG_LibBlk`
+#[derive(Debug)]
+struct LibBlock {
+	name_offset: u16,
+	doc_string: Option<String>,
+	help_file: Option<String>,
+	help_context: u32,
+	syskind: types::SysKind,
+	lcid: u32,
+	flags: u16,
+	version: types::Version,
+	guid: types::Guid,
+}
+
+/// The part of a typeinfo that is stored in the library block
+#[derive(Debug)]
+struct OtherTypeInfo {
+	index_name: String,
+	doc_string: Option<String>,
+	name_offset: u16,
+	help_context: u32,
+	guid: types::Guid,
+}
+
+struct Reader<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+	fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], crate::Error> {
+		offset.checked_add(len)
+			.and_then(|end| self.data.get(offset..end))
+			.ok_or_else(|| malformed(offset, "unexpected end of file"))
+	}
+
+	fn u8(&self, offset: usize) -> Result<u8, crate::Error> {
+		Ok(self.bytes(offset, 1)?[0])
+	}
+
+	fn u16(&self, offset: usize) -> Result<u16, crate::Error> {
+		let bytes = self.bytes(offset, 2)?;
+		Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn u32(&self, offset: usize) -> Result<u32, crate::Error> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn i32(&self, offset: usize) -> Result<i32, crate::Error> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn guid(&self, offset: usize) -> Result<types::Guid, crate::Error> {
+		let data4 = self.bytes(offset + 8, 8)?;
+		Ok(types::Guid {
+			data1: self.u32(offset)?,
+			data2: self.u16(offset + 4)?,
+			data3: self.u16(offset + 6)?,
+			data4: [data4[0], data4[1], data4[2], data4[3], data4[4], data4[5], data4[6], data4[7]],
+		})
+	}
+
+	/// A NUL-terminated string
+	fn c_str(&self, offset: usize) -> Result<String, crate::Error> {
+		let rest = self.data.get(offset..).ok_or_else(|| malformed(offset, "unexpected end of file"))?;
+		let len = rest.iter().position(|&b| b == 0).ok_or_else(|| malformed(offset, "unterminated string"))?;
+		Ok(decode_ansi(&rest[..len]))
+	}
+
+	/// A `SLTG_Name` - a string prefixed with its length, or `0xFFFF` for no string.
+	///
+	/// Returns the string and the offset of the data after it.
+	fn sltg_name(&self, offset: usize) -> Result<(Option<String>, usize), crate::Error> {
+		let len = self.u16(offset)?;
+		if len == 0xFFFF {
+			return Ok((None, offset + 2));
+		}
+
+		let len = usize::from(len);
+		Ok((Some(decode_ansi(self.bytes(offset + 2, len)?)), offset + 2 + len))
+	}
+
+	/// Parses the `SLTG_LibBlk` at the start of the library block.
+	///
+	/// Returns the library attributes and the offset of the data after the `SLTG_LibBlk`.
+	fn lib_block(&self, offset: usize) -> Result<(LibBlock, usize), crate::Error> {
+		if self.u16(offset)? != LIB_BLOCK_MAGIC {
+			return Err(malformed(offset, "missing library block magic"));
+		}
+
+		// magic, unknown, offset of the library name in the name table, unknown
+		let name_offset = self.u16(offset + 4)?;
+		let next = offset + 8;
+
+		let (doc_string, next) = self.sltg_name(next)?;
+		let (help_file, next) = self.sltg_name(next)?;
+		let help_context = self.u32(next)?;
+		let next = next + 4;
+
+		let syskind = match self.u16(next)? {
+			0 => types::SysKind::Win16,
+			1 => types::SysKind::Win32,
+			2 => types::SysKind::Mac,
+			3 => types::SysKind::Win64,
+			_ => return Err(malformed(next, "unknown SYSKIND")),
+		};
+
+		// SYSKIND, LCID, unknown, LIBFLAGs, major version, minor version, LIBID
+		let lcid = u32::from(self.u16(next + 2)?);
+		let flags = self.u16(next + 8)?;
+		let version = types::Version {
+			major: self.u16(next + 10)?,
+			minor: self.u16(next + 12)?,
+		};
+		let guid = self.guid(next + 14)?;
+		let next = next + 14 + 16;
+
+		Ok((LibBlock { name_offset, doc_string, help_file, help_context, syskind, lcid, flags, version, guid }, next))
+	}
+
+	/// Parses the `SLTG_OtherTypeInfo` at the given offset.
+	///
+	/// Returns the typeinfo data and the offset of the next `SLTG_OtherTypeInfo`.
+	fn other_type_info(&self, offset: usize) -> Result<(OtherTypeInfo, usize), crate::Error> {
+		let (index_name, next) = self.sltg_name(offset + 2)?;
+		let index_name = index_name.ok_or_else(|| malformed(offset + 2, "typeinfo has no index string"))?;
+		let (doc_string, next) = self.sltg_name(next)?;
+
+		// unknown, name offset, number of extra bytes, extra bytes
+		let name_offset = self.u16(next + 2)?;
+		let extra_len = usize::from(self.u16(next + 4)?);
+		let next = next + 6 + extra_len;
+
+		// unknown, help context, unknown, GUID, TYPEKIND
+		let help_context = self.u32(next + 2)?;
+		let guid = self.guid(next + 8)?;
+		let next = next + 8 + 16 + 2;
+
+		Ok((OtherTypeInfo { index_name, doc_string, name_offset, help_context, guid }, next))
+	}
+}
+
+struct TypeInfoReader<'a> {
+	reader: Reader<'a>,
+	name_table_offset: usize,
+
+	/// The typelibs referenced by the typeinfos read so far
+	imports: std::cell::RefCell<Vec<types::ImportedTypeLib>>,
+}
+
+/// The location of a typeinfo's members. Offsets within the members are relative to the start of the member data.
+#[derive(Clone, Copy)]
+struct Members<'a> {
+	offset: usize,
+	refs: &'a [types::TypeRef],
+}
+
+impl Members<'_> {
+	fn type_ref(self, index: u16, offset: usize) -> Result<types::TypeRef, crate::Error> {
+		self.refs.get(usize::from(index)).cloned().ok_or_else(|| malformed(offset, "invalid reference index"))
+	}
+}
+
+impl<'a> std::ops::Deref for TypeInfoReader<'a> {
+	type Target = Reader<'a>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.reader
+	}
+}
+
+impl TypeInfoReader<'_> {
+	fn name(&self, offset: u16) -> Result<String, crate::Error> {
+		self.c_str(self.name_table_offset + usize::from(offset))
+	}
+
+	fn type_info(&self, offset: usize, other_type_info: &OtherTypeInfo) -> Result<types::TypeInfo, crate::Error> {
+		// SLTG_TypeInfoHeader
+		if self.u16(offset)? != TYPE_INFO_HEADER_MAGIC {
+			return Err(malformed(offset, "missing typeinfo header magic"));
+		}
+
+		let ref_table_offset = self.u32(offset + 0x02)?;
+		let member_header_offset = offset + self.u32(offset + 0x0A)? as usize;
+		let typeflags1 = self.u8(offset + 0x1A)?;
+		let typeflags2 = self.u8(offset + 0x1B)?;
+		let flags = u16::from(typeflags1 >> 3) | (u16::from(typeflags2) << 5);
+		let version = types::Version {
+			major: self.u16(offset + 0x12)?,
+			minor: self.u16(offset + 0x14)?,
+		};
+
+		let typekind = match self.u8(offset + 0x1D)? {
+			0 => types::TypeKind::Enum,
+			1 => types::TypeKind::Record,
+			2 => types::TypeKind::Module,
+			3 => types::TypeKind::Interface,
+			4 => types::TypeKind::Dispatch,
+			5 => types::TypeKind::CoClass,
+			6 => types::TypeKind::Alias,
+			7 => types::TypeKind::Union,
+			_ => return Err(malformed(offset + 0x1D, "unknown TYPEKIND")),
+		};
+
+		let refs =
+			if ref_table_offset == 0xFFFF_FFFF {
+				vec![]
+			}
+			else {
+				self.refs(offset + ref_table_offset as usize)?
+			};
+
+		// SLTG_MemberHeader, followed by the member data, followed by SLTG_TypeInfoTail
+		let members = Members {
+			offset: member_header_offset + MEMBER_HEADER_SIZE,
+			refs: &refs,
+		};
+		let tail_offset = members.offset + self.u32(member_header_offset + 5)? as usize;
+
+		let num_functions = usize::from(self.u16(tail_offset)?);
+		let num_vars = usize::from(self.u16(tail_offset + 0x02)?);
+		let functions_offset = self.u16(tail_offset + 0x08)?;
+		let vars_offset = self.u16(tail_offset + 0x0A)?;
+		let alias_type = self.u16(tail_offset + 0x14)?;
+		let is_simple_alias = self.u16(tail_offset + 0x1C)? != 0;
+		let size_instance = u32::from(self.u16(tail_offset + 0x20)?);
+		let alignment = self.u16(tail_offset + 0x22)?;
+		let size_vft = self.u16(tail_offset + 0x28)?;
+
+		let functions =
+			if num_functions > 0 && functions_offset != 0xFFFF {
+				self.functions(members, members.offset + usize::from(functions_offset), num_functions)?
+			}
+			else {
+				vec![]
+			};
+
+		let vars =
+			if num_vars > 0 && vars_offset != 0xFFFF {
+				self.vars(members, members.offset + usize::from(vars_offset), num_vars)?
+			}
+			else {
+				vec![]
+			};
+
+		let mut impl_types =
+			if typekind == types::TypeKind::CoClass || self.u16(members.offset)? == IMPL_MAGIC {
+				self.impl_types(members)?
+			}
+			else {
+				vec![]
+			};
+
+		let alias_type = match typekind {
+			types::TypeKind::Alias if is_simple_alias => Some(types::TypeDesc::Base(alias_type)),
+			types::TypeKind::Alias => Some(self.type_desc(members, members.offset + usize::from(alias_type), 0)?.0),
+			_ => None,
+		};
+
+		// A dual interface is stored as an interface. Present it like the MSFT reader does.
+		let typekind =
+			if typekind == types::TypeKind::Interface && (flags & types::TYPEFLAG_FDUAL) == types::TYPEFLAG_FDUAL {
+				types::TypeKind::Dispatch
+			}
+			else {
+				typekind
+			};
+
+		// All dispinterfaces inherit from IDispatch, even if the typelib doesn't say so.
+		if typekind == types::TypeKind::Dispatch && impl_types.is_empty() {
+			impl_types.push(types::ImplType {
+				type_ref: types::TypeRef::External {
+					lib_guid: Some(types::LIBID_STDOLE),
+					type_: types::ImportedType::Guid(types::IID_IDISPATCH),
+				},
+				flags: 0,
+			});
+		}
+
+		Ok(types::TypeInfo {
+			name: self.name(other_type_info.name_offset)?,
+			doc_string: other_type_info.doc_string.clone(),
+			help_context: other_type_info.help_context,
+			guid: other_type_info.guid,
+			typekind,
+			flags,
+			version,
+			size_instance,
+			alignment,
+			size_vft,
+			functions,
+			vars,
+			impl_types,
+			alias_type,
+
+			// Where SLTG typelibs store the DLL of a module isn't known
+			dll_name: None,
+		})
+	}
+
+	/// Parses the `SLTG_RefInfo` at the given offset, which lists the types that the typeinfo references.
+	///
+	/// Each reference is a string of the form `*\R<lib offset>*#<type index>`, where the lib offset is either `ffff` for types in this typelib,
+	/// or the offset of a string in the name table that describes the imported typelib.
+	fn refs(&self, offset: usize) -> Result<Vec<types::TypeRef>, crate::Error> {
+		if self.u8(offset)? != REF_MAGIC {
+			return Err(malformed(offset, "missing reference table magic"));
+		}
+
+		// Eight bytes of unknown data per reference, followed by seven bytes of unknown data, followed by the reference strings.
+		let num_refs_bytes = self.u32(offset + 0x44)? as usize;
+		let mut next = offset + 0x48 + num_refs_bytes + 7;
+
+		(0..(num_refs_bytes / 8)).map(|_| {
+			let (ref_string, ref_string_end) = self.sltg_name(next)?;
+			let ref_string = ref_string.ok_or_else(|| malformed(next, "missing reference string"))?;
+			let ref_string_offset = next;
+			next = ref_string_end;
+
+			let (lib_offset, type_index) =
+				ref_string.strip_prefix("*\\R")
+				.and_then(|ref_string| ref_string.split_once("*#"))
+				.and_then(|(lib_offset, type_index)| Some((u32::from_str_radix(lib_offset, 16).ok()?, u32::from_str_radix(type_index, 16).ok()?)))
+				.ok_or_else(|| malformed(ref_string_offset, "invalid reference string"))?;
+
+			if lib_offset == INTERNAL_REF_LIB_OFFSET {
+				return Ok(types::TypeRef::Local(type_index as usize));
+			}
+
+			let lib_guid = self.imported_type_lib(self.name_table_offset + lib_offset as usize)?;
+
+			Ok(types::TypeRef::External {
+				lib_guid: Some(lib_guid),
+				type_: types::ImportedType::Index(type_index),
+			})
+		}).collect()
+	}
+
+	/// Parses the description of an imported typelib, which is a string of the form `*\G{<LIBID>}#<major>.<minor>#<lcid>#<path>#<name>`
+	/// with the numbers in hex, and records it in the typelib's imports.
+	///
+	/// Returns the LIBID of the imported typelib.
+	fn imported_type_lib(&self, offset: usize) -> Result<types::Guid, crate::Error> {
+		let lib_string = self.c_str(offset)?;
+
+		let imported_type_lib =
+			lib_string.strip_prefix("*\\G{")
+			.and_then(|lib_string| lib_string.split_once("}#"))
+			.and_then(|(guid, rest)| {
+				let guid = guid.parse().ok()?;
+				let mut parts = rest.splitn(4, '#');
+				let (major, minor) = parts.next()?.split_once('.')?;
+				let version = types::Version {
+					major: u16::from_str_radix(major, 16).ok()?,
+					minor: u16::from_str_radix(minor, 16).ok()?,
+				};
+				let lcid = u32::from_str_radix(parts.next()?, 16).ok()?;
+				let file_name = parts.next()?.to_string();
+				Some(types::ImportedTypeLib { guid, version, lcid, file_name })
+			})
+			.ok_or_else(|| malformed(offset, "invalid imported typelib string"))?;
+
+		let guid = imported_type_lib.guid;
+
+		let mut imports = self.imports.borrow_mut();
+		if !imports.iter().any(|import| import.guid == guid) {
+			imports.push(imported_type_lib);
+		}
+
+		Ok(guid)
+	}
+
+	/// Parses the chain of `SLTG_ImplInfo` at the start of the member data.
+	fn impl_types(&self, members: Members<'_>) -> Result<Vec<types::ImplType>, crate::Error> {
+		let mut result = vec![];
+
+		let mut offset = members.offset;
+		loop {
+			if self.u16(offset)? != IMPL_MAGIC {
+				return Err(malformed(offset, "missing implemented type magic"));
+			}
+
+			result.push(types::ImplType {
+				type_ref: members.type_ref(self.u16(offset + 0x0A)?, offset + 0x0A)?,
+				flags: u16::from(self.u8(offset + 0x06)?),
+			});
+
+			let next = self.u16(offset + 0x02)?;
+			if next == 0xFFFF {
+				break;
+			}
+
+			let next = members.offset + usize::from(next);
+			if next < offset + IMPL_INFO_SIZE {
+				return Err(malformed(offset, "implemented types are not in order"));
+			}
+			offset = next;
+		}
+
+		Ok(result)
+	}
+
+	/// Parses a type description, which is a sequence of `u16`s.
+	///
+	/// Returns the type, the `PARAMFLAG`s encoded in its first `u16`, and the offset of the data after it.
+	fn type_desc(&self, members: Members<'_>, offset: usize, depth: usize) -> Result<(types::TypeDesc, u16, usize), crate::Error> {
+		if depth > MAX_TYPE_DESC_DEPTH {
+			return Err(malformed(offset, "type descriptions are nested too deeply"));
+		}
+
+		let encoded = self.u16(offset)?;
+
+		let (type_, next) = match encoded & 0x3F {
+			types::VT_PTR => {
+				let (pointee, _, next) = self.type_desc(members, offset + 2, depth + 1)?;
+				(types::TypeDesc::Ptr(Box::new(pointee)), next)
+			},
+
+			types::VT_SAFEARRAY => {
+				// The next u16 is the offset of a SAFEARRAY that isn't needed
+				let (element, _, next) = self.type_desc(members, offset + 4, depth + 1)?;
+				(types::TypeDesc::SafeArray(Box::new(element)), next)
+			},
+
+			types::VT_CARRAY => {
+				// The next u16 is the offset of a SAFEARRAY whose bounds are the dimensions of the array
+				let safe_array_offset = members.offset + usize::from(self.u16(offset + 2)?);
+				let num_dimensions = self.u16(safe_array_offset)?;
+				let dimensions =
+					(0..usize::from(num_dimensions))
+					.map(|dimension| self.u32(safe_array_offset + SAFEARRAY_BOUNDS_OFFSET + dimension * 8))
+					.collect::<Result<_, _>>()?;
+				let (element, _, next) = self.type_desc(members, offset + 4, depth + 1)?;
+				(types::TypeDesc::CArray(Box::new(element), dimensions), next)
+			},
+
+			types::VT_USERDEFINED => {
+				let type_ref = members.type_ref(self.u16(offset + 2)? / 4, offset + 2)?;
+				(types::TypeDesc::UserDefined(type_ref), offset + 4)
+			},
+
+			vt => (types::TypeDesc::Base(vt), offset + 2),
+		};
+
+		// An extra level of indirection
+		let type_ =
+			if (encoded & 0x0E00) == 0x0E00 {
+				types::TypeDesc::Ptr(Box::new(type_))
+			}
+			else {
+				type_
+			};
+
+		let mut flags = match encoded & 0xC000 {
+			0xC000 => 0,
+			0x8000 => types::PARAMFLAG_FIN | types::PARAMFLAG_FOUT,
+			0x4000 => types::PARAMFLAG_FOUT,
+			_ => types::PARAMFLAG_FIN,
+		};
+		if (encoded & 0x2000) != 0 {
+			flags |= types::PARAMFLAG_FLCID;
+		}
+		if (encoded & 0x0080) != 0 {
+			flags |= types::PARAMFLAG_FRETVAL;
+		}
+
+		Ok((type_, flags, next))
+	}
+
+	/// Parses the chain of `SLTG_Function`.
+	fn functions(&self, members: Members<'_>, mut offset: usize, num_functions: usize) -> Result<Vec<types::Function>, crate::Error> {
+		let mut functions = Vec::with_capacity(num_functions);
+
+		while functions.len() < num_functions {
+			let magic = self.u8(offset)?;
+			let func_kind = match magic & !FUNCTION_FLAGS_PRESENT {
+				FUNCTION_MAGIC => types::FuncKind::PureVirtual,
+				DISPATCH_FUNCTION_MAGIC => types::FuncKind::Dispatch,
+				STATIC_FUNCTION_MAGIC => types::FuncKind::Static,
+				_ => return Err(malformed(offset, "unknown function magic")),
+			};
+
+			let invoke_kind = match self.u8(offset + 0x01)? >> 4 {
+				1 => types::InvokeKind::Func,
+				2 => types::InvokeKind::PropertyGet,
+				4 => types::InvokeKind::PropertyPut,
+				8 => types::InvokeKind::PropertyPutRef,
+				_ => return Err(malformed(offset + 0x01, "unknown INVOKEKIND")),
+			};
+
+			let next = self.u16(offset + 0x02)?;
+			let name = self.name(self.u16(offset + 0x04)?)?;
+			let member_id = self.i32(offset + 0x06)?;
+			let help_context = u32::from(self.u16(offset + 0x0A)?);
+			let params_offset = members.offset + usize::from(self.u16(offset + 0x0E)?);
+			let num_params_and_call_conv = self.u8(offset + 0x10)?;
+			let num_params = usize::from(num_params_and_call_conv >> 3);
+			let call_conv = match num_params_and_call_conv & 0x7 {
+				0 => types::CallConv::FastCall,
+				1 => types::CallConv::CDecl,
+				2 => types::CallConv::Pascal,
+				3 => types::CallConv::MacPascal,
+				4 => types::CallConv::StdCall,
+				5 => types::CallConv::FpFastCall,
+				6 => types::CallConv::SysCall,
+				_ => types::CallConv::MpwCDecl,
+			};
+			let return_type_and_num_optional_params = self.u8(offset + 0x11)?;
+			let num_optional_params = usize::from((return_type_and_num_optional_params & 0x7E) >> 1);
+			let ovft = self.u16(offset + 0x14)? & !1;
+			let flags =
+				if (magic & FUNCTION_FLAGS_PRESENT) == FUNCTION_FLAGS_PRESENT {
+					self.u16(offset + 0x16)?
+				}
+				else {
+					0
+				};
+
+			let return_type_offset =
+				if (return_type_and_num_optional_params & 0x80) == 0x80 {
+					offset + 0x12
+				}
+				else {
+					members.offset + usize::from(self.u16(offset + 0x12)?)
+				};
+			let (return_type, _, _) = self.type_desc(members, return_type_offset, 0)?;
+
+			let mut params = Vec::with_capacity(num_params);
+			let mut param_offset = params_offset;
+			for index in 0..num_params {
+				let (name, type_, mut flags, next) = self.param(members, param_offset)?;
+
+				let name = name.unwrap_or_else(||
+					if index == num_params - 1 && matches!(invoke_kind, types::InvokeKind::PropertyPut | types::InvokeKind::PropertyPutRef) {
+						// The parameter to "put" was omitted.
+						"value".to_string()
+					}
+					else {
+						format!("arg{}", index)
+					});
+
+				if num_params - index <= num_optional_params {
+					flags |= types::PARAMFLAG_FOPT;
+				}
+
+				params.push(types::Param { name, type_, flags, default_value: None });
+				param_offset = next;
+			}
+
+			functions.push(types::Function {
+				name,
+				doc_string: None,
+				help_context,
+				member_id,
+				func_kind,
+				invoke_kind,
+				call_conv,
+				flags,
+				ovft,
+				return_type,
+				params,
+				entry_point: None,
+			});
+
+			if next == 0xFFFF {
+				break;
+			}
+			offset = members.offset + usize::from(next);
+		}
+
+		if functions.len() != num_functions {
+			return Err(malformed(offset, "function list is shorter than the function count"));
+		}
+
+		Ok(functions)
+	}
+
+	/// Parses a parameter of a function.
+	///
+	/// Each parameter is the offset of its name, followed by either its type or the offset of its type.
+	/// The two cases are distinguished by having the name offset point to the second character of the name if the type follows inline.
+	///
+	/// Returns the parameter's name, type, flags and the offset of the next parameter.
+	fn param(&self, members: Members<'_>, offset: usize) -> Result<(Option<String>, types::TypeDesc, u16, usize), crate::Error> {
+		let name_offset = self.u16(offset)?;
+
+		let (name, type_offset, next) = match name_offset {
+			// No name, type follows
+			0xFFFF => (None, offset + 2, None),
+
+			// No name, offset of type follows
+			0xFFFE => (None, members.offset + usize::from(self.u16(offset + 2)?), Some(offset + 4)),
+
+			name_offset => {
+				let name_offset = self.name_table_offset + usize::from(name_offset);
+				let previous = self.u8(name_offset - 1)?;
+				if previous != 0 && !previous.is_ascii_alphanumeric() {
+					(Some(self.c_str(name_offset)?), members.offset + usize::from(self.u16(offset + 2)?), Some(offset + 4))
+				}
+				else {
+					(Some(self.c_str(name_offset - 1)?), offset + 2, None)
+				}
+			},
+		};
+
+		let (type_, flags, type_end) = self.type_desc(members, type_offset, 0)?;
+		Ok((name, type_, flags, next.unwrap_or(type_end)))
+	}
+
+	/// Parses the chain of `SLTG_Variable`.
+	fn vars(&self, members: Members<'_>, mut offset: usize, num_vars: usize) -> Result<Vec<types::Var>, crate::Error> {
+		const FLAG_TYPE_INLINE: u8 = 0x02;
+		const FLAG_VALUE_INLINE: u8 = 0x08;
+		const FLAG_CONST: u8 = 0x10;
+		const FLAG_DISPATCH: u8 = 0x40;
+
+		let mut vars: Vec<types::Var> = Vec::with_capacity(num_vars);
+
+		while vars.len() < num_vars {
+			let magic = self.u8(offset)?;
+			if magic != VAR_MAGIC && magic != VAR_WITH_FLAGS_MAGIC {
+				return Err(malformed(offset, "unknown variable magic"));
+			}
+
+			let flags = self.u8(offset + 0x01)?;
+			let next = self.u16(offset + 0x02)?;
+			let name_offset = self.u16(offset + 0x04)?;
+			let value_or_offset = self.u16(offset + 0x06)?;
+			let member_id = self.i32(offset + 0x0A)?;
+			let help_context = u32::from(self.u16(offset + 0x0E)?);
+			let var_flags =
+				if magic == VAR_WITH_FLAGS_MAGIC {
+					self.u16(offset + 0x12)?
+				}
+				else {
+					0
+				};
+
+			let name = match (name_offset, vars.last()) {
+				// Same name as the previous variable
+				(0xFFFE, Some(previous)) => previous.name.clone(),
+				_ => self.name(name_offset)?,
+			};
+
+			let type_offset =
+				if (flags & FLAG_TYPE_INLINE) == FLAG_TYPE_INLINE {
+					offset + 0x08
+				}
+				else {
+					members.offset + usize::from(self.u16(offset + 0x08)?)
+				};
+			let (type_, _, _) = self.type_desc(members, type_offset, 0)?;
+
+			let var_kind =
+				if (flags & FLAG_DISPATCH) == FLAG_DISPATCH {
+					types::VarKind::Dispatch
+				}
+				else if (flags & FLAG_CONST) == FLAG_CONST {
+					let vt = type_.vt();
+					let value =
+						if (flags & FLAG_VALUE_INLINE) == FLAG_VALUE_INLINE {
+							types::Variant::from_bits(vt, u64::from(value_or_offset))
+						}
+						else {
+							match vt {
+								types::VT_I1 | types::VT_UI1 | types::VT_I2 | types::VT_UI2 | types::VT_I4 | types::VT_UI4 |
+								types::VT_INT | types::VT_UINT | types::VT_R4 | types::VT_BOOL | types::VT_ERROR =>
+									types::Variant::from_bits(vt, u64::from(self.u32(members.offset + usize::from(value_or_offset))?)),
+								vt => types::Variant::Other(vt),
+							}
+						};
+					types::VarKind::Const(value)
+				}
+				else {
+					types::VarKind::PerInstance(u32::from(value_or_offset))
+				};
+
+			vars.push(types::Var {
+				name,
+				doc_string: None,
+				help_context,
+				member_id,
+				var_kind,
+				flags: var_flags,
+				type_,
+			});
+
+			if next == 0xFFFF {
+				break;
+			}
+			offset = members.offset + usize::from(next);
+		}
+
+		if vars.len() != num_vars {
+			return Err(malformed(offset, "variable list is shorter than the variable count"));
+		}
+
+		Ok(vars)
+	}
+}
+
+/// Strings in SLTG typelibs are in the ANSI codepage. Assume it's Windows-1252, which is mostly the same as Latin-1.
+fn decode_ansi(bytes: &[u8]) -> String {
+	bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+fn malformed(offset: usize, reason: &'static str) -> crate::Error {
+	crate::Error::Malformed { offset, reason }
+}
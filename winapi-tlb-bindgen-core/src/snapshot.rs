@@ -0,0 +1,49 @@
+//! JSON snapshots of a typelib.
+//!
+//! A snapshot is the [`crate::types::TypeLib`] model of a typelib serialized to JSON, wrapped in an object that records the version of the format.
+//! Bindings can be generated from a snapshot without access to the original typelib.
+
+use crate::types;
+
+/// The version of the snapshot format written by this crate. Snapshots with a different version are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+pub(crate) fn is_snapshot(data: &[u8]) -> bool {
+	data.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{')
+}
+
+/// Parses the JSON snapshot in the given buffer.
+pub(crate) fn read(data: &[u8]) -> Result<types::TypeLib, crate::Error> {
+	#[derive(serde::Deserialize)]
+	struct Header {
+		format_version: u32,
+	}
+
+	#[derive(serde::Deserialize)]
+	struct Snapshot {
+		type_lib: types::TypeLib,
+	}
+
+	// Check the version first, so that snapshots of a different version are reported as such rather than as invalid.
+	let Header { format_version } = serde_json::from_slice(data).map_err(crate::Error::Snapshot)?;
+	if format_version != FORMAT_VERSION {
+		return Err(crate::Error::UnsupportedSnapshotVersion(format_version));
+	}
+
+	let Snapshot { type_lib } = serde_json::from_slice(data).map_err(crate::Error::Snapshot)?;
+	Ok(type_lib)
+}
+
+/// Serializes the given typelib as a JSON snapshot to the given writer.
+pub(crate) fn write<W>(type_lib: &types::TypeLib, mut out: W) -> Result<(), crate::Error> where W: std::io::Write {
+	#[derive(serde::Serialize)]
+	struct Snapshot<'a> {
+		format_version: u32,
+		type_lib: &'a types::TypeLib,
+	}
+
+	serde_json::to_writer_pretty(&mut out, &Snapshot { format_version: FORMAT_VERSION, type_lib })
+		.map_err(|err| if err.is_io() { crate::Error::Io(err.into()) } else { crate::Error::Snapshot(err) })?;
+	writeln!(out)?;
+	Ok(())
+}
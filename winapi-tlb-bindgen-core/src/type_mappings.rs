@@ -0,0 +1,141 @@
+use crate::types;
+
+/// Maps types in imported typelibs to the Rust paths of their bindings, such as in `winapi` or in a crate that has the bindings of another typelib.
+///
+/// [`TypeMappings::default`] maps the types in `stdole2.tlb` that `winapi` has bindings for, [`TypeMappings::windows`] maps them to the `windows` crates
+/// and [`TypeMappings::com`] to the `com` crate. Mappings that are added later take precedence over earlier ones, so these can be overridden.
+///
+/// The paths are used wherever a type is used, such as by fields and parameters, and by the parents of interfaces in the `windows`, `#[repr(C)]`
+/// and `com` backends. The `#[repr(C)]` backend refers to the vtable of a parent by its path with `Vtbl` appended.
+/// The `winapi` backend still refers to parents by their names, because `RIDL!` requires them to be identifiers, so they must be imported
+/// into the module that the bindings are included in, such as with the `use` items that are emitted with `emit_imports`.
+#[derive(Clone, Debug)]
+pub struct TypeMappings {
+	mappings: Vec<TypeMapping>,
+}
+
+/// Identifies an imported typelib, for [`TypeMappings`]
+#[derive(Clone, Debug)]
+pub enum TypeLibSelector {
+	/// The LIBID of the typelib
+	Guid(types::Guid),
+
+	/// The name of the typelib, like `stdole` or `Office`. This is compared case-insensitively.
+	///
+	/// Only typelibs that are given to [`crate::build_from_type_lib`] have names, other than `stdole2.tlb`, which is named `stdole`.
+	Name(String),
+}
+
+#[derive(Clone, Debug)]
+struct TypeMapping {
+	type_lib: TypeLibSelector,
+
+	/// The name of the type, or `None` for a mapping of all the types in the typelib to a module
+	type_name: Option<String>,
+	path: String,
+}
+
+/// The `winapi` paths of the types in `stdole2.tlb` that `winapi` has bindings for
+const STDOLE_WINAPI_PATHS: &[(&str, &str)] = &[
+	("GUID", "winapi::shared::guiddef::GUID"),
+	("DISPPARAMS", "winapi::um::oaidl::DISPPARAMS"),
+	("EXCEPINFO", "winapi::um::oaidl::EXCEPINFO"),
+	("IUnknown", "winapi::um::unknwnbase::IUnknown"),
+	("IDispatch", "winapi::um::oaidl::IDispatch"),
+];
+
+/// The `windows` and `windows-core` paths of the types in `stdole2.tlb` that they have bindings for
+const STDOLE_WINDOWS_PATHS: &[(&str, &str)] = &[
+	("GUID", "windows_core::GUID"),
+	("DISPPARAMS", "windows::Win32::System::Com::DISPPARAMS"),
+	("EXCEPINFO", "windows::Win32::System::Com::EXCEPINFO"),
+	("IUnknown", "windows_core::IUnknown"),
+	("IDispatch", "windows::Win32::System::Com::IDispatch"),
+	("IEnumVARIANT", "windows::Win32::System::Ole::IEnumVARIANT"),
+	("IFont", "windows::Win32::System::Ole::IFont"),
+	("IFontDisp", "windows::Win32::System::Ole::IFontDisp"),
+	("IPicture", "windows::Win32::System::Ole::IPicture"),
+	("IPictureDisp", "windows::Win32::System::Ole::IPictureDisp"),
+];
+
+/// The `com` paths of the types in `stdole2.tlb` that it has bindings for
+const STDOLE_COM_PATHS: &[(&str, &str)] = &[
+	("GUID", "com::sys::GUID"),
+	("IUnknown", "com::interfaces::IUnknown"),
+];
+
+impl TypeMappings {
+	/// Mappings that don't map any types, so that every imported type is referred to by its name
+	pub fn empty() -> Self {
+		TypeMappings { mappings: vec![] }
+	}
+
+	/// Mappings of the types in `stdole2.tlb` to their `windows` and `windows-core` paths, for [`crate::Backend::Windows`]
+	pub fn windows() -> Self {
+		let mut result = TypeMappings::empty();
+		for &(type_name, path) in STDOLE_WINDOWS_PATHS {
+			result.add(TypeLibSelector::Guid(types::LIBID_STDOLE), type_name, path);
+		}
+		result
+	}
+
+	/// Mappings of the types in `stdole2.tlb` to their `com` paths, for [`crate::Backend::Com`]
+	pub fn com() -> Self {
+		let mut result = TypeMappings::empty();
+		for &(type_name, path) in STDOLE_COM_PATHS {
+			result.add(TypeLibSelector::Guid(types::LIBID_STDOLE), type_name, path);
+		}
+		result
+	}
+
+	/// The mappings of the types in `stdole2.tlb` that the given backend's bindings should use
+	///
+	/// This is [`TypeMappings::default`] for [`crate::Backend::Winapi`], [`TypeMappings::windows`] for [`crate::Backend::Windows`],
+	/// [`TypeMappings::empty`] for [`crate::Backend::ReprC`] and [`TypeMappings::com`] for [`crate::Backend::Com`].
+	pub fn for_backend(backend: crate::Backend) -> Self {
+		match backend {
+			crate::Backend::Winapi => TypeMappings::default(),
+			crate::Backend::Windows => TypeMappings::windows(),
+			crate::Backend::ReprC => TypeMappings::empty(),
+			crate::Backend::Com => TypeMappings::com(),
+		}
+	}
+
+	/// Maps the type with the given name in the given typelib to the given path, like `other_crate::IFoo`
+	pub fn add(&mut self, type_lib: TypeLibSelector, type_name: impl Into<String>, path: impl Into<String>) -> &mut Self {
+		self.mappings.push(TypeMapping { type_lib, type_name: Some(type_name.into()), path: path.into() });
+		self
+	}
+
+	/// Maps all the types in the given typelib to items with the same names in the given module, like `other_crate::foo`
+	///
+	/// This is for typelibs whose bindings were generated by this crate too.
+	pub fn add_type_lib(&mut self, type_lib: TypeLibSelector, module_path: impl Into<String>) -> &mut Self {
+		self.mappings.push(TypeMapping { type_lib, type_name: None, path: module_path.into() });
+		self
+	}
+
+	/// Gets the path of the type with the given name in the typelib with the given LIBID and name
+	pub(crate) fn get(&self, lib_guid: types::Guid, lib_name: &str, type_name: &str) -> Option<String> {
+		self.mappings.iter().rev()
+			.filter(|mapping| match &mapping.type_lib {
+				TypeLibSelector::Guid(guid) => *guid == lib_guid,
+				TypeLibSelector::Name(name) => name.eq_ignore_ascii_case(lib_name),
+			})
+			.find_map(|mapping| match &mapping.type_name {
+				Some(mapping_type_name) if mapping_type_name == type_name => Some(mapping.path.clone()),
+				Some(_) => None,
+				None => Some(format!("{}::{}", mapping.path, type_name)),
+			})
+	}
+}
+
+impl Default for TypeMappings {
+	fn default() -> Self {
+		let mut result = TypeMappings::empty();
+		for &(type_name, path) in STDOLE_WINAPI_PATHS {
+			result.add(TypeLibSelector::Guid(types::LIBID_STDOLE), type_name, path);
+		}
+		result
+	}
+}
@@ -0,0 +1,717 @@
+//! An owned model of the contents of a typelib.
+//!
+//! This is what the typelib readers produce and what the code generator consumes. It holds no references to the original typelib data,
+//! so it can also be constructed by hand.
+
+/// A `VARTYPE`
+pub type VarType = u16;
+
+pub const VT_EMPTY: VarType = 0;
+pub const VT_NULL: VarType = 1;
+pub const VT_I2: VarType = 2;
+pub const VT_I4: VarType = 3;
+pub const VT_R4: VarType = 4;
+pub const VT_R8: VarType = 5;
+pub const VT_CY: VarType = 6;
+pub const VT_DATE: VarType = 7;
+pub const VT_BSTR: VarType = 8;
+pub const VT_DISPATCH: VarType = 9;
+pub const VT_ERROR: VarType = 10;
+pub const VT_BOOL: VarType = 11;
+pub const VT_VARIANT: VarType = 12;
+pub const VT_UNKNOWN: VarType = 13;
+pub const VT_DECIMAL: VarType = 14;
+pub const VT_I1: VarType = 16;
+pub const VT_UI1: VarType = 17;
+pub const VT_UI2: VarType = 18;
+pub const VT_UI4: VarType = 19;
+pub const VT_I8: VarType = 20;
+pub const VT_UI8: VarType = 21;
+pub const VT_INT: VarType = 22;
+pub const VT_UINT: VarType = 23;
+pub const VT_VOID: VarType = 24;
+pub const VT_HRESULT: VarType = 25;
+pub const VT_PTR: VarType = 26;
+pub const VT_SAFEARRAY: VarType = 27;
+pub const VT_CARRAY: VarType = 28;
+pub const VT_USERDEFINED: VarType = 29;
+pub const VT_LPSTR: VarType = 30;
+pub const VT_LPWSTR: VarType = 31;
+pub const VT_RECORD: VarType = 36;
+pub const VT_INT_PTR: VarType = 37;
+pub const VT_UINT_PTR: VarType = 38;
+pub const VT_FILETIME: VarType = 64;
+pub const VT_BLOB: VarType = 65;
+pub const VT_STREAM: VarType = 66;
+pub const VT_STORAGE: VarType = 67;
+pub const VT_STREAMED_OBJECT: VarType = 68;
+pub const VT_STORED_OBJECT: VarType = 69;
+pub const VT_BLOB_OBJECT: VarType = 70;
+pub const VT_CF: VarType = 71;
+pub const VT_CLSID: VarType = 72;
+pub const VT_VERSIONED_STREAM: VarType = 73;
+pub const VT_BSTR_BLOB: VarType = 0xFFF;
+pub const VT_VECTOR: VarType = 0x1000;
+pub const VT_ARRAY: VarType = 0x2000;
+pub const VT_BYREF: VarType = 0x4000;
+pub const VT_RESERVED: VarType = 0x8000;
+pub const VT_ILLEGAL: VarType = 0xFFFF;
+pub const VT_TYPEMASK: VarType = 0xFFF;
+
+/// Gets the name of the given `VARTYPE`, like `VT_I4`, or `None` if it isn't a known `VARTYPE` without flags.
+///
+/// Use [`display_var_type`] to format a `VARTYPE` with flags, like `VT_BYREF | VT_VARIANT`.
+pub fn var_type_name(vt: VarType) -> Option<&'static str> {
+	Some(match vt {
+		VT_EMPTY => "VT_EMPTY",
+		VT_NULL => "VT_NULL",
+		VT_I2 => "VT_I2",
+		VT_I4 => "VT_I4",
+		VT_R4 => "VT_R4",
+		VT_R8 => "VT_R8",
+		VT_CY => "VT_CY",
+		VT_DATE => "VT_DATE",
+		VT_BSTR => "VT_BSTR",
+		VT_DISPATCH => "VT_DISPATCH",
+		VT_ERROR => "VT_ERROR",
+		VT_BOOL => "VT_BOOL",
+		VT_VARIANT => "VT_VARIANT",
+		VT_UNKNOWN => "VT_UNKNOWN",
+		VT_DECIMAL => "VT_DECIMAL",
+		VT_I1 => "VT_I1",
+		VT_UI1 => "VT_UI1",
+		VT_UI2 => "VT_UI2",
+		VT_UI4 => "VT_UI4",
+		VT_I8 => "VT_I8",
+		VT_UI8 => "VT_UI8",
+		VT_INT => "VT_INT",
+		VT_UINT => "VT_UINT",
+		VT_VOID => "VT_VOID",
+		VT_HRESULT => "VT_HRESULT",
+		VT_PTR => "VT_PTR",
+		VT_SAFEARRAY => "VT_SAFEARRAY",
+		VT_CARRAY => "VT_CARRAY",
+		VT_USERDEFINED => "VT_USERDEFINED",
+		VT_LPSTR => "VT_LPSTR",
+		VT_LPWSTR => "VT_LPWSTR",
+		VT_RECORD => "VT_RECORD",
+		VT_INT_PTR => "VT_INT_PTR",
+		VT_UINT_PTR => "VT_UINT_PTR",
+		VT_FILETIME => "VT_FILETIME",
+		VT_BLOB => "VT_BLOB",
+		VT_STREAM => "VT_STREAM",
+		VT_STORAGE => "VT_STORAGE",
+		VT_STREAMED_OBJECT => "VT_STREAMED_OBJECT",
+		VT_STORED_OBJECT => "VT_STORED_OBJECT",
+		VT_BLOB_OBJECT => "VT_BLOB_OBJECT",
+		VT_CF => "VT_CF",
+		VT_CLSID => "VT_CLSID",
+		VT_VERSIONED_STREAM => "VT_VERSIONED_STREAM",
+		VT_BSTR_BLOB => "VT_BSTR_BLOB",
+		VT_ILLEGAL => "VT_ILLEGAL",
+		_ => return None,
+	})
+}
+
+/// Formats the given `VARTYPE` with its flags, like `VT_BYREF | VT_VARIANT`. Unknown `VARTYPE`s are formatted in hex.
+pub fn display_var_type(vt: VarType) -> impl std::fmt::Display {
+	struct DisplayVarType(VarType);
+
+	impl std::fmt::Display for DisplayVarType {
+		fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+			if let Some(name) = var_type_name(self.0) {
+				return f.write_str(name);
+			}
+
+			for &(flag, flag_name) in &[(VT_RESERVED, "VT_RESERVED"), (VT_BYREF, "VT_BYREF"), (VT_ARRAY, "VT_ARRAY"), (VT_VECTOR, "VT_VECTOR")] {
+				if (self.0 & flag) == flag {
+					write!(f, "{} | ", flag_name)?;
+				}
+			}
+
+			match var_type_name(self.0 & VT_TYPEMASK) {
+				Some(name) => f.write_str(name),
+				None => write!(f, "0x{:x}", self.0 & VT_TYPEMASK),
+			}
+		}
+	}
+
+	DisplayVarType(vt)
+}
+
+pub const LIBFLAG_FRESTRICTED: u16 = 0x1;
+pub const LIBFLAG_FCONTROL: u16 = 0x2;
+pub const LIBFLAG_FHIDDEN: u16 = 0x4;
+pub const LIBFLAG_FHASDISKIMAGE: u16 = 0x8;
+
+pub const TYPEFLAG_FAPPOBJECT: u16 = 0x1;
+pub const TYPEFLAG_FCANCREATE: u16 = 0x2;
+pub const TYPEFLAG_FLICENSED: u16 = 0x4;
+pub const TYPEFLAG_FPREDECLID: u16 = 0x8;
+pub const TYPEFLAG_FHIDDEN: u16 = 0x10;
+pub const TYPEFLAG_FCONTROL: u16 = 0x20;
+pub const TYPEFLAG_FDUAL: u16 = 0x40;
+pub const TYPEFLAG_FNONEXTENSIBLE: u16 = 0x80;
+pub const TYPEFLAG_FOLEAUTOMATION: u16 = 0x100;
+pub const TYPEFLAG_FRESTRICTED: u16 = 0x200;
+pub const TYPEFLAG_FAGGREGATABLE: u16 = 0x400;
+pub const TYPEFLAG_FREPLACEABLE: u16 = 0x800;
+pub const TYPEFLAG_FDISPATCHABLE: u16 = 0x1000;
+pub const TYPEFLAG_FREVERSEBIND: u16 = 0x2000;
+pub const TYPEFLAG_FPROXY: u16 = 0x4000;
+
+pub const FUNCFLAG_FRESTRICTED: u16 = 0x1;
+pub const FUNCFLAG_FSOURCE: u16 = 0x2;
+pub const FUNCFLAG_FBINDABLE: u16 = 0x4;
+pub const FUNCFLAG_FREQUESTEDIT: u16 = 0x8;
+pub const FUNCFLAG_FDISPLAYBIND: u16 = 0x10;
+pub const FUNCFLAG_FDEFAULTBIND: u16 = 0x20;
+pub const FUNCFLAG_FHIDDEN: u16 = 0x40;
+pub const FUNCFLAG_FUSESGETLASTERROR: u16 = 0x80;
+pub const FUNCFLAG_FDEFAULTCOLLELEM: u16 = 0x100;
+pub const FUNCFLAG_FUIDEFAULT: u16 = 0x200;
+pub const FUNCFLAG_FNONBROWSABLE: u16 = 0x400;
+pub const FUNCFLAG_FREPLACEABLE: u16 = 0x800;
+pub const FUNCFLAG_FIMMEDIATEBIND: u16 = 0x1000;
+
+pub const VARFLAG_FREADONLY: u16 = 0x1;
+pub const VARFLAG_FSOURCE: u16 = 0x2;
+pub const VARFLAG_FBINDABLE: u16 = 0x4;
+pub const VARFLAG_FREQUESTEDIT: u16 = 0x8;
+pub const VARFLAG_FDISPLAYBIND: u16 = 0x10;
+pub const VARFLAG_FDEFAULTBIND: u16 = 0x20;
+pub const VARFLAG_FHIDDEN: u16 = 0x40;
+pub const VARFLAG_FRESTRICTED: u16 = 0x80;
+pub const VARFLAG_FDEFAULTCOLLELEM: u16 = 0x100;
+pub const VARFLAG_FUIDEFAULT: u16 = 0x200;
+pub const VARFLAG_FNONBROWSABLE: u16 = 0x400;
+pub const VARFLAG_FREPLACEABLE: u16 = 0x800;
+pub const VARFLAG_FIMMEDIATEBIND: u16 = 0x1000;
+
+pub const PARAMFLAG_FIN: u16 = 0x1;
+pub const PARAMFLAG_FOUT: u16 = 0x2;
+pub const PARAMFLAG_FLCID: u16 = 0x4;
+pub const PARAMFLAG_FRETVAL: u16 = 0x8;
+pub const PARAMFLAG_FOPT: u16 = 0x10;
+pub const PARAMFLAG_FHASDEFAULT: u16 = 0x20;
+pub const PARAMFLAG_FHASCUSTDATA: u16 = 0x40;
+
+pub const IMPLTYPEFLAG_FDEFAULT: u16 = 0x1;
+pub const IMPLTYPEFLAG_FSOURCE: u16 = 0x2;
+pub const IMPLTYPEFLAG_FRESTRICTED: u16 = 0x4;
+pub const IMPLTYPEFLAG_FDEFAULTVTABLE: u16 = 0x8;
+
+/// A GUID
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Guid {
+	pub data1: u32,
+	pub data2: u16,
+	pub data3: u16,
+	pub data4: [u8; 8],
+}
+
+impl std::fmt::Display for Guid {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+			self.data1, self.data2, self.data3,
+			self.data4[0], self.data4[1], self.data4[2], self.data4[3],
+			self.data4[4], self.data4[5], self.data4[6], self.data4[7])
+	}
+}
+
+/// Parses a GUID of the form `00000000-0000-0000-0000-000000000000`
+impl std::str::FromStr for Guid {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		fn parse(s: &str) -> Option<Guid> {
+			if !s.is_ascii() {
+				return None;
+			}
+
+			let mut parts = s.split('-');
+			let data1 = u32::from_str_radix(parts.next()?, 16).ok()?;
+			let data2 = u16::from_str_radix(parts.next()?, 16).ok()?;
+			let data3 = u16::from_str_radix(parts.next()?, 16).ok()?;
+			let data4_1 = parts.next()?;
+			let data4_2 = parts.next()?;
+			if parts.next().is_some() || data4_1.len() != 4 || data4_2.len() != 12 {
+				return None;
+			}
+
+			let mut data4 = [0_u8; 8];
+			for (i, b) in data4.iter_mut().enumerate() {
+				let digits = if i < 2 { &data4_1[(i * 2)..(i * 2 + 2)] } else { &data4_2[((i - 2) * 2)..((i - 2) * 2 + 2)] };
+				*b = u8::from_str_radix(digits, 16).ok()?;
+			}
+
+			Some(Guid { data1, data2, data3, data4 })
+		}
+
+		parse(s).ok_or(())
+	}
+}
+
+/// GUIDs are serialized as strings of the form `00000000-0000-0000-0000-000000000000`
+impl serde::Serialize for Guid {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for Guid {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+		let s: std::borrow::Cow<'de, str> = serde::Deserialize::deserialize(deserializer)?;
+		s.parse().map_err(|()| serde::de::Error::invalid_value(serde::de::Unexpected::Str(&s), &"a GUID"))
+	}
+}
+
+/// A major and minor version number
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize)]
+pub struct Version {
+	pub major: u16,
+	pub minor: u16,
+}
+
+/// The platform that a typelib was created for
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum SysKind {
+	Win16,
+	Win32,
+	Mac,
+	Win64,
+}
+
+impl SysKind {
+	/// The size of a pointer on this platform. Vtable offsets and sizes in the typelib are in multiples of this.
+	pub fn pointer_size(self) -> u16 {
+		match self {
+			SysKind::Win16 | SysKind::Win32 | SysKind::Mac => 4,
+			SysKind::Win64 => 8,
+		}
+	}
+}
+
+/// A typelib
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct TypeLib {
+	pub name: String,
+	pub doc_string: Option<String>,
+	pub help_file: Option<String>,
+	pub help_context: u32,
+
+	/// The LIBID
+	pub guid: Guid,
+	pub lcid: u32,
+	pub version: Version,
+
+	/// `LIBFLAG`s
+	pub flags: u16,
+	pub syskind: SysKind,
+
+	/// The typelibs that this typelib references types from
+	pub imports: Vec<ImportedTypeLib>,
+	pub type_infos: Vec<TypeInfo>,
+}
+
+impl TypeLib {
+	/// Gets the typeinfo that the given type reference refers to, if it is in this typelib.
+	pub fn get_type_info(&self, type_ref: &TypeRef) -> Option<&TypeInfo> {
+		match type_ref {
+			TypeRef::Local(index) => self.type_infos.get(*index),
+			TypeRef::External { .. } => None,
+		}
+	}
+
+	/// Gets the name of the type that the given type reference refers to, if it is known.
+	pub fn get_type_name(&self, type_ref: &TypeRef) -> Option<&str> {
+		match type_ref {
+			TypeRef::Local(index) => self.type_infos.get(*index).map(|type_info| &*type_info.name),
+			TypeRef::External { lib_guid, type_ } => well_known_type(*lib_guid, *type_).map(|(name, _)| name),
+		}
+	}
+
+	/// Gets the typeinfo that the given reference to a type in an imported typelib refers to, by looking for it in the given typelibs.
+	///
+	/// A typelib is only used if it [satisfies](ImportedTypeLib::is_satisfied_by) the import.
+	/// Returns the typelib that the type was found in, along with the typeinfo.
+	pub fn get_imported_type_info<'a>(&self, type_ref: &TypeRef, dependencies: &'a [TypeLib]) -> Option<(&'a TypeLib, &'a TypeInfo)> {
+		let (lib_guid, type_) = match type_ref {
+			TypeRef::Local(_) => return None,
+			TypeRef::External { lib_guid, type_ } => (*lib_guid, *type_),
+		};
+
+		dependencies.iter()
+			.filter(|dependency| lib_guid.map_or(true, |lib_guid| dependency.guid == lib_guid))
+			.filter(|dependency| self.imports.iter().filter(|import| import.guid == dependency.guid).all(|import| import.is_satisfied_by(dependency)))
+			.find_map(|dependency| {
+				let type_info = match type_ {
+					ImportedType::Guid(guid) => dependency.type_infos.iter().find(|type_info| type_info.guid == guid),
+
+					// An index is only meaningful in the typelib that the reference is to
+					ImportedType::Index(index) if lib_guid.is_some() => dependency.type_infos.get(index as usize),
+					ImportedType::Index(_) => None,
+				};
+				type_info.map(|type_info| (dependency, type_info))
+			})
+	}
+
+	/// Gets the size of the vtable of the interface that the given type reference refers to, if it is known.
+	pub fn get_vtbl_size(&self, type_ref: &TypeRef) -> Option<u16> {
+		match type_ref {
+			TypeRef::Local(index) => self.type_infos.get(*index).map(|type_info| type_info.size_vft),
+			TypeRef::External { lib_guid, type_ } =>
+				well_known_type(*lib_guid, *type_)
+				.and_then(|(_, num_vtbl_entries)| num_vtbl_entries)
+				.map(|num_vtbl_entries| num_vtbl_entries * self.syskind.pointer_size()),
+		}
+	}
+}
+
+/// A typelib that another typelib references types from
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ImportedTypeLib {
+	/// The LIBID
+	pub guid: Guid,
+	pub version: Version,
+	pub lcid: u32,
+
+	/// The file name of the typelib when the importing typelib was created, such as `stdole2.tlb`
+	pub file_name: String,
+}
+
+impl ImportedTypeLib {
+	/// Whether the given typelib can be used as this imported typelib, because it has the same LIBID,
+	/// the same major version, and at least the same minor version.
+	pub fn is_satisfied_by(&self, type_lib: &TypeLib) -> bool {
+		type_lib.guid == self.guid && type_lib.version.major == self.version.major && type_lib.version.minor >= self.version.minor
+	}
+}
+
+/// A `TYPEKIND`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum TypeKind {
+	Enum,
+	Record,
+	Module,
+	Interface,
+	Dispatch,
+	CoClass,
+	Alias,
+	Union,
+}
+
+/// A type in a typelib
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct TypeInfo {
+	pub name: String,
+	pub doc_string: Option<String>,
+	pub help_context: u32,
+	pub guid: Guid,
+	pub typekind: TypeKind,
+
+	/// `TYPEFLAG`s
+	pub flags: u16,
+	pub version: Version,
+	pub size_instance: u32,
+	pub alignment: u16,
+
+	/// The size of the vtable in bytes, including the vtables of the parents
+	pub size_vft: u16,
+	pub functions: Vec<Function>,
+	pub vars: Vec<Var>,
+
+	/// The parents of an interface, or the interfaces that a coclass implements
+	pub impl_types: Vec<ImplType>,
+
+	/// The type that an alias is an alias of
+	pub alias_type: Option<TypeDesc>,
+
+	/// The DLL that a module's functions are exported from
+	pub dll_name: Option<String>,
+}
+
+impl TypeInfo {
+	/// A dual interface is stored as a dispinterface with the `TYPEFLAG_FDUAL` flag,
+	/// whose functions and parents are those of its interface half.
+	pub fn is_dual(&self) -> bool {
+		self.typekind == TypeKind::Dispatch && (self.flags & TYPEFLAG_FDUAL) == TYPEFLAG_FDUAL
+	}
+}
+
+/// A parent of an interface, or an interface implemented by a coclass
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ImplType {
+	pub type_ref: TypeRef,
+
+	/// `IMPLTYPEFLAG`s
+	pub flags: u16,
+}
+
+/// A reference to a type
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum TypeRef {
+	/// Index into [`TypeLib::type_infos`]
+	Local(usize),
+
+	/// A type in an imported typelib
+	External {
+		/// The LIBID of the imported typelib, if known
+		lib_guid: Option<Guid>,
+
+		#[serde(rename = "type")]
+		type_: ImportedType,
+	},
+}
+
+/// Identifies a type within an imported typelib
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum ImportedType {
+	/// The GUID of the type
+	Guid(Guid),
+
+	/// The index of the type in the imported typelib, for types that do not have a GUID or typelibs that refer to types by index
+	Index(u32),
+}
+
+/// A `TYPEDESC`
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum TypeDesc {
+	/// Any `VARTYPE` that does not need additional information
+	Base(VarType),
+	Ptr(Box<TypeDesc>),
+	SafeArray(Box<TypeDesc>),
+
+	/// A C array of the given element type with the given dimensions, outermost first
+	CArray(Box<TypeDesc>, Vec<u32>),
+	UserDefined(TypeRef),
+}
+
+impl TypeDesc {
+	/// The `VARTYPE` of this type
+	pub fn vt(&self) -> VarType {
+		match self {
+			TypeDesc::Base(vt) => *vt,
+			TypeDesc::Ptr(_) => VT_PTR,
+			TypeDesc::SafeArray(_) => VT_SAFEARRAY,
+			TypeDesc::CArray(_, _) => VT_CARRAY,
+			TypeDesc::UserDefined(_) => VT_USERDEFINED,
+		}
+	}
+}
+
+/// A `FUNCKIND`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum FuncKind {
+	Virtual,
+	PureVirtual,
+	NonVirtual,
+	Static,
+	Dispatch,
+}
+
+/// An `INVOKEKIND`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum InvokeKind {
+	Func,
+	PropertyGet,
+	PropertyPut,
+	PropertyPutRef,
+}
+
+/// A `CALLCONV`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum CallConv {
+	FastCall,
+	CDecl,
+	Pascal,
+	MacPascal,
+	StdCall,
+	FpFastCall,
+	SysCall,
+	MpwCDecl,
+	MpwPascal,
+}
+
+/// A function of an interface, dispinterface or module
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Function {
+	pub name: String,
+	pub doc_string: Option<String>,
+	pub help_context: u32,
+	pub member_id: i32,
+	pub func_kind: FuncKind,
+	pub invoke_kind: InvokeKind,
+	pub call_conv: CallConv,
+
+	/// `FUNCFLAG`s
+	pub flags: u16,
+
+	/// The offset of the function in the vtable in bytes
+	pub ovft: u16,
+	pub return_type: TypeDesc,
+	pub params: Vec<Param>,
+
+	/// The DLL entry point of a module function
+	pub entry_point: Option<EntryPoint>,
+}
+
+/// A parameter of a function
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Param {
+	pub name: String,
+	#[serde(rename = "type")]
+	pub type_: TypeDesc,
+
+	/// `PARAMFLAG`s
+	pub flags: u16,
+
+	/// The default value of a parameter with `PARAMFLAG_FHASDEFAULT`
+	pub default_value: Option<Variant>,
+}
+
+/// How a module function is exported from its DLL
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum EntryPoint {
+	Name(String),
+	Ordinal(u16),
+}
+
+/// A field of a record or union, a member of an enum, a constant of a module or a property of a dispinterface
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Var {
+	pub name: String,
+	pub doc_string: Option<String>,
+	pub help_context: u32,
+	pub member_id: i32,
+	pub var_kind: VarKind,
+
+	/// `VARFLAG`s
+	pub flags: u16,
+	#[serde(rename = "type")]
+	pub type_: TypeDesc,
+}
+
+/// A `VARKIND`, along with the data specific to each kind
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum VarKind {
+	/// A field of a record or union, at the given offset
+	PerInstance(u32),
+	Static,
+
+	/// A constant with the given value, such as an enum member
+	Const(Variant),
+
+	/// A property of a dispinterface
+	Dispatch,
+}
+
+/// The value of a constant or a parameter's default value
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum Variant {
+	Empty,
+	Null,
+	I1(i8),
+	I2(i16),
+	I4(i32),
+	I8(i64),
+	UI1(u8),
+	UI2(u16),
+	UI4(u32),
+	UI8(u64),
+	Int(i32),
+	UInt(u32),
+	R4(f32),
+	R8(f64),
+	Cy(i64),
+	Date(f64),
+	Bool(bool),
+	Error(i32),
+	Bstr(String),
+
+	/// A value of any other type
+	Other(VarType),
+}
+
+impl Variant {
+	/// Interprets the low bytes of the given little-endian value as a value of the given type.
+	pub(crate) fn from_bits(vt: VarType, bits: u64) -> Self {
+		match vt {
+			VT_EMPTY => Variant::Empty,
+			VT_NULL => Variant::Null,
+			VT_I1 => Variant::I1(bits as i8),
+			VT_I2 => Variant::I2(bits as i16),
+			VT_I4 => Variant::I4(bits as i32),
+			VT_I8 => Variant::I8(bits as i64),
+			VT_UI1 => Variant::UI1(bits as u8),
+			VT_UI2 => Variant::UI2(bits as u16),
+			VT_UI4 => Variant::UI4(bits as u32),
+			VT_UI8 => Variant::UI8(bits),
+			VT_INT => Variant::Int(bits as i32),
+			VT_UINT => Variant::UInt(bits as u32),
+			VT_R4 => Variant::R4(f32::from_bits(bits as u32)),
+			VT_R8 => Variant::R8(f64::from_bits(bits)),
+			VT_CY => Variant::Cy(bits as i64),
+			VT_DATE => Variant::Date(f64::from_bits(bits)),
+			VT_BOOL => Variant::Bool(bits as i16 != 0),
+			VT_ERROR => Variant::Error(bits as i32),
+			vt => Variant::Other(vt),
+		}
+	}
+
+	/// The `VARTYPE` of this value
+	pub fn vt(&self) -> VarType {
+		match self {
+			Variant::Empty => VT_EMPTY,
+			Variant::Null => VT_NULL,
+			Variant::I1(_) => VT_I1,
+			Variant::I2(_) => VT_I2,
+			Variant::I4(_) => VT_I4,
+			Variant::I8(_) => VT_I8,
+			Variant::UI1(_) => VT_UI1,
+			Variant::UI2(_) => VT_UI2,
+			Variant::UI4(_) => VT_UI4,
+			Variant::UI8(_) => VT_UI8,
+			Variant::Int(_) => VT_INT,
+			Variant::UInt(_) => VT_UINT,
+			Variant::R4(_) => VT_R4,
+			Variant::R8(_) => VT_R8,
+			Variant::Cy(_) => VT_CY,
+			Variant::Date(_) => VT_DATE,
+			Variant::Bool(_) => VT_BOOL,
+			Variant::Error(_) => VT_ERROR,
+			Variant::Bstr(_) => VT_BSTR,
+			Variant::Other(vt) => *vt,
+		}
+	}
+}
+
+const fn ole_guid(data1: u32) -> Guid {
+	Guid { data1, data2: 0x0000, data3: 0x0000, data4: [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46] }
+}
+
+/// The LIBID of `stdole2.tlb`
+pub const LIBID_STDOLE: Guid = ole_guid(0x0002_0430);
+
+pub const IID_IUNKNOWN: Guid = ole_guid(0x0000_0000);
+pub const IID_IDISPATCH: Guid = ole_guid(0x0002_0400);
+pub const IID_IENUMVARIANT: Guid = ole_guid(0x0002_0404);
+
+/// Types from `stdole2.tlb` that almost every typelib references. These are resolved without having to load `stdole2.tlb` itself.
+///
+/// Returns the name of the type and, for interfaces, the number of entries in its vtable.
+fn well_known_type(lib_guid: Option<Guid>, type_: ImportedType) -> Option<(&'static str, Option<u16>)> {
+	// Name, IID, index in stdole2.tlb, number of vtable entries
+	const WELL_KNOWN_TYPES: &[(&str, Option<Guid>, u32, Option<u16>)] = &[
+		("GUID", None, 0, None),
+		("IUnknown", Some(IID_IUNKNOWN), 3, Some(3)),
+		("IDispatch", Some(IID_IDISPATCH), 4, Some(7)),
+		("IEnumVARIANT", Some(IID_IENUMVARIANT), 5, Some(7)),
+	];
+
+	let (name, _, _, num_vtbl_entries) = WELL_KNOWN_TYPES.iter().find(|(_, guid, index, _)| match type_ {
+		ImportedType::Guid(type_guid) => *guid == Some(type_guid),
+		ImportedType::Index(type_index) => lib_guid == Some(LIBID_STDOLE) && type_index == *index,
+	})?;
+	Some((name, *num_vtbl_entries))
+}
@@ -0,0 +1,75 @@
+use crate::{types, Location};
+
+/// Something about the emitted bindings that might need attention, like a referenced type that couldn't be found
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Warning {
+	#[serde(flatten)]
+	pub kind: WarningKind,
+
+	/// The type, member or parameter that the warning is about
+	pub location: Location,
+}
+
+/// What a [`Warning`] is about
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WarningKind {
+	/// A referenced type could not be found, and was emitted as `__missing_type__`. For a type in an imported typelib, the reference has the LIBID of that typelib.
+	MissingType {
+		type_ref: types::TypeRef,
+	},
+
+	/// A dispinterface was skipped because emitting dispinterfaces wasn't enabled
+	SkippedDispinterface,
+
+	/// Only the interface half of a dual interface was emitted
+	SkippedDispinterfaceOfDualInterface,
+
+	/// An identifier is a Rust keyword, so it was emitted with a different name
+	RenamedIdentifier {
+		original: String,
+		renamed: String,
+	},
+
+	/// A restricted member that a dispinterface inherits from `IUnknown` or `IDispatch` was left out
+	DroppedRestrictedMember,
+
+	/// A type was emitted as a Rust type that doesn't capture all of it, like a `SAFEARRAY` whose element type is only in a comment.
+	/// Interface pointers that a backend always emits as `*mut c_void`, like in the vtables of the `windows` backend, aren't reported.
+	LossyTypeMapping {
+		type_: types::TypeDesc,
+		emitted_as: String,
+	},
+}
+
+impl std::fmt::Display for Warning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.kind {
+			WarningKind::MissingType { type_ref } =>
+				write!(f, "{}", crate::MissingType { referrer: self.location.to_string(), type_ref: type_ref.clone() }),
+			WarningKind::SkippedDispinterface => write!(f, "dispinterface {} was skipped because emitting dispinterfaces wasn't enabled", self.location),
+			WarningKind::SkippedDispinterfaceOfDualInterface => write!(f, "dispinterface half of dual interface {} was skipped", self.location),
+			WarningKind::RenamedIdentifier { original, renamed } => write!(f, "{}: {} was renamed to {} because it's a Rust keyword", self.location, original, renamed),
+			WarningKind::DroppedRestrictedMember => write!(f, "restricted member {} was left out", self.location),
+			WarningKind::LossyTypeMapping { type_, emitted_as } => write!(f, "{}: {:?} was emitted as {}", self.location, type_, emitted_as),
+		}
+	}
+}
+
+/// A summary of a [`crate::BuildResult`] that can be serialized, such as to JSON for CI to compare against the previous build
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Summary {
+	pub warnings: Vec<Warning>,
+
+	/// The types that were left out because bindings could not be emitted for them, with why
+	pub failed_types: Vec<FailedTypeSummary>,
+}
+
+/// A [`crate::FailedType`] in a [`Summary`]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FailedTypeSummary {
+	pub type_name: String,
+
+	/// The error that the type failed with, as displayed
+	pub error: String,
+}
@@ -0,0 +1,826 @@
+//! Bindings for the `winapi` crate, using its `RIDL!`, `STRUCT!` and `ENUM!` macros.
+
+use crate::{is_inherited_dispatch_member, mapped_type_path, resolve_type_info, sanitize_reserved, type_ref_to_string, types, var_type_kind, vtbl_size, BuildResult, Context, Location, VarTypeKind, WarningKind, WellKnownType};
+
+/// Writes the bindings for the given type of the typelib.
+///
+/// The `winapi` items that the bindings refer to by name are added to `referenced_names`, for [`write_imports`].
+pub(crate) fn write_item<W>(
+	context: &Context<'_>,
+	type_info: &types::TypeInfo,
+	emit_dispinterfaces: bool,
+	referenced_names: &mut std::collections::BTreeSet<&'static str>,
+	build_result: &mut BuildResult,
+	mut out: W,
+) -> Result<(), crate::Error> where W: std::io::Write {
+	let type_lib = context.type_lib;
+	let pointer_size = type_lib.syskind.pointer_size();
+
+	let typekind =
+		if type_info.is_dual() {
+			// Emit the interface half of dual interfaces
+			// TODO: Also emit codegen for dispinterface side?
+			build_result.skipped_dispinterface_of_dual_interface(&type_info.name);
+			types::TypeKind::Interface
+		}
+		else {
+			type_info.typekind
+		};
+
+	let type_name = &type_info.name;
+
+	match typekind {
+		types::TypeKind::Enum => {
+			referenced_names.insert("ENUM");
+			writeln!(out, "ENUM!{{enum {} {{", type_name)?;
+
+			for member in &type_info.vars {
+				write!(out, "    {} = ", sanitize_reserved(&member.name, &Location::member(type_name, &member.name), build_result))?;
+				match &member.var_kind {
+					types::VarKind::Const(types::Variant::I4(value)) =>
+						if *value >= 0 {
+							writeln!(out, "{},", value)?;
+						}
+						else {
+							writeln!(out, "0x{:08x},", value)?;
+						},
+					types::VarKind::Const(value) =>
+						return Err(crate::Error::UnsupportedVarType { location: Location::member(type_name, &member.name), vt: value.vt() }),
+					_ => return Err(crate::Error::Inconsistent { location: Location::member(type_name, &member.name), reason: "enum member is not a constant" }),
+				}
+			}
+
+			writeln!(out, "}}}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Record => {
+			referenced_names.insert("STRUCT");
+			writeln!(out, "STRUCT!{{struct {} {{", type_name)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				writeln!(out, "    {}: {},", sanitize_reserved(&field.name, &location, build_result), type_to_string(&field.type_, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+			}
+
+			writeln!(out, "}}}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Module => {
+			writeln!(out, r#"extern "system" {{"#)?;
+
+			for function in &type_info.functions {
+				let location = Location::member(type_name, &function.name);
+
+				if function.func_kind != types::FuncKind::Static {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				writeln!(out, "    pub fn {}(", function.name)?;
+
+				for (param_index, param) in function.params.iter().enumerate() {
+					writeln!(out, "        {}: {},",
+						sanitize_reserved(&param.name, &location.param(param_index), build_result),
+						type_to_string(&param.type_, param.flags, &location.param(param_index), context, referenced_names, build_result)?)?;
+				}
+
+				writeln!(out, "    ) -> {};", type_to_string(&function.return_type, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Interface => {
+			write_uuid(&mut out, type_info.guid)?;
+			referenced_names.insert("RIDL");
+			write!(out, "interface {}({}Vtbl)", type_name, type_name)?;
+
+			let mut have_parents = false;
+			let mut parents_vtbl_size = 0;
+
+			for parent in type_info.impl_types.iter().map(|impl_type| &impl_type.type_ref) {
+				let parent_name = type_ref_to_string(parent, &Location::type_(type_name), context, build_result);
+				refer(referenced_names, parent_name);
+				refer(referenced_names, &format!("{}Vtbl", parent_name));
+
+				if have_parents {
+					write!(out, ", {}({}Vtbl)", parent_name, parent_name)?;
+				}
+				else {
+					write!(out, ": {}({}Vtbl)", parent_name, parent_name)?;
+				}
+				have_parents = true;
+
+				parents_vtbl_size += vtbl_size(parent, context).unwrap_or(0);
+			}
+
+			writeln!(out, " {{")?;
+
+			for function in &type_info.functions {
+				if function.ovft < parents_vtbl_size {
+					// Inherited from ancestors
+					continue;
+				}
+
+				let function_name = &function.name;
+				let location = Location::member(type_name, function_name);
+
+				if function.func_kind == types::FuncKind::Static || function.func_kind == types::FuncKind::Dispatch {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				match function.invoke_kind {
+					types::InvokeKind::Func => {
+						writeln!(out, "    fn {}(", function_name)?;
+
+						for (param_index, param) in function.params.iter().enumerate() {
+							writeln!(out, "        {}: {},",
+								sanitize_reserved(&param.name, &location.param(param_index), build_result),
+								type_to_string(&param.type_, param.flags, &location.param(param_index), context, referenced_names, build_result)?)?;
+						}
+
+						writeln!(out, "    ) -> {},", type_to_string(&function.return_type, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+					},
+
+					types::InvokeKind::PropertyGet => {
+						writeln!(out, "    fn get_{}(", function_name)?;
+
+						let mut explicit_ret_val = false;
+
+						for (param_index, param) in function.params.iter().enumerate() {
+							writeln!(out, "        {}: {},",
+								sanitize_reserved(&param.name, &location.param(param_index), build_result),
+								type_to_string(&param.type_, param.flags, &location.param(param_index), context, referenced_names, build_result)?)?;
+
+							if (param.flags & types::PARAMFLAG_FRETVAL) == types::PARAMFLAG_FRETVAL {
+								explicit_ret_val = true;
+							}
+						}
+
+						if explicit_ret_val {
+							if function.return_type.vt() != types::VT_HRESULT {
+								return Err(crate::Error::Inconsistent { location, reason: "function with a retval parameter doesn't return HRESULT" });
+							}
+
+							writeln!(out, "    ) -> {},",
+								type_to_string(&function.return_type, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+						}
+						else {
+							writeln!(out, "        value: *mut {},",
+								type_to_string(&function.return_type, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+							referenced_names.insert("HRESULT");
+							writeln!(out, "    ) -> HRESULT,")?;
+						}
+					},
+
+					types::InvokeKind::PropertyPut |
+					types::InvokeKind::PropertyPutRef => {
+						writeln!(out, "    fn {}{}(",
+							match function.invoke_kind {
+								types::InvokeKind::PropertyPut => "put_",
+								types::InvokeKind::PropertyPutRef => "putref_",
+								_ => unreachable!(),
+							},
+							function_name)?;
+
+						for (param_index, param) in function.params.iter().enumerate() {
+							writeln!(out, "        {}: {},",
+								sanitize_reserved(&param.name, &location.param(param_index), build_result),
+								type_to_string(&param.type_, param.flags, &location.param(param_index), context, referenced_names, build_result)?)?;
+						}
+
+						writeln!(out, "    ) -> {},", type_to_string(&function.return_type, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+					},
+				}
+			}
+
+			for property in &type_info.vars {
+				// Synthesize get_() and put_() functions for each property.
+
+				let location = Location::member(type_name, &property.name);
+				let property_name = sanitize_reserved(&property.name, &location, build_result);
+
+				referenced_names.insert("HRESULT");
+				writeln!(out, "    fn get_{}(", property_name)?;
+				writeln!(out, "        value: *mut {},", type_to_string(&property.type_, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+				writeln!(out, "    ) -> HRESULT,")?;
+				writeln!(out, "    fn put_{}(", property_name)?;
+				writeln!(out, "        value: {},", type_to_string(&property.type_, types::PARAMFLAG_FIN, &location, context, referenced_names, build_result)?)?;
+				writeln!(out, "    ) -> HRESULT,")?;
+			}
+
+			writeln!(out, "}}}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Dispatch => {
+			if !emit_dispinterfaces {
+				build_result.skipped_dispinterface(&type_info.name);
+				return Ok(());
+			}
+
+			write_uuid(&mut out, type_info.guid)?;
+			referenced_names.extend(&["RIDL", "IDispatch", "IDispatchVtbl"]);
+			writeln!(out, "interface {}({}Vtbl): IDispatch(IDispatchVtbl) {{", type_name, type_name)?;
+			writeln!(out, "}}}}")?;
+
+			match &*type_info.impl_types {
+				[parent] if
+					type_lib.get_type_name(&parent.type_ref) == Some("IDispatch") &&
+					type_lib.get_vtbl_size(&parent.type_ref) == Some(7 * pointer_size) => (), // 3 from IUnknown + 4 from IDispatch
+
+				_ => return Err(crate::Error::Inconsistent { location: Location::type_(type_name), reason: "dispinterface doesn't have IDispatch as its only parent" }),
+			}
+
+			writeln!(out)?;
+			writeln!(out, "impl {} {{", type_name)?;
+
+			for function in &type_info.functions {
+				let function_name = &function.name;
+				let location = Location::member(type_name, function_name);
+
+				if function.func_kind != types::FuncKind::Dispatch {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				if is_inherited_dispatch_member(type_info, function, context) {
+					build_result.warn(WarningKind::DroppedRestrictedMember, location);
+					continue;
+				}
+
+				referenced_names.extend(&["HRESULT", "VARIANT", "EXCEPINFO", "UINT", "VariantInit", "DISPPARAMS", "IID_NULL"]);
+
+				let params: Vec<_> =
+					function.params.iter().enumerate()
+					.filter(|(_, param)| (param.flags & types::PARAMFLAG_FRETVAL) == 0)
+					.collect();
+
+				writeln!(out, "    pub unsafe fn {}{}(",
+					match function.invoke_kind {
+						types::InvokeKind::Func => "",
+						types::InvokeKind::PropertyGet => "get_",
+						types::InvokeKind::PropertyPut => "put_",
+						types::InvokeKind::PropertyPutRef => "putref_",
+					},
+					function_name)?;
+
+				writeln!(out, "        &self,")?;
+
+				for &(param_index, param) in &params {
+					writeln!(out, "        {}: {},",
+						sanitize_reserved(&param.name, &location.param(param_index), build_result),
+						type_to_string(&param.type_, param.flags, &location.param(param_index), context, referenced_names, build_result)?)?;
+				}
+
+				writeln!(out, "    ) -> (HRESULT, VARIANT, EXCEPINFO, UINT) {{")?;
+
+				if !params.is_empty() {
+					writeln!(out, "        let mut args: [VARIANT; {}] = [", params.len())?;
+
+					for &(param_index, param) in params.iter().rev() {
+						let (vt, mutator) = vartype_mutator(&param.type_, &sanitize_reserved(&param.name, &location.param(param_index), build_result), &location.param(param_index), context, build_result)?;
+						writeln!(out, "            {{ let mut v: VARIANT = ::core::mem::uninitialized(); VariantInit(&mut v); *v.vt_mut() = {}; *v{}; v }},", vt, mutator)?;
+					}
+
+					writeln!(out, "        ];")?;
+					writeln!(out)?;
+				}
+
+				if function.invoke_kind == types::InvokeKind::PropertyPut || function.invoke_kind == types::InvokeKind::PropertyPutRef {
+					referenced_names.insert("DISPID_PROPERTYPUT");
+					writeln!(out, "        let disp_id_put = DISPID_PROPERTYPUT;")?;
+					writeln!(out)?;
+				}
+
+				writeln!(out, "        let mut result: VARIANT = ::core::mem::uninitialized();")?;
+				writeln!(out, "        VariantInit(&mut result);")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut exception_info: EXCEPINFO = ::core::mem::zeroed();")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut error_arg: UINT = 0;")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut disp_params = DISPPARAMS {{")?;
+				writeln!(out, "            rgvarg: {},", if params.is_empty() { "::core::ptr::null_mut()" } else { "args.as_mut_ptr()" })?;
+				writeln!(out, "            rgdispidNamedArgs: {},",
+					match function.invoke_kind {
+						types::InvokeKind::Func |
+						types::InvokeKind::PropertyGet => "::core::ptr::null_mut()",
+						types::InvokeKind::PropertyPut |
+						types::InvokeKind::PropertyPutRef => "&disp_id_put",
+					})?;
+				writeln!(out, "            cArgs: {},", params.len())?;
+				writeln!(out, "            cNamedArgs: {},",
+					match function.invoke_kind {
+						types::InvokeKind::Func |
+						types::InvokeKind::PropertyGet => "0",
+						types::InvokeKind::PropertyPut |
+						types::InvokeKind::PropertyPutRef => "1",
+					})?;
+				writeln!(out, "        }};")?;
+				writeln!(out)?;
+				writeln!(out, "        let hr = ((*self.lpVtbl).parent.Invoke)(")?;
+				writeln!(out, "            self as *const _ as *mut _,")?;
+				writeln!(out, "            /* dispIdMember */ {},", function.member_id)?;
+				writeln!(out, "            /* riid */ &IID_NULL,")?;
+				writeln!(out, "            /* lcid */ 0,")?;
+				let flags = match function.invoke_kind {
+					types::InvokeKind::Func => "DISPATCH_METHOD",
+					types::InvokeKind::PropertyGet => "DISPATCH_PROPERTYGET",
+					types::InvokeKind::PropertyPut => "DISPATCH_PROPERTYPUT",
+					types::InvokeKind::PropertyPutRef => "DISPATCH_PROPERTYPUTREF",
+				};
+				referenced_names.insert(flags);
+				writeln!(out, "            /* wFlags */ {},", flags)?;
+				writeln!(out, "            /* pDispParams */ &mut disp_params,")?;
+				writeln!(out, "            /* pVarResult */ &mut result,")?;
+				writeln!(out, "            /* pExcepInfo */ &mut exception_info,")?;
+				writeln!(out, "            /* puArgErr */ &mut error_arg,")?;
+				writeln!(out, "        );")?;
+				writeln!(out)?;
+				writeln!(out, "        (hr, result, exception_info, error_arg)")?;
+				writeln!(out, "    }}")?;
+				writeln!(out)?;
+			}
+
+			for property in &type_info.vars {
+				// Synthesize get_() and put_() functions for each property.
+
+				let location = Location::member(type_name, &property.name);
+				let property_name = sanitize_reserved(&property.name, &location, build_result);
+
+				referenced_names.extend(&["HRESULT", "VARIANT", "EXCEPINFO", "UINT", "VariantInit", "DISPPARAMS", "IID_NULL", "DISPATCH_PROPERTYGET", "DISPATCH_PROPERTYPUT"]);
+				writeln!(out, "    pub unsafe fn get_{}(", property_name)?;
+				writeln!(out, "    ) -> (HRESULT, VARIANT, EXCEPINFO, UINT) {{")?;
+				writeln!(out, "        let mut result: VARIANT = ::core::mem::uninitialized();")?;
+				writeln!(out, "        VariantInit(&mut result);")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut exception_info: EXCEPINFO = ::core::mem::zeroed();")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut error_arg: UINT = 0;")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut disp_params = DISPPARAMS {{")?;
+				writeln!(out, "            rgvarg: ::core::ptr::null_mut(),")?;
+				writeln!(out, "            rgdispidNamedArgs: ::core::ptr::null_mut(),")?;
+				writeln!(out, "            cArgs: 0,")?;
+				writeln!(out, "            cNamedArgs: 0,")?;
+				writeln!(out, "        }};")?;
+				writeln!(out)?;
+				writeln!(out, "        let hr = ((*self.lpVtbl).parent.Invoke)(")?;
+				writeln!(out, "            self as *const _ as *mut _,")?;
+				writeln!(out, "            /* dispIdMember */ {},", property.member_id)?;
+				writeln!(out, "            /* riid */ &IID_NULL,")?;
+				writeln!(out, "            /* lcid */ 0,")?;
+				writeln!(out, "            /* wFlags */ DISPATCH_PROPERTYGET,")?;
+				writeln!(out, "            /* pDispParams */ &mut disp_params,")?;
+				writeln!(out, "            /* pVarResult */ &mut result,")?;
+				writeln!(out, "            /* pExcepInfo */ &mut exception_info,")?;
+				writeln!(out, "            /* puArgErr */ &mut error_arg,")?;
+				writeln!(out, "        );")?;
+				writeln!(out)?;
+				writeln!(out, "        (hr, result, exception_info, error_arg)")?;
+				writeln!(out, "    }}")?;
+				writeln!(out)?;
+				writeln!(out, "    pub unsafe fn put_{}(", property_name)?;
+				writeln!(out, "        value: {},", type_to_string(&property.type_, types::PARAMFLAG_FIN, &location, context, referenced_names, build_result)?)?;
+				writeln!(out, "    ) -> (HRESULT, VARIANT, EXCEPINFO, UINT) {{")?;
+				writeln!(out, "        let mut args: [VARIANT; 1] = [")?;
+				let (vt, mutator) = vartype_mutator(&property.type_, "value", &location, context, build_result)?;
+				writeln!(out, "            {{ let mut v: VARIANT = ::core::mem::uninitialized(); VariantInit(&mut v); *v.vt_mut() = {}; *v{}; v }},", vt, mutator)?;
+				writeln!(out, "        ];")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut result: VARIANT = ::core::mem::uninitialized();")?;
+				writeln!(out, "        VariantInit(&mut result);")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut exception_info: EXCEPINFO = ::core::mem::zeroed();")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut error_arg: UINT = 0;")?;
+				writeln!(out)?;
+				writeln!(out, "        let mut disp_params = DISPPARAMS {{")?;
+				writeln!(out, "            rgvarg: args.as_mut_ptr(),")?;
+				writeln!(out, "            rgdispidNamedArgs: ::core::ptr::null_mut(),")?; // TODO: PROPERTYPUT needs named args?
+				writeln!(out, "            cArgs: 1,")?;
+				writeln!(out, "            cNamedArgs: 0,")?;
+				writeln!(out, "        }};")?;
+				writeln!(out)?;
+				writeln!(out, "        let hr = ((*self.lpVtbl).parent.Invoke)(")?;
+				writeln!(out, "            self as *const _ as *mut _,")?;
+				writeln!(out, "            /* dispIdMember */ {},", property.member_id)?;
+				writeln!(out, "            /* riid */ &IID_NULL,")?;
+				writeln!(out, "            /* lcid */ 0,")?;
+				writeln!(out, "            /* wFlags */ DISPATCH_PROPERTYPUT,")?;
+				writeln!(out, "            /* pDispParams */ &mut disp_params,")?;
+				writeln!(out, "            /* pVarResult */ &mut result,")?;
+				writeln!(out, "            /* pExcepInfo */ &mut exception_info,")?;
+				writeln!(out, "            /* puArgErr */ &mut error_arg,")?;
+				writeln!(out, "        );")?;
+				writeln!(out)?;
+				// TODO: VariantClear() on args
+				writeln!(out, "        (hr, result, exception_info, error_arg)")?;
+				writeln!(out, "    }}")?;
+				writeln!(out)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::CoClass => {
+			for parent in type_info.impl_types.iter().map(|impl_type| &impl_type.type_ref) {
+				let parent_name = type_ref_to_string(parent, &Location::type_(type_name), context, build_result);
+				writeln!(out, "// Implements {}", parent_name)?;
+			}
+
+			write_uuid(&mut out, type_info.guid)?;
+			referenced_names.insert("RIDL");
+			writeln!(out, "class {}; }}", type_name)?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Alias => {
+			let location = Location::type_(type_name);
+			let alias_type = type_info.alias_type.as_ref().ok_or_else(|| crate::Error::Inconsistent { location: location.clone(), reason: "alias has no target" })?;
+			writeln!(out, "pub type {} = {};", type_name, type_to_string(alias_type, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Union => {
+			let alignment = match type_info.alignment {
+				4 => "u32",
+				8 => "u64",
+				_ => return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union alignment is not 4 or 8" }),
+			};
+
+			let num_aligned_elements = type_info.size_instance.div_ceil(u32::from(type_info.alignment));
+			if num_aligned_elements == 0 {
+				return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union is empty" });
+			}
+			let wrapped_type = match num_aligned_elements {
+				1 => alignment.to_string(),
+				_ => format!("[{}; {}]", alignment, num_aligned_elements),
+			};
+
+			// winapi doesn't export its UNION! macro, so emit a Rust union. The first field gives it the size and alignment of the typelib's union.
+			writeln!(out, "#[repr(C)]")?;
+			writeln!(out, "#[derive(Clone, Copy)]")?;
+			writeln!(out, "pub union {} {{", type_name)?;
+			writeln!(out, "    _alignment: {},", wrapped_type)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				writeln!(out, "    pub {}: {},", sanitize_reserved(&field.name, &location, build_result), type_to_string(&field.type_, types::PARAMFLAG_FOUT, &location, context, referenced_names, build_result)?)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+	}
+
+	Ok(())
+}
+
+/// Writes `use` items for the given names that [`write_item`] found the bindings to refer to, other than the ones that the typelib defines itself
+pub(crate) fn write_imports<W>(context: &Context<'_>, referenced_names: &std::collections::BTreeSet<&'static str>, mut out: W) -> Result<(), crate::Error> where W: std::io::Write {
+	let type_lib = context.type_lib;
+
+	let mut defined_names = std::collections::BTreeSet::new();
+	for type_info in context.type_infos() {
+		defined_names.insert(type_info.name.clone());
+		defined_names.insert(format!("{}Vtbl", type_info.name));
+	}
+
+	// Module path => names imported from it, as `name` or `name as alias`
+	let mut imports: std::collections::BTreeMap<String, std::collections::BTreeSet<String>> = std::collections::BTreeMap::new();
+	let mut imported_names = std::collections::BTreeSet::new();
+
+	// RIDL! requires the parents of interfaces to be identifiers, so the ones in imported typelibs are imported by the paths they're mapped to.
+	for type_info in context.type_infos() {
+		if type_info.typekind != types::TypeKind::Interface && !type_info.is_dual() {
+			continue;
+		}
+
+		for parent in type_info.impl_types.iter().map(|impl_type| &impl_type.type_ref) {
+			let name =
+				type_lib.get_type_name(parent)
+				.or_else(|| type_lib.get_imported_type_info(parent, context.dependencies).map(|(_, type_info)| &*type_info.name));
+			let (Some(name), Some(path)) = (name, mapped_type_path(parent, context)) else { continue; };
+			let Some((module_path, item_name)) = path.rsplit_once("::") else { continue; };
+
+			if !imported_names.insert(name.to_string()) {
+				continue;
+			}
+
+			let module_imports = imports.entry(module_path.to_string()).or_default();
+			for (item_name, name) in &[(item_name.to_string(), name.to_string()), (format!("{}Vtbl", item_name), format!("{}Vtbl", name))] {
+				module_imports.insert(if item_name == name { name.clone() } else { format!("{} as {}", item_name, name) });
+			}
+			imported_names.insert(format!("{}Vtbl", name));
+		}
+	}
+
+	for &(name, module_path) in WINAPI_ITEMS {
+		if referenced_names.contains(name) && !defined_names.contains(name) && imported_names.insert(name.to_string()) {
+			imports.entry(module_path.to_string()).or_default().insert(name.to_string());
+		}
+	}
+
+	if imports.is_empty() {
+		return Ok(());
+	}
+
+	for (module_path, names) in imports {
+		if names.len() == 1 {
+			writeln!(out, "use {}::{};", module_path, names.into_iter().next().unwrap())?;
+		}
+		else {
+			writeln!(out, "use {}::{{{}}};", module_path, names.into_iter().collect::<Vec<_>>().join(", "))?;
+		}
+	}
+	writeln!(out)?;
+
+	Ok(())
+}
+
+/// Adds the given name to `referenced_names` if it's a `winapi` item that bindings might refer to
+fn refer(referenced_names: &mut std::collections::BTreeSet<&'static str>, name: &str) {
+	if let Some(&(name, _)) = WINAPI_ITEMS.iter().find(|&&(item_name, _)| item_name == name) {
+		referenced_names.insert(name);
+	}
+}
+
+fn write_uuid<W>(mut out: W, guid: types::Guid) -> std::io::Result<()> where W: std::io::Write {
+	writeln!(out, "RIDL!{{#[uuid(0x{:08x}, 0x{:04x}, 0x{:04x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x}, 0x{:02x})]",
+		guid.data1, guid.data2, guid.data3,
+		guid.data4[0], guid.data4[1], guid.data4[2], guid.data4[3],
+		guid.data4[4], guid.data4[5], guid.data4[6], guid.data4[7])
+}
+
+/// Formats the given type as Rust. `location` is where the type is used, for reporting missing and unsupported types.
+///
+/// The `winapi` items that the type refers to by name are added to `referenced_names`.
+fn type_to_string(
+	type_: &types::TypeDesc,
+	param_flags: u16,
+	location: &Location,
+	context: &Context<'_>,
+	referenced_names: &mut std::collections::BTreeSet<&'static str>,
+	build_result: &mut BuildResult,
+) -> Result<String, crate::Error> {
+	Ok(match type_ {
+		types::TypeDesc::Ptr(pointee) =>
+			if (param_flags & types::PARAMFLAG_FIN) == types::PARAMFLAG_FIN && (param_flags & types::PARAMFLAG_FOUT) == 0 {
+				// [in] => *const
+				format!("*const {}", type_to_string(pointee, param_flags, location, context, referenced_names, build_result)?)
+			}
+			else {
+				// [in, out] => *mut
+				// [] => *mut (Some functions like IXMLError::GetErrorInfo don't annotate [out] on their out parameter)
+				format!("*mut {}", type_to_string(pointee, param_flags, location, context, referenced_names, build_result)?)
+			},
+
+		types::TypeDesc::CArray(element, dimensions) => {
+			let mut type_name = type_to_string(element, param_flags, location, context, referenced_names, build_result)?;
+
+			for dimension in dimensions {
+				type_name = format!("[{}; {}]", type_name, dimension);
+			}
+
+			type_name
+		},
+
+		types::TypeDesc::UserDefined(type_ref) =>
+			if let Some(path) = mapped_type_path(type_ref, context) {
+				path
+			}
+			else {
+				let type_name = type_ref_to_string(type_ref, location, context, build_result);
+				refer(referenced_names, type_name);
+				type_name.to_string()
+			},
+
+		types::TypeDesc::SafeArray(element) => {
+			// SAFEARRAYs are passed by pointer. The element type isn't part of the Rust type, so it's written next to it in a comment,
+			// and reported for the bindings that don't keep comments, like the ones from `build_tokens`.
+			// The names that the element type refers to don't need to be imported, since it's only in a comment.
+			let element = type_to_string(element, 0, location, context, &mut std::collections::BTreeSet::new(), build_result)?;
+			referenced_names.insert("SAFEARRAY");
+			let type_name = "*mut SAFEARRAY";
+			build_result.lossy_type_mapping(type_, type_name, location);
+			format!("{} /* of {} */", type_name, element)
+		},
+
+		types::TypeDesc::Base(vt) => match var_type_kind(*vt, location)? {
+			VarTypeKind::Expanded(type_) => type_to_string(&type_, param_flags, location, context, referenced_names, build_result)?,
+
+			VarTypeKind::Record => {
+				referenced_names.insert("c_void");
+				let type_name = "*mut c_void";
+				build_result.lossy_type_mapping(type_, type_name, location);
+				type_name.to_string()
+			},
+
+			VarTypeKind::WellKnown(well_known) => {
+				let type_name = well_known_type_path(well_known);
+				referenced_names.insert(type_name);
+				type_name.to_string()
+			},
+		},
+	})
+}
+
+/// The `winapi` modules of the items that bindings refer to by name
+const WINAPI_ITEMS: &[(&str, &str)] = &[
+	("ENUM", "winapi"),
+	("RIDL", "winapi"),
+	("STRUCT", "winapi"),
+	("c_void", "winapi::ctypes"),
+	("INT_PTR", "winapi::shared::basetsd"),
+	("UINT_PTR", "winapi::shared::basetsd"),
+	("CLSID", "winapi::shared::guiddef"),
+	("GUID", "winapi::shared::guiddef"),
+	("IID_NULL", "winapi::shared::guiddef"),
+	("FILETIME", "winapi::shared::minwindef"),
+	("INT", "winapi::shared::minwindef"),
+	("UINT", "winapi::shared::minwindef"),
+	("HRESULT", "winapi::shared::ntdef"),
+	("LPCWSTR", "winapi::shared::ntdef"),
+	("LPSTR", "winapi::shared::ntdef"),
+	("BSTR", "winapi::shared::wtypes"),
+	("BSTRBLOB", "winapi::shared::wtypes"),
+	("CY", "winapi::shared::wtypes"),
+	("DATE", "winapi::shared::wtypes"),
+	("DECIMAL", "winapi::shared::wtypes"),
+	("VARIANT_BOOL", "winapi::shared::wtypes"),
+	("BLOB", "winapi::shared::wtypesbase"),
+	("SCODE", "winapi::shared::wtypesbase"),
+	("DISPID_PROPERTYPUT", "winapi::um::oaidl"),
+	("DISPPARAMS", "winapi::um::oaidl"),
+	("EXCEPINFO", "winapi::um::oaidl"),
+	("IDispatch", "winapi::um::oaidl"),
+	("IDispatchVtbl", "winapi::um::oaidl"),
+	("LPDISPATCH", "winapi::um::oaidl"),
+	("SAFEARRAY", "winapi::um::oaidl"),
+	("VARIANT", "winapi::um::oaidl"),
+	("LPSTORAGE", "winapi::um::objidl"),
+	("LPSTREAM", "winapi::um::objidlbase"),
+	("DISPATCH_METHOD", "winapi::um::oleauto"),
+	("DISPATCH_PROPERTYGET", "winapi::um::oleauto"),
+	("DISPATCH_PROPERTYPUT", "winapi::um::oleauto"),
+	("DISPATCH_PROPERTYPUTREF", "winapi::um::oleauto"),
+	("VariantInit", "winapi::um::oleauto"),
+	("CABOOL", "winapi::um::propidl"),
+	("CABSTR", "winapi::um::propidl"),
+	("CABSTRBLOB", "winapi::um::propidl"),
+	("CAC", "winapi::um::propidl"),
+	("CACLSID", "winapi::um::propidl"),
+	("CACY", "winapi::um::propidl"),
+	("CADATE", "winapi::um::propidl"),
+	("CADBL", "winapi::um::propidl"),
+	("CAFILETIME", "winapi::um::propidl"),
+	("CAFLT", "winapi::um::propidl"),
+	("CAH", "winapi::um::propidl"),
+	("CAI", "winapi::um::propidl"),
+	("CAL", "winapi::um::propidl"),
+	("CALPSTR", "winapi::um::propidl"),
+	("CALPWSTR", "winapi::um::propidl"),
+	("CASCODE", "winapi::um::propidl"),
+	("CAUB", "winapi::um::propidl"),
+	("CAUH", "winapi::um::propidl"),
+	("CAUI", "winapi::um::propidl"),
+	("CAUL", "winapi::um::propidl"),
+	("IUnknown", "winapi::um::unknwnbase"),
+	("IUnknownVtbl", "winapi::um::unknwnbase"),
+	("LPUNKNOWN", "winapi::um::unknwnbase"),
+];
+
+fn well_known_type_path(well_known: WellKnownType) -> &'static str {
+	match well_known {
+		WellKnownType::Primitive(type_name) |
+		WellKnownType::CountedArray(type_name) => type_name,
+		WellKnownType::Void => "c_void",
+		WellKnownType::Int => "INT",
+		WellKnownType::UInt => "UINT",
+		WellKnownType::IntPtr => "INT_PTR",
+		WellKnownType::UIntPtr => "UINT_PTR",
+		WellKnownType::Error => "SCODE",
+		WellKnownType::Bool => "VARIANT_BOOL",
+		WellKnownType::Currency => "CY",
+		WellKnownType::Date => "DATE",
+		WellKnownType::Decimal => "DECIMAL",
+		WellKnownType::HResult => "HRESULT",
+		WellKnownType::Bstr => "BSTR",
+		WellKnownType::BstrBlob => "BSTRBLOB",
+		WellKnownType::LpStr => "LPSTR",
+		WellKnownType::LpWStr => "LPCWSTR",
+		WellKnownType::Variant => "VARIANT",
+		WellKnownType::FileTime => "FILETIME",
+		WellKnownType::Blob => "BLOB",
+		WellKnownType::Clsid => "CLSID",
+		WellKnownType::Dispatch => "LPDISPATCH",
+		WellKnownType::Unknown => "LPUNKNOWN",
+		WellKnownType::Stream => "LPSTREAM",
+		WellKnownType::Storage => "LPSTORAGE",
+	}
+}
+
+/// Gets the `VARTYPE` of the given parameter type, and the code that stores the parameter with the given name in a `VARIANT`
+fn vartype_mutator(
+	type_: &types::TypeDesc,
+	param_name: &str,
+	location: &Location,
+	context: &Context<'_>,
+	build_result: &mut BuildResult,
+) -> Result<(types::VarType, String), crate::Error> {
+	let unsupported = |vt| crate::Error::UnsupportedVarType { location: location.clone(), vt };
+
+	Ok(match type_ {
+		types::TypeDesc::Base(vt) => match *vt {
+			vt @ types::VT_I2 => (vt, format!(".iVal_mut() = {}", param_name)),
+			vt @ types::VT_I4 => (vt, format!(".lVal_mut() = {}", param_name)),
+			vt @ types::VT_CY => (vt, format!(".cyVal_mut() = {}", param_name)),
+			vt @ types::VT_BSTR => (vt, format!(".bstrVal_mut() = {}", param_name)),
+			vt @ types::VT_DISPATCH => (vt, format!(".pdispVal_mut() = {}", param_name)),
+			vt @ types::VT_ERROR => (vt, format!(".scode_mut() = {}", param_name)),
+			vt @ types::VT_BOOL => (vt, format!(".boolVal_mut() = {}", param_name)),
+			vt @ types::VT_VARIANT => (vt, format!(" = *(&{} as *const _ as *mut _)", param_name)),
+			vt @ types::VT_UNKNOWN => (vt, format!(".punkVal_mut() = {}", param_name)),
+			vt @ types::VT_UI2 => (vt, format!(".uiVal_mut() = {}", param_name)),
+			vt @ types::VT_UI4 => (vt, format!(".ulVal_mut() = {}", param_name)),
+			vt @ types::VT_INT => (vt, format!(".intVal_mut() = {}", param_name)),
+			vt @ types::VT_UINT => (vt, format!(".uintVal_mut() = {}", param_name)),
+			vt => return Err(unsupported(vt)),
+		},
+		types::TypeDesc::Ptr(pointee) => {
+			let pointee_vt = pointee.vt();
+			match pointee_vt {
+				types::VT_I4 => (pointee_vt | types::VT_BYREF, format!(".plVal_mut() = {}", param_name)),
+				types::VT_BSTR => (pointee_vt | types::VT_BYREF, format!(".pbstrVal_mut() = {}", param_name)),
+				types::VT_DISPATCH => (pointee_vt | types::VT_BYREF, format!(".ppdispVal_mut() = {}", param_name)),
+				types::VT_BOOL => (pointee_vt | types::VT_BYREF, format!(".pboolVal_mut() = {}", param_name)),
+				types::VT_VARIANT => (pointee_vt | types::VT_BYREF, format!(".pvarval_mut() = {}", param_name)),
+				types::VT_USERDEFINED => (types::VT_DISPATCH, format!(".pdispVal_mut() = {}", param_name)),
+				_ => return Err(unsupported(pointee_vt | types::VT_BYREF)),
+			}
+		},
+		types::TypeDesc::UserDefined(type_ref) => {
+			let Some(ref_type) = resolve_type_info(type_ref, context) else {
+				let missing_type = build_result.missing_type(type_ref, location);
+				return Err(crate::Error::MissingTypes(vec![missing_type]));
+			};
+
+			match ref_type.size_instance {
+				4 => (types::VT_I4, format!(".lVal_mut() = {}", param_name)), // enum
+				_ => return Err(crate::Error::UnexpectedTypeKind { location: location.clone(), typekind: ref_type.typekind }),
+			}
+		},
+		types::TypeDesc::SafeArray(element) =>
+			(safe_array_element_vartype(element, location, context, build_result)? | types::VT_ARRAY, format!(".parray_mut() = {}", param_name)),
+		types::TypeDesc::CArray(..) => return Err(unsupported(type_.vt())),
+	})
+}
+
+/// Gets the `VARTYPE` of the elements of a `SAFEARRAY` with the given element type, which is combined with `VT_ARRAY` for the `VARTYPE` of the `SAFEARRAY`
+fn safe_array_element_vartype(
+	element: &types::TypeDesc,
+	location: &Location,
+	context: &Context<'_>,
+	build_result: &mut BuildResult,
+) -> Result<types::VarType, crate::Error> {
+	let resolve = |type_ref, build_result: &mut BuildResult| resolve_type_info(type_ref, context).ok_or_else(|| {
+		let missing_type = build_result.missing_type(type_ref, location);
+		crate::Error::MissingTypes(vec![missing_type])
+	});
+
+	match element {
+		types::TypeDesc::Base(vt) => Ok(*vt),
+
+		types::TypeDesc::UserDefined(type_ref) => {
+			let element_type = resolve(type_ref, build_result)?;
+			match element_type.typekind {
+				types::TypeKind::Enum => Ok(types::VT_I4),
+				types::TypeKind::Record => Ok(types::VT_RECORD),
+				types::TypeKind::Alias => {
+					let alias_type = element_type.alias_type.as_ref().ok_or_else(|| crate::Error::Inconsistent { location: location.clone(), reason: "alias has no target" })?;
+					safe_array_element_vartype(alias_type, location, context, build_result)
+				},
+				typekind => Err(crate::Error::UnexpectedTypeKind { location: location.clone(), typekind }),
+			}
+		},
+
+		types::TypeDesc::Ptr(pointee) => match &**pointee {
+			types::TypeDesc::UserDefined(type_ref) => {
+				let element_type = resolve(type_ref, build_result)?;
+				match element_type.typekind {
+					types::TypeKind::Dispatch => Ok(types::VT_DISPATCH),
+					types::TypeKind::Interface if element_type.is_dual() => Ok(types::VT_DISPATCH),
+					types::TypeKind::Interface => Ok(types::VT_UNKNOWN),
+					typekind => Err(crate::Error::UnexpectedTypeKind { location: location.clone(), typekind }),
+				}
+			},
+			_ => Err(crate::Error::UnsupportedVarType { location: location.clone(), vt: element.vt() | types::VT_ARRAY }),
+		},
+
+		_ => Err(crate::Error::UnsupportedVarType { location: location.clone(), vt: element.vt() | types::VT_ARRAY }),
+	}
+}
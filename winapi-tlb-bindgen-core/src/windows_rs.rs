@@ -0,0 +1,654 @@
+//! Bindings for the `windows` and `windows-core` crates.
+//!
+//! Each interface is a struct that wraps its parent interface, with an implementation of `windows_core::Interface`, a `#[repr(C)]` vtable struct,
+//! and methods that call through the vtable. Methods whose function returns an `HRESULT` return a `windows_core::Result` instead,
+//! with the value of the `[out, retval]` parameter if there is one.
+
+use crate::{is_inherited_dispatch_member, resolve_type_info, sanitize_reserved, type_ref_path, type_ref_to_string, types, var_type_kind, vtbl_size, well_known_path, BuildResult, Context, Location, VarTypeKind, WarningKind, WellKnownType};
+
+/// Writes the bindings for the given type of the typelib
+pub(crate) fn write_item<W>(context: &Context<'_>, type_info: &types::TypeInfo, emit_dispinterfaces: bool, build_result: &mut BuildResult, mut out: W) -> Result<(), crate::Error> where W: std::io::Write {
+	let typekind =
+		if type_info.is_dual() {
+			// Emit the interface half of dual interfaces
+			build_result.skipped_dispinterface_of_dual_interface(&type_info.name);
+			types::TypeKind::Interface
+		}
+		else {
+			type_info.typekind
+		};
+
+	let type_name = &type_info.name;
+
+	match typekind {
+		types::TypeKind::Enum => {
+			writeln!(out, "#[repr(transparent)]")?;
+			writeln!(out, "#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]")?;
+			writeln!(out, "pub struct {}(pub i32);", type_name)?;
+
+			for member in &type_info.vars {
+				match &member.var_kind {
+					types::VarKind::Const(types::Variant::I4(value)) =>
+						writeln!(out, "pub const {}: {} = {}({});", sanitize_reserved(&member.name, &Location::member(type_name, &member.name), build_result), type_name, type_name, value)?,
+					types::VarKind::Const(value) =>
+						return Err(crate::Error::UnsupportedVarType { location: Location::member(type_name, &member.name), vt: value.vt() }),
+					_ => return Err(crate::Error::Inconsistent { location: Location::member(type_name, &member.name), reason: "enum member is not a constant" }),
+				}
+			}
+
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Record => {
+			writeln!(out, "#[repr(C)]")?;
+			if fields_are_copy(type_info, context)? {
+				writeln!(out, "#[derive(Clone, Copy)]")?;
+			}
+			writeln!(out, "pub struct {} {{", type_name)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				writeln!(out, "    pub {}: {},", sanitize_reserved(&field.name, &location, build_result), value_type_to_string(&field.type_, &location, context, build_result)?)?;
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Module => {
+			writeln!(out, r#"unsafe extern "system" {{"#)?;
+
+			for function in &type_info.functions {
+				let location = Location::member(type_name, &function.name);
+
+				if function.func_kind != types::FuncKind::Static {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				writeln!(out, "    pub fn {}(", function.name)?;
+
+				for (param_index, param) in function.params.iter().enumerate() {
+					writeln!(out, "        {}: {},",
+						sanitize_reserved(&param.name, &location.param(param_index), build_result),
+						param_type_to_string(&param.type_, param.flags, &location.param(param_index), context, build_result)?)?;
+				}
+
+				match &function.return_type {
+					types::TypeDesc::Base(types::VT_VOID) => writeln!(out, "    );")?,
+					return_type => writeln!(out, "    ) -> {};", type_to_string(return_type, types::PARAMFLAG_FOUT, &location, context, build_result)?)?,
+				}
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Interface => {
+			let mut functions = vec![];
+
+			let parent = type_info.impl_types.first().map(|impl_type| &impl_type.type_ref);
+			let parent_vtbl_size = parent.and_then(|parent| vtbl_size(parent, context)).unwrap_or(0);
+
+			for function in &type_info.functions {
+				if function.ovft < parent_vtbl_size {
+					// Inherited from ancestors
+					continue;
+				}
+
+				let location = Location::member(type_name, &function.name);
+
+				if function.func_kind == types::FuncKind::Static || function.func_kind == types::FuncKind::Dispatch {
+					return Err(crate::Error::UnexpectedFuncKind { location, func_kind: function.func_kind });
+				}
+
+				match function.invoke_kind {
+					types::InvokeKind::Func =>
+						functions.push(Function::new(
+							function.name.clone(), &function.params, &function.return_type, &location, context, build_result)?),
+
+					types::InvokeKind::PropertyGet => {
+						let explicit_ret_val = function.params.iter().any(|param| (param.flags & types::PARAMFLAG_FRETVAL) == types::PARAMFLAG_FRETVAL);
+						if explicit_ret_val {
+							if function.return_type.vt() != types::VT_HRESULT {
+								return Err(crate::Error::Inconsistent { location, reason: "property getter with a retval parameter doesn't return HRESULT" });
+							}
+							functions.push(Function::new(
+								format!("get_{}", function.name), &function.params, &function.return_type, &location, context, build_result)?);
+						}
+						else {
+							let mut params = function.params.clone();
+							params.push(ret_val_param(function.return_type.clone()));
+							functions.push(Function::new(
+								format!("get_{}", function.name), &params, &types::TypeDesc::Base(types::VT_HRESULT), &location, context, build_result)?);
+						}
+					},
+
+					types::InvokeKind::PropertyPut |
+					types::InvokeKind::PropertyPutRef => {
+						let prefix = if function.invoke_kind == types::InvokeKind::PropertyPut { "put_" } else { "putref_" };
+						functions.push(Function::new(
+							format!("{}{}", prefix, function.name), &function.params, &function.return_type, &location, context, build_result)?);
+					},
+				}
+			}
+
+			for property in &type_info.vars {
+				// Synthesize get_() and put_() functions for each property.
+
+				let location = Location::member(type_name, &property.name);
+				let property_name = sanitize_reserved(&property.name, &location, build_result);
+
+				functions.push(Function::new(
+					format!("get_{}", property_name),
+					&[ret_val_param(property.type_.clone())],
+					&types::TypeDesc::Base(types::VT_HRESULT),
+					&location, context, build_result)?);
+
+				functions.push(Function::new(
+					format!("put_{}", property_name),
+					&[types::Param { name: "value".to_string(), type_: property.type_.clone(), flags: types::PARAMFLAG_FIN, default_value: None }],
+					&types::TypeDesc::Base(types::VT_HRESULT),
+					&location, context, build_result)?);
+			}
+
+			let parent = parent.map(|parent| type_ref_path(parent, &Location::type_(type_name), context, build_result));
+			write_interface(&mut out, type_name, type_info.guid, parent.as_deref(), &functions)?;
+		},
+
+		types::TypeKind::Dispatch => {
+			if !emit_dispinterfaces {
+				build_result.skipped_dispinterface(&type_info.name);
+				return Ok(());
+			}
+
+			// The members of a dispinterface are only reachable through IDispatch::Invoke, so only the interface itself is emitted.
+			for function in type_info.functions.iter().filter(|function| is_inherited_dispatch_member(type_info, function, context)) {
+				build_result.warn(WarningKind::DroppedRestrictedMember, Location::member(type_name, &function.name));
+			}
+
+			let parent = well_known_path("IDispatch", context);
+			write_interface(&mut out, type_name, type_info.guid, Some(&parent), &[])?;
+		},
+
+		types::TypeKind::CoClass => {
+			for parent in type_info.impl_types.iter().map(|impl_type| &impl_type.type_ref) {
+				let parent_name = type_ref_to_string(parent, &Location::type_(type_name), context, build_result);
+				writeln!(out, "// Implements {}", parent_name)?;
+			}
+
+			writeln!(out, "pub const CLSID_{}: windows_core::GUID = {};", type_name, guid_to_string(type_info.guid))?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Alias => {
+			let location = Location::type_(type_name);
+			let alias_type = type_info.alias_type.as_ref().ok_or_else(|| crate::Error::Inconsistent { location: location.clone(), reason: "alias has no target" })?;
+			writeln!(out, "pub type {} = {};", type_name, type_to_string(alias_type, types::PARAMFLAG_FOUT, &location, context, build_result)?)?;
+			writeln!(out)?;
+		},
+
+		types::TypeKind::Union => {
+			let alignment = match type_info.alignment {
+				4 => "u32",
+				8 => "u64",
+				_ => return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union alignment is not 4 or 8" }),
+			};
+
+			let num_aligned_elements = type_info.size_instance.div_ceil(u32::from(type_info.alignment));
+			if num_aligned_elements == 0 {
+				return Err(crate::Error::UnsupportedLayout { location: Location::type_(type_name), reason: "union is empty" });
+			}
+			let wrapped_type = match num_aligned_elements {
+				1 => alignment.to_string(),
+				_ => format!("[{}; {}]", alignment, num_aligned_elements),
+			};
+
+			// The first field gives the union the size and alignment of the typelib's union.
+			// Fields of unions must be Copy, so the ones that aren't are wrapped in ManuallyDrop.
+			writeln!(out, "#[repr(C)]")?;
+			if fields_are_copy(type_info, context)? {
+				writeln!(out, "#[derive(Clone, Copy)]")?;
+			}
+			writeln!(out, "pub union {} {{", type_name)?;
+			writeln!(out, "    _alignment: {},", wrapped_type)?;
+
+			for field in &type_info.vars {
+				let location = Location::member(type_name, &field.name);
+				let field_type = type_to_string(&field.type_, types::PARAMFLAG_FOUT, &location, context, build_result)?;
+				if is_copy(&field.type_, context, &mut vec![type_info])? {
+					writeln!(out, "    pub {}: {},", sanitize_reserved(&field.name, &location, build_result), field_type)?;
+				}
+				else {
+					writeln!(out, "    pub {}: core::mem::ManuallyDrop<{}>,", sanitize_reserved(&field.name, &location, build_result), field_type)?;
+				}
+			}
+
+			writeln!(out, "}}")?;
+			writeln!(out)?;
+		},
+	}
+
+	Ok(())
+}
+
+/// A function in the vtable of an interface
+struct Function {
+	name: String,
+	params: Vec<Param>,
+
+	/// The type that the function in the vtable returns, if it returns a value
+	return_type: Option<String>,
+
+	/// What the method that calls the function returns
+	method_return: MethodReturn,
+}
+
+/// A parameter of a function in the vtable of an interface
+struct Param {
+	name: String,
+
+	/// The type of the parameter of the function in the vtable
+	abi_type: String,
+
+	/// The type of the parameter of the method that calls the function
+	method_type: String,
+
+	/// The expression that converts the parameter of the method to the parameter of the function
+	arg: String,
+}
+
+/// What the method that calls a function in a vtable returns
+enum MethodReturn {
+	/// What the function returns, as is
+	Value,
+
+	/// The `HRESULT` that the function returns, as a `windows_core::Result<()>`
+	Hresult,
+
+	/// The `HRESULT` that the function returns, as a `windows_core::Result` of the value of its last parameter, which is `[out, retval]`
+	RetVal {
+		value_type: String,
+
+		/// Whether the value is an interface, which is returned as the interface rather than as an `Option` of it
+		is_interface: bool,
+	},
+}
+
+impl Function {
+	fn new(
+		name: String,
+		params: &[types::Param],
+		return_type: &types::TypeDesc,
+		location: &Location,
+		context: &Context<'_>,
+		build_result: &mut BuildResult,
+	) -> Result<Self, crate::Error> {
+		let method_return =
+			match (return_type.vt(), params.last()) {
+				(types::VT_HRESULT, Some(types::Param { type_: types::TypeDesc::Ptr(pointee), flags, .. }))
+					if (flags & types::PARAMFLAG_FRETVAL) == types::PARAMFLAG_FRETVAL =>
+					match interface_path(pointee, location, context, build_result) {
+						Some(value_type) => MethodReturn::RetVal { value_type, is_interface: true },
+						None => MethodReturn::RetVal {
+							value_type: type_to_string(pointee, types::PARAMFLAG_FOUT, location, context, build_result)?,
+							is_interface: false,
+						},
+					},
+
+				(types::VT_HRESULT, _) => MethodReturn::Hresult,
+
+				_ => MethodReturn::Value,
+			};
+
+		let params = params.iter().enumerate().map(|(param_index, param)| {
+			let location = location.param(param_index);
+			let name = sanitize_reserved(&param.name, &location, build_result);
+			let abi_type = param_type_to_string(&param.type_, param.flags, &location, context, build_result)?;
+
+			let (method_type, arg) =
+				if let Some(interface) = interface_path(&param.type_, &location, context, build_result) {
+					(format!("Option<&{}>", interface), format!("{}.map_or(core::ptr::null_mut(), windows_core::Interface::as_raw)", name))
+				}
+				else if let Some(interface) = match &param.type_ { types::TypeDesc::Ptr(pointee) => interface_path(pointee, &location, context, build_result), _ => None } {
+					let pointer = abi_type.split_whitespace().next().unwrap();
+					(format!("{} Option<{}>", pointer, interface), format!("{}.cast()", name))
+				}
+				else if is_owned(&param.type_) {
+					(format!("&{}", type_to_string(&param.type_, param.flags, &location, context, build_result)?), format!("core::mem::transmute_copy({})", name))
+				}
+				else {
+					(abi_type.clone(), name.clone())
+				};
+
+			Ok(Param { name, abi_type, method_type, arg })
+		}).collect::<Result<_, crate::Error>>()?;
+
+		let return_type = match return_type {
+			types::TypeDesc::Base(types::VT_VOID) => None,
+			return_type => Some(type_to_string(return_type, types::PARAMFLAG_FOUT, location, context, build_result)?),
+		};
+
+		Ok(Function { name, params, return_type, method_return })
+	}
+}
+
+/// An `[out, retval]` parameter for a property getter that returns the given type
+fn ret_val_param(type_: types::TypeDesc) -> types::Param {
+	types::Param {
+		name: "value".to_string(),
+		type_: types::TypeDesc::Ptr(Box::new(type_)),
+		flags: types::PARAMFLAG_FOUT | types::PARAMFLAG_FRETVAL,
+		default_value: None,
+	}
+}
+
+/// Writes the IID constant, the struct, the implementation of `windows_core::Interface`, the methods and the vtable struct of an interface
+fn write_interface<W>(mut out: W, type_name: &str, guid: types::Guid, parent: Option<&str>, functions: &[Function]) -> std::io::Result<()> where W: std::io::Write {
+	writeln!(out, "pub const IID_{}: windows_core::GUID = {};", type_name, guid_to_string(guid))?;
+	writeln!(out)?;
+
+	writeln!(out, "#[repr(transparent)]")?;
+	writeln!(out, "#[derive(Clone, Debug, Eq, PartialEq)]")?;
+	match parent {
+		Some(parent) => writeln!(out, "pub struct {}({});", type_name, parent)?,
+		None => writeln!(out, "pub struct {}(core::ptr::NonNull<core::ffi::c_void>);", type_name)?,
+	}
+	writeln!(out)?;
+
+	writeln!(out, "unsafe impl windows_core::Interface for {} {{", type_name)?;
+	writeln!(out, "    type Vtable = {}_Vtbl;", type_name)?;
+	writeln!(out, "    const IID: windows_core::GUID = IID_{};", type_name)?;
+	if parent.is_none() {
+		writeln!(out, "    const UNKNOWN: bool = false;")?;
+	}
+	writeln!(out, "}}")?;
+	writeln!(out)?;
+
+	if let Some(parent) = parent {
+		writeln!(out, "impl core::ops::Deref for {} {{", type_name)?;
+		writeln!(out, "    type Target = {};", parent)?;
+		writeln!(out)?;
+		writeln!(out, "    fn deref(&self) -> &Self::Target {{")?;
+		writeln!(out, "        &self.0")?;
+		writeln!(out, "    }}")?;
+		writeln!(out, "}}")?;
+		writeln!(out)?;
+	}
+
+	if !functions.is_empty() {
+		writeln!(out, "impl {} {{", type_name)?;
+
+		for (i, function) in functions.iter().enumerate() {
+			if i > 0 {
+				writeln!(out)?;
+			}
+
+			let (params, ret_val) = match &function.method_return {
+				MethodReturn::RetVal { .. } => (&function.params[..function.params.len() - 1], function.params.last()),
+				_ => (&function.params[..], None),
+			};
+
+			writeln!(out, "    pub unsafe fn {}(", function.name)?;
+			writeln!(out, "        &self,")?;
+			for param in params {
+				writeln!(out, "        {}: {},", param.name, param.method_type)?;
+			}
+			match &function.method_return {
+				MethodReturn::Value => match &function.return_type {
+					Some(return_type) => writeln!(out, "    ) -> {} {{", return_type)?,
+					None => writeln!(out, "    ) {{")?,
+				},
+				MethodReturn::Hresult => writeln!(out, "    ) -> windows_core::Result<()> {{")?,
+				MethodReturn::RetVal { value_type, .. } => writeln!(out, "    ) -> windows_core::Result<{}> {{", value_type)?,
+			}
+
+			match &function.method_return {
+				MethodReturn::RetVal { value_type, is_interface: true } => writeln!(out, "        let mut result__: Option<{}> = None;", value_type)?,
+				MethodReturn::RetVal { value_type, is_interface: false } => writeln!(out, "        let mut result__: {} = core::mem::zeroed();", value_type)?,
+				_ => (),
+			}
+
+			writeln!(out, "        (windows_core::Interface::vtable(self).{})(", function.name)?;
+			writeln!(out, "            windows_core::Interface::as_raw(self),")?;
+			for param in params {
+				writeln!(out, "            {},", param.arg)?;
+			}
+			if let Some(ret_val) = ret_val {
+				writeln!(out, "            core::ptr::addr_of_mut!(result__){},", if ret_val.abi_type.contains("c_void") { ".cast()" } else { "" })?;
+			}
+
+			match &function.method_return {
+				MethodReturn::Value => writeln!(out, "        )")?,
+				MethodReturn::Hresult => writeln!(out, "        ).ok()")?,
+				MethodReturn::RetVal { is_interface: true, .. } => {
+					writeln!(out, "        ).ok()?;")?;
+					writeln!(out, "        result__.ok_or_else(|| windows_core::Error::from(windows_core::HRESULT(0x8000_4003_u32 as i32))) // E_POINTER")?;
+				},
+				MethodReturn::RetVal { is_interface: false, .. } => writeln!(out, "        ).ok().map(|()| result__)")?,
+			}
+
+			writeln!(out, "    }}")?;
+		}
+
+		writeln!(out, "}}")?;
+		writeln!(out)?;
+	}
+
+	writeln!(out, "#[repr(C)]")?;
+	writeln!(out, "pub struct {}_Vtbl {{", type_name)?;
+	if let Some(parent) = parent {
+		writeln!(out, "    pub base__: {}_Vtbl,", parent)?;
+	}
+	for function in functions {
+		writeln!(out, r#"    pub {}: unsafe extern "system" fn("#, function.name)?;
+		writeln!(out, "        this: *mut core::ffi::c_void,")?;
+		for param in &function.params {
+			writeln!(out, "        {}: {},", param.name, param.abi_type)?;
+		}
+		match &function.return_type {
+			Some(return_type) => writeln!(out, "    ) -> {},", return_type)?,
+			None => writeln!(out, "    ),")?,
+		}
+	}
+	writeln!(out, "}}")?;
+	writeln!(out)?;
+
+	Ok(())
+}
+
+fn guid_to_string(guid: types::Guid) -> String {
+	format!("windows_core::GUID::from_u128(0x{:08x}_{:04x}_{:04x}_{:02x}{:02x}_{:02x}{:02x}{:02x}{:02x}{:02x}{:02x})",
+		guid.data1, guid.data2, guid.data3,
+		guid.data4[0], guid.data4[1], guid.data4[2], guid.data4[3],
+		guid.data4[4], guid.data4[5], guid.data4[6], guid.data4[7])
+}
+
+/// Formats the given type as Rust. Interface pointers are `*mut c_void`, like in the vtables of the `windows` crate.
+fn type_to_string(type_: &types::TypeDesc, param_flags: u16, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Result<String, crate::Error> {
+	Ok(match type_ {
+		types::TypeDesc::Ptr(pointee) => {
+			if let types::TypeDesc::UserDefined(type_ref) = &**pointee {
+				if is_interface(type_ref, context) {
+					return Ok("*mut core::ffi::c_void".to_string());
+				}
+			}
+
+			if (param_flags & types::PARAMFLAG_FIN) == types::PARAMFLAG_FIN && (param_flags & types::PARAMFLAG_FOUT) == 0 {
+				// [in] => *const
+				format!("*const {}", type_to_string(pointee, param_flags, location, context, build_result)?)
+			}
+			else {
+				// [in, out] => *mut
+				// [] => *mut (Some functions like IXMLError::GetErrorInfo don't annotate [out] on their out parameter)
+				format!("*mut {}", type_to_string(pointee, param_flags, location, context, build_result)?)
+			}
+		},
+
+		types::TypeDesc::SafeArray(element) => {
+			// The element type of the SAFEARRAY isn't part of the Rust type, so it's written next to it in a comment,
+			// and reported for the bindings that don't keep comments, like the ones from `build_tokens`
+			let element = match interface_path(element, location, context, build_result) {
+				Some(interface) => interface,
+				None => type_to_string(element, 0, location, context, build_result)?,
+			};
+			let type_name = "*mut windows::Win32::System::Com::SAFEARRAY";
+			build_result.lossy_type_mapping(type_, type_name, location);
+			format!("{} /* of {} */", type_name, element)
+		},
+
+		types::TypeDesc::CArray(element, dimensions) => {
+			let mut type_name = type_to_string(element, param_flags, location, context, build_result)?;
+
+			for dimension in dimensions {
+				type_name = format!("[{}; {}]", type_name, dimension);
+			}
+
+			type_name
+		},
+
+		types::TypeDesc::UserDefined(type_ref) => type_ref_path(type_ref, location, context, build_result),
+
+		types::TypeDesc::Base(vt) => match var_type_kind(*vt, location)? {
+			VarTypeKind::Expanded(type_) => type_to_string(&type_, param_flags, location, context, build_result)?,
+
+			VarTypeKind::Record => {
+				let type_name = "*mut core::ffi::c_void";
+				build_result.lossy_type_mapping(type_, type_name, location);
+				type_name.to_string()
+			},
+
+			VarTypeKind::WellKnown(well_known) => well_known_type_path(well_known),
+		},
+	})
+}
+
+/// Formats the given type as Rust, for a value that's owned by someone else, like a field of a record or a parameter
+///
+/// `BSTR`s and `VARIANT`s are wrapped in `ManuallyDrop`, so that they aren't freed by the code that only borrows them.
+fn value_type_to_string(type_: &types::TypeDesc, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Result<String, crate::Error> {
+	let type_name = type_to_string(type_, types::PARAMFLAG_FOUT, location, context, build_result)?;
+	if is_owned(type_) {
+		Ok(format!("core::mem::ManuallyDrop<{}>", type_name))
+	}
+	else {
+		Ok(type_name)
+	}
+}
+
+fn param_type_to_string(type_: &types::TypeDesc, param_flags: u16, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Result<String, crate::Error> {
+	if is_owned(type_) {
+		value_type_to_string(type_, location, context, build_result)
+	}
+	else {
+		type_to_string(type_, param_flags, location, context, build_result)
+	}
+}
+
+/// Whether the given type frees what it owns when it's dropped
+fn is_owned(type_: &types::TypeDesc) -> bool {
+	matches!(type_, types::TypeDesc::Base(types::VT_BSTR | types::VT_VARIANT))
+}
+
+/// Whether the Rust types that the fields of the given record or union are emitted as are all `Copy`
+fn fields_are_copy(type_info: &types::TypeInfo, context: &Context<'_>) -> Result<bool, crate::Error> {
+	fields_are_copy_inner(type_info, context, &mut vec![])
+}
+
+/// `containing` is the records, unions and aliases that `type_info` is a part of, so that ones that contain themselves are an error instead of infinite recursion
+fn fields_are_copy_inner<'a>(type_info: &'a types::TypeInfo, context: &Context<'a>, containing: &mut Vec<&'a types::TypeInfo>) -> Result<bool, crate::Error> {
+	if containing.iter().any(|&containing| std::ptr::eq(containing, type_info)) {
+		return Err(crate::Error::Inconsistent { location: Location::type_(&type_info.name), reason: "type contains itself" });
+	}
+
+	containing.push(type_info);
+
+	let mut result = true;
+	match type_info.typekind {
+		types::TypeKind::Record |
+		types::TypeKind::Union =>
+			for field in &type_info.vars {
+				result &= is_copy(&field.type_, context, containing)?;
+			},
+
+		types::TypeKind::Alias =>
+			if let Some(alias_type) = &type_info.alias_type {
+				result = is_copy(alias_type, context, containing)?;
+			},
+
+		_ => (),
+	}
+
+	containing.pop();
+
+	Ok(result)
+}
+
+/// Whether the Rust type that the given type is emitted as is `Copy`
+fn is_copy<'a>(type_: &types::TypeDesc, context: &Context<'a>, containing: &mut Vec<&'a types::TypeInfo>) -> Result<bool, crate::Error> {
+	match type_ {
+		types::TypeDesc::CArray(element, _) => is_copy(element, context, containing),
+
+		types::TypeDesc::UserDefined(type_ref) => match resolve_type_info(type_ref, context) {
+			Some(type_info) => fields_are_copy_inner(type_info, context, containing),
+			None => Ok(true),
+		},
+
+		_ => Ok(!is_owned(type_)),
+	}
+}
+
+/// Whether the given type reference refers to an interface or dispinterface
+fn is_interface(type_ref: &types::TypeRef, context: &Context<'_>) -> bool {
+	match resolve_type_info(type_ref, context) {
+		Some(type_info) => matches!(type_info.typekind, types::TypeKind::Interface | types::TypeKind::Dispatch),
+
+		// Only the well-known interfaces of stdole2.tlb have a vtable size without their typelib
+		None => context.type_lib.get_vtbl_size(type_ref).is_some(),
+	}
+}
+
+/// If the given type is a pointer to an interface, gets the path of the interface
+fn interface_path(type_: &types::TypeDesc, location: &Location, context: &Context<'_>, build_result: &mut BuildResult) -> Option<String> {
+	match type_ {
+		types::TypeDesc::Base(types::VT_DISPATCH) => Some(well_known_path("IDispatch", context)),
+		types::TypeDesc::Base(types::VT_UNKNOWN) => Some(well_known_path("IUnknown", context)),
+		types::TypeDesc::Base(types::VT_STREAM | types::VT_STREAMED_OBJECT) => Some("windows::Win32::System::Com::IStream".to_string()),
+		types::TypeDesc::Base(types::VT_STORAGE | types::VT_STORED_OBJECT) => Some("windows::Win32::System::Com::StructuredStorage::IStorage".to_string()),
+		types::TypeDesc::Ptr(pointee) => match &**pointee {
+			types::TypeDesc::UserDefined(type_ref) if is_interface(type_ref, context) => Some(type_ref_path(type_ref, location, context, build_result)),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+fn well_known_type_path(well_known: WellKnownType) -> String {
+	let path = match well_known {
+		WellKnownType::Primitive(type_name) => type_name,
+		WellKnownType::CountedArray(type_name) => return format!("windows::Win32::System::Com::StructuredStorage::{}", type_name),
+		WellKnownType::Void => "core::ffi::c_void",
+		WellKnownType::Int |
+		WellKnownType::Error => "i32",
+		WellKnownType::UInt => "u32",
+		WellKnownType::IntPtr => "isize",
+		WellKnownType::UIntPtr => "usize",
+		WellKnownType::Bool => "windows::Win32::Foundation::VARIANT_BOOL",
+		WellKnownType::Currency => "windows::Win32::System::Com::CY",
+		WellKnownType::Date => "f64",
+		WellKnownType::Decimal => "windows::Win32::Foundation::DECIMAL",
+		WellKnownType::HResult => "windows_core::HRESULT",
+		WellKnownType::Bstr => "windows_core::BSTR",
+		WellKnownType::BstrBlob => "windows::Win32::System::Com::BSTRBLOB",
+		WellKnownType::LpStr => "windows_core::PSTR",
+		WellKnownType::LpWStr => "windows_core::PCWSTR",
+		WellKnownType::Variant => "windows::Win32::System::Variant::VARIANT",
+		WellKnownType::FileTime => "windows::Win32::Foundation::FILETIME",
+		WellKnownType::Blob => "windows::Win32::System::Com::BLOB",
+		WellKnownType::Clsid => "windows_core::GUID",
+		WellKnownType::Dispatch |
+		WellKnownType::Unknown |
+		WellKnownType::Stream |
+		WellKnownType::Storage => "*mut core::ffi::c_void",
+	};
+	path.to_string()
+}
@@ -0,0 +1,146 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! A proc macro that expands to the bindings for a typelib, as an alternative to generating them in a build script.
+
+/// Expands to the bindings for the typelib at the given path.
+///
+/// ```ignore
+/// winapi_tlb_bindgen_macros::typelib!("MsXml.Tlb", emit_dispinterfaces = false);
+/// ```
+///
+/// `winapi-tlb-bindgen` re-exports it as `winapi_tlb_bindgen::typelib!` with its `macros` feature.
+///
+/// The path is relative to the directory of the crate that uses the macro, and is interpreted the same way as for `winapi_tlb_bindgen::build`.
+/// The crate is rebuilt when the typelib changes, or for an IDL file, when a file that it imports or `importlib`s changes.
+///
+/// Referenced types that can't be found fail the expansion, like with `MissingTypePolicy::Error`. The warnings in `BuildResult::warnings`
+/// are reported together as a deprecation warning at the macro call, which `#[allow(deprecated)]` silences.
+///
+/// The options are:
+///
+/// - `backend = "winapi"`: The crate to emit bindings for, `"winapi"`, `"windows"`, `"repr-c"` or `"com"`. The types in `stdole2.tlb`
+///   are mapped to the paths of the backend's crate, like `winapi-tlb-bindgen-bin` does.
+///
+/// - `emit_dispinterfaces = false`: Whether to emit bindings for dispinterfaces.
+///
+/// - `emit_imports = false`: Whether to start the bindings with the `use` items that they need, or with the prelude for the `"repr-c"` backend.
+#[proc_macro]
+pub fn typelib(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as Input);
+
+	match expand(&input) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => syn::Error::new(input.path.span(), err).to_compile_error().into(),
+	}
+}
+
+struct Input {
+	path: syn::LitStr,
+	backend: winapi_tlb_bindgen_core::Backend,
+	emit_dispinterfaces: bool,
+	emit_imports: bool,
+}
+
+impl syn::parse::Parse for Input {
+	fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
+		let path = input.parse()?;
+
+		let mut result = Input {
+			path,
+			backend: winapi_tlb_bindgen_core::Backend::Winapi,
+			emit_dispinterfaces: false,
+			emit_imports: false,
+		};
+
+		while !input.is_empty() {
+			let _: syn::Token![,] = input.parse()?;
+			if input.is_empty() {
+				break;
+			}
+
+			let name: syn::Ident = input.parse()?;
+			let _: syn::Token![=] = input.parse()?;
+
+			if name == "backend" {
+				let value: syn::LitStr = input.parse()?;
+				result.backend = match &*value.value() {
+					"winapi" => winapi_tlb_bindgen_core::Backend::Winapi,
+					"windows" => winapi_tlb_bindgen_core::Backend::Windows,
+					"repr-c" => winapi_tlb_bindgen_core::Backend::ReprC,
+					"com" => winapi_tlb_bindgen_core::Backend::Com,
+					_ => return Err(syn::Error::new(value.span(), r#"expected "winapi", "windows", "repr-c" or "com""#)),
+				};
+			}
+			else if name == "emit_dispinterfaces" {
+				result.emit_dispinterfaces = input.parse::<syn::LitBool>()?.value;
+			}
+			else if name == "emit_imports" {
+				result.emit_imports = input.parse::<syn::LitBool>()?.value;
+			}
+			else {
+				return Err(syn::Error::new(name.span(), "expected `backend`, `emit_dispinterfaces` or `emit_imports`"));
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+fn expand(input: &Input) -> Result<proc_macro2::TokenStream, String> {
+	let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").ok_or("CARGO_MANIFEST_DIR is not set")?;
+	let path = std::path::Path::new(&manifest_dir).join(input.path.value());
+
+	let (bindings, build_result) =
+		winapi_tlb_bindgen_core::Builder::from_path(&path)
+		.backend(input.backend)
+		.emit_dispinterfaces(input.emit_dispinterfaces)
+		.emit_imports(input.emit_imports)
+		// Fail with the types that couldn't be found, rather than emit `__missing_type__`s that fail to compile far from the macro call.
+		// Types that bindings can't be emitted for fail too, since `continue_on_error` isn't set.
+		.missing_types(winapi_tlb_bindgen_core::MissingTypePolicy::Error)
+		.build_tokens()
+		.map_err(|err| format!("could not emit bindings for {}: {}", path.display(), err))?;
+
+	// Including the files makes cargo rebuild the crate when the typelib or a file that it imports changes
+	let tracked_paths =
+		build_result.files.iter()
+		.map(|file| file.to_str().ok_or_else(|| format!("{} is not valid UTF-8", file.display())))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let warnings = warnings(&build_result.warnings, input.path.span());
+
+	Ok(quote::quote! {
+		#(const _: &[u8] = include_bytes!(#tracked_paths);)*
+
+		#warnings
+
+		#bindings
+	})
+}
+
+/// Reports the given warnings as the deprecation warning of an item that's used at the given span,
+/// since proc macros can't emit warnings of their own on stable
+fn warnings(warnings: &[winapi_tlb_bindgen_core::Warning], span: proc_macro2::Span) -> proc_macro2::TokenStream {
+	if warnings.is_empty() {
+		return proc_macro2::TokenStream::new();
+	}
+
+	let mut note = format!("the bindings have {} warnings:", warnings.len());
+	for warning in warnings {
+		note.push('\n');
+		note.push_str(&warning.to_string());
+	}
+
+	quote::quote_spanned! { span =>
+		const _: () = {
+			#[deprecated(note = #note)]
+			struct TypelibWarnings;
+
+			#[allow(dead_code)]
+			fn warn() {
+				let _ = TypelibWarnings;
+			}
+		};
+	}
+}
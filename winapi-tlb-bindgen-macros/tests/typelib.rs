@@ -0,0 +1,26 @@
+#![warn(rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+
+//! Tests for the `typelib!` macro, using the fixtures of `winapi-tlb-bindgen`.
+
+#[allow(dead_code, deprecated, non_camel_case_types, non_snake_case, non_upper_case_globals, clippy::all, clippy::pedantic)]
+mod simple {
+	winapi_tlb_bindgen_macros::typelib!("../tests/fixtures/simple.tlb", backend = "repr-c", emit_imports = true);
+}
+
+#[allow(dead_code, deprecated, non_camel_case_types, non_snake_case, non_upper_case_globals, clippy::all, clippy::pedantic)]
+mod simple_idl {
+	// Through the re-export of the `macros` feature of `winapi-tlb-bindgen`
+	winapi_tlb_bindgen::typelib!("../tests/fixtures/simple.idl", backend = "repr-c", emit_dispinterfaces = true, emit_imports = true,);
+}
+
+#[test]
+fn typelib() {
+	assert_eq!(simple::Blue, -2);
+	assert_eq!(std::mem::size_of::<simple::Point>(), 8);
+	assert_eq!(simple::IID_IShape.Data4, [0x8f, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+
+	// Dispinterfaces are only emitted when asked for
+	assert_eq!(simple_idl::IID_DShapeEvents.Data4[7], 0x03);
+	assert_eq!(std::mem::size_of::<simple_idl::Value>(), 8);
+}